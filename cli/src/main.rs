@@ -12,10 +12,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod sshfp;
 mod version;
 
+use sshfp::print_sshfp;
+use std::env;
+use std::process;
 use version::fmt_version_info;
 
+const USAGE: &str = "usage: dns_cli [sshfp FILE [HOST]]";
+
 fn main() {
-    println!("{}", fmt_version_info());
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let result = match args[..] {
+        [] => {
+            println!("{}", fmt_version_info());
+            Ok(())
+        }
+        ["sshfp", path] => print_sshfp(path, None),
+        ["sshfp", path, host] => print_sshfp(path, Some(host)),
+        _ => Err(USAGE.to_string()),
+    };
+    if let Err(message) = result {
+        eprintln!("{}", message);
+        process::exit(1);
+    }
 }
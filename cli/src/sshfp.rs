@@ -0,0 +1,148 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use dns_lib::domain::Domain;
+use dns_lib::sshfp::{HostKey, SshfpAlgorithm};
+use std::fs;
+use std::net::IpAddr;
+
+/// Writes the `SSHFP` records of the host keys in `path`, which may be a
+/// `.pub` file or use the `known_hosts` format, where lines start with a
+/// comma separated list of host names.
+///
+/// Keys without host names are published at `host`. Hashed host names,
+/// patterns, addresses, hosts on ports other than 22 and `@revoked` or
+/// `@cert-authority` lines are skipped.
+pub fn print_sshfp(path: &str, host: Option<&str>) -> Result<(), String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    for (number, line) in content.lines().enumerate() {
+        let error = |message: String| format!("{}:{}: {}", path, number + 1, message);
+        let Some((hosts, key)) = parse_line(line, host).map_err(error)? else {
+            continue;
+        };
+        let key: HostKey = key.parse().map_err(|e| error(format!("{}", e)))?;
+
+        for name in hosts {
+            let owner = Domain::from_presentation(name)
+                .map_err(|e| error(format!("invalid host name '{}': {}", name, e)))?;
+            for sshfp in key.to_sshfp_records() {
+                println!("{} IN SSHFP {}", owner.to_fqdn_string(), sshfp);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Splits a line into the host names owning its key and the key, returning
+/// `None` for lines without keys to publish.
+fn parse_line<'a>(
+    line: &'a str,
+    host: Option<&'a str>,
+) -> Result<Option<(Vec<&'a str>, &'a str)>, String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') || line.starts_with('@') {
+        return Ok(None);
+    }
+    let (hosts, key) = match line.split_whitespace().next() {
+        Some(first) if SshfpAlgorithm::from_key_type(first).is_none() => {
+            let hosts: Vec<&str> = first.split(',').filter_map(host_name).collect();
+            (hosts, line[first.len()..].trim_start())
+        }
+        _ => match host {
+            Some(host) => (vec![host], line),
+            None => return Err("key has no host name and no HOST was given".to_string()),
+        },
+    };
+    Ok((!hosts.is_empty()).then_some((hosts, key)))
+}
+
+/// Returns the name of a `known_hosts` host pattern that can own `SSHFP`
+/// records, as `SSHFP` has no way to tell a port other than 22.
+fn host_name(pattern: &str) -> Option<&str> {
+    if pattern.starts_with('|') || pattern.contains(['*', '?', '!']) {
+        return None;
+    }
+    let name = match pattern.strip_prefix('[') {
+        Some(bracketed) => match bracketed.split_once("]:") {
+            Some((name, "22")) => name,
+            _ => return None,
+        },
+        None => pattern,
+    };
+    name.parse::<IpAddr>().is_err().then_some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const KEY: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIEj0Hz0xkEbgNJd5WKSV3Hx0WCN9xHWtNLhz3fxkNb6G";
+
+    #[rstest]
+    #[case("host.example", Some("host.example"))]
+    #[case("[host.example]:22", Some("host.example"))]
+    #[case("[host.example]:2222", None)]
+    #[case("[host.example]", None)]
+    #[case("|1|F1E1KeoE/eEWhi10WpGv4OdiO6Y=|3988QV0VE8wmZL7suNrYQLITLCg=", None)]
+    #[case("*.example", None)]
+    #[case("host?.example", None)]
+    #[case("!host.example", None)]
+    #[case("192.0.2.1", None)]
+    #[case("2001:db8::1", None)]
+    #[case("[192.0.2.1]:22", None)]
+    fn host_name_of_pattern(#[case] pattern: &str, #[case] expected: Option<&str>) {
+        assert_eq!(host_name(pattern), expected);
+    }
+
+    #[rstest]
+    #[case(
+        format!("host.example,192.0.2.1,alias.example {}", KEY),
+        None,
+        vec!["host.example", "alias.example"]
+    )]
+    #[case(format!("[host.example]:22 {} comment", KEY), None, vec!["host.example"])]
+    #[case(format!("  host.example {}", KEY), Some("other.example"), vec!["host.example"])]
+    #[case(KEY.to_string(), Some("host.example"), vec!["host.example"])]
+    fn parse_line_accepts(
+        #[case] line: String,
+        #[case] host: Option<&str>,
+        #[case] expected: Vec<&str>,
+    ) {
+        let (hosts, key) = parse_line(&line, host).unwrap().unwrap();
+        assert_eq!(hosts, expected);
+        assert!(key.starts_with(KEY));
+    }
+
+    #[rstest]
+    #[case(String::new())]
+    #[case("# comment".to_string())]
+    #[case(format!("@cert-authority *.example {}", KEY))]
+    #[case(format!("@revoked host.example {}", KEY))]
+    #[case(format!("|1|F1E1KeoE/eEWhi10WpGv4OdiO6Y=|3988QV0VE8wmZL7suNrYQLITLCg= {}", KEY))]
+    #[case(format!("*.example,192.0.2.1 {}", KEY))]
+    #[case(format!("[host.example]:2222 {}", KEY))]
+    fn parse_line_skips(#[case] line: String) {
+        assert_eq!(parse_line(&line, Some("host.example")), Ok(None));
+    }
+
+    #[test]
+    fn parse_line_requires_host_for_keys_without_names() {
+        assert_eq!(
+            parse_line(KEY, None),
+            Err("key has no host name and no HOST was given".to_string())
+        );
+    }
+}
@@ -0,0 +1,319 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Generates `KnownQType` and `KnownQClass` from the IANA registries of
+//! resource record types and classes kept in `data/`, as downloaded from:
+//! - <https://www.iana.org/assignments/dns-parameters/dns-parameters-4.csv>
+//! - <https://www.iana.org/assignments/dns-parameters/dns-parameters-2.csv>
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+const TYPES: &str = "data/dns-parameters-4.csv";
+const CLASSES: &str = "data/dns-parameters-2.csv";
+
+/// Types made obsolete by an RFC without the registry saying so, such as
+/// `SPF`, see RFC 7208, Section 3.1.
+const OBSOLETE_TYPES: &[&str] = &["SPF"];
+
+/// Classes no longer listed in the registry, kept for compatibility as
+/// `(mnemonic, value, description)`. They are all obsolete.
+const LEGACY_CLASSES: &[(&str, u16, &str)] = &[("CS", 2, "CSNET (CS), obsolete")];
+
+struct Entry {
+    mnemonic: String,
+    variant: String,
+    value: u16,
+    description: String,
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed={}", TYPES);
+    println!("cargo:rerun-if-changed={}", CLASSES);
+
+    let mut code = String::from("// Generated by build.rs from the IANA registries in data/.\n");
+    generate_types(&mut code, &read_csv(TYPES));
+    generate_classes(&mut code, &read_csv(CLASSES));
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("registry.rs");
+    fs::write(out, code).unwrap();
+}
+
+fn read_csv(path: &str) -> Vec<Vec<String>> {
+    let text = fs::read_to_string(path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    let mut rows = parse_csv(&text);
+    rows.remove(0);
+    rows
+}
+
+/// Splits CSV text into rows of fields, where quoted fields may contain
+/// commas, line breaks and doubled quotes.
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let (mut row, mut field) = (Vec::new(), String::new());
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', _) => quoted = !quoted,
+            (',', false) => row.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            (c, _) => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+/// Rows of unassigned and reserved values, including ranges, which have no
+/// mnemonic.
+fn is_assigned(name: &str, value: &str) -> bool {
+    !value.contains('-')
+        && !name.starts_with("Unassigned")
+        && !name.starts_with("Reserved")
+        && !name.starts_with("Private use")
+}
+
+fn generate_types(code: &mut String, rows: &[Vec<String>]) {
+    let entries: Vec<Entry> = rows
+        .iter()
+        .filter(|row| is_assigned(&row[0], &row[1]))
+        .map(|row| {
+            // The mnemonic of type 255 is `*`, but `ANY` is used in master
+            // files and by most tools.
+            let mnemonic = match row[0].as_str() {
+                "*" => "ANY".to_string(),
+                mnemonic => mnemonic.to_string(),
+            };
+            Entry {
+                variant: mnemonic.replace('-', "_"),
+                mnemonic,
+                value: row[1].parse().unwrap(),
+                description: row[2].clone(),
+            }
+        })
+        .collect();
+
+    // See RFC 6895, Section 3.1: values from 128 to 255 are reserved for
+    // meta and question types, with OPT being an older meta type.
+    let question_only = |e: &Entry| (251..=255).contains(&e.value);
+    let meta = |e: &Entry| e.value == 41 || (128..=250).contains(&e.value);
+    let obsolete = |e: &Entry| {
+        e.description.contains("OBSOLETE")
+            || e.description.contains("DEPRECATED")
+            || OBSOLETE_TYPES.contains(&e.mnemonic.as_str())
+    };
+
+    code.push_str(
+        "
+/// Resource record type assigned in the [IANA registry], generated from its
+/// CSV export.
+///
+/// Types are either data types, meta types carrying transient data such as
+/// `OPT`, or question types only valid in queries such as `AXFR`, see
+/// [RFC 6895, Section 3.1].
+///
+/// [IANA registry]: https://www.iana.org/assignments/dns-parameters/dns-parameters.xhtml#dns-parameters-4
+/// [RFC 6895, Section 3.1]: https://datatracker.ietf.org/doc/html/rfc6895#section-3.1
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[repr(u16)]
+#[allow(clippy::upper_case_acronyms, non_camel_case_types)]
+pub enum KnownQType {
+",
+    );
+    write_variants(code, &entries);
+    code.push_str("}\n\nimpl KnownQType {\n");
+    write_lookups(code, &entries, "type");
+    write_predicate(
+        code,
+        "Returns whether the type is a meta type, whose records only carry\n    /// transient data and are never stored.",
+        "is_meta",
+        entries.iter().filter(|e| meta(e)),
+    );
+    write_predicate(
+        code,
+        "Returns whether the type may only appear in questions.",
+        "is_question_only",
+        entries.iter().filter(|e| question_only(e)),
+    );
+    write_predicate(
+        code,
+        "Returns whether the registry marks the type as obsolete or deprecated.",
+        "is_obsolete",
+        entries.iter().filter(|e| obsolete(e)),
+    );
+    code.push_str("}\n");
+}
+
+fn generate_classes(code: &mut String, rows: &[Vec<String>]) {
+    let mut entries: Vec<Entry> = rows
+        .iter()
+        .filter(|row| is_assigned(&row[2], &row[0]))
+        .map(|row| {
+            // Names are written as `Internet (IN)` or `QCLASS NONE`.
+            let name = &row[2];
+            let mnemonic = match name.rsplit_once('(') {
+                Some((_, rest)) => rest.trim_end_matches(')'),
+                None => name.trim_start_matches("QCLASS "),
+            };
+            Entry {
+                mnemonic: mnemonic.to_string(),
+                variant: mnemonic.to_string(),
+                value: row[0].parse().unwrap(),
+                description: name.clone(),
+            }
+        })
+        .collect();
+    entries.extend(
+        LEGACY_CLASSES
+            .iter()
+            .map(|(mnemonic, value, description)| Entry {
+                mnemonic: mnemonic.to_string(),
+                variant: mnemonic.to_string(),
+                value: *value,
+                description: description.to_string(),
+            }),
+    );
+    entries.sort_by_key(|e| e.value);
+
+    code.push_str(
+        "
+/// Class assigned in the [IANA registry], generated from its CSV export.
+///
+/// `NONE` and `ANY` are only valid in questions and in dynamic updates, see
+/// [RFC 6895, Section 3.2].
+///
+/// [IANA registry]: https://www.iana.org/assignments/dns-parameters/dns-parameters.xhtml#dns-parameters-2
+/// [RFC 6895, Section 3.2]: https://datatracker.ietf.org/doc/html/rfc6895#section-3.2
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[repr(u16)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum KnownQClass {
+",
+    );
+    write_variants(code, &entries);
+    code.push_str("}\n\nimpl KnownQClass {\n");
+    write_lookups(code, &entries, "class");
+    write_predicate(
+        code,
+        "Returns whether the class is obsolete.",
+        "is_obsolete",
+        entries.iter().filter(|e| {
+            LEGACY_CLASSES
+                .iter()
+                .any(|(mnemonic, ..)| *mnemonic == e.mnemonic)
+        }),
+    );
+    code.push_str("}\n");
+}
+
+fn write_variants(code: &mut String, entries: &[Entry]) {
+    for entry in entries {
+        if !entry.description.is_empty() {
+            writeln!(code, "    #[doc = {:?}]", entry.description).unwrap();
+        }
+        writeln!(code, "    {} = {},", entry.variant, entry.value).unwrap();
+    }
+}
+
+fn write_lookups(code: &mut String, entries: &[Entry], kind: &str) {
+    writeln!(
+        code,
+        "    /// Returns the {} with `value`, if it is assigned.
+    pub fn from_value(value: u16) -> Option<Self> {{
+        match value {{",
+        kind
+    )
+    .unwrap();
+    for entry in entries {
+        writeln!(
+            code,
+            "            {} => Some(Self::{}),",
+            entry.value, entry.variant
+        )
+        .unwrap();
+    }
+    code.push_str("            _ => None,\n        }\n    }\n\n");
+
+    writeln!(
+        code,
+        "    /// Returns the {} with the mnemonic `s`, regardless of case.
+    pub fn from_mnemonic(s: &str) -> Option<Self> {{
+        match s.to_ascii_uppercase().as_str() {{",
+        kind
+    )
+    .unwrap();
+    for entry in entries {
+        writeln!(
+            code,
+            "            {:?} => Some(Self::{}),",
+            entry.mnemonic, entry.variant
+        )
+        .unwrap();
+    }
+    code.push_str("            _ => None,\n        }\n    }\n\n");
+
+    writeln!(
+        code,
+        "    /// Returns the mnemonic of the {} used in presentation format.
+    pub fn mnemonic(&self) -> &'static str {{
+        match self {{",
+        kind
+    )
+    .unwrap();
+    for entry in entries {
+        writeln!(
+            code,
+            "            Self::{} => {:?},",
+            entry.variant, entry.mnemonic
+        )
+        .unwrap();
+    }
+    code.push_str("        }\n    }\n");
+}
+
+fn write_predicate<'a>(
+    code: &mut String,
+    doc: &str,
+    name: &str,
+    entries: impl Iterator<Item = &'a Entry>,
+) {
+    let variants: Vec<String> = entries.map(|e| format!("Self::{}", e.variant)).collect();
+    writeln!(
+        code,
+        "
+    /// {}
+    pub fn {}(&self) -> bool {{
+        matches!(self, {})
+    }}",
+        doc,
+        name,
+        variants.join(" | ")
+    )
+    .unwrap();
+}
@@ -0,0 +1,77 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub struct IssuerValueFromStrError(pub String);
+
+impl fmt::Display for IssuerValueFromStrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "malformed CAA issuer value '{}'", self.0)
+    }
+}
+
+impl Error for IssuerValueFromStrError {}
+
+/// Reasons why a certification authority may not issue a certificate.
+#[derive(Debug, PartialEq)]
+pub enum CaaError {
+    /// The relevant record set has a critical property the evaluator does
+    /// not understand.
+    UnknownCriticalTag(String),
+    /// No property authorizes the issuer.
+    NotAuthorized,
+    /// A property authorizes the issuer for another ACME account only.
+    AccountMismatch,
+    /// A property authorizes the issuer with other validation methods only.
+    ValidationMethodMismatch,
+}
+
+impl fmt::Display for CaaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnknownCriticalTag(tag) => {
+                write!(f, "critical CAA property '{}' is not understood", tag)
+            }
+            Self::NotAuthorized => write!(f, "CAA records do not authorize the issuer"),
+            Self::AccountMismatch => {
+                write!(f, "CAA records authorize the issuer for another account")
+            }
+            Self::ValidationMethodMismatch => write!(
+                f,
+                "CAA records authorize the issuer for other validation methods"
+            ),
+        }
+    }
+}
+
+impl Error for CaaError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(
+        CaaError::UnknownCriticalTag("tbs".to_string()),
+        "critical CAA property 'tbs' is not understood"
+    )]
+    #[case(CaaError::NotAuthorized, "CAA records do not authorize the issuer")]
+    fn caa_error_display(#[case] err: CaaError, #[case] msg: &str) {
+        assert_eq!(err.to_string(), msg);
+    }
+}
@@ -0,0 +1,464 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::caa::error::{CaaError, IssuerValueFromStrError};
+use crate::domain::Domain;
+use crate::record::rdata::Caa;
+use crate::record::{RData, RecordSet};
+use std::str::FromStr;
+
+/// Property tags defined by [RFC 8659, Section 4.2], the only ones that may
+/// be marked critical.
+///
+/// [RFC 8659, Section 4.2]: https://datatracker.ietf.org/doc/html/rfc8659#section-4.2
+const KNOWN_TAGS: [&str; 3] = ["issue", "issuewild", "iodef"];
+
+/// Value of an `issue` or `issuewild` property: the domain name of the
+/// authorized issuer, if any, followed by `tag=value` parameters.
+///
+/// For more details, see [RFC 8659, Section 4.2].
+///
+/// # Example
+/// ```
+/// use dns_lib::caa::IssuerValue;
+///
+/// let value: IssuerValue = "ca.example.net; account=230123".parse().unwrap();
+/// assert_eq!(value.issuer.as_deref(), Some("ca.example.net"));
+/// assert_eq!(value.parameter("account"), Some("230123"));
+///
+/// let nobody: IssuerValue = ";".parse().unwrap();
+/// assert_eq!(nobody.issuer, None);
+/// ```
+///
+/// [RFC 8659, Section 4.2]: https://datatracker.ietf.org/doc/html/rfc8659#section-4.2
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssuerValue {
+    pub issuer: Option<String>,
+    pub parameters: Vec<(String, String)>,
+}
+
+impl IssuerValue {
+    pub fn parameter(&self, tag: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(t, _)| t.eq_ignore_ascii_case(tag))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl FromStr for IssuerValue {
+    type Err = IssuerValueFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let malformed = || IssuerValueFromStrError(s.to_string());
+        let (issuer, parameters) = match s.split_once(';') {
+            Some((issuer, parameters)) => (issuer, Some(parameters)),
+            None => (s, None),
+        };
+
+        let issuer = issuer.trim_matches(is_wsp);
+        let issuer = match issuer.is_empty() {
+            true => None,
+            false if issuer.split('.').all(is_label) => Some(issuer.to_string()),
+            false => return Err(malformed()),
+        };
+
+        let parameters = match parameters.map(|p| p.trim_matches(is_wsp)) {
+            None | Some("") => Vec::new(),
+            Some(parameters) => parameters
+                .split(';')
+                .map(|parameter| {
+                    let (tag, value) = parameter.split_once('=')?;
+                    let (tag, value) = (tag.trim_matches(is_wsp), value.trim_matches(is_wsp));
+                    let valid_value = value.bytes().all(|b| (0x21..=0x7e).contains(&b));
+                    (is_label(tag) && valid_value).then(|| (tag.to_string(), value.to_string()))
+                })
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(malformed)?,
+        };
+        Ok(IssuerValue { issuer, parameters })
+    }
+}
+
+fn is_wsp(c: char) -> bool {
+    c == ' ' || c == '\t'
+}
+
+/// Returns whether `text` is letters and digits, possibly with inner
+/// hyphens, as required of issuer labels and parameter tags.
+fn is_label(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    match (bytes.first(), bytes.last()) {
+        (Some(first), Some(last)) => {
+            first.is_ascii_alphanumeric()
+                && last.is_ascii_alphanumeric()
+                && bytes
+                    .iter()
+                    .all(|b| b.is_ascii_alphanumeric() || *b == b'-')
+        }
+        _ => false,
+    }
+}
+
+/// Returns the names whose `CAA` record sets must be looked up for `name`,
+/// from `name` itself up to the top level domain. Wildcard names start
+/// from the name below the `*` label.
+///
+/// For more details, see [RFC 8659, Section 3].
+///
+/// [RFC 8659, Section 3]: https://datatracker.ietf.org/doc/html/rfc8659#section-3
+pub fn relevant_names(name: &Domain) -> Vec<Domain> {
+    let mut names = Vec::new();
+    let mut current = match name.is_wildcard() {
+        true => name.parent(),
+        false => Some(name.clone()),
+    };
+    while let Some(name) = current.filter(|name| !name.is_root()) {
+        current = name.parent();
+        names.push(name);
+    }
+    names
+}
+
+/// Certificate request checked against `CAA` records by a certification
+/// authority identified by its issuer domain name.
+///
+/// Besides the checks of [RFC 8659], the `accounturi` and
+/// `validationmethods` parameters of [RFC 8657] are enforced.
+///
+/// # Example
+/// ```
+/// use dns_lib::caa::IssuanceRequest;
+/// use dns_lib::caa::error::CaaError;
+/// use dns_lib::message::KnownQType;
+/// use dns_lib::record::{RData, RecordSet};
+/// use dns_lib::Domain;
+///
+/// let origin = Domain::from_presentation("example.com.").unwrap();
+/// let rdata = RData::from_presentation(
+///     KnownQType::CAA.into(),
+///     &["0", "issue", "\"ca.example.net\""],
+///     &origin,
+/// )
+/// .unwrap();
+/// let rrset = RecordSet {
+///     name: origin,
+///     r_type: KnownQType::CAA.into(),
+///     r_class: dns_lib::message::KnownQClass::IN.into(),
+///     ttl: 3600,
+///     rdatas: vec![rdata],
+/// };
+///
+/// // No record at www.example.com, so example.com is relevant.
+/// let rrsets = [rrset];
+/// assert_eq!(IssuanceRequest::new("ca.example.net").check(&rrsets), Ok(()));
+/// assert_eq!(
+///     IssuanceRequest::new("other.example").check(&rrsets),
+///     Err(CaaError::NotAuthorized)
+/// );
+/// ```
+///
+/// [RFC 8659]: https://datatracker.ietf.org/doc/html/rfc8659
+/// [RFC 8657]: https://datatracker.ietf.org/doc/html/rfc8657
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssuanceRequest {
+    issuer: String,
+    wildcard: bool,
+    account_uri: Option<String>,
+    validation_method: Option<String>,
+}
+
+impl IssuanceRequest {
+    pub fn new(issuer: impl Into<String>) -> Self {
+        IssuanceRequest {
+            issuer: issuer.into(),
+            wildcard: false,
+            account_uri: None,
+            validation_method: None,
+        }
+    }
+
+    /// Marks the request as being for a wildcard certificate, governed by
+    /// `issuewild` properties when there are any.
+    pub fn with_wildcard(mut self, wildcard: bool) -> Self {
+        self.wildcard = wildcard;
+        self
+    }
+
+    pub fn with_account_uri(mut self, account_uri: impl Into<String>) -> Self {
+        self.account_uri = Some(account_uri.into());
+        self
+    }
+
+    pub fn with_validation_method(mut self, method: impl Into<String>) -> Self {
+        self.validation_method = Some(method.into());
+        self
+    }
+
+    /// Checks whether the issuer may issue, given the `CAA` record sets
+    /// found at the [`relevant_names`], in the same order. The first set
+    /// that is not empty is the relevant one; when there is none, any
+    /// issuer may issue.
+    ///
+    /// For more details, see [RFC 8659, Section 4].
+    ///
+    /// [RFC 8659, Section 4]: https://datatracker.ietf.org/doc/html/rfc8659#section-4
+    pub fn check(&self, rrsets: &[RecordSet]) -> Result<(), CaaError> {
+        let relevant = rrsets
+            .iter()
+            .map(|rrset| {
+                rrset
+                    .rdatas
+                    .iter()
+                    .filter_map(|rdata| match rdata {
+                        RData::CAA(caa) => Some(caa),
+                        _ => None,
+                    })
+                    .collect::<Vec<&Caa>>()
+            })
+            .find(|properties| !properties.is_empty());
+        let Some(properties) = relevant else {
+            return Ok(());
+        };
+
+        if let Some(caa) = properties.iter().find(|caa| {
+            caa.is_critical() && !KNOWN_TAGS.iter().any(|t| t.eq_ignore_ascii_case(&caa.tag))
+        }) {
+            return Err(CaaError::UnknownCriticalTag(caa.tag.clone()));
+        }
+
+        let with_tag = |tag: &str| -> Vec<&Caa> {
+            properties
+                .iter()
+                .filter(|caa| caa.tag.eq_ignore_ascii_case(tag))
+                .copied()
+                .collect()
+        };
+        let wildcard_properties = with_tag("issuewild");
+        let properties = match self.wildcard && !wildcard_properties.is_empty() {
+            true => wildcard_properties,
+            false => with_tag("issue"),
+        };
+        if properties.is_empty() {
+            return Ok(());
+        }
+
+        // Malformed values authorize no issuer.
+        let mut error = CaaError::NotAuthorized;
+        for value in properties.iter().filter_map(|caa| {
+            std::str::from_utf8(&caa.value)
+                .ok()
+                .and_then(|value| value.parse::<IssuerValue>().ok())
+        }) {
+            if !value
+                .issuer
+                .as_deref()
+                .is_some_and(|issuer| self.is_issuer(issuer))
+            {
+                continue;
+            }
+            match self.check_parameters(&value) {
+                Ok(()) => return Ok(()),
+                Err(e) => error = e,
+            }
+        }
+        Err(error)
+    }
+
+    fn is_issuer(&self, issuer: &str) -> bool {
+        issuer
+            .trim_end_matches('.')
+            .eq_ignore_ascii_case(self.issuer.trim_end_matches('.'))
+    }
+
+    /// Enforces the parameters of [RFC 8657, Section 3 and 4].
+    ///
+    /// [RFC 8657, Section 3 and 4]: https://datatracker.ietf.org/doc/html/rfc8657#section-3
+    fn check_parameters(&self, value: &IssuerValue) -> Result<(), CaaError> {
+        if let Some(account_uri) = value.parameter("accounturi") {
+            if self.account_uri.as_deref() != Some(account_uri) {
+                return Err(CaaError::AccountMismatch);
+            }
+        }
+        if let Some(methods) = value.parameter("validationmethods") {
+            let allowed = self
+                .validation_method
+                .as_deref()
+                .is_some_and(|method| methods.split(',').any(|m| m == method));
+            if !allowed {
+                return Err(CaaError::ValidationMethodMismatch);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::{KnownQClass, KnownQType};
+    use rstest::rstest;
+
+    fn rrset(name: &str, properties: &[(u8, &str, &str)]) -> RecordSet {
+        RecordSet {
+            name: Domain::from_presentation(name).unwrap(),
+            r_type: KnownQType::CAA.into(),
+            r_class: KnownQClass::IN.into(),
+            ttl: 3600,
+            rdatas: properties
+                .iter()
+                .map(|(flags, tag, value)| {
+                    RData::CAA(Caa {
+                        flags: *flags,
+                        tag: tag.to_string(),
+                        value: value.as_bytes().to_vec(),
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    #[rstest]
+    #[case("ca.example.net", Some("ca.example.net"), &[])]
+    #[case("  ca.example.net ; ", Some("ca.example.net"), &[])]
+    #[case(";", None, &[])]
+    #[case("", None, &[])]
+    #[case(
+        "ca.example.net; account-id = 1;policy=ev",
+        Some("ca.example.net"),
+        &[("account-id", "1"), ("policy", "ev")]
+    )]
+    fn issuer_value_from_str(
+        #[case] text: &str,
+        #[case] issuer: Option<&str>,
+        #[case] parameters: &[(&str, &str)],
+    ) {
+        let value: IssuerValue = text.parse().unwrap();
+        assert_eq!(value.issuer.as_deref(), issuer);
+        let expected: Vec<(String, String)> = parameters
+            .iter()
+            .map(|(t, v)| (t.to_string(), v.to_string()))
+            .collect();
+        assert_eq!(value.parameters, expected);
+    }
+
+    #[rstest]
+    #[case("ca..example")]
+    #[case("-ca.example")]
+    #[case("ca.example; policy")]
+    #[case("ca.example; policy=a b")]
+    #[case("ca.example;; policy=ev")]
+    fn issuer_value_from_str_fails(#[case] text: &str) {
+        assert_eq!(
+            text.parse::<IssuerValue>(),
+            Err(IssuerValueFromStrError(text.to_string()))
+        );
+    }
+
+    #[rstest]
+    #[case("www.example.com.", &["www.example.com.", "example.com.", "com."])]
+    #[case("*.example.com.", &["example.com.", "com."])]
+    #[case(".", &[])]
+    fn relevant_names_climb_to_tld(#[case] name: &str, #[case] expected: &[&str]) {
+        let names: Vec<String> = relevant_names(&Domain::from_presentation(name).unwrap())
+            .iter()
+            .map(Domain::to_fqdn_string)
+            .collect();
+        assert_eq!(names, expected);
+    }
+
+    #[test]
+    fn check_uses_first_non_empty_rrset() {
+        let rrsets = [
+            rrset("www.example.com.", &[]),
+            rrset("example.com.", &[(0, "issue", "ca.example.net")]),
+            rrset("com.", &[(0, "issue", "other.example")]),
+        ];
+        assert_eq!(
+            IssuanceRequest::new("CA.Example.Net.").check(&rrsets),
+            Ok(())
+        );
+        assert_eq!(
+            IssuanceRequest::new("other.example").check(&rrsets),
+            Err(CaaError::NotAuthorized)
+        );
+        assert_eq!(IssuanceRequest::new("anyone.example").check(&[]), Ok(()));
+    }
+
+    #[rstest]
+    #[case(&[(0, "iodef", "mailto:security@example.com")], false, Ok(()))]
+    #[case(&[(0, "issue", ";")], false, Err(CaaError::NotAuthorized))]
+    #[case(&[(0, "issue", "ca..example"), (0, "issue", "ca.example")], false, Ok(()))]
+    #[case(&[(0, "issue", "ca.example"), (0, "issuewild", ";")], false, Ok(()))]
+    #[case(&[(0, "issue", "ca.example"), (0, "issuewild", ";")], true, Err(CaaError::NotAuthorized))]
+    #[case(&[(0, "issue", ";"), (0, "issuewild", "ca.example")], true, Ok(()))]
+    #[case(&[(0, "issue", "ca.example")], true, Ok(()))]
+    #[case(&[(0, "issue", "ca.example"), (0, "tbs", "x")], false, Ok(()))]
+    #[case(
+        &[(0, "issue", "ca.example"), (128, "tbs", "x")],
+        false,
+        Err(CaaError::UnknownCriticalTag("tbs".to_string()))
+    )]
+    #[case(&[(128, "ISSUE", "ca.example")], false, Ok(()))]
+    fn check_properties(
+        #[case] properties: &[(u8, &str, &str)],
+        #[case] wildcard: bool,
+        #[case] expected: Result<(), CaaError>,
+    ) {
+        let rrsets = [rrset("example.com.", properties)];
+        let request = IssuanceRequest::new("ca.example").with_wildcard(wildcard);
+        assert_eq!(request.check(&rrsets), expected);
+    }
+
+    #[rstest]
+    #[case(None, None, Err(CaaError::AccountMismatch))]
+    #[case(
+        Some("https://ca.example/acct/2"),
+        Some("dns-01"),
+        Err(CaaError::AccountMismatch)
+    )]
+    #[case(
+        Some("https://ca.example/acct/1"),
+        None,
+        Err(CaaError::ValidationMethodMismatch)
+    )]
+    #[case(
+        Some("https://ca.example/acct/1"),
+        Some("tls-alpn-01"),
+        Err(CaaError::ValidationMethodMismatch)
+    )]
+    #[case(Some("https://ca.example/acct/1"), Some("http-01"), Ok(()))]
+    fn check_parameters(
+        #[case] account_uri: Option<&str>,
+        #[case] method: Option<&str>,
+        #[case] expected: Result<(), CaaError>,
+    ) {
+        let rrsets = [rrset(
+            "example.com.",
+            &[(
+                0,
+                "issue",
+                "ca.example; accounturi=https://ca.example/acct/1; validationmethods=dns-01,http-01",
+            )],
+        )];
+        let mut request = IssuanceRequest::new("ca.example");
+        if let Some(account_uri) = account_uri {
+            request = request.with_account_uri(account_uri);
+        }
+        if let Some(method) = method {
+            request = request.with_validation_method(method);
+        }
+        assert_eq!(request.check(&rrsets), expected);
+    }
+}
@@ -0,0 +1,115 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Minimal reader of the DER encoding of X.509 certificates, enough to
+//! locate their `SubjectPublicKeyInfo`.
+
+const SEQUENCE: u8 = 0x30;
+const INTEGER: u8 = 0x02;
+/// Context specific, constructed tag `[0]` of the optional version.
+const VERSION: u8 = 0xa0;
+
+/// DER element, with `encoded` spanning its tag, length and content.
+struct Element<'a> {
+    tag: u8,
+    content: &'a [u8],
+    encoded: &'a [u8],
+}
+
+/// Reads the element at the start of `input`, returning it with the bytes
+/// that follow it.
+fn read_element(input: &[u8]) -> Option<(Element<'_>, &[u8])> {
+    let (&tag, rest) = input.split_first()?;
+    let (&first, mut rest) = rest.split_first()?;
+    let len = match first {
+        0..=0x7f => first as usize,
+        0x81..=0x84 => {
+            let count = (first & 0x7f) as usize;
+            let octets = rest.get(..count)?;
+            rest = &rest[count..];
+            octets.iter().fold(0, |len, &b| (len << 8) | b as usize)
+        }
+        _ => return None,
+    };
+    let content = rest.get(..len)?;
+    let after = &rest[len..];
+    let header_len = input.len() - rest.len();
+    let element = Element {
+        tag,
+        content,
+        encoded: &input[..header_len + len],
+    };
+    Some((element, after))
+}
+
+fn expect(input: &[u8], tag: u8) -> Option<(Element<'_>, &[u8])> {
+    read_element(input).filter(|(element, _)| element.tag == tag)
+}
+
+/// Returns the DER encoded `SubjectPublicKeyInfo` of a DER certificate, see
+/// [RFC 5280, Section 4.1].
+///
+/// [RFC 5280, Section 4.1]: https://datatracker.ietf.org/doc/html/rfc5280#section-4.1
+pub(crate) fn subject_public_key_info(certificate: &[u8]) -> Option<&[u8]> {
+    let (certificate, trailing) = expect(certificate, SEQUENCE)?;
+    if !trailing.is_empty() {
+        return None;
+    }
+    let (tbs, _) = expect(certificate.content, SEQUENCE)?;
+
+    let mut rest = tbs.content;
+    if rest.first() == Some(&VERSION) {
+        (_, rest) = read_element(rest)?;
+    }
+    (_, rest) = expect(rest, INTEGER)?;
+    // Signature algorithm, issuer, validity and subject.
+    for _ in 0..4 {
+        (_, rest) = expect(rest, SEQUENCE)?;
+    }
+    expect(rest, SEQUENCE).map(|(spki, _)| spki.encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(&[0x30, 0x03, 0x02, 0x01, 0x05], Some((0x30, 3, 5, 0)))]
+    #[case(&[0x04, 0x81, 0x01, 0xff, 0x00], Some((0x04, 1, 4, 1)))]
+    #[case(&[0x30, 0x05, 0x02], None)]
+    #[case(&[0x30, 0x80], None)]
+    #[case(&[0x30, 0x85, 0, 0, 0, 0, 1], None)]
+    fn read_element_works(
+        #[case] input: &[u8],
+        #[case] expected: Option<(u8, usize, usize, usize)>,
+    ) {
+        let element = read_element(input).map(|(element, rest)| {
+            (
+                element.tag,
+                element.content.len(),
+                element.encoded.len(),
+                rest.len(),
+            )
+        });
+        assert_eq!(element, expected);
+    }
+
+    #[test]
+    fn subject_public_key_info_rejects_malformed_certificates() {
+        assert_eq!(subject_public_key_info(&[]), None);
+        assert_eq!(subject_public_key_info(&[0x30, 0x02, 0x30, 0x00]), None);
+        assert_eq!(subject_public_key_info(&[0x30, 0x00, 0x00]), None);
+    }
+}
@@ -0,0 +1,95 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub struct CertificateUsageTryFromError(pub u8);
+
+impl fmt::Display for CertificateUsageTryFromError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TLSA certificate usage '{}' is not supported", self.0)
+    }
+}
+
+impl Error for CertificateUsageTryFromError {}
+
+#[derive(Debug, PartialEq)]
+pub struct SelectorTryFromError(pub u8);
+
+impl fmt::Display for SelectorTryFromError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TLSA selector '{}' is not supported", self.0)
+    }
+}
+
+impl Error for SelectorTryFromError {}
+
+#[derive(Debug, PartialEq)]
+pub struct MatchingTypeTryFromError(pub u8);
+
+impl fmt::Display for MatchingTypeTryFromError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TLSA matching type '{}' is not supported", self.0)
+    }
+}
+
+impl Error for MatchingTypeTryFromError {}
+
+/// Errors found while matching a certificate chain against `TLSA` records.
+#[derive(Debug, PartialEq)]
+pub enum DaneError {
+    EmptyChain,
+    /// The certificate at the given depth of the chain is not valid DER.
+    InvalidCertificate(usize),
+    /// No record uses a supported usage, selector and matching type, which
+    /// for SMTP means falling back to unauthenticated TLS, see
+    /// [RFC 7672, Section 2.2].
+    ///
+    /// [RFC 7672, Section 2.2]: https://datatracker.ietf.org/doc/html/rfc7672#section-2.2
+    NoUsableRecords,
+    NoMatch,
+}
+
+impl fmt::Display for DaneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::EmptyChain => write!(f, "certificate chain is empty"),
+            Self::InvalidCertificate(depth) => {
+                write!(f, "certificate at depth {} is not valid DER", depth)
+            }
+            Self::NoUsableRecords => write!(f, "no usable TLSA records"),
+            Self::NoMatch => write!(f, "no TLSA record matches the certificate chain"),
+        }
+    }
+}
+
+impl Error for DaneError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(
+        DaneError::InvalidCertificate(1),
+        "certificate at depth 1 is not valid DER"
+    )]
+    #[case(DaneError::NoMatch, "no TLSA record matches the certificate chain")]
+    fn dane_error_display(#[case] err: DaneError, #[case] msg: &str) {
+        assert_eq!(err.to_string(), msg);
+    }
+}
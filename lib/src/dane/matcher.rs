@@ -0,0 +1,286 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dane::certificate::subject_public_key_info;
+use crate::dane::error::DaneError;
+use crate::dane::parameters::{CertificateUsage, MatchingType, Selector};
+use crate::domain::error::TryFromError;
+use crate::domain::Domain;
+use crate::record::rdata::Tlsa;
+use sha2::{Digest, Sha256, Sha512};
+
+/// `TLSA` record that matched a certificate of the chain.
+#[derive(Debug, PartialEq)]
+pub struct TlsaMatch<'a> {
+    pub record: &'a Tlsa,
+    pub usage: CertificateUsage,
+    /// Position in the chain of the matching certificate, the end entity
+    /// certificate being at depth 0.
+    pub depth: usize,
+}
+
+impl TlsaMatch<'_> {
+    /// Checks if the chain must still pass PKIX validation for the match to
+    /// authenticate the server, as with `PKIX-TA(0)` and `PKIX-EE(1)`.
+    pub fn requires_pkix_validation(&self) -> bool {
+        self.usage.requires_pkix()
+    }
+}
+
+/// Returns the owner name of the `TLSA` records of a service, such as
+/// `_25._tcp.mx.example.com.`, see [RFC 6698, Section 3].
+///
+/// # Example
+/// ```
+/// use dns_lib::dane::tlsa_owner;
+/// use dns_lib::domain::Domain;
+///
+/// let host = Domain::from_presentation("mx.example.com.").unwrap();
+/// let owner = tlsa_owner(25, "tcp", &host).unwrap();
+/// assert_eq!(owner.to_string(), "_25._tcp.mx.example.com");
+/// ```
+///
+/// [RFC 6698, Section 3]: https://datatracker.ietf.org/doc/html/rfc6698#section-3
+pub fn tlsa_owner(port: u16, protocol: &str, host: &Domain) -> Result<Domain, TryFromError> {
+    host.prepend_label(format!("_{}", protocol).as_bytes())?
+        .prepend_label(format!("_{}", port).as_bytes())
+}
+
+/// Finds a `TLSA` record matching the DER certificate `chain`, given from
+/// the end entity certificate up to the trust anchor as sent by the server.
+///
+/// `DANE-EE(3)` and `PKIX-EE(1)` records are compared with the end entity
+/// certificate only, `DANE-TA(2)` and `PKIX-TA(0)` records with the other
+/// certificates. Records with parameters that are not supported are
+/// ignored, and so are `SHA2-256(1)` records whenever there are
+/// `SHA2-512(2)` records with the same usage and selector, following the
+/// digest algorithm agility of [RFC 7671, Section 9].
+///
+/// Checking that the chain is signed up to a matching `DANE-TA(2)` anchor,
+/// the name of the server and, for `PKIX-TA(0)` and `PKIX-EE(1)`, PKIX
+/// validation, are left to the TLS implementation, see
+/// [RFC 7671, Section 5].
+///
+/// [RFC 7671, Section 5]: https://datatracker.ietf.org/doc/html/rfc7671#section-5
+/// [RFC 7671, Section 9]: https://datatracker.ietf.org/doc/html/rfc7671#section-9
+pub fn match_chain<'a, C: AsRef<[u8]>>(
+    records: &'a [Tlsa],
+    chain: &[C],
+) -> Result<TlsaMatch<'a>, DaneError> {
+    if chain.is_empty() {
+        return Err(DaneError::EmptyChain);
+    }
+
+    let usable: Vec<(&Tlsa, CertificateUsage, Selector, MatchingType)> = records
+        .iter()
+        .filter_map(|record| {
+            let usage = CertificateUsage::try_from(record.certificate_usage).ok()?;
+            let selector = Selector::try_from(record.selector).ok()?;
+            let matching_type = MatchingType::try_from(record.matching_type).ok()?;
+            Some((record, usage, selector, matching_type))
+        })
+        .collect();
+    if usable.is_empty() {
+        return Err(DaneError::NoUsableRecords);
+    }
+
+    let strongest = |usage: CertificateUsage, selector: Selector| {
+        usable
+            .iter()
+            .filter(|(_, u, s, m)| *u == usage && *s == selector && m.is_digest())
+            .map(|(_, _, _, m)| *m)
+            .max()
+    };
+
+    for (depth, certificate) in chain.iter().enumerate() {
+        let certificate = certificate.as_ref();
+        let spki =
+            subject_public_key_info(certificate).ok_or(DaneError::InvalidCertificate(depth))?;
+
+        for &(record, usage, selector, matching_type) in &usable {
+            if usage.is_end_entity() != (depth == 0) {
+                continue;
+            }
+            if matching_type.is_digest() && strongest(usage, selector) != Some(matching_type) {
+                continue;
+            }
+            let selected = match selector {
+                Selector::FullCertificate => certificate,
+                Selector::SubjectPublicKeyInfo => spki,
+            };
+            let matches = match matching_type {
+                MatchingType::Full => selected == record.certificate_association_data,
+                MatchingType::Sha256 => {
+                    Sha256::digest(selected)[..] == record.certificate_association_data
+                }
+                MatchingType::Sha512 => {
+                    Sha512::digest(selected)[..] == record.certificate_association_data
+                }
+            };
+            if matches {
+                return Ok(TlsaMatch {
+                    record,
+                    usage,
+                    depth,
+                });
+            }
+        }
+    }
+    Err(DaneError::NoMatch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_encoding::{BASE64, HEXLOWER};
+    use rstest::rstest;
+
+    const CA: &str = "MIIBezCCASGgAwIBAgIUfhlhiZ3FnrC0dQ0ZxBPX6745NLYwCgYIKoZIzj0EAwIwEjEQMA4GA1UEAwwHVGVzdCBDQTAgFw0yNjEwMTgxNTMzMjBaGA8yMTI2MDkyNDE1MzMyMFowEjEQMA4GA1UEAwwHVGVzdCBDQTBZMBMGByqGSM49AgEGCCqGSM49AwEHA0IABAo81OoJd/jBCTvLY6FZ4O4hmlLSQ0gozDqs2xdyI7N2UohqOpK3vd8OF9UpBSM+fNqlwl0UTToPucWv7vUg0BCjUzBRMB0GA1UdDgQWBBRJMuKF609QnQ7e7hqYyn+mH96QPjAfBgNVHSMEGDAWgBRJMuKF609QnQ7e7hqYyn+mH96QPjAPBgNVHRMBAf8EBTADAQH/MAoGCCqGSM49BAMCA0gAMEUCICxufU6VRSjGHw7OTnBkUORna4Rq1i+d2OU3pjIANbscAiEAwlPQJtY7FZhT8eES7ikvUSpKaGe6WNreJb2Ssd4p3CU=";
+    const EE: &str = "MIIBcjCCARegAwIBAgIUAZKGD/qgEXh1pPc/x5UEkdIvjmEwCgYIKoZIzj0EAwIwEjEQMA4GA1UEAwwHVGVzdCBDQTAgFw0yNjEwMTgxNTMzMjBaGA8yMTI2MDkyNDE1MzMyMFowGTEXMBUGA1UEAwwObXguZXhhbXBsZS5jb20wWTATBgcqhkjOPQIBBggqhkjOPQMBBwNCAAQym12K/IRv2AMOlP+uFoSkMYVi9JTEw9JPPX/8f81vxoRXglzBaJN76mT6iqxYSjkiGluwoUI1XeAkw0avScono0IwQDAdBgNVHQ4EFgQULta/bd1uPZgumvBM5PuNFT0OjgYwHwYDVR0jBBgwFoAUSTLihetPUJ0O3u4amMp/ph/ekD4wCgYIKoZIzj0EAwIDSQAwRgIhAOhWlS5OPWYUykH2WO1z+X9T4Yd91oCcI2aS7wS22BIRAiEA+dO/LDo6bLu86qmvddceDGzD0H/Z1CAOGHRLSUBZhTI=";
+    const CA_SPKI: &str = "3059301306072a8648ce3d020106082a8648ce3d030107034200040a3cd4ea0977f8c1093bcb63a159e0ee219a52d2434828cc3aacdb177223b37652886a3a92b7bddf0e17d52905233e7cdaa5c25d144d3a0fb9c5afeef520d010";
+
+    fn chain() -> Vec<Vec<u8>> {
+        vec![
+            BASE64.decode(EE.as_bytes()).unwrap(),
+            BASE64.decode(CA.as_bytes()).unwrap(),
+        ]
+    }
+
+    fn tlsa(usage: u8, selector: u8, matching_type: u8, data: &str) -> Tlsa {
+        Tlsa {
+            certificate_usage: usage,
+            selector,
+            matching_type,
+            certificate_association_data: HEXLOWER.decode(data.as_bytes()).unwrap(),
+        }
+    }
+
+    #[test]
+    fn subject_public_key_info_works() {
+        let chain = chain();
+        assert_eq!(
+            subject_public_key_info(&chain[1]),
+            Some(&HEXLOWER.decode(CA_SPKI.as_bytes()).unwrap()[..])
+        );
+    }
+
+    #[rstest]
+    #[case(
+        tlsa(
+            3,
+            1,
+            1,
+            "af77b4d1c41d1129b2237c10077d23b00f878628a34bc1d201c4636c4d781f98"
+        ),
+        CertificateUsage::DaneEe,
+        0
+    )]
+    #[case(
+        tlsa(
+            3,
+            0,
+            1,
+            "0a0e5368219fa4ae12f404a942d36594339a3fd1c32ce384c18abbc875ab68e4"
+        ),
+        CertificateUsage::DaneEe,
+        0
+    )]
+    #[case(tlsa(1, 1, 2, "5976c0d00c0394b7684f2a35d82a1196843567c3f11fa58662788398cdaf90aefda3735276085e1c0e92aecf65982d56a229a99e4d71e5ef5ab96c408ca4c9eb"), CertificateUsage::PkixEe, 0)]
+    #[case(
+        tlsa(
+            2,
+            1,
+            1,
+            "a55e1207302af94e93a65c1f89006f1cf979a6947cc554404eec9ec77a84d09a"
+        ),
+        CertificateUsage::DaneTa,
+        1
+    )]
+    #[case(tlsa(2, 1, 0, CA_SPKI), CertificateUsage::DaneTa, 1)]
+    #[case(
+        tlsa(
+            0,
+            0,
+            1,
+            "d7107052a5cefe966a1ffe3659564e8c30f952e4391361be8cd32365287af743"
+        ),
+        CertificateUsage::PkixTa,
+        1
+    )]
+    fn match_chain_works(
+        #[case] record: Tlsa,
+        #[case] usage: CertificateUsage,
+        #[case] depth: usize,
+    ) {
+        let records = [tlsa(3, 1, 1, &"00".repeat(32)), record.clone()];
+        let found = match_chain(&records, &chain()).unwrap();
+        assert_eq!(
+            (found.record, found.usage, found.depth),
+            (&record, usage, depth)
+        );
+        assert_eq!(found.requires_pkix_validation(), usage.requires_pkix());
+    }
+
+    #[rstest]
+    // The end entity key does not match trust anchor usages, and the other
+    // way around.
+    #[case(
+        vec![tlsa(2, 1, 1, "af77b4d1c41d1129b2237c10077d23b00f878628a34bc1d201c4636c4d781f98")],
+        DaneError::NoMatch
+    )]
+    #[case(
+        vec![tlsa(3, 1, 1, "a55e1207302af94e93a65c1f89006f1cf979a6947cc554404eec9ec77a84d09a")],
+        DaneError::NoMatch
+    )]
+    // A matching SHA2-256 record is ignored next to a SHA2-512 one.
+    #[case(
+        vec![
+            tlsa(3, 1, 1, "af77b4d1c41d1129b2237c10077d23b00f878628a34bc1d201c4636c4d781f98"),
+            tlsa(3, 1, 2, &"00".repeat(64)),
+        ],
+        DaneError::NoMatch
+    )]
+    #[case(vec![tlsa(4, 1, 1, "00"), tlsa(3, 2, 1, "00"), tlsa(3, 1, 3, "00")], DaneError::NoUsableRecords)]
+    #[case(vec![], DaneError::NoUsableRecords)]
+    fn match_chain_fails(#[case] records: Vec<Tlsa>, #[case] expected: DaneError) {
+        assert_eq!(match_chain(&records, &chain()), Err(expected));
+    }
+
+    #[test]
+    fn match_chain_rejects_invalid_chains() {
+        let records = [tlsa(3, 1, 1, &"00".repeat(32))];
+        let empty: [&[u8]; 0] = [];
+        assert_eq!(match_chain(&records, &empty), Err(DaneError::EmptyChain));
+        assert_eq!(
+            match_chain(&records, &[&[0x30, 0x00][..]]),
+            Err(DaneError::InvalidCertificate(0))
+        );
+    }
+
+    #[rstest]
+    #[case(443, "tcp", "www.example.com.", "_443._tcp.www.example.com")]
+    #[case(853, "udp", "example.", "_853._udp.example")]
+    fn tlsa_owner_works(
+        #[case] port: u16,
+        #[case] protocol: &str,
+        #[case] host: &str,
+        #[case] expected: &str,
+    ) {
+        let host = Domain::from_presentation(host).unwrap();
+        assert_eq!(
+            tlsa_owner(port, protocol, &host).unwrap().to_string(),
+            expected
+        );
+    }
+}
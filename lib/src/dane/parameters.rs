@@ -0,0 +1,149 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dane::error::{
+    CertificateUsageTryFromError, MatchingTypeTryFromError, SelectorTryFromError,
+};
+
+/// Certificate usage of a `TLSA` record, telling which certificate of the
+/// chain must match and whether PKIX validation is still required.
+///
+/// For more details, see [RFC 7218, Section 2.1] and [RFC 7671, Section 5].
+///
+/// [RFC 7218, Section 2.1]: https://datatracker.ietf.org/doc/html/rfc7218#section-2.1
+/// [RFC 7671, Section 5]: https://datatracker.ietf.org/doc/html/rfc7671#section-5
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum CertificateUsage {
+    PkixTa = 0,
+    PkixEe = 1,
+    DaneTa = 2,
+    DaneEe = 3,
+}
+
+impl CertificateUsage {
+    /// Checks if the usage matches the end entity certificate rather than
+    /// a trust anchor.
+    pub fn is_end_entity(&self) -> bool {
+        matches!(self, Self::PkixEe | Self::DaneEe)
+    }
+
+    /// Checks if the chain must also pass PKIX validation against the
+    /// trust anchors of the client.
+    pub fn requires_pkix(&self) -> bool {
+        matches!(self, Self::PkixTa | Self::PkixEe)
+    }
+}
+
+impl TryFrom<u8> for CertificateUsage {
+    type Error = CertificateUsageTryFromError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::PkixTa),
+            1 => Ok(Self::PkixEe),
+            2 => Ok(Self::DaneTa),
+            3 => Ok(Self::DaneEe),
+            unsupported => Err(CertificateUsageTryFromError(unsupported)),
+        }
+    }
+}
+
+/// Part of the certificate matched by a `TLSA` record, see
+/// [RFC 6698, Section 2.1.2].
+///
+/// [RFC 6698, Section 2.1.2]: https://datatracker.ietf.org/doc/html/rfc6698#section-2.1.2
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Selector {
+    FullCertificate = 0,
+    SubjectPublicKeyInfo = 1,
+}
+
+impl TryFrom<u8> for Selector {
+    type Error = SelectorTryFromError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::FullCertificate),
+            1 => Ok(Self::SubjectPublicKeyInfo),
+            unsupported => Err(SelectorTryFromError(unsupported)),
+        }
+    }
+}
+
+/// How the selected data is compared to the certificate association data,
+/// see [RFC 6698, Section 2.1.3].
+///
+/// [RFC 6698, Section 2.1.3]: https://datatracker.ietf.org/doc/html/rfc6698#section-2.1.3
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum MatchingType {
+    Full = 0,
+    Sha256 = 1,
+    Sha512 = 2,
+}
+
+impl MatchingType {
+    pub fn is_digest(&self) -> bool {
+        !matches!(self, Self::Full)
+    }
+}
+
+impl TryFrom<u8> for MatchingType {
+    type Error = MatchingTypeTryFromError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Full),
+            1 => Ok(Self::Sha256),
+            2 => Ok(Self::Sha512),
+            unsupported => Err(MatchingTypeTryFromError(unsupported)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, Ok(CertificateUsage::PkixTa))]
+    #[case(3, Ok(CertificateUsage::DaneEe))]
+    #[case(4, Err(CertificateUsageTryFromError(4)))]
+    fn certificate_usage_try_from(
+        #[case] value: u8,
+        #[case] expected: Result<CertificateUsage, CertificateUsageTryFromError>,
+    ) {
+        assert_eq!(CertificateUsage::try_from(value), expected);
+    }
+
+    #[rstest]
+    #[case(1, Ok(Selector::SubjectPublicKeyInfo))]
+    #[case(2, Err(SelectorTryFromError(2)))]
+    fn selector_try_from(
+        #[case] value: u8,
+        #[case] expected: Result<Selector, SelectorTryFromError>,
+    ) {
+        assert_eq!(Selector::try_from(value), expected);
+    }
+
+    #[rstest]
+    #[case(2, Ok(MatchingType::Sha512))]
+    #[case(255, Err(MatchingTypeTryFromError(255)))]
+    fn matching_type_try_from(
+        #[case] value: u8,
+        #[case] expected: Result<MatchingType, MatchingTypeTryFromError>,
+    ) {
+        assert_eq!(MatchingType::try_from(value), expected);
+    }
+}
@@ -0,0 +1,118 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::ddds::error::E164FromStrError;
+use crate::domain::Domain;
+use std::fmt;
+use std::str::FromStr;
+
+/// Telephone number in the international E.164 format, which ENUM uses as
+/// the application unique string of its DDDS application.
+///
+/// Spaces, dashes, dots and parentheses separating the digits are ignored.
+///
+/// # Example
+/// ```
+/// use dns_lib::ddds::E164Number;
+///
+/// let number: E164Number = "+44 (1632) 960-083".parse().unwrap();
+/// assert_eq!(number.to_string(), "+441632960083");
+/// assert_eq!(
+///     number.to_domain().to_string(),
+///     "3.8.0.0.6.9.2.3.6.1.4.4.e164.arpa"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct E164Number {
+    digits: String,
+}
+
+impl E164Number {
+    /// Maximum number of digits of a number, country code included.
+    pub const MAX_DIGITS: usize = 15;
+
+    pub fn digits(&self) -> &str {
+        &self.digits
+    }
+
+    /// Returns the domain name where the `NAPTR` records of the number are
+    /// found: its digits in reverse order, each in its own label, under
+    /// `e164.arpa`.
+    ///
+    /// For more details, see [RFC 6116, Section 2.4].
+    ///
+    /// [RFC 6116, Section 2.4]: https://datatracker.ietf.org/doc/html/rfc6116#section-2.4
+    pub fn to_domain(&self) -> Domain {
+        let mut domain = Domain::from_presentation("e164.arpa.").unwrap();
+        for digit in self.digits.bytes() {
+            // At most 15 one octet labels under e164.arpa always fit.
+            domain = domain.prepend_label(&[digit]).unwrap();
+        }
+        domain
+    }
+}
+
+impl FromStr for E164Number {
+    type Err = E164FromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rest = s.strip_prefix('+').ok_or(E164FromStrError::MissingPlus)?;
+        let mut digits = String::with_capacity(rest.len());
+        for c in rest.chars() {
+            match c {
+                '0'..='9' => digits.push(c),
+                ' ' | '-' | '.' | '(' | ')' => {}
+                c => return Err(E164FromStrError::InvalidCharacter(c)),
+            }
+        }
+        if digits.is_empty() || digits.len() > Self::MAX_DIGITS {
+            return Err(E164FromStrError::InvalidLength(digits.len()));
+        }
+        if digits.starts_with('0') {
+            return Err(E164FromStrError::LeadingZero);
+        }
+        Ok(E164Number { digits })
+    }
+}
+
+impl fmt::Display for E164Number {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "+{}", self.digits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("+1-555-0100", "0.0.1.0.5.5.5.1.e164.arpa")]
+    #[case("+4", "4.e164.arpa")]
+    #[case("+123456789012345", "5.4.3.2.1.0.9.8.7.6.5.4.3.2.1.e164.arpa")]
+    fn e164_number_to_domain(#[case] number: &str, #[case] expected: &str) {
+        let number: E164Number = number.parse().unwrap();
+        assert_eq!(number.to_domain().to_string(), expected);
+    }
+
+    #[rstest]
+    #[case("441632960083", E164FromStrError::MissingPlus)]
+    #[case("+44 1632 96008x", E164FromStrError::InvalidCharacter('x'))]
+    #[case("+", E164FromStrError::InvalidLength(0))]
+    #[case("+1234567890123456", E164FromStrError::InvalidLength(16))]
+    #[case("+0123", E164FromStrError::LeadingZero)]
+    fn e164_number_from_str_fails(#[case] number: &str, #[case] expected: E164FromStrError) {
+        assert_eq!(number.parse::<E164Number>(), Err(expected));
+    }
+}
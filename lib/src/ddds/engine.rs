@@ -0,0 +1,319 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::ddds::error::DddsError;
+use crate::ddds::substitution::SubstitutionExpression;
+use crate::domain::Domain;
+use crate::record::rdata::Naptr;
+
+/// Result of the terminal rule that ends a DDDS resolution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DddsOutput {
+    /// The `S` flag: the next lookup is for `SRV` records at the name.
+    Srv(Domain),
+    /// The `A` flag: the next lookup is for address records at the name.
+    Address(Domain),
+    /// The `U` flag: the output of the regular expression is a URI.
+    Uri(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DddsMatch {
+    /// Terminal `NAPTR` rule that produced the output.
+    pub rule: Naptr,
+    pub output: DddsOutput,
+}
+
+/// Dynamic Delegation Discovery System engine running the `NAPTR` rules of
+/// an application, looked up with a caller provided function.
+///
+/// Starting from the key produced by the first well known rule of the
+/// application, the rules found at the key are sorted by order and
+/// preference. The first one with supported flags and accepted services
+/// whose rewrite applies to the application unique string is used: its
+/// output becomes the next key if it has no flags, or ends the resolution
+/// for the `S`, `A` and `U` flags. Rules with a higher order are thus only
+/// considered when none with a lower order applies.
+///
+/// For more details, see [RFC 3402, Section 3] and [RFC 3403, Section 4].
+///
+/// # Example
+/// ```
+/// use dns_lib::ddds::{Ddds, DddsOutput, E164Number};
+/// use dns_lib::domain::Domain;
+/// use dns_lib::record::rdata::Naptr;
+///
+/// let number: E164Number = "+441632960083".parse().unwrap();
+/// let rule = Naptr {
+///     order: 10,
+///     preference: 100,
+///     flags: "u".parse().unwrap(),
+///     services: "E2U+sip".parse().unwrap(),
+///     regexp: "!^.*$!sip:info@example.com!".parse().unwrap(),
+///     replacement: Domain::from_presentation(".").unwrap(),
+/// };
+///
+/// let mut ddds = Ddds::new(|key: &Domain| match *key == number.to_domain() {
+///     true => vec![rule.clone()],
+///     false => vec![],
+/// });
+/// let found = ddds
+///     .resolve(&number.to_string(), &number.to_domain(), |services| {
+///         services.eq_ignore_ascii_case("E2U+sip")
+///     })
+///     .unwrap();
+/// assert_eq!(found.output, DddsOutput::Uri("sip:info@example.com".to_string()));
+/// ```
+///
+/// [RFC 3402, Section 3]: https://datatracker.ietf.org/doc/html/rfc3402#section-3
+/// [RFC 3403, Section 4]: https://datatracker.ietf.org/doc/html/rfc3403#section-4
+pub struct Ddds<F> {
+    lookup: F,
+    max_rewrites: usize,
+}
+
+impl<F> Ddds<F>
+where
+    F: FnMut(&Domain) -> Vec<Naptr>,
+{
+    /// Default number of non-terminal rules followed before giving up,
+    /// which breaks loops between rules.
+    pub const DEFAULT_MAX_REWRITES: usize = 16;
+
+    pub fn new(lookup: F) -> Self {
+        Ddds {
+            lookup,
+            max_rewrites: Self::DEFAULT_MAX_REWRITES,
+        }
+    }
+
+    pub fn with_max_rewrites(mut self, max_rewrites: usize) -> Self {
+        self.max_rewrites = max_rewrites;
+        self
+    }
+
+    /// Runs the rules for the application unique string `aus`, starting at
+    /// `key`. Terminal rules are only used if `services` accepts their
+    /// services field; non-terminal rules are also used when it is empty.
+    pub fn resolve<S>(
+        &mut self,
+        aus: &str,
+        key: &Domain,
+        services: S,
+    ) -> Result<DddsMatch, DddsError>
+    where
+        S: Fn(&str) -> bool,
+    {
+        let mut key = key.clone();
+        for _ in 0..=self.max_rewrites {
+            let mut rules = (self.lookup)(&key);
+            rules.sort_by_key(|rule| (rule.order, rule.preference));
+
+            let applied = rules.into_iter().find_map(|rule| {
+                let flag = Flag::from_rule(&rule)?;
+                let field = String::from_utf8_lossy(rule.services.as_bytes());
+                let accepted = (flag == Flag::None && field.is_empty()) || services(&field);
+                if !accepted {
+                    return None;
+                }
+                let output = rewrite(&rule, flag, aus)?;
+                Some((flag, rule, output))
+            });
+            let Some((flag, rule, output)) = applied else {
+                return Err(DddsError::NoMatch);
+            };
+            let output = match flag {
+                Flag::Uri => DddsOutput::Uri(output),
+                Flag::Srv => DddsOutput::Srv(parse_key(&output)?),
+                Flag::Address => DddsOutput::Address(parse_key(&output)?),
+                Flag::None => {
+                    key = parse_key(&output)?;
+                    continue;
+                }
+            };
+            return Ok(DddsMatch { rule, output });
+        }
+        Err(DddsError::TooManyRewrites)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Flag {
+    None,
+    Srv,
+    Address,
+    Uri,
+}
+
+impl Flag {
+    /// Returns the flag of `rule`, or `None` if the rule must be ignored
+    /// because its flags are unknown.
+    fn from_rule(rule: &Naptr) -> Option<Flag> {
+        match rule.flags.as_bytes() {
+            [] => Some(Flag::None),
+            [b's' | b'S'] => Some(Flag::Srv),
+            [b'a' | b'A'] => Some(Flag::Address),
+            [b'u' | b'U'] => Some(Flag::Uri),
+            _ => None,
+        }
+    }
+}
+
+/// Rewrites `aus` with the regular expression of `rule`, or returns its
+/// replacement. Rules with both, with an invalid expression, or with the
+/// `U` flag and no expression do not apply.
+fn rewrite(rule: &Naptr, flag: Flag, aus: &str) -> Option<String> {
+    match (rule.regexp.is_empty(), rule.replacement.is_root()) {
+        (false, true) => std::str::from_utf8(rule.regexp.as_bytes())
+            .ok()?
+            .parse::<SubstitutionExpression>()
+            .ok()?
+            .apply(aus),
+        (true, false) if flag != Flag::Uri => Some(rule.replacement.to_fqdn_string()),
+        _ => None,
+    }
+}
+
+fn parse_key(text: &str) -> Result<Domain, DddsError> {
+    Domain::from_presentation(text).map_err(|_| DddsError::InvalidKey(text.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::rdata::CharacterString;
+    use std::collections::HashMap;
+
+    fn naptr(
+        order: u16,
+        preference: u16,
+        flags: &str,
+        services: &str,
+        regexp: &str,
+        replacement: &str,
+    ) -> Naptr {
+        Naptr {
+            order,
+            preference,
+            flags: CharacterString::new(flags).unwrap(),
+            services: CharacterString::new(services).unwrap(),
+            regexp: CharacterString::new(regexp).unwrap(),
+            replacement: Domain::from_presentation(replacement).unwrap(),
+        }
+    }
+
+    fn name(text: &str) -> Domain {
+        Domain::from_presentation(text).unwrap()
+    }
+
+    /// Rules of the CID URN example of RFC 3403, Section 6.2.
+    fn cid_rules() -> HashMap<Domain, Vec<Naptr>> {
+        HashMap::from([
+            (
+                name("cid.urn.arpa."),
+                vec![naptr(
+                    100,
+                    10,
+                    "",
+                    "",
+                    "!^urn:cid:.+@([^\\.]+\\.)(.*)$!\\2!i",
+                    ".",
+                )],
+            ),
+            (
+                name("example.com."),
+                vec![
+                    naptr(100, 50, "a", "z3950+N2L+N2C", "", "cidserver.example.com."),
+                    naptr(100, 50, "a", "rcds+N2C", "", "cidserver.example.com."),
+                    naptr(100, 50, "s", "http+N2L+N2C+N2R", "", "www.example.com."),
+                ],
+            ),
+        ])
+    }
+
+    #[test]
+    fn ddds_resolve_follows_non_terminal_rules() {
+        let rules = cid_rules();
+        let mut ddds = Ddds::new(|key: &Domain| rules.get(key).cloned().unwrap_or_default());
+        let aus = "urn:cid:199606121851.1@bar.example.com";
+
+        let found = ddds
+            .resolve(aus, &name("cid.urn.arpa."), |services| {
+                services.starts_with("http+")
+            })
+            .unwrap();
+        assert_eq!(found.output, DddsOutput::Srv(name("www.example.com.")));
+        assert_eq!(found.rule, rules[&name("example.com.")][2]);
+
+        let found = ddds
+            .resolve(aus, &name("cid.urn.arpa."), |services| {
+                services.contains("N2C")
+            })
+            .unwrap();
+        assert_eq!(
+            found.output,
+            DddsOutput::Address(name("cidserver.example.com."))
+        );
+
+        assert_eq!(
+            ddds.resolve(aus, &name("cid.urn.arpa."), |services| services == "sip"),
+            Err(DddsError::NoMatch)
+        );
+    }
+
+    #[test]
+    fn ddds_resolve_sorts_by_order_and_preference() {
+        let key = name("3.8.0.0.6.9.2.3.6.1.4.4.e164.arpa.");
+        let rules = vec![
+            naptr(20, 10, "u", "E2U+sip", "!^.*$!sip:third@example.com!", "."),
+            naptr(10, 20, "u", "E2U+sip", "!^.*$!sip:second@example.com!", "."),
+            // Does not match the string, unknown flags, and a replacement
+            // with the U flag.
+            naptr(10, 5, "u", "E2U+sip", "!^\\+1!sip:us@example.com!", "."),
+            naptr(10, 5, "x", "E2U+sip", "!^.*$!sip:unknown@example.com!", "."),
+            naptr(10, 5, "u", "E2U+sip", "", "example.com."),
+            naptr(
+                10,
+                10,
+                "U",
+                "E2U+sip",
+                "!^\\+(.*)$!sip:\\1@example.com!",
+                ".",
+            ),
+        ];
+        let mut ddds = Ddds::new(|_: &Domain| rules.clone());
+        let found = ddds.resolve("+441632960083", &key, |_| true).unwrap();
+        assert_eq!(
+            found.output,
+            DddsOutput::Uri("sip:441632960083@example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn ddds_resolve_fails() {
+        let looping = vec![naptr(10, 10, "", "", "", "loop.example.")];
+        let mut ddds = Ddds::new(|_: &Domain| looping.clone()).with_max_rewrites(3);
+        assert_eq!(
+            ddds.resolve("x", &name("loop.example."), |_| true),
+            Err(DddsError::TooManyRewrites)
+        );
+
+        let invalid = vec![naptr(10, 10, "", "", "!^.*$!a..b!", ".")];
+        let mut ddds = Ddds::new(|_: &Domain| invalid.clone());
+        assert_eq!(
+            ddds.resolve("x", &name("example."), |_| true),
+            Err(DddsError::InvalidKey("a..b".to_string()))
+        );
+    }
+}
@@ -0,0 +1,104 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error;
+use std::fmt;
+
+/// Errors found while parsing the `regexp` field of a `NAPTR` record.
+#[derive(Debug, PartialEq)]
+pub enum SubstitutionFromStrError {
+    InvalidDelimiter(char),
+    /// The expression does not have three delimiters.
+    Unterminated,
+    InvalidFlags(String),
+    InvalidRegex(String),
+    /// The replacement refers to a group the expression does not have.
+    InvalidBackref(usize),
+}
+
+impl fmt::Display for SubstitutionFromStrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidDelimiter(c) => write!(f, "'{}' cannot delimit a substitution", c),
+            Self::Unterminated => write!(f, "unterminated substitution expression"),
+            Self::InvalidFlags(flags) => write!(f, "invalid substitution flags '{}'", flags),
+            Self::InvalidRegex(e) => write!(f, "invalid regular expression: {}", e),
+            Self::InvalidBackref(group) => write!(f, "no group {} to refer to", group),
+        }
+    }
+}
+
+impl Error for SubstitutionFromStrError {}
+
+#[derive(Debug, PartialEq)]
+pub enum E164FromStrError {
+    MissingPlus,
+    InvalidCharacter(char),
+    /// The number has no digits or more than 15.
+    InvalidLength(usize),
+    LeadingZero,
+}
+
+impl fmt::Display for E164FromStrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingPlus => write!(f, "E.164 number does not start with '+'"),
+            Self::InvalidCharacter(c) => write!(f, "invalid character '{}' in E.164 number", c),
+            Self::InvalidLength(len) => {
+                write!(f, "E.164 number has {} digits instead of 1 to 15", len)
+            }
+            Self::LeadingZero => write!(f, "E.164 number starts with 0"),
+        }
+    }
+}
+
+impl Error for E164FromStrError {}
+
+/// Reasons why the rules of a DDDS application do not produce a result.
+#[derive(Debug, PartialEq)]
+pub enum DddsError {
+    /// No rule applies to the string in the order being considered.
+    NoMatch,
+    TooManyRewrites,
+    /// A rule rewrote the string into something that is not a domain name.
+    InvalidKey(String),
+}
+
+impl fmt::Display for DddsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoMatch => write!(f, "no NAPTR rule applies"),
+            Self::TooManyRewrites => write!(f, "too many non-terminal NAPTR rules"),
+            Self::InvalidKey(key) => write!(f, "rewritten key '{}' is not a domain name", key),
+        }
+    }
+}
+
+impl Error for DddsError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(DddsError::NoMatch, "no NAPTR rule applies")]
+    #[case(
+        DddsError::InvalidKey("a..b".to_string()),
+        "rewritten key 'a..b' is not a domain name"
+    )]
+    fn ddds_error_display(#[case] err: DddsError, #[case] msg: &str) {
+        assert_eq!(err.to_string(), msg);
+    }
+}
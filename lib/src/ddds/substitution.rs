@@ -0,0 +1,210 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::ddds::error::SubstitutionFromStrError;
+use regex::{Regex, RegexBuilder};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Backref(usize),
+}
+
+/// Sed-like substitution expression found in the `regexp` field of a
+/// `NAPTR` record, such as `!^.*$!sip:info@example.com!`.
+///
+/// Any character other than a digit, a backslash or the `i` flag may
+/// delimit the extended regular expression and the replacement, in which
+/// it is escaped with a backslash. The replacement refers to the groups of
+/// the expression with `\1` to `\9`.
+///
+/// For more details, see [RFC 3402, Section 3.2].
+///
+/// # Example
+/// ```
+/// use dns_lib::ddds::SubstitutionExpression;
+///
+/// let expression: SubstitutionExpression = "!^\\+(.*)$!sip:\\1@example.com!".parse().unwrap();
+/// assert_eq!(
+///     expression.apply("+441632960083").as_deref(),
+///     Some("sip:441632960083@example.com")
+/// );
+/// assert_eq!(expression.apply("441632960083"), None);
+/// ```
+///
+/// [RFC 3402, Section 3.2]: https://datatracker.ietf.org/doc/html/rfc3402#section-3.2
+#[derive(Debug, Clone)]
+pub struct SubstitutionExpression {
+    regex: Regex,
+    replacement: Vec<Part>,
+}
+
+impl SubstitutionExpression {
+    /// Applies the expression to the first match found in `input`, leaving
+    /// the text around it untouched. Returns `None` if nothing matches.
+    pub fn apply(&self, input: &str) -> Option<String> {
+        let captures = self.regex.captures(input)?;
+        let matched = captures.get(0)?;
+        let mut output = input[..matched.start()].to_string();
+        for part in &self.replacement {
+            match part {
+                Part::Literal(text) => output.push_str(text),
+                Part::Backref(group) => {
+                    output.push_str(captures.get(*group).map_or("", |m| m.as_str()))
+                }
+            }
+        }
+        output.push_str(&input[matched.end()..]);
+        Some(output)
+    }
+}
+
+impl FromStr for SubstitutionExpression {
+    type Err = SubstitutionFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let delimiter = chars.next().ok_or(SubstitutionFromStrError::Unterminated)?;
+        if delimiter.is_ascii_digit() || delimiter == '\\' || delimiter == 'i' {
+            return Err(SubstitutionFromStrError::InvalidDelimiter(delimiter));
+        }
+
+        // Splits the rest of the expression at the unescaped delimiters,
+        // keeping the escapes for the regular expression and the
+        // replacement to resolve.
+        let mut fields = vec![String::new()];
+        while let Some(c) = chars.next() {
+            let in_flags = fields.len() == 3;
+            let field = fields.last_mut().unwrap();
+            match c {
+                '\\' => match chars.next() {
+                    Some(escaped) => {
+                        field.push('\\');
+                        field.push(escaped);
+                    }
+                    None => return Err(SubstitutionFromStrError::Unterminated),
+                },
+                c if c == delimiter && !in_flags => fields.push(String::new()),
+                c => field.push(c),
+            }
+        }
+        let [ere, replacement, flags] =
+            <[String; 3]>::try_from(fields).map_err(|_| SubstitutionFromStrError::Unterminated)?;
+        let case_insensitive = match flags.as_str() {
+            "" => false,
+            "i" => true,
+            _ => return Err(SubstitutionFromStrError::InvalidFlags(flags)),
+        };
+
+        let escaped_delimiter = format!("\\{}", delimiter);
+        let ere = ere.replace(&escaped_delimiter, &regex::escape(&delimiter.to_string()));
+        let regex = RegexBuilder::new(&ere)
+            .case_insensitive(case_insensitive)
+            .build()
+            .map_err(|e| SubstitutionFromStrError::InvalidRegex(e.to_string()))?;
+
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = replacement.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                literal.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some(d @ '1'..='9') => {
+                    let group = d as usize - '0' as usize;
+                    if group >= regex.captures_len() {
+                        return Err(SubstitutionFromStrError::InvalidBackref(group));
+                    }
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(Part::Backref(group));
+                }
+                Some(escaped) => literal.push(escaped),
+                None => return Err(SubstitutionFromStrError::Unterminated),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+
+        Ok(SubstitutionExpression {
+            regex,
+            replacement: parts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(
+        "!^.*$!sip:info@example.com!",
+        "+441632960083",
+        Some("sip:info@example.com")
+    )]
+    #[case(
+        "/urn:cid:.+@([^\\.]+\\.)(.*)$/\\2/i",
+        "URN:CID:1@bar.example.com",
+        Some("example.com")
+    )]
+    #[case(
+        "/urn:cid:.+@([^\\.]+\\.)(.*)$/\\2/",
+        "URN:CID:1@bar.example.com",
+        None
+    )]
+    #[case("#a\\#b#<\\#>#", "xa#by", Some("x<#>y"))]
+    #[case("!(b)(c)?!\\2\\1\\\\!", "abd", Some("ab\\d"))]
+    #[case("!x!y!", "abc", None)]
+    fn substitution_expression_apply(
+        #[case] expression: &str,
+        #[case] input: &str,
+        #[case] expected: Option<&str>,
+    ) {
+        let expression: SubstitutionExpression = expression.parse().unwrap();
+        assert_eq!(expression.apply(input).as_deref(), expected);
+    }
+
+    #[rstest]
+    #[case("", SubstitutionFromStrError::Unterminated)]
+    #[case("!a!b", SubstitutionFromStrError::Unterminated)]
+    #[case("!a!b\\", SubstitutionFromStrError::Unterminated)]
+    #[case("1a1b1", SubstitutionFromStrError::InvalidDelimiter('1'))]
+    #[case("!a!b!g", SubstitutionFromStrError::InvalidFlags("g".to_string()))]
+    #[case("!a!b!i!", SubstitutionFromStrError::InvalidFlags("i!".to_string()))]
+    #[case("!(a)!\\2!", SubstitutionFromStrError::InvalidBackref(2))]
+    fn substitution_expression_from_str_fails(
+        #[case] expression: &str,
+        #[case] expected: SubstitutionFromStrError,
+    ) {
+        assert_eq!(
+            expression.parse::<SubstitutionExpression>().unwrap_err(),
+            expected
+        );
+    }
+
+    #[test]
+    fn substitution_expression_rejects_invalid_regex() {
+        assert!(matches!(
+            "!(a!b!".parse::<SubstitutionExpression>(),
+            Err(SubstitutionFromStrError::InvalidRegex(_))
+        ));
+    }
+}
@@ -0,0 +1,200 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dnssec::error::{AlgorithmTryFromError, DigestTypeTryFromError};
+use std::fmt;
+
+/// DNSSEC algorithm numbers, as used in `DNSKEY`, `RRSIG` and `DS` records.
+///
+/// Only [`Algorithm::EcdsaP256Sha256`] and [`Algorithm::Ed25519`] can be
+/// used to generate keys and signatures, see [`Algorithm::is_supported`].
+///
+/// For more details, see the [IANA registry].
+///
+/// [IANA registry]: https://www.iana.org/assignments/dns-sec-alg-numbers/dns-sec-alg-numbers.xhtml
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Algorithm {
+    RsaMd5 = 1,
+    Dh = 2,
+    Dsa = 3,
+    RsaSha1 = 5,
+    DsaNsec3Sha1 = 6,
+    RsaSha1Nsec3Sha1 = 7,
+    RsaSha256 = 8,
+    RsaSha512 = 10,
+    EccGost = 12,
+    EcdsaP256Sha256 = 13,
+    EcdsaP384Sha384 = 14,
+    Ed25519 = 15,
+    Ed448 = 16,
+}
+
+impl Algorithm {
+    /// Checks if keys and signatures of this algorithm can be generated and
+    /// verified by this library.
+    pub fn is_supported(&self) -> bool {
+        matches!(self, Self::EcdsaP256Sha256 | Self::Ed25519)
+    }
+
+    /// Returns the mnemonic of the algorithm, as listed by IANA.
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Self::RsaMd5 => "RSAMD5",
+            Self::Dh => "DH",
+            Self::Dsa => "DSA",
+            Self::RsaSha1 => "RSASHA1",
+            Self::DsaNsec3Sha1 => "DSA-NSEC3-SHA1",
+            Self::RsaSha1Nsec3Sha1 => "RSASHA1-NSEC3-SHA1",
+            Self::RsaSha256 => "RSASHA256",
+            Self::RsaSha512 => "RSASHA512",
+            Self::EccGost => "ECC-GOST",
+            Self::EcdsaP256Sha256 => "ECDSAP256SHA256",
+            Self::EcdsaP384Sha384 => "ECDSAP384SHA384",
+            Self::Ed25519 => "ED25519",
+            Self::Ed448 => "ED448",
+        }
+    }
+}
+
+impl TryFrom<u8> for Algorithm {
+    type Error = AlgorithmTryFromError;
+
+    /// Tries to convert an algorithm number into an `Algorithm`.
+    ///
+    /// # Example
+    /// ```
+    /// use dns_lib::dnssec::Algorithm;
+    ///
+    /// assert_eq!(Algorithm::try_from(15), Ok(Algorithm::Ed25519));
+    /// assert!(Algorithm::try_from(4).is_err());
+    /// ```
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Self::RsaMd5),
+            2 => Ok(Self::Dh),
+            3 => Ok(Self::Dsa),
+            5 => Ok(Self::RsaSha1),
+            6 => Ok(Self::DsaNsec3Sha1),
+            7 => Ok(Self::RsaSha1Nsec3Sha1),
+            8 => Ok(Self::RsaSha256),
+            10 => Ok(Self::RsaSha512),
+            12 => Ok(Self::EccGost),
+            13 => Ok(Self::EcdsaP256Sha256),
+            14 => Ok(Self::EcdsaP384Sha384),
+            15 => Ok(Self::Ed25519),
+            16 => Ok(Self::Ed448),
+            unassigned => Err(AlgorithmTryFromError(unassigned)),
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.mnemonic())
+    }
+}
+
+/// Digest algorithms used by `DS` and `CDS` records.
+///
+/// GOST R 34.11-94 (digest type 3) is deprecated by [RFC 8624] and not
+/// supported.
+///
+/// For more details, see the [IANA registry].
+///
+/// [RFC 8624]: https://datatracker.ietf.org/doc/html/rfc8624#section-3.3
+/// [IANA registry]: https://www.iana.org/assignments/ds-rr-types/ds-rr-types.xhtml
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DigestType {
+    Sha1 = 1,
+    Sha256 = 2,
+    Sha384 = 4,
+}
+
+impl DigestType {
+    /// Returns the length in octets of digests of this type.
+    pub fn digest_len(&self) -> usize {
+        match self {
+            Self::Sha1 => 20,
+            Self::Sha256 => 32,
+            Self::Sha384 => 48,
+        }
+    }
+}
+
+impl TryFrom<u8> for DigestType {
+    type Error = DigestTypeTryFromError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Self::Sha1),
+            2 => Ok(Self::Sha256),
+            4 => Ok(Self::Sha384),
+            unsupported => Err(DigestTypeTryFromError(unsupported)),
+        }
+    }
+}
+
+impl fmt::Display for DigestType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Sha1 => write!(f, "SHA-1"),
+            Self::Sha256 => write!(f, "SHA-256"),
+            Self::Sha384 => write!(f, "SHA-384"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(8, Algorithm::RsaSha256, "RSASHA256", false)]
+    #[case(13, Algorithm::EcdsaP256Sha256, "ECDSAP256SHA256", true)]
+    #[case(15, Algorithm::Ed25519, "ED25519", true)]
+    #[case(16, Algorithm::Ed448, "ED448", false)]
+    fn algorithm_try_from_u8_succeeds(
+        #[case] input: u8,
+        #[case] expected: Algorithm,
+        #[case] mnemonic: &str,
+        #[case] supported: bool,
+    ) {
+        let algorithm = Algorithm::try_from(input).unwrap();
+        assert_eq!(algorithm, expected);
+        assert_eq!(algorithm as u8, input);
+        assert_eq!(algorithm.to_string(), mnemonic);
+        assert_eq!(algorithm.is_supported(), supported);
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(4)]
+    #[case(253)]
+    fn algorithm_try_from_u8_fails(#[case] input: u8) {
+        assert_eq!(
+            Algorithm::try_from(input),
+            Err(AlgorithmTryFromError(input))
+        );
+    }
+
+    #[rstest]
+    #[case(1, Some(DigestType::Sha1))]
+    #[case(2, Some(DigestType::Sha256))]
+    #[case(3, None)]
+    #[case(4, Some(DigestType::Sha384))]
+    fn digest_type_try_from_u8(#[case] input: u8, #[case] expected: Option<DigestType>) {
+        assert_eq!(DigestType::try_from(input).ok(), expected);
+    }
+}
@@ -0,0 +1,84 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dnssec::algorithm::DigestType;
+use crate::domain::Domain;
+use crate::record::rdata::{Dnskey, Ds};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384};
+
+/// Builds the `DS` record referring to `dnskey`, owned by `owner`. The
+/// digest is computed over the canonical owner name followed by the
+/// `DNSKEY` `RDATA`.
+///
+/// The same `RDATA` is published by the child zone in `CDS` records.
+///
+/// For more details, see [RFC 4034, Section 5.1.4].
+///
+/// [RFC 4034, Section 5.1.4]: https://datatracker.ietf.org/doc/html/rfc4034#section-5.1.4
+pub fn ds_from_dnskey(owner: &Domain, dnskey: &Dnskey, digest_type: DigestType) -> Ds {
+    let mut data = owner.to_canonical_bytes();
+    data.extend_from_slice(&dnskey.to_bytes());
+    let digest = match digest_type {
+        DigestType::Sha1 => Sha1::digest(&data).to_vec(),
+        DigestType::Sha256 => Sha256::digest(&data).to_vec(),
+        DigestType::Sha384 => Sha384::digest(&data).to_vec(),
+    };
+    Ds {
+        key_tag: dnskey.key_tag(),
+        algorithm: dnskey.algorithm,
+        digest_type: digest_type as u8,
+        digest,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dnssec::parse_key_file;
+    use rstest::rstest;
+
+    #[rstest]
+    // Example from RFC 8080, Section 6.1.
+    #[case(
+        "example.com. 3600 IN DNSKEY 257 3 15 l02Woi0iS8Aa25FQkUd9RMzZHJpBoRQwAQEX1SxZJA4=",
+        "3613 15 2 3AA5AB37EFCE57F737FC1627013FEE07BDF241BD10F3B1964AB55C78E79A304B"
+    )]
+    // Example from RFC 6605, Section 6.1.
+    #[case(
+        "example.net. 3600 IN DNSKEY 257 3 13 (
+            GojIhhXUN/u4v54ZQqGSnyhWJwaubCvTmeexv7bR6edbkrSqQpF64cYbcB7w
+            NcP+e+MAnLr+Wi9xMWyQLc8NAA== )",
+        "55648 13 2 B4C8C1FE2E7477127B27115656AD6256F424625BF5C1E2770CE6D6E37DF61D17"
+    )]
+    fn ds_from_dnskey_matches_rfc_examples(#[case] key: &str, #[case] expected: &str) {
+        let (owner, dnskey) = parse_key_file(key).unwrap();
+        let ds = ds_from_dnskey(&owner, &dnskey, DigestType::Sha256);
+        assert_eq!(ds.to_string(), expected);
+    }
+
+    #[rstest]
+    #[case(DigestType::Sha1)]
+    #[case(DigestType::Sha256)]
+    #[case(DigestType::Sha384)]
+    fn ds_from_dnskey_digest_len(#[case] digest_type: DigestType) {
+        let (owner, dnskey) = parse_key_file(
+            "example.com. 3600 IN DNSKEY 257 3 15 l02Woi0iS8Aa25FQkUd9RMzZHJpBoRQwAQEX1SxZJA4=",
+        )
+        .unwrap();
+        let ds = ds_from_dnskey(&owner, &dnskey, digest_type);
+        assert_eq!(ds.digest_type, digest_type as u8);
+        assert_eq!(ds.digest.len(), digest_type.digest_len());
+    }
+}
@@ -0,0 +1,355 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::error::TryFromError;
+use crate::domain::Domain;
+use crate::message::error::MessageTryFromError;
+use crate::zone::error::ZoneError;
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+#[derive(Debug, PartialEq)]
+pub struct AlgorithmTryFromError(pub u8);
+
+impl fmt::Display for AlgorithmTryFromError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DNSSEC algorithm '{}' is not assigned", self.0)
+    }
+}
+
+impl Error for AlgorithmTryFromError {}
+
+#[derive(Debug, PartialEq)]
+pub struct DigestTypeTryFromError(pub u8);
+
+impl fmt::Display for DigestTypeTryFromError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DS digest type '{}' is not supported", self.0)
+    }
+}
+
+impl Error for DigestTypeTryFromError {}
+
+#[derive(Debug, PartialEq)]
+pub enum KeyError {
+    UnsupportedAlgorithm(u8),
+    InvalidKeyRecord(String),
+    MissingField(&'static str),
+    InvalidField(&'static str),
+    InvalidKeyMaterial,
+    KeyMismatch,
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnsupportedAlgorithm(algorithm) => {
+                write!(f, "DNSSEC algorithm '{}' is not supported", algorithm)
+            }
+            Self::InvalidKeyRecord(line) => write!(f, "'{}' is not a valid DNSKEY record", line),
+            Self::MissingField(field) => write!(f, "private key file has no '{}' field", field),
+            Self::InvalidField(field) => {
+                write!(f, "private key file has an invalid '{}' field", field)
+            }
+            Self::InvalidKeyMaterial => write!(f, "key material is invalid for its algorithm"),
+            Self::KeyMismatch => write!(f, "private key does not match the public key"),
+        }
+    }
+}
+
+impl Error for KeyError {}
+
+#[derive(Debug, PartialEq)]
+pub enum SignError {
+    NoSigningKeys,
+    MissingSoa,
+    KeyOwnerMismatch(Domain),
+    Nsec3HashCollision(Domain, Domain),
+    OwnerName(TryFromError),
+    Zone(ZoneError),
+}
+
+impl From<TryFromError> for SignError {
+    fn from(error: TryFromError) -> SignError {
+        SignError::OwnerName(error)
+    }
+}
+
+impl From<ZoneError> for SignError {
+    fn from(error: ZoneError) -> SignError {
+        SignError::Zone(error)
+    }
+}
+
+impl fmt::Display for SignError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoSigningKeys => write!(f, "no signing keys were provided"),
+            Self::MissingSoa => write!(f, "zone has no SOA record at its apex"),
+            Self::KeyOwnerMismatch(owner) => {
+                write!(f, "key owned by '{}' does not belong to the zone", owner)
+            }
+            Self::Nsec3HashCollision(a, b) => {
+                write!(f, "NSEC3 hashes of '{}' and '{}' collide", a, b)
+            }
+            Self::OwnerName(e) => e.fmt(f),
+            Self::Zone(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for SignError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::OwnerName(e) => Some(e),
+            Self::Zone(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RolloverError {
+    RoleMismatch(u16),
+}
+
+impl fmt::Display for RolloverError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::RoleMismatch(key_tag) => {
+                write!(
+                    f,
+                    "key '{}' does not have the role of the rollover",
+                    key_tag
+                )
+            }
+        }
+    }
+}
+
+impl Error for RolloverError {}
+
+#[derive(Debug)]
+pub enum TrustAnchorError {
+    Io(io::Error),
+    InvalidRootAnchors(&'static str),
+    InvalidState(String),
+    InvalidStateLine(usize),
+    NoAnchors,
+    NotTrustPoint(Domain),
+    NotValidated,
+}
+
+impl From<io::Error> for TrustAnchorError {
+    fn from(error: io::Error) -> TrustAnchorError {
+        TrustAnchorError::Io(error)
+    }
+}
+
+impl fmt::Display for TrustAnchorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(e) => e.fmt(f),
+            Self::InvalidRootAnchors(field) => {
+                write!(f, "root anchors have an invalid '{}' element", field)
+            }
+            Self::InvalidState(state) => write!(f, "'{}' is not a trust anchor state", state),
+            Self::InvalidStateLine(line) => {
+                write!(f, "trust anchor state file is invalid at line {}", line)
+            }
+            Self::NoAnchors => write!(f, "no trust anchors were found"),
+            Self::NotTrustPoint(name) => write!(f, "'{}' is not the trust point", name),
+            Self::NotValidated => write!(f, "DNSKEY set is not signed by a trust anchor"),
+        }
+    }
+}
+
+impl Error for TrustAnchorError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ValidationError {
+    TypeMismatch,
+    KeyMismatch,
+    InvalidLabelCount(u8),
+    NotYetValid(u32),
+    Expired(u32),
+    UnsupportedAlgorithm(u8),
+    InvalidSignature,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::TypeMismatch => write!(f, "signature does not cover the record set type"),
+            Self::KeyMismatch => write!(f, "signature was not made by the given key"),
+            Self::InvalidLabelCount(labels) => {
+                write!(
+                    f,
+                    "signature label count '{}' exceeds the owner name",
+                    labels
+                )
+            }
+            Self::NotYetValid(inception) => {
+                write!(f, "signature is not valid before '{}'", inception)
+            }
+            Self::Expired(expiration) => write!(f, "signature expired at '{}'", expiration),
+            Self::UnsupportedAlgorithm(algorithm) => {
+                write!(f, "DNSSEC algorithm '{}' is not supported", algorithm)
+            }
+            Self::InvalidSignature => write!(f, "signature is invalid"),
+        }
+    }
+}
+
+impl Error for ValidationError {}
+
+#[derive(Debug, PartialEq)]
+pub enum Sig0Error {
+    Message(MessageTryFromError),
+    Unsigned,
+    Malformed,
+    UnknownKey(Domain, u16),
+    Validation(ValidationError),
+}
+
+impl From<MessageTryFromError> for Sig0Error {
+    fn from(error: MessageTryFromError) -> Sig0Error {
+        Sig0Error::Message(error)
+    }
+}
+
+impl From<ValidationError> for Sig0Error {
+    fn from(error: ValidationError) -> Sig0Error {
+        Sig0Error::Validation(error)
+    }
+}
+
+impl fmt::Display for Sig0Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Message(e) => e.fmt(f),
+            Self::Unsigned => write!(f, "message is not signed with SIG(0)"),
+            Self::Malformed => write!(f, "SIG(0) record is malformed"),
+            Self::UnknownKey(signer, key_tag) => {
+                write!(f, "no KEY '{}' found for signer '{}'", key_tag, signer)
+            }
+            Self::Validation(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for Sig0Error {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Message(e) => Some(e),
+            Self::Validation(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(
+        KeyError::UnsupportedAlgorithm(8),
+        "DNSSEC algorithm '8' is not supported"
+    )]
+    #[case(KeyError::InvalidKeyRecord("foo".to_string()), "'foo' is not a valid DNSKEY record")]
+    #[case(
+        KeyError::MissingField("PrivateKey"),
+        "private key file has no 'PrivateKey' field"
+    )]
+    #[case(
+        KeyError::InvalidField("Algorithm"),
+        "private key file has an invalid 'Algorithm' field"
+    )]
+    #[case(
+        KeyError::InvalidKeyMaterial,
+        "key material is invalid for its algorithm"
+    )]
+    #[case(KeyError::KeyMismatch, "private key does not match the public key")]
+    fn key_error_display(#[case] err: KeyError, #[case] msg: &str) {
+        assert_eq!(err.to_string(), msg);
+    }
+
+    #[rstest]
+    #[case(SignError::NoSigningKeys, "no signing keys were provided")]
+    #[case(SignError::MissingSoa, "zone has no SOA record at its apex")]
+    #[case(SignError::KeyOwnerMismatch(Domain::try_from("example.org".to_string()).unwrap()), "key owned by 'example.org' does not belong to the zone")]
+    fn sign_error_display(#[case] err: SignError, #[case] msg: &str) {
+        assert_eq!(err.to_string(), msg);
+    }
+
+    #[rstest]
+    #[case(Sig0Error::Unsigned, "message is not signed with SIG(0)")]
+    #[case(
+        Sig0Error::UnknownKey(Domain::from_presentation("host.example").unwrap(), 42),
+        "no KEY '42' found for signer 'host.example'"
+    )]
+    #[case(
+        Sig0Error::Validation(ValidationError::InvalidSignature),
+        "signature is invalid"
+    )]
+    fn sig0_error_display(#[case] err: Sig0Error, #[case] msg: &str) {
+        assert_eq!(err.to_string(), msg);
+    }
+
+    #[rstest]
+    #[case(
+        ValidationError::TypeMismatch,
+        "signature does not cover the record set type"
+    )]
+    #[case(ValidationError::Expired(10), "signature expired at '10'")]
+    #[case(ValidationError::InvalidSignature, "signature is invalid")]
+    fn validation_error_display(#[case] err: ValidationError, #[case] msg: &str) {
+        assert_eq!(err.to_string(), msg);
+    }
+
+    #[test]
+    fn rollover_error_display() {
+        assert_eq!(
+            RolloverError::RoleMismatch(3613).to_string(),
+            "key '3613' does not have the role of the rollover"
+        );
+    }
+
+    #[rstest]
+    #[case(
+        TrustAnchorError::InvalidRootAnchors("Digest"),
+        "root anchors have an invalid 'Digest' element"
+    )]
+    #[case(
+        TrustAnchorError::InvalidStateLine(3),
+        "trust anchor state file is invalid at line 3"
+    )]
+    #[case(
+        TrustAnchorError::NotValidated,
+        "DNSKEY set is not signed by a trust anchor"
+    )]
+    fn trust_anchor_error_display(#[case] err: TrustAnchorError, #[case] msg: &str) {
+        assert_eq!(err.to_string(), msg);
+    }
+}
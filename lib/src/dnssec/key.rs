@@ -0,0 +1,520 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dnssec::error::KeyError;
+use crate::dnssec::Algorithm;
+use crate::domain::Domain;
+use crate::record::rdata::Dnskey;
+use ed25519_dalek::Signer as _;
+use p256::ecdsa::signature::Verifier as _;
+use rand::rngs::OsRng;
+use std::fmt;
+
+/// Private half of a key pair, for one of the supported algorithms.
+#[derive(Clone)]
+enum KeyPair {
+    EcdsaP256(p256::ecdsa::SigningKey),
+    Ed25519(ed25519_dalek::SigningKey),
+}
+
+impl KeyPair {
+    fn from_private_key(algorithm: Algorithm, private_key: &[u8]) -> Result<Self, KeyError> {
+        match algorithm {
+            Algorithm::EcdsaP256Sha256 => p256::ecdsa::SigningKey::from_slice(private_key)
+                .map(KeyPair::EcdsaP256)
+                .map_err(|_| KeyError::InvalidKeyMaterial),
+            Algorithm::Ed25519 => <[u8; 32]>::try_from(private_key)
+                .map(|bytes| KeyPair::Ed25519(ed25519_dalek::SigningKey::from_bytes(&bytes)))
+                .map_err(|_| KeyError::InvalidKeyMaterial),
+            other => Err(KeyError::UnsupportedAlgorithm(other as u8)),
+        }
+    }
+
+    fn generate(algorithm: Algorithm) -> Result<Self, KeyError> {
+        match algorithm {
+            Algorithm::EcdsaP256Sha256 => Ok(KeyPair::EcdsaP256(p256::ecdsa::SigningKey::random(
+                &mut OsRng,
+            ))),
+            Algorithm::Ed25519 => Ok(KeyPair::Ed25519(ed25519_dalek::SigningKey::generate(
+                &mut OsRng,
+            ))),
+            other => Err(KeyError::UnsupportedAlgorithm(other as u8)),
+        }
+    }
+
+    fn algorithm(&self) -> Algorithm {
+        match self {
+            KeyPair::EcdsaP256(_) => Algorithm::EcdsaP256Sha256,
+            KeyPair::Ed25519(_) => Algorithm::Ed25519,
+        }
+    }
+
+    /// Public key in the format carried by `DNSKEY` records: the
+    /// uncompressed curve point without its leading `0x04` for ECDSA, see
+    /// [RFC 6605, Section 4], and the raw key for EdDSA, see
+    /// [RFC 8080, Section 3].
+    ///
+    /// [RFC 6605, Section 4]: https://datatracker.ietf.org/doc/html/rfc6605#section-4
+    /// [RFC 8080, Section 3]: https://datatracker.ietf.org/doc/html/rfc8080#section-3
+    fn public_key(&self) -> Vec<u8> {
+        match self {
+            KeyPair::EcdsaP256(key) => {
+                key.verifying_key().to_encoded_point(false).as_bytes()[1..].to_vec()
+            }
+            KeyPair::Ed25519(key) => key.verifying_key().to_bytes().to_vec(),
+        }
+    }
+
+    fn private_key(&self) -> Vec<u8> {
+        match self {
+            KeyPair::EcdsaP256(key) => key.to_bytes().to_vec(),
+            KeyPair::Ed25519(key) => key.to_bytes().to_vec(),
+        }
+    }
+
+    fn sign(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            KeyPair::EcdsaP256(key) => {
+                let signature: p256::ecdsa::Signature = key.sign(data);
+                signature.to_bytes().to_vec()
+            }
+            KeyPair::Ed25519(key) => key.sign(data).to_bytes().to_vec(),
+        }
+    }
+}
+
+/// DNSSEC key pair able to sign zone data on behalf of its owner.
+///
+/// Keys can be generated, or loaded from and saved to the `.key` and
+/// `.private` files written by BIND's `dnssec-keygen`.
+#[derive(Clone)]
+pub struct SigningKey {
+    owner: Domain,
+    dnskey: Dnskey,
+    key_pair: KeyPair,
+}
+
+impl SigningKey {
+    /// Generates a new key pair for `owner`.
+    ///
+    /// `flags` ends up in the `DNSKEY` record: `257` is customary for
+    /// key-signing keys and `256` for zone-signing keys.
+    ///
+    /// # Example
+    /// ```
+    /// use dns_lib::dnssec::{Algorithm, SigningKey};
+    /// use dns_lib::Domain;
+    ///
+    /// let owner = Domain::try_from("example.com".to_string()).unwrap();
+    /// let ksk = SigningKey::generate(owner, Algorithm::Ed25519, 257).unwrap();
+    /// assert!(ksk.dnskey().is_secure_entry_point());
+    /// ```
+    pub fn generate(owner: Domain, algorithm: Algorithm, flags: u16) -> Result<Self, KeyError> {
+        Ok(Self::from_key_pair(
+            owner,
+            flags,
+            KeyPair::generate(algorithm)?,
+        ))
+    }
+
+    /// Builds a key out of raw private key material: the 32-byte seed for
+    /// Ed25519 and the 32-byte scalar for ECDSA P-256.
+    pub fn from_private_key(
+        owner: Domain,
+        algorithm: Algorithm,
+        flags: u16,
+        private_key: &[u8],
+    ) -> Result<Self, KeyError> {
+        let key_pair = KeyPair::from_private_key(algorithm, private_key)?;
+        Ok(Self::from_key_pair(owner, flags, key_pair))
+    }
+
+    /// Loads a key from the contents of a BIND `.key` file and the matching
+    /// `.private` file.
+    pub fn from_bind_files(key_file: &str, private_file: &str) -> Result<Self, KeyError> {
+        let (owner, dnskey) = parse_key_file(key_file)?;
+        let algorithm = match Algorithm::try_from(dnskey.algorithm) {
+            Ok(algorithm) if algorithm.is_supported() => algorithm,
+            _ => return Err(KeyError::UnsupportedAlgorithm(dnskey.algorithm)),
+        };
+
+        let fields = parse_private_file(private_file)?;
+        let private_algorithm = fields
+            .iter()
+            .find(|(name, _)| *name == "Algorithm")
+            .and_then(|(_, value)| value.split_whitespace().next())
+            .ok_or(KeyError::MissingField("Algorithm"))?
+            .parse::<u8>()
+            .map_err(|_| KeyError::InvalidField("Algorithm"))?;
+        if private_algorithm != dnskey.algorithm {
+            return Err(KeyError::KeyMismatch);
+        }
+
+        let private_key = fields
+            .iter()
+            .find(|(name, _)| *name == "PrivateKey")
+            .ok_or(KeyError::MissingField("PrivateKey"))?;
+        let private_key = data_encoding::BASE64
+            .decode(private_key.1.as_bytes())
+            .map_err(|_| KeyError::InvalidField("PrivateKey"))?;
+
+        let key = Self::from_private_key(owner, algorithm, dnskey.flags, &private_key)?;
+        match key.dnskey == dnskey {
+            true => Ok(key),
+            false => Err(KeyError::KeyMismatch),
+        }
+    }
+
+    fn from_key_pair(owner: Domain, flags: u16, key_pair: KeyPair) -> Self {
+        let dnskey = Dnskey {
+            flags,
+            protocol: Dnskey::PROTOCOL,
+            algorithm: key_pair.algorithm() as u8,
+            public_key: key_pair.public_key(),
+        };
+        SigningKey {
+            owner,
+            dnskey,
+            key_pair,
+        }
+    }
+
+    pub fn owner(&self) -> &Domain {
+        &self.owner
+    }
+
+    pub fn dnskey(&self) -> &Dnskey {
+        &self.dnskey
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.key_pair.algorithm()
+    }
+
+    pub fn key_tag(&self) -> u16 {
+        self.dnskey.key_tag()
+    }
+
+    /// Returns a copy of the key with the `REVOKE` flag set, to sign the
+    /// `DNSKEY` set announcing its revocation, see [RFC 5011, Section 2.1].
+    ///
+    /// Setting the flag changes the key tag of the key.
+    ///
+    /// [RFC 5011, Section 2.1]: https://datatracker.ietf.org/doc/html/rfc5011#section-2.1
+    pub fn revoke(&self) -> SigningKey {
+        let mut revoked = self.clone();
+        revoked.dnskey.flags |= Dnskey::REVOKE_FLAG;
+        revoked
+    }
+
+    /// Signs `data`, returning the signature in the format carried by
+    /// `RRSIG` records.
+    pub fn sign(&self, data: &[u8]) -> Vec<u8> {
+        self.key_pair.sign(data)
+    }
+
+    /// Base name of the files BIND uses for this key, for example
+    /// `Kexample.com.+015+03613`.
+    pub fn file_stem(&self) -> String {
+        format!(
+            "K{}+{:03}+{:05}",
+            self.owner.to_fqdn_string(),
+            self.dnskey.algorithm,
+            self.key_tag()
+        )
+    }
+
+    /// Formats the public key as the contents of a BIND `.key` file.
+    pub fn to_key_file(&self) -> String {
+        let kind = match self.dnskey.is_secure_entry_point() {
+            true => "key-signing",
+            false => "zone-signing",
+        };
+        format!(
+            "; This is a {} key, keyid {}, for {}\n{} IN DNSKEY {}\n",
+            kind,
+            self.key_tag(),
+            self.owner.to_fqdn_string(),
+            self.owner.to_fqdn_string(),
+            self.dnskey
+        )
+    }
+
+    /// Formats the private key as the contents of a BIND `.private` file.
+    pub fn to_private_file(&self) -> String {
+        format!(
+            "Private-key-format: v1.3\nAlgorithm: {} ({})\nPrivateKey: {}\n",
+            self.dnskey.algorithm,
+            self.algorithm(),
+            data_encoding::BASE64.encode(&self.key_pair.private_key())
+        )
+    }
+}
+
+impl fmt::Debug for SigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SigningKey")
+            .field("owner", &self.owner)
+            .field("dnskey", &self.dnskey)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Parses the contents of a BIND `.key` file, returning the owner name and
+/// the `DNSKEY` record it holds. Comments starting with `;` are ignored, and
+/// the record may span several lines within parentheses.
+pub fn parse_key_file(key_file: &str) -> Result<(Domain, Dnskey), KeyError> {
+    let line = key_file
+        .lines()
+        .map(|line| line.split(';').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ");
+    let invalid = || KeyError::InvalidKeyRecord(line.clone());
+
+    let tokens: Vec<&str> = line
+        .split(|c: char| c.is_whitespace() || c == '(' || c == ')')
+        .filter(|token| !token.is_empty())
+        .collect();
+    let owner = tokens.first().ok_or_else(invalid)?;
+    let owner = Domain::from_presentation(owner).map_err(|_| invalid())?;
+
+    let rdata_start = tokens
+        .iter()
+        .position(|token| token.eq_ignore_ascii_case("DNSKEY"))
+        .ok_or_else(invalid)?
+        + 1;
+    let rdata = &tokens[rdata_start..];
+    if rdata.len() < 4 {
+        return Err(invalid());
+    }
+
+    let dnskey = Dnskey {
+        flags: rdata[0].parse().map_err(|_| invalid())?,
+        protocol: rdata[1].parse().map_err(|_| invalid())?,
+        algorithm: rdata[2].parse().map_err(|_| invalid())?,
+        public_key: data_encoding::BASE64
+            .decode(rdata[3..].concat().as_bytes())
+            .map_err(|_| invalid())?,
+    };
+    Ok((owner, dnskey))
+}
+
+fn parse_private_file(private_file: &str) -> Result<Vec<(&str, &str)>, KeyError> {
+    let fields: Vec<(&str, &str)> = private_file
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim(), value.trim()))
+        .collect();
+
+    match fields
+        .iter()
+        .find(|(name, _)| *name == "Private-key-format")
+    {
+        Some((_, version)) if version.starts_with("v1.") => Ok(fields),
+        Some(_) => Err(KeyError::InvalidField("Private-key-format")),
+        None => Err(KeyError::MissingField("Private-key-format")),
+    }
+}
+
+/// Verifies `signature` over `data` with the public key held in `dnskey`.
+pub(crate) fn verify_signature(
+    dnskey: &Dnskey,
+    data: &[u8],
+    signature: &[u8],
+) -> Result<bool, KeyError> {
+    match Algorithm::try_from(dnskey.algorithm) {
+        Ok(Algorithm::EcdsaP256Sha256) => {
+            let mut point = vec![0x04];
+            point.extend_from_slice(&dnskey.public_key);
+            let key = p256::ecdsa::VerifyingKey::from_sec1_bytes(&point)
+                .map_err(|_| KeyError::InvalidKeyMaterial)?;
+            let Ok(signature) = p256::ecdsa::Signature::from_slice(signature) else {
+                return Ok(false);
+            };
+            Ok(key.verify(data, &signature).is_ok())
+        }
+        Ok(Algorithm::Ed25519) => {
+            let bytes = <[u8; 32]>::try_from(dnskey.public_key.as_slice())
+                .map_err(|_| KeyError::InvalidKeyMaterial)?;
+            let key = ed25519_dalek::VerifyingKey::from_bytes(&bytes)
+                .map_err(|_| KeyError::InvalidKeyMaterial)?;
+            let Ok(signature) = ed25519_dalek::Signature::from_slice(signature) else {
+                return Ok(false);
+            };
+            Ok(key.verify_strict(data, &signature).is_ok())
+        }
+        _ => Err(KeyError::UnsupportedAlgorithm(dnskey.algorithm)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    // Example from RFC 8080, Section 6.1.
+    const ED25519_KEY: &str = "example.com. 3600 IN DNSKEY 257 3 15 (
+            l02Woi0iS8Aa25FQkUd9RMzZHJpBoRQwAQEX1SxZJA4= )";
+    const ED25519_PRIVATE: &str = "Private-key-format: v1.2
+Algorithm: 15 (ED25519)
+PrivateKey: ODIyNjAzODQ2MjgwODAxMjI2NDUxOTAyMDQxNDIyNjI=
+";
+
+    // Example from RFC 6605, Section 6.1.
+    const ECDSA_KEY: &str = "example.net. 3600 IN DNSKEY 257 3 13 (
+            GojIhhXUN/u4v54ZQqGSnyhWJwaubCvTmeexv7bR6edbkrSqQpF64cYbcB7w
+            NcP+e+MAnLr+Wi9xMWyQLc8NAA== )";
+    const ECDSA_PRIVATE: &str = "Private-key-format: v1.2
+Algorithm: 13 (ECDSAP256SHA256)
+PrivateKey: GU6SnQ/Ou+xC5RumuIUIuJZteXT2z0O/ok1s38Et6mQ=
+";
+
+    #[rstest]
+    #[case(ED25519_KEY, ED25519_PRIVATE, Algorithm::Ed25519, 3613)]
+    #[case(ECDSA_KEY, ECDSA_PRIVATE, Algorithm::EcdsaP256Sha256, 55648)]
+    fn signing_key_from_bind_files_succeeds(
+        #[case] key_file: &str,
+        #[case] private_file: &str,
+        #[case] algorithm: Algorithm,
+        #[case] key_tag: u16,
+    ) {
+        let key = SigningKey::from_bind_files(key_file, private_file).unwrap();
+        assert_eq!(key.algorithm(), algorithm);
+        assert_eq!(key.key_tag(), key_tag);
+    }
+
+    #[rstest]
+    #[case(
+        ED25519_KEY,
+        "Algorithm: 15\nPrivateKey: AA==",
+        KeyError::MissingField("Private-key-format")
+    )]
+    #[case(
+        ED25519_KEY,
+        "Private-key-format: v2.0",
+        KeyError::InvalidField("Private-key-format")
+    )]
+    #[case(
+        ED25519_KEY,
+        "Private-key-format: v1.3\nPrivateKey: AA==",
+        KeyError::MissingField("Algorithm")
+    )]
+    #[case(
+        ED25519_KEY,
+        "Private-key-format: v1.3\nAlgorithm: 15 (ED25519)",
+        KeyError::MissingField("PrivateKey")
+    )]
+    #[case(
+        ED25519_KEY,
+        "Private-key-format: v1.3\nAlgorithm: 15\nPrivateKey: !!",
+        KeyError::InvalidField("PrivateKey")
+    )]
+    #[case(
+        ED25519_KEY,
+        "Private-key-format: v1.3\nAlgorithm: 15\nPrivateKey: AA==",
+        KeyError::InvalidKeyMaterial
+    )]
+    #[case(ED25519_KEY, ECDSA_PRIVATE, KeyError::KeyMismatch)]
+    #[case(ECDSA_KEY, "Private-key-format: v1.3\nAlgorithm: 13\nPrivateKey: ODIyNjAzODQ2MjgwODAxMjI2NDUxOTAyMDQxNDIyNjI=", KeyError::KeyMismatch)]
+    #[case(
+        "example.com. IN DNSKEY 257 3 8 AwEAAQ==",
+        ED25519_PRIVATE,
+        KeyError::UnsupportedAlgorithm(8)
+    )]
+    #[case(
+        "; only a comment",
+        ED25519_PRIVATE,
+        KeyError::InvalidKeyRecord(String::new())
+    )]
+    #[case("example.com. IN DNSKEY 257 3", ED25519_PRIVATE, KeyError::InvalidKeyRecord("example.com. IN DNSKEY 257 3".to_string()))]
+    fn signing_key_from_bind_files_fails(
+        #[case] key_file: &str,
+        #[case] private_file: &str,
+        #[case] expected: KeyError,
+    ) {
+        let result = SigningKey::from_bind_files(key_file, private_file);
+        assert_eq!(result.unwrap_err(), expected);
+    }
+
+    #[rstest]
+    #[case(Algorithm::Ed25519, 257)]
+    #[case(Algorithm::EcdsaP256Sha256, 256)]
+    fn signing_key_bind_files_round_trip(#[case] algorithm: Algorithm, #[case] flags: u16) {
+        let owner = Domain::from_presentation("example.com").unwrap();
+        let key = SigningKey::generate(owner, algorithm, flags).unwrap();
+        let loaded = SigningKey::from_bind_files(&key.to_key_file(), &key.to_private_file());
+        assert_eq!(loaded.unwrap().dnskey(), key.dnskey());
+        assert!(key
+            .file_stem()
+            .starts_with(&format!("Kexample.com.+{:03}+", algorithm as u8)));
+    }
+
+    #[test]
+    fn signing_key_to_bind_files() {
+        let key = SigningKey::from_bind_files(ED25519_KEY, ED25519_PRIVATE).unwrap();
+        assert_eq!(key.file_stem(), "Kexample.com.+015+03613");
+        assert_eq!(
+            key.to_key_file(),
+            "; This is a key-signing key, keyid 3613, for example.com.
+example.com. IN DNSKEY 257 3 15 l02Woi0iS8Aa25FQkUd9RMzZHJpBoRQwAQEX1SxZJA4=
+"
+        );
+        assert_eq!(
+            key.to_private_file(),
+            "Private-key-format: v1.3
+Algorithm: 15 (ED25519)
+PrivateKey: ODIyNjAzODQ2MjgwODAxMjI2NDUxOTAyMDQxNDIyNjI=
+"
+        );
+    }
+
+    #[test]
+    fn signing_key_revoke() {
+        let key = SigningKey::from_bind_files(ED25519_KEY, ED25519_PRIVATE).unwrap();
+        let revoked = key.revoke();
+
+        assert!(revoked.dnskey().is_revoked());
+        assert_eq!(revoked.dnskey().flags, 385);
+        assert_eq!(revoked.dnskey().public_key, key.dnskey().public_key);
+        assert_ne!(revoked.key_tag(), key.key_tag());
+        assert_eq!(revoked.sign(b"data"), key.sign(b"data"));
+    }
+
+    #[test]
+    fn signing_key_generate_fails_for_unsupported_algorithm() {
+        let owner = Domain::from_presentation("example.com").unwrap();
+        let result = SigningKey::generate(owner, Algorithm::RsaSha256, 257);
+        assert_eq!(result.unwrap_err(), KeyError::UnsupportedAlgorithm(8));
+    }
+
+    #[rstest]
+    #[case(Algorithm::Ed25519)]
+    #[case(Algorithm::EcdsaP256Sha256)]
+    fn verify_signature_works(#[case] algorithm: Algorithm) {
+        let owner = Domain::from_presentation("example.com").unwrap();
+        let key = SigningKey::generate(owner, algorithm, 256).unwrap();
+        let signature = key.sign(b"data");
+        assert_eq!(signature.len(), 64);
+        assert_eq!(
+            verify_signature(key.dnskey(), b"data", &signature),
+            Ok(true)
+        );
+        assert_eq!(
+            verify_signature(key.dnskey(), b"other", &signature),
+            Ok(false)
+        );
+        assert_eq!(verify_signature(key.dnskey(), b"data", &[0; 3]), Ok(false));
+    }
+}
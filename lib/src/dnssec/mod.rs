@@ -0,0 +1,37 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod algorithm;
+mod ds;
+pub mod error;
+mod key;
+mod nsec3;
+mod rollover;
+mod sig0;
+mod signer;
+mod trust_anchor;
+mod verify;
+
+pub use algorithm::{Algorithm, DigestType};
+pub use ds::ds_from_dnskey;
+pub use key::{parse_key_file, SigningKey};
+pub use nsec3::{hashed_owner_name, nsec3_hash};
+pub use rollover::{KeyRole, KeyTimeline, RolloverPlan, RolloverPlanner, RolloverTiming};
+pub use sig0::{sign_message, verify_message, KeyLookup};
+pub use signer::{sign_zone, Denial, Nsec3Config, SigningConfig};
+pub use trust_anchor::{
+    refresh_interval, retry_interval, AnchorKey, AnchorState, TrustAnchor, TrustPoint,
+    ADD_HOLD_DOWN, REMOVE_HOLD_DOWN,
+};
+pub use verify::{signed_data, verify_rrsig};
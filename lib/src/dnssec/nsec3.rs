@@ -0,0 +1,86 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::error::TryFromError;
+use crate::domain::Domain;
+use sha1::{Digest, Sha1};
+
+/// Hash algorithm number of SHA-1, the only one defined for `NSEC3`.
+pub const NSEC3_SHA1: u8 = 1;
+
+/// Computes the `NSEC3` hash of `name`, applying SHA-1 to the canonical
+/// wire form of the name and the salt, then `iterations` more times to the
+/// previous digest and the salt.
+///
+/// For more details, see [RFC 5155, Section 5].
+///
+/// # Example
+/// ```
+/// use dns_lib::dnssec::nsec3_hash;
+/// use dns_lib::Domain;
+///
+/// let name = Domain::try_from("example".to_string()).unwrap();
+/// let hash = nsec3_hash(&name, &[0xaa, 0xbb, 0xcc, 0xdd], 12);
+/// assert_eq!(hash.len(), 20);
+/// ```
+///
+/// [RFC 5155, Section 5]: https://datatracker.ietf.org/doc/html/rfc5155#section-5
+pub fn nsec3_hash(name: &Domain, salt: &[u8], iterations: u16) -> Vec<u8> {
+    let mut digest = Sha1::new()
+        .chain_update(name.to_canonical_bytes())
+        .chain_update(salt)
+        .finalize();
+    for _ in 0..iterations {
+        digest = Sha1::new()
+            .chain_update(digest)
+            .chain_update(salt)
+            .finalize();
+    }
+    digest.to_vec()
+}
+
+/// Returns the owner name of the `NSEC3` record for `name`: the base32hex
+/// encoding of its hash, prepended as a single label to `zone`.
+pub fn hashed_owner_name(
+    name: &Domain,
+    zone: &Domain,
+    salt: &[u8],
+    iterations: u16,
+) -> Result<Domain, TryFromError> {
+    let hash = nsec3_hash(name, salt, iterations);
+    let label = data_encoding::BASE32HEX_NOPAD
+        .encode(&hash)
+        .to_ascii_lowercase();
+    zone.prepend_label(label.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    // Examples from RFC 5155, Appendix A.
+    #[rstest]
+    #[case("example", "0p9mhaveqvm6t7vbl5lop2u3t2rp3tom.example")]
+    #[case("a.example", "35mthgpgcu1qg68fab165klnsnk3dpvl.example")]
+    #[case("ns1.example", "2t7b4g4vsa5smi47k61mv5bv1a22bojr.example")]
+    #[case("*.w.example", "r53bq7cc2uvmubfu5ocmm6pers9tk9en.example")]
+    #[case("xx.example", "t644ebqk9bibcna874givr6joj62mlhv.example")]
+    fn hashed_owner_name_works(#[case] name: &str, #[case] expected: &str) {
+        let zone = Domain::from_presentation("example").unwrap();
+        let name = Domain::from_presentation(name).unwrap();
+        let result = hashed_owner_name(&name, &zone, &[0xaa, 0xbb, 0xcc, 0xdd], 12);
+        assert_eq!(result.unwrap().to_string(), expected);
+    }
+}
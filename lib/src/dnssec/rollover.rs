@@ -0,0 +1,672 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dnssec::algorithm::DigestType;
+use crate::dnssec::ds::ds_from_dnskey;
+use crate::dnssec::error::RolloverError;
+use crate::domain::Domain;
+use crate::message::KnownQType;
+use crate::record::rdata::{Dnskey, Ds};
+use crate::record::{RData, ResourceRecord};
+use crate::zone::error::ZoneError;
+use crate::zone::Zone;
+
+/// Timing parameters of a zone and its parent, in seconds, from which
+/// rollover intervals are derived.
+///
+/// For more details, see [RFC 7583, Section 3].
+///
+/// [RFC 7583, Section 3]: https://datatracker.ietf.org/doc/html/rfc7583#section-3
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RolloverTiming {
+    /// TTL of the `DNSKEY` record set (TTLkey).
+    pub dnskey_ttl: u32,
+    /// Largest TTL of the signed record sets in the zone (TTLsig).
+    pub max_zone_ttl: u32,
+    /// TTL of the `DS` record set in the parent zone (TTLds).
+    pub ds_ttl: u32,
+    /// Time for a change to reach all secondary servers (Dprp).
+    pub propagation_delay: u32,
+    /// Time for a change to reach all secondary servers of the parent
+    /// (DprpP).
+    pub parent_propagation_delay: u32,
+    /// Time for the parent to publish a submitted `DS` record (Dreg).
+    pub registration_delay: u32,
+    /// Time to re-sign the whole zone with a new key (Dsgn).
+    pub signing_delay: u32,
+    /// Safety margin added to publication intervals (Ipubsafety).
+    pub publish_safety: u32,
+    /// Safety margin added to retire intervals (Iretsafety).
+    pub retire_safety: u32,
+}
+
+impl RolloverTiming {
+    /// Time for a new `DNSKEY` to be known to every validator,
+    /// Ipub = Dprp + TTLkey.
+    pub fn publish_interval(&self) -> u32 {
+        self.propagation_delay + self.dnskey_ttl
+    }
+
+    /// Time for signatures of a retired zone-signing key to expire from
+    /// caches, Iret = Dsgn + Dprp + TTLsig.
+    pub fn retire_interval(&self) -> u32 {
+        self.signing_delay + self.propagation_delay + self.max_zone_ttl
+    }
+
+    /// Time for a submitted `DS` record to be known to every validator,
+    /// IpubP = Dreg + DprpP + TTLds.
+    pub fn parent_publish_interval(&self) -> u32 {
+        self.registration_delay + self.parent_propagation_delay + self.ds_ttl
+    }
+}
+
+/// Role of a key in the zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyRole {
+    /// Key-signing key: signs the apex `DNSKEY`, `CDS` and `CDNSKEY` sets
+    /// and is referred to by the parent `DS` set.
+    Ksk,
+    /// Zone-signing key: signs every other record set.
+    Zsk,
+}
+
+/// Lifecycle of a key, as the times at which it enters each state. A
+/// `None` time means the event is not scheduled.
+///
+/// For more details, see [RFC 7583, Section 3.1].
+///
+/// [RFC 7583, Section 3.1]: https://datatracker.ietf.org/doc/html/rfc7583#section-3.1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyTimeline {
+    pub dnskey: Dnskey,
+    pub role: KeyRole,
+    /// The key is added to the `DNSKEY` set (Tpub).
+    pub publish: Option<u32>,
+    /// The key starts signing (Tact).
+    pub activate: Option<u32>,
+    /// The key stops signing (Tret).
+    pub retire: Option<u32>,
+    /// The key is removed from the `DNSKEY` set (Trem).
+    pub remove: Option<u32>,
+    /// `CDS` and `CDNSKEY` records for the key are published, asking the
+    /// parent to add its `DS` record (Tsbm).
+    pub sync_publish: Option<u32>,
+    /// `CDS` and `CDNSKEY` records for the key are withdrawn.
+    pub sync_delete: Option<u32>,
+}
+
+impl KeyTimeline {
+    /// Creates a timeline with no scheduled events.
+    pub fn new(dnskey: Dnskey, role: KeyRole) -> Self {
+        Self {
+            dnskey,
+            role,
+            publish: None,
+            activate: None,
+            retire: None,
+            remove: None,
+            sync_publish: None,
+            sync_delete: None,
+        }
+    }
+
+    /// Creates a timeline for a key published and signing since `since`.
+    /// Key-signing keys are also considered submitted to the parent.
+    pub fn active(dnskey: Dnskey, role: KeyRole, since: u32) -> Self {
+        let mut timeline = Self::new(dnskey, role);
+        timeline.publish = Some(since);
+        timeline.activate = Some(since);
+        if role == KeyRole::Ksk {
+            timeline.sync_publish = Some(since);
+        }
+        timeline
+    }
+
+    /// Checks if the key belongs to the `DNSKEY` set at `now`.
+    pub fn is_published(&self, now: u32) -> bool {
+        is_within(now, self.publish, self.remove)
+    }
+
+    /// Checks if the key signs at `now`.
+    pub fn is_signing(&self, now: u32) -> bool {
+        self.is_published(now) && is_within(now, self.activate, self.retire)
+    }
+
+    /// Checks if `CDS` and `CDNSKEY` records for the key are published at
+    /// `now`.
+    pub fn is_sync_published(&self, now: u32) -> bool {
+        self.is_published(now) && is_within(now, self.sync_publish, self.sync_delete)
+    }
+
+    fn events(&self) -> impl Iterator<Item = u32> {
+        [
+            self.publish,
+            self.activate,
+            self.retire,
+            self.remove,
+            self.sync_publish,
+            self.sync_delete,
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+fn is_within(now: u32, start: Option<u32>, end: Option<u32>) -> bool {
+    start.is_some_and(|start| start <= now) && end.map_or(true, |end| now < end)
+}
+
+/// What a zone should publish and sign with at a point in time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RolloverPlan {
+    /// Content of the apex `DNSKEY` record set.
+    pub dnskeys: Vec<Dnskey>,
+    /// Content of the apex `CDS` record set.
+    pub cds: Vec<Ds>,
+    /// Content of the apex `CDNSKEY` record set.
+    pub cdnskeys: Vec<Dnskey>,
+    /// Keys signing the `DNSKEY`, `CDS` and `CDNSKEY` record sets.
+    pub ksk_signers: Vec<Dnskey>,
+    /// Keys signing every other record set.
+    pub zsk_signers: Vec<Dnskey>,
+    /// Time of the next scheduled event, when the plan must be computed
+    /// again.
+    pub next_event: Option<u32>,
+}
+
+/// Schedules key rollovers and derives, from the keys' timelines, what a
+/// zone must publish at any given time.
+///
+/// # Example
+/// ```
+/// use dns_lib::dnssec::{Algorithm, KeyRole, KeyTimeline, RolloverPlanner, RolloverTiming, SigningKey};
+/// use dns_lib::Domain;
+///
+/// let owner = Domain::from_presentation("example.com.").unwrap();
+/// let old = SigningKey::generate(owner.clone(), Algorithm::Ed25519, 256).unwrap();
+/// let new = SigningKey::generate(owner.clone(), Algorithm::Ed25519, 256).unwrap();
+///
+/// let timing = RolloverTiming {
+///     dnskey_ttl: 3600,
+///     max_zone_ttl: 86400,
+///     ..Default::default()
+/// };
+/// let planner = RolloverPlanner::new(timing);
+/// let mut keys = vec![
+///     KeyTimeline::active(old.dnskey().clone(), KeyRole::Zsk, 0),
+///     KeyTimeline::new(new.dnskey().clone(), KeyRole::Zsk),
+/// ];
+/// let (current, successor) = keys.split_at_mut(1);
+/// planner
+///     .schedule_zsk_rollover(&mut current[0], &mut successor[0], 1000)
+///     .unwrap();
+///
+/// let plan = planner.plan(&owner, &keys, 1000);
+/// assert_eq!(plan.dnskeys.len(), 2);
+/// assert_eq!(plan.zsk_signers, [old.dnskey().clone()]);
+/// assert_eq!(plan.next_event, Some(4600));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RolloverPlanner {
+    pub timing: RolloverTiming,
+    /// Digest type of the published `CDS` records.
+    pub digest_type: DigestType,
+}
+
+impl RolloverPlanner {
+    /// Creates a planner publishing SHA-256 `CDS` records.
+    pub fn new(timing: RolloverTiming) -> Self {
+        Self {
+            timing,
+            digest_type: DigestType::Sha256,
+        }
+    }
+
+    /// Schedules a pre-publication rollover from the zone-signing key
+    /// `current` to `successor`, starting at `publish_at`.
+    ///
+    /// The successor is published first and only signs once it has reached
+    /// every validator; the current key is removed once its signatures have
+    /// expired from caches.
+    ///
+    /// For more details, see [RFC 7583, Section 3.2.1].
+    ///
+    /// [RFC 7583, Section 3.2.1]: https://datatracker.ietf.org/doc/html/rfc7583#section-3.2.1
+    pub fn schedule_zsk_rollover(
+        &self,
+        current: &mut KeyTimeline,
+        successor: &mut KeyTimeline,
+        publish_at: u32,
+    ) -> Result<(), RolloverError> {
+        check_role(current, KeyRole::Zsk)?;
+        check_role(successor, KeyRole::Zsk)?;
+
+        let activate_at = publish_at + self.timing.publish_interval() + self.timing.publish_safety;
+        successor.publish = Some(publish_at);
+        successor.activate = Some(activate_at);
+        current.retire = Some(activate_at);
+        current.remove =
+            Some(activate_at + self.timing.retire_interval() + self.timing.retire_safety);
+        Ok(())
+    }
+
+    /// Schedules a double-signature rollover from the key-signing key
+    /// `current` to `successor`, starting at `publish_at`.
+    ///
+    /// The successor is published and signs the `DNSKEY` set alongside the
+    /// current key. Once it has reached every validator, the `CDS` and
+    /// `CDNSKEY` sets switch to it, and the current key is removed after
+    /// the parent's `DS` set has been replaced and expired from caches.
+    ///
+    /// For more details, see [RFC 7583, Section 3.3.1].
+    ///
+    /// [RFC 7583, Section 3.3.1]: https://datatracker.ietf.org/doc/html/rfc7583#section-3.3.1
+    pub fn schedule_ksk_rollover(
+        &self,
+        current: &mut KeyTimeline,
+        successor: &mut KeyTimeline,
+        publish_at: u32,
+    ) -> Result<(), RolloverError> {
+        check_role(current, KeyRole::Ksk)?;
+        check_role(successor, KeyRole::Ksk)?;
+
+        let submit_at = publish_at + self.timing.publish_interval() + self.timing.publish_safety;
+        let remove_at =
+            submit_at + self.timing.parent_publish_interval() + self.timing.publish_safety;
+        successor.publish = Some(publish_at);
+        successor.activate = Some(publish_at);
+        successor.sync_publish = Some(submit_at);
+        current.sync_delete = Some(submit_at);
+        current.retire = Some(remove_at);
+        current.remove = Some(remove_at);
+        Ok(())
+    }
+
+    /// Computes what the zone owned by `owner` must publish and sign with
+    /// at `now`, given the timelines of its keys.
+    pub fn plan(&self, owner: &Domain, keys: &[KeyTimeline], now: u32) -> RolloverPlan {
+        let signers = |role: KeyRole| -> Vec<Dnskey> {
+            keys.iter()
+                .filter(|key| key.role == role && key.is_signing(now))
+                .map(|key| key.dnskey.clone())
+                .collect()
+        };
+        let cdnskeys: Vec<Dnskey> = keys
+            .iter()
+            .filter(|key| key.role == KeyRole::Ksk && key.is_sync_published(now))
+            .map(|key| key.dnskey.clone())
+            .collect();
+
+        RolloverPlan {
+            dnskeys: keys
+                .iter()
+                .filter(|key| key.is_published(now))
+                .map(|key| key.dnskey.clone())
+                .collect(),
+            cds: cdnskeys
+                .iter()
+                .map(|dnskey| ds_from_dnskey(owner, dnskey, self.digest_type))
+                .collect(),
+            cdnskeys,
+            ksk_signers: signers(KeyRole::Ksk),
+            zsk_signers: signers(KeyRole::Zsk),
+            next_event: keys
+                .iter()
+                .flat_map(KeyTimeline::events)
+                .filter(|&time| time > now)
+                .min(),
+        }
+    }
+
+    /// Returns a copy of `zone` whose apex `DNSKEY`, `CDS` and `CDNSKEY`
+    /// record sets are replaced by the ones of `plan`, ready to be signed.
+    pub fn apply(&self, plan: &RolloverPlan, zone: &Zone) -> Result<Zone, ZoneError> {
+        let mut applied = zone.clone();
+        let origin = applied.origin().clone();
+        for r_type in [KnownQType::DNSKEY, KnownQType::CDS, KnownQType::CDNSKEY] {
+            applied.remove_rrset(&origin, r_type.into());
+        }
+
+        let rdatas = plan
+            .dnskeys
+            .iter()
+            .map(|dnskey| (KnownQType::DNSKEY, RData::DNSKEY(dnskey.clone())))
+            .chain(
+                plan.cds
+                    .iter()
+                    .map(|ds| (KnownQType::CDS, RData::CDS(ds.clone()))),
+            )
+            .chain(
+                plan.cdnskeys
+                    .iter()
+                    .map(|dnskey| (KnownQType::CDNSKEY, RData::CDNSKEY(dnskey.clone()))),
+            );
+        for (r_type, rdata) in rdatas {
+            applied.insert(ResourceRecord {
+                name: origin.clone(),
+                r_type: r_type.into(),
+                r_class: zone.class(),
+                ttl: self.timing.dnskey_ttl,
+                rdata,
+            })?;
+        }
+        Ok(applied)
+    }
+}
+
+fn check_role(key: &KeyTimeline, role: KeyRole) -> Result<(), RolloverError> {
+    match key.role == role {
+        true => Ok(()),
+        false => Err(RolloverError::RoleMismatch(key.dnskey.key_tag())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dnssec::{sign_zone, verify_rrsig, Algorithm, SigningConfig, SigningKey};
+    use crate::message::KnownQClass;
+    use crate::record::rdata::Soa;
+    use crate::record::RecordSet;
+
+    const TIMING: RolloverTiming = RolloverTiming {
+        dnskey_ttl: 3600,
+        max_zone_ttl: 86400,
+        ds_ttl: 86400,
+        propagation_delay: 300,
+        parent_propagation_delay: 600,
+        registration_delay: 3600,
+        signing_delay: 0,
+        publish_safety: 600,
+        retire_safety: 600,
+    };
+
+    fn owner() -> Domain {
+        Domain::from_presentation("example.com.").unwrap()
+    }
+
+    fn key(flags: u16) -> SigningKey {
+        SigningKey::generate(owner(), Algorithm::Ed25519, flags).unwrap()
+    }
+
+    fn zone() -> Zone {
+        let mut zone = Zone::new(owner(), KnownQClass::IN.into());
+        let soa = Soa {
+            m_name: Domain::from_presentation("ns1.example.com.").unwrap(),
+            r_name: Domain::from_presentation("hostmaster.example.com.").unwrap(),
+            serial: 1,
+            refresh: 7200,
+            retry: 3600,
+            expire: 1209600,
+            minimum: 300,
+        };
+        zone.insert(ResourceRecord {
+            name: owner(),
+            r_type: KnownQType::SOA.into(),
+            r_class: KnownQClass::IN.into(),
+            ttl: 3600,
+            rdata: RData::SOA(soa),
+        })
+        .unwrap();
+        zone
+    }
+
+    fn signing_keys(keys: &[SigningKey], dnskeys: &[Dnskey]) -> Vec<SigningKey> {
+        keys.iter()
+            .filter(|key| dnskeys.contains(key.dnskey()))
+            .cloned()
+            .collect()
+    }
+
+    /// Signs the zone as planned and checks that each record set is signed
+    /// by exactly the planned keys.
+    fn sign_as_planned(planner: &RolloverPlanner, plan: &RolloverPlan, keys: &[SigningKey]) {
+        let zone = planner.apply(plan, &zone()).unwrap();
+        let ksks = signing_keys(keys, &plan.ksk_signers);
+        let zsks = signing_keys(keys, &plan.zsk_signers);
+        let signed = sign_zone(&zone, &ksks, &zsks, &SigningConfig::new(0, u32::MAX)).unwrap();
+
+        let check = |r_type: KnownQType, signers: &[Dnskey]| {
+            let rrset = signed.rrset(&owner(), r_type.into()).unwrap();
+            let rrsigs = rrsigs_over(&signed, &rrset);
+            assert_eq!(rrsigs.len(), signers.len());
+            for (rrsig, dnskey) in rrsigs.iter().zip(signers) {
+                assert!(plan.dnskeys.contains(dnskey));
+                verify_rrsig(&rrset, rrsig, dnskey, 1).unwrap();
+            }
+        };
+        check(KnownQType::DNSKEY, &plan.ksk_signers);
+        check(KnownQType::SOA, &plan.zsk_signers);
+        if !plan.cds.is_empty() {
+            check(KnownQType::CDS, &plan.ksk_signers);
+            check(KnownQType::CDNSKEY, &plan.ksk_signers);
+        }
+    }
+
+    /// Returns the signatures over `rrset`, in the order of `plan` signers.
+    fn rrsigs_over(zone: &Zone, rrset: &RecordSet) -> Vec<crate::record::rdata::Rrsig> {
+        let mut rrsigs: Vec<_> = zone
+            .rrset(&rrset.name, KnownQType::RRSIG.into())
+            .unwrap()
+            .rdatas
+            .into_iter()
+            .filter_map(|rdata| match rdata {
+                RData::RRSIG(rrsig) if rrsig.type_covered == rrset.r_type => Some(rrsig),
+                _ => None,
+            })
+            .collect();
+        rrsigs.sort_by_key(|rrsig| rrsig.key_tag);
+        rrsigs
+    }
+
+    /// Replays the timelines from `start`, jumping from one event to the
+    /// next, and returns the plan in force after each of them.
+    fn replay(
+        planner: &RolloverPlanner,
+        keys: &[KeyTimeline],
+        start: u32,
+    ) -> Vec<(u32, RolloverPlan)> {
+        let mut clock = start;
+        let mut plans = vec![(clock, planner.plan(&owner(), keys, clock))];
+        while let Some(next) = plans.last().unwrap().1.next_event {
+            clock = next;
+            plans.push((clock, planner.plan(&owner(), keys, clock)));
+        }
+        plans
+    }
+
+    fn sorted(mut dnskeys: Vec<Dnskey>) -> Vec<Dnskey> {
+        dnskeys.sort_by_key(Dnskey::key_tag);
+        dnskeys
+    }
+
+    fn sorted_plan(plan: &RolloverPlan) -> RolloverPlan {
+        RolloverPlan {
+            ksk_signers: sorted(plan.ksk_signers.clone()),
+            zsk_signers: sorted(plan.zsk_signers.clone()),
+            ..plan.clone()
+        }
+    }
+
+    #[test]
+    fn zsk_pre_publication_rollover() {
+        let planner = RolloverPlanner::new(TIMING);
+        let ksk = key(257);
+        let (old, new) = (key(256), key(256));
+        let mut keys = vec![
+            KeyTimeline::active(ksk.dnskey().clone(), KeyRole::Ksk, 0),
+            KeyTimeline::active(old.dnskey().clone(), KeyRole::Zsk, 0),
+            KeyTimeline::new(new.dnskey().clone(), KeyRole::Zsk),
+        ];
+        let (current, successor) = keys.split_at_mut(2);
+        planner
+            .schedule_zsk_rollover(&mut current[1], &mut successor[0], 10000)
+            .unwrap();
+
+        let plans = replay(&planner, &keys, 0);
+        let times: Vec<u32> = plans.iter().map(|(time, _)| *time).collect();
+        // Ipub = 300 + 3600, Iret = 0 + 300 + 86400, plus safety margins.
+        assert_eq!(times, [0, 10000, 14500, 101800]);
+
+        let ksk_dnskey = ksk.dnskey().clone();
+        let expected = [
+            (vec![&ksk, &old], &old),
+            (vec![&ksk, &old, &new], &old),
+            (vec![&ksk, &old, &new], &new),
+            (vec![&ksk, &new], &new),
+        ];
+        for ((time, plan), (published, signer)) in plans.iter().zip(expected) {
+            let published: Vec<Dnskey> = published.iter().map(|k| k.dnskey().clone()).collect();
+            assert_eq!(plan.dnskeys, published, "at {}", time);
+            assert_eq!(plan.zsk_signers, [signer.dnskey().clone()], "at {}", time);
+            assert_eq!(
+                plan.ksk_signers,
+                std::slice::from_ref(&ksk_dnskey),
+                "at {}",
+                time
+            );
+            assert_eq!(
+                plan.cdnskeys,
+                std::slice::from_ref(&ksk_dnskey),
+                "at {}",
+                time
+            );
+            sign_as_planned(&planner, plan, &[ksk.clone(), old.clone(), new.clone()]);
+        }
+        assert_eq!(plans.last().unwrap().1.next_event, None);
+    }
+
+    #[test]
+    fn ksk_double_signature_rollover() {
+        let planner = RolloverPlanner::new(TIMING);
+        let zsk = key(256);
+        let (old, new) = (key(257), key(257));
+        let mut keys = vec![
+            KeyTimeline::active(zsk.dnskey().clone(), KeyRole::Zsk, 0),
+            KeyTimeline::active(old.dnskey().clone(), KeyRole::Ksk, 0),
+            KeyTimeline::new(new.dnskey().clone(), KeyRole::Ksk),
+        ];
+        let (current, successor) = keys.split_at_mut(2);
+        planner
+            .schedule_ksk_rollover(&mut current[1], &mut successor[0], 10000)
+            .unwrap();
+
+        let plans = replay(&planner, &keys, 0);
+        let times: Vec<u32> = plans.iter().map(|(time, _)| *time).collect();
+        // Ipub = 300 + 3600, IpubP = 3600 + 600 + 86400, plus safety margins.
+        assert_eq!(times, [0, 10000, 14500, 105700]);
+
+        let expected = [
+            (vec![&zsk, &old], vec![&old], &old),
+            (vec![&zsk, &old, &new], vec![&old, &new], &old),
+            (vec![&zsk, &old, &new], vec![&old, &new], &new),
+            (vec![&zsk, &new], vec![&new], &new),
+        ];
+        for ((time, plan), (published, ksk_signers, submitted)) in plans.iter().zip(expected) {
+            let dnskeys = |keys: Vec<&SigningKey>| -> Vec<Dnskey> {
+                keys.iter().map(|k| k.dnskey().clone()).collect()
+            };
+            let plan = sorted_plan(plan);
+            assert_eq!(plan.dnskeys, dnskeys(published), "at {}", time);
+            assert_eq!(
+                plan.ksk_signers,
+                sorted(dnskeys(ksk_signers)),
+                "at {}",
+                time
+            );
+            assert_eq!(plan.zsk_signers, [zsk.dnskey().clone()], "at {}", time);
+            assert_eq!(plan.cdnskeys, [submitted.dnskey().clone()], "at {}", time);
+            assert_eq!(
+                plan.cds,
+                [ds_from_dnskey(
+                    &owner(),
+                    submitted.dnskey(),
+                    DigestType::Sha256
+                )],
+                "at {}",
+                time
+            );
+            sign_as_planned(&planner, &plan, &[zsk.clone(), old.clone(), new.clone()]);
+        }
+        assert_eq!(plans.last().unwrap().1.next_event, None);
+    }
+
+    #[test]
+    fn rollover_keeps_safety_invariants() {
+        let planner = RolloverPlanner::new(TIMING);
+        let mut keys = vec![KeyTimeline::active(
+            key(256).dnskey().clone(),
+            KeyRole::Zsk,
+            0,
+        )];
+        // Roll the zone-signing key three times in a row, each rollover
+        // starting as soon as the previous one has completed.
+        let mut start = 1000;
+        for _ in 0..3 {
+            let mut successor = KeyTimeline::new(key(256).dnskey().clone(), KeyRole::Zsk);
+            let current = keys.last_mut().unwrap();
+            planner
+                .schedule_zsk_rollover(current, &mut successor, start)
+                .unwrap();
+            start = current.remove.unwrap();
+            keys.push(successor);
+        }
+
+        for (time, plan) in replay(&planner, &keys, 0) {
+            assert_eq!(plan.zsk_signers.len(), 1, "at {}", time);
+            for key in keys.iter().filter(|key| key.is_signing(time)) {
+                // A key only signs once its DNSKEY has reached validators...
+                let published = key.publish.unwrap();
+                assert!(published == 0 || time - published >= TIMING.publish_interval());
+            }
+            for key in keys
+                .iter()
+                .filter(|key| key.remove.is_some_and(|r| r <= time))
+            {
+                // ...and is only removed once its signatures have expired.
+                let retired = key.retire.unwrap();
+                assert!(key.remove.unwrap() - retired >= TIMING.retire_interval());
+            }
+        }
+    }
+
+    #[test]
+    fn schedule_rollover_fails_for_wrong_role() {
+        let planner = RolloverPlanner::new(TIMING);
+        let ksk = key(257);
+        let mut current = KeyTimeline::active(ksk.dnskey().clone(), KeyRole::Ksk, 0);
+        let mut successor = KeyTimeline::new(key(256).dnskey().clone(), KeyRole::Zsk);
+
+        assert_eq!(
+            planner.schedule_zsk_rollover(&mut current, &mut successor, 10),
+            Err(RolloverError::RoleMismatch(ksk.key_tag()))
+        );
+        assert_eq!(
+            planner.schedule_ksk_rollover(&mut current, &mut successor, 10),
+            Err(RolloverError::RoleMismatch(successor.dnskey.key_tag()))
+        );
+        assert_eq!(current.retire, None);
+    }
+
+    #[test]
+    fn plan_ignores_unscheduled_keys() {
+        let planner = RolloverPlanner::new(TIMING);
+        let keys = [KeyTimeline::new(key(256).dnskey().clone(), KeyRole::Zsk)];
+        let plan = planner.plan(&owner(), &keys, 100);
+
+        assert!(plan.dnskeys.is_empty());
+        assert!(plan.zsk_signers.is_empty());
+        assert_eq!(plan.next_event, None);
+    }
+}
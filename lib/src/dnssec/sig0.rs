@@ -0,0 +1,332 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dnssec::error::{KeyError, Sig0Error, ValidationError};
+use crate::dnssec::key::verify_signature;
+use crate::dnssec::SigningKey;
+use crate::domain::Domain;
+use crate::message::error::MessageTryFromError;
+use crate::message::{Header, KnownQClass, KnownQType, Message, QType};
+use crate::record::rdata::{Dnskey, Rrsig};
+use crate::record::{RData, ResourceRecord};
+use crate::zone::Zone;
+
+/// Flag of a `KEY` record whose key must not be used for authentication,
+/// see [RFC 2535, Section 3.1.2].
+///
+/// [RFC 2535, Section 3.1.2]: https://datatracker.ietf.org/doc/html/rfc2535#section-3.1.2
+const NO_AUTH_FLAG: u16 = 0x8000;
+
+/// Source of the `KEY` records against which SIG(0) signatures are
+/// verified.
+///
+/// It is implemented for [`Zone`], so that a server can verify requests
+/// signed by keys published in the zone it serves, and for closures.
+pub trait KeyLookup {
+    /// Returns the `KEY` records owned by `signer`.
+    fn keys(&self, signer: &Domain) -> Vec<Dnskey>;
+}
+
+impl KeyLookup for Zone {
+    fn keys(&self, signer: &Domain) -> Vec<Dnskey> {
+        self.rrset(signer, KnownQType::KEY.into())
+            .map(|rrset| {
+                rrset
+                    .rdatas
+                    .into_iter()
+                    .filter_map(|rdata| match rdata {
+                        RData::KEY(key) => Some(key),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl<F: Fn(&Domain) -> Vec<Dnskey>> KeyLookup for F {
+    fn keys(&self, signer: &Domain) -> Vec<Dnskey> {
+        self(signer)
+    }
+}
+
+/// Appends a SIG(0) record to `message`, signed with `key` and valid from
+/// `inception` to `expiration`, in seconds since the UNIX epoch.
+///
+/// When `message` is a response, `request` is the request it answers, as
+/// received and including its own SIG(0) record, and is covered by the
+/// signature too.
+///
+/// For more details, see [RFC 2931, Section 3].
+///
+/// [RFC 2931, Section 3]: https://datatracker.ietf.org/doc/html/rfc2931#section-3
+pub fn sign_message(
+    message: &[u8],
+    request: Option<&[u8]>,
+    key: &SigningKey,
+    inception: u32,
+    expiration: u32,
+) -> Result<Vec<u8>, Sig0Error> {
+    let header = Header::try_from(message).map_err(MessageTryFromError::from)?;
+
+    let mut sig = Rrsig {
+        type_covered: QType::new(0),
+        algorithm: key.algorithm() as u8,
+        labels: 0,
+        original_ttl: 0,
+        signature_expiration: expiration,
+        signature_inception: inception,
+        key_tag: key.key_tag(),
+        signer_name: key.owner().clone(),
+        signature: Vec::new(),
+    };
+    sig.signature = key.sign(&signed_data(&sig, request, message));
+
+    let mut signed = message.to_vec();
+    signed[10..12].copy_from_slice(&(header.ar_count + 1).to_be_bytes());
+    let record = ResourceRecord {
+        name: Domain::new(),
+        r_type: KnownQType::SIG.into(),
+        r_class: KnownQClass::ANY.into(),
+        ttl: 0,
+        rdata: RData::SIG(sig),
+    };
+    signed.extend_from_slice(&record.to_bytes());
+    Ok(signed)
+}
+
+/// Verifies the SIG(0) record of `message` at time `now`, in seconds since
+/// the UNIX epoch, returning it.
+///
+/// The signer's `KEY` records are found through `keys`; keys flagged as
+/// not usable for authentication are ignored. `request` must be given
+/// when verifying a response, as for [`sign_message`].
+///
+/// For more details, see [RFC 2931, Section 3.2].
+///
+/// [RFC 2931, Section 3.2]: https://datatracker.ietf.org/doc/html/rfc2931#section-3.2
+pub fn verify_message(
+    message: &[u8],
+    request: Option<&[u8]>,
+    keys: &impl KeyLookup,
+    now: u32,
+) -> Result<Rrsig, Sig0Error> {
+    let (decoded, offsets) = Message::from_wire(message)?;
+    let sig0s: Vec<_> = decoded.records().filter(|r| is_sig0(r)).collect();
+    let (Some(record), Some(&offset)) = (decoded.additionals.last(), offsets.last()) else {
+        return Err(Sig0Error::Unsigned);
+    };
+    let RData::SIG(sig) = &record.rdata else {
+        return Err(Sig0Error::Unsigned);
+    };
+    if !is_sig0(record) {
+        return Err(Sig0Error::Unsigned);
+    }
+    if sig0s.len() > 1 || !record.name.is_root() || record.r_class != KnownQClass::ANY {
+        return Err(Sig0Error::Malformed);
+    }
+
+    if now < sig.signature_inception {
+        return Err(ValidationError::NotYetValid(sig.signature_inception).into());
+    }
+    if now > sig.signature_expiration {
+        return Err(ValidationError::Expired(sig.signature_expiration).into());
+    }
+
+    let candidates: Vec<Dnskey> = keys
+        .keys(&sig.signer_name)
+        .into_iter()
+        .filter(|key| {
+            key.flags & NO_AUTH_FLAG == 0
+                && key.protocol == Dnskey::PROTOCOL
+                && key.algorithm == sig.algorithm
+                && key.key_tag() == sig.key_tag
+        })
+        .collect();
+    if candidates.is_empty() {
+        return Err(Sig0Error::UnknownKey(sig.signer_name.clone(), sig.key_tag));
+    }
+
+    let mut unsigned = message[..offset].to_vec();
+    unsigned[10..12].copy_from_slice(&(decoded.header.ar_count - 1).to_be_bytes());
+    let data = signed_data(sig, request, &unsigned);
+    for key in &candidates {
+        match verify_signature(key, &data, &sig.signature) {
+            Ok(true) => return Ok(sig.clone()),
+            Ok(false) | Err(KeyError::InvalidKeyMaterial) => {}
+            Err(_) => return Err(ValidationError::UnsupportedAlgorithm(key.algorithm).into()),
+        }
+    }
+    Err(ValidationError::InvalidSignature.into())
+}
+
+fn is_sig0(record: &ResourceRecord) -> bool {
+    matches!(&record.rdata, RData::SIG(sig) if sig.type_covered == 0)
+}
+
+/// Builds the data covered by a SIG(0) signature: its `RDATA` but the
+/// signature itself, the request when signing a response, and the message
+/// without the SIG(0) record.
+fn signed_data(sig: &Rrsig, request: Option<&[u8]>, message: &[u8]) -> Vec<u8> {
+    let mut data = sig.to_signed_prefix();
+    data.extend_from_slice(request.unwrap_or_default());
+    data.extend_from_slice(message);
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dnssec::Algorithm;
+    use crate::message::{Question, QR};
+    use rstest::rstest;
+
+    const NOW: u32 = 1_700_000_000;
+
+    fn name(text: &str) -> Domain {
+        Domain::from_presentation(text).unwrap()
+    }
+
+    fn host_key(algorithm: Algorithm) -> SigningKey {
+        SigningKey::generate(name("host.example.com"), algorithm, 512).unwrap()
+    }
+
+    fn zone(keys: &[&SigningKey]) -> Zone {
+        let mut zone = Zone::new(name("example.com"), KnownQClass::IN.into());
+        for key in keys {
+            zone.insert(ResourceRecord {
+                name: key.owner().clone(),
+                r_type: KnownQType::KEY.into(),
+                r_class: KnownQClass::IN.into(),
+                ttl: 3600,
+                rdata: RData::KEY(key.dnskey().clone()),
+            })
+            .unwrap();
+        }
+        zone
+    }
+
+    fn query() -> Vec<u8> {
+        let mut message = Message::new(Header::new(42));
+        message.questions.push(Question {
+            q_name: name("example.com"),
+            q_type: KnownQType::SOA.into(),
+            q_class: KnownQClass::IN.into(),
+        });
+        message.to_bytes()
+    }
+
+    fn sign(message: &[u8], request: Option<&[u8]>, key: &SigningKey) -> Vec<u8> {
+        sign_message(message, request, key, NOW - 300, NOW + 300).unwrap()
+    }
+
+    #[rstest]
+    #[case(Algorithm::EcdsaP256Sha256)]
+    #[case(Algorithm::Ed25519)]
+    fn sign_and_verify_request(#[case] algorithm: Algorithm) {
+        let key = host_key(algorithm);
+        let signed = sign(&query(), None, &key);
+
+        let decoded = Message::try_from(signed.as_slice()).unwrap();
+        assert_eq!(decoded.header.ar_count, 1);
+        let record = &decoded.additionals[0];
+        assert!(record.name.is_root());
+        assert_eq!(record.r_type, KnownQType::SIG);
+        assert_eq!(record.r_class, KnownQClass::ANY);
+
+        let sig = verify_message(&signed, None, &zone(&[&key]), NOW).unwrap();
+        assert_eq!(sig.type_covered, 0);
+        assert_eq!(sig.key_tag, key.key_tag());
+        assert_eq!(sig.signer_name, name("host.example.com"));
+    }
+
+    #[test]
+    fn sign_and_verify_response() {
+        let client = host_key(Algorithm::Ed25519);
+        let server = SigningKey::generate(name("ns.example.com"), Algorithm::Ed25519, 512).unwrap();
+        let keys = zone(&[&client, &server]);
+
+        let request = sign(&query(), None, &client);
+        verify_message(&request, None, &keys, NOW).unwrap();
+
+        let mut response = Message::try_from(query().as_slice()).unwrap();
+        response.header.qr = QR::Response;
+        let response = sign(&response.to_bytes(), Some(&request), &server);
+        assert!(verify_message(&response, Some(&request), &keys, NOW).is_ok());
+        // The response is bound to the request it answers.
+        assert_eq!(
+            verify_message(&response, None, &keys, NOW),
+            Err(ValidationError::InvalidSignature.into())
+        );
+    }
+
+    #[test]
+    fn verify_with_lookup_closure() {
+        let key = host_key(Algorithm::Ed25519);
+        let signed = sign(&query(), None, &key);
+        let lookup = |signer: &Domain| match *signer == name("host.example.com") {
+            true => vec![key.dnskey().clone()],
+            false => Vec::new(),
+        };
+        assert!(verify_message(&signed, None, &lookup, NOW).is_ok());
+    }
+
+    #[test]
+    fn verify_fails() {
+        let key = host_key(Algorithm::Ed25519);
+        let keys = zone(&[&key]);
+        let signed = sign(&query(), None, &key);
+
+        assert_eq!(
+            verify_message(&query(), None, &keys, NOW),
+            Err(Sig0Error::Unsigned)
+        );
+        assert_eq!(
+            verify_message(&signed, None, &keys, NOW - 301),
+            Err(ValidationError::NotYetValid(NOW - 300).into())
+        );
+        assert_eq!(
+            verify_message(&signed, None, &keys, NOW + 301),
+            Err(ValidationError::Expired(NOW + 300).into())
+        );
+
+        let mut tampered = signed.clone();
+        // Turn the question into an NS query.
+        tampered[27] = 2;
+        assert_eq!(
+            verify_message(&tampered, None, &keys, NOW),
+            Err(ValidationError::InvalidSignature.into())
+        );
+
+        let other = host_key(Algorithm::Ed25519);
+        assert_eq!(
+            verify_message(&signed, None, &zone(&[&other]), NOW),
+            Err(Sig0Error::UnknownKey(
+                name("host.example.com"),
+                key.key_tag()
+            ))
+        );
+
+        let mut no_auth = key.dnskey().clone();
+        no_auth.flags |= NO_AUTH_FLAG;
+        let lookup = |_: &Domain| vec![no_auth.clone()];
+        assert_eq!(
+            verify_message(&signed, None, &lookup, NOW),
+            Err(Sig0Error::UnknownKey(
+                name("host.example.com"),
+                key.key_tag()
+            ))
+        );
+    }
+}
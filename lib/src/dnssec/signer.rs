@@ -0,0 +1,716 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dnssec::error::SignError;
+use crate::dnssec::nsec3::{hashed_owner_name, nsec3_hash, NSEC3_SHA1};
+use crate::dnssec::{signed_data, SigningKey};
+use crate::domain::Domain;
+use crate::message::{KnownQType, QType};
+use crate::record::rdata::{Nsec, Nsec3, Nsec3Param, Rrsig, TypeBitmap};
+use crate::record::{RData, RecordSet, ResourceRecord};
+use crate::zone::Zone;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Parameters of a signing run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SigningConfig {
+    /// Start of the validity period of the signatures, in seconds since the
+    /// UNIX epoch.
+    pub inception: u32,
+
+    /// End of the validity period of the signatures, in seconds since the
+    /// UNIX epoch.
+    pub expiration: u32,
+
+    /// Upper bound, in seconds, of the amount subtracted from `expiration`
+    /// for each record set, so that signatures do not all expire at once.
+    /// The amount is derived from the owner name and type, so re-signing
+    /// the same zone produces the same expirations.
+    pub jitter: u32,
+
+    /// How non-existence of names and types is proven.
+    pub denial: Denial,
+}
+
+impl SigningConfig {
+    /// Creates a configuration with the given validity window, no jitter
+    /// and `NSEC` denial of existence.
+    pub fn new(inception: u32, expiration: u32) -> Self {
+        SigningConfig {
+            inception,
+            expiration,
+            jitter: 0,
+            denial: Denial::Nsec,
+        }
+    }
+}
+
+/// Authenticated denial of existence mechanism.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Denial {
+    /// A chain of `NSEC` records, see [RFC 4034, Section 4].
+    ///
+    /// [RFC 4034, Section 4]: https://datatracker.ietf.org/doc/html/rfc4034#section-4
+    Nsec,
+
+    /// A chain of `NSEC3` records, see [RFC 5155].
+    ///
+    /// [RFC 5155]: https://datatracker.ietf.org/doc/html/rfc5155
+    Nsec3(Nsec3Config),
+}
+
+/// Parameters of an `NSEC3` chain.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Nsec3Config {
+    pub salt: Vec<u8>,
+    pub iterations: u16,
+
+    /// Leave unsigned delegations out of the chain, see
+    /// [RFC 5155, Section 6].
+    ///
+    /// [RFC 5155, Section 6]: https://datatracker.ietf.org/doc/html/rfc5155#section-6
+    pub opt_out: bool,
+}
+
+/// Signs `zone`, returning a copy of it with `DNSKEY`, `RRSIG` and either
+/// `NSEC` or `NSEC3` and `NSEC3PARAM` records added.
+///
+/// The apex `DNSKEY`, `CDS` and `CDNSKEY` record sets are signed by `ksks`,
+/// as required by [RFC 7344, Section 4.1], and every other
+/// authoritative record set by `zsks`. When either list is empty, the keys
+/// in the other one sign everything, as with a combined signing key.
+/// `DNSKEY` records already in the zone are kept, so keys can be published
+/// without signing with them. Any existing `RRSIG`, `NSEC`, `NSEC3` and
+/// `NSEC3PARAM` records are replaced.
+///
+/// For more details, see [RFC 4035, Section 2].
+///
+/// [RFC 4035, Section 2]: https://datatracker.ietf.org/doc/html/rfc4035#section-2
+/// [RFC 7344, Section 4.1]: https://datatracker.ietf.org/doc/html/rfc7344#section-4.1
+pub fn sign_zone(
+    zone: &Zone,
+    ksks: &[SigningKey],
+    zsks: &[SigningKey],
+    config: &SigningConfig,
+) -> Result<Zone, SignError> {
+    if ksks.is_empty() && zsks.is_empty() {
+        return Err(SignError::NoSigningKeys);
+    }
+    if let Some(key) = ksks.iter().chain(zsks).find(|k| k.owner() != zone.origin()) {
+        return Err(SignError::KeyOwnerMismatch(key.owner().clone()));
+    }
+
+    let (soa_record, soa) = zone.soa().ok_or(SignError::MissingSoa)?;
+    let soa_ttl = soa_record.ttl;
+    // RFC 9077: denial records must not outlive negative caching.
+    let denial_ttl = soa.minimum.min(soa_ttl);
+
+    let mut signed = zone.clone();
+    for r_type in [
+        KnownQType::RRSIG,
+        KnownQType::NSEC,
+        KnownQType::NSEC3,
+        KnownQType::NSEC3PARAM,
+    ] {
+        let names: Vec<Domain> = signed.names().cloned().collect();
+        for name in names {
+            signed.remove_rrset(&name, r_type.into());
+        }
+    }
+
+    let origin = signed.origin().clone();
+    let dnskey_ttl = signed
+        .rrset(&origin, KnownQType::DNSKEY.into())
+        .map_or(soa_ttl, |rrset| rrset.ttl);
+    for key in ksks.iter().chain(zsks) {
+        let dnskey = RData::DNSKEY(key.dnskey().clone());
+        signed.insert(record(&signed, &origin, dnskey_ttl, dnskey))?;
+    }
+
+    match &config.denial {
+        Denial::Nsec => add_nsec_chain(&mut signed, denial_ttl)?,
+        Denial::Nsec3(nsec3) => add_nsec3_chain(&mut signed, nsec3, denial_ttl)?,
+    }
+
+    let dnskey_signers = if ksks.is_empty() { zsks } else { ksks };
+    let zone_signers = if zsks.is_empty() { ksks } else { zsks };
+    let rrsigs: Vec<ResourceRecord> = signed
+        .rrsets()
+        .filter(|rrset| is_signed(&signed, rrset))
+        .flat_map(|rrset| {
+            let signers = match rrset.name == origin && is_key_set(rrset.r_type) {
+                true => dnskey_signers,
+                false => zone_signers,
+            };
+            signers
+                .iter()
+                .map(|key| sign_rrset(&rrset, key, config))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    for rrsig in rrsigs {
+        signed.insert(rrsig)?;
+    }
+
+    Ok(signed)
+}
+
+fn is_key_set(r_type: QType) -> bool {
+    r_type == KnownQType::DNSKEY || r_type == KnownQType::CDS || r_type == KnownQType::CDNSKEY
+}
+
+fn record(zone: &Zone, name: &Domain, ttl: u32, rdata: RData) -> ResourceRecord {
+    let r_type = match &rdata {
+        RData::DNSKEY(_) => KnownQType::DNSKEY,
+        RData::RRSIG(_) => KnownQType::RRSIG,
+        RData::NSEC(_) => KnownQType::NSEC,
+        RData::NSEC3(_) => KnownQType::NSEC3,
+        _ => KnownQType::NSEC3PARAM,
+    };
+    ResourceRecord {
+        name: name.clone(),
+        r_type: r_type.into(),
+        r_class: zone.class(),
+        ttl,
+        rdata,
+    }
+}
+
+/// Only authoritative data is signed: neither glue nor occluded data below
+/// a delegation, nor the `NS` record set at the delegation point itself.
+fn is_signed(zone: &Zone, rrset: &RecordSet) -> bool {
+    if rrset.r_type == KnownQType::RRSIG || zone.enclosing_delegation(&rrset.name).is_some() {
+        return false;
+    }
+    match zone.is_delegation(&rrset.name) {
+        true => rrset.r_type == KnownQType::DS || rrset.r_type == KnownQType::NSEC,
+        false => true,
+    }
+}
+
+/// Types whose presence at `name` must be proven by denial records. At
+/// delegation points, only the `NS` and `DS` record sets belong to the zone.
+fn authoritative_types(zone: &Zone, name: &Domain) -> Vec<QType> {
+    let types = zone.types_at(name);
+    match zone.is_delegation(name) {
+        true => types
+            .into_iter()
+            .filter(|t| *t == KnownQType::NS || *t == KnownQType::DS)
+            .collect(),
+        false => types,
+    }
+}
+
+fn add_nsec_chain(zone: &mut Zone, ttl: u32) -> Result<(), SignError> {
+    let names: Vec<Domain> = zone
+        .names()
+        .filter(|name| zone.enclosing_delegation(name).is_none())
+        .cloned()
+        .collect();
+
+    let mut records = Vec::with_capacity(names.len());
+    for (i, name) in names.iter().enumerate() {
+        let mut type_bitmap: TypeBitmap = authoritative_types(zone, name).into_iter().collect();
+        type_bitmap.insert(KnownQType::RRSIG.into());
+        type_bitmap.insert(KnownQType::NSEC.into());
+
+        let nsec = Nsec {
+            next_domain_name: names[(i + 1) % names.len()].clone(),
+            type_bitmap,
+        };
+        records.push(record(zone, name, ttl, RData::NSEC(nsec)));
+    }
+
+    for record in records {
+        zone.insert(record)?;
+    }
+    Ok(())
+}
+
+fn add_nsec3_chain(zone: &mut Zone, config: &Nsec3Config, ttl: u32) -> Result<(), SignError> {
+    let origin = zone.origin().clone();
+    let param = Nsec3Param {
+        hash_algorithm: NSEC3_SHA1,
+        flags: 0,
+        iterations: config.iterations,
+        salt: config.salt.clone(),
+    };
+    // RFC 5155, Section 4: the TTL of NSEC3PARAM is customarily zero, as
+    // only authoritative servers make use of it.
+    zone.insert(record(zone, &origin, 0, RData::NSEC3PARAM(param)))?;
+
+    // Every authoritative name, plus the empty non-terminals between them
+    // and the apex, needs a hashed owner name.
+    let mut names = BTreeSet::new();
+    for name in zone.names() {
+        if zone.enclosing_delegation(name).is_some() {
+            continue;
+        }
+        let insecure =
+            zone.is_delegation(name) && !zone.types_at(name).contains(&KnownQType::DS.into());
+        if config.opt_out && insecure {
+            continue;
+        }
+
+        let mut current = name.clone();
+        while current.is_subdomain_of(&origin) && names.insert(current.clone()) {
+            match current.parent() {
+                Some(parent) => current = parent,
+                None => break,
+            }
+        }
+    }
+
+    let mut hashes: BTreeMap<Vec<u8>, Domain> = BTreeMap::new();
+    for name in names {
+        let hash = nsec3_hash(&name, &config.salt, config.iterations);
+        if let Some(other) = hashes.insert(hash, name.clone()) {
+            return Err(SignError::Nsec3HashCollision(other, name));
+        }
+    }
+
+    let flags = match config.opt_out {
+        true => Nsec3::OPT_OUT_FLAG,
+        false => 0,
+    };
+    let hashes: Vec<(&Vec<u8>, &Domain)> = hashes.iter().collect();
+    let mut records = Vec::with_capacity(hashes.len());
+    for (i, (_, name)) in hashes.iter().enumerate() {
+        let types = authoritative_types(zone, name);
+        let mut type_bitmap: TypeBitmap = types.iter().copied().collect();
+        let has_signatures = match zone.is_delegation(name) {
+            true => types.contains(&KnownQType::DS.into()),
+            false => !types.is_empty(),
+        };
+        if has_signatures {
+            type_bitmap.insert(KnownQType::RRSIG.into());
+        }
+
+        let nsec3 = Nsec3 {
+            hash_algorithm: NSEC3_SHA1,
+            flags,
+            iterations: config.iterations,
+            salt: config.salt.clone(),
+            next_hashed_owner_name: hashes[(i + 1) % hashes.len()].0.clone(),
+            type_bitmap,
+        };
+        let owner = hashed_owner_name(name, &origin, &config.salt, config.iterations)?;
+        records.push(record(zone, &owner, ttl, RData::NSEC3(nsec3)));
+    }
+
+    for record in records {
+        zone.insert(record)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn sign_rrset(
+    rrset: &RecordSet,
+    key: &SigningKey,
+    config: &SigningConfig,
+) -> ResourceRecord {
+    let labels = match rrset.name.is_wildcard() {
+        true => rrset.name.label_count() - 1,
+        false => rrset.name.label_count(),
+    };
+
+    let mut rrsig = Rrsig {
+        type_covered: rrset.r_type,
+        algorithm: key.algorithm() as u8,
+        labels: labels as u8,
+        original_ttl: rrset.ttl,
+        signature_expiration: expiration(rrset, config),
+        signature_inception: config.inception,
+        key_tag: key.key_tag(),
+        signer_name: key.owner().clone(),
+        signature: Vec::new(),
+    };
+    rrsig.signature = key.sign(&signed_data(rrset, &rrsig));
+
+    ResourceRecord {
+        name: rrset.name.clone(),
+        r_type: KnownQType::RRSIG.into(),
+        r_class: rrset.r_class,
+        ttl: rrset.ttl,
+        rdata: RData::RRSIG(rrsig),
+    }
+}
+
+fn expiration(rrset: &RecordSet, config: &SigningConfig) -> u32 {
+    if config.jitter == 0 {
+        return config.expiration;
+    }
+
+    let digest = Sha256::new()
+        .chain_update(rrset.name.to_canonical_bytes())
+        .chain_update(rrset.r_type.value.to_be_bytes())
+        .finalize();
+    let value = u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]);
+    let jitter = (value as u64 % (config.jitter as u64 + 1)) as u32;
+    config
+        .expiration
+        .saturating_sub(jitter)
+        .max(config.inception)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dnssec::error::SignError;
+    use crate::dnssec::{verify_rrsig, Algorithm};
+    use crate::message::KnownQClass;
+    use crate::record::rdata::{Ds, Mx, Soa};
+    use std::net::Ipv4Addr;
+
+    const INCEPTION: u32 = 1438207200;
+    const EXPIRATION: u32 = 1440021600;
+
+    fn name(text: &str) -> Domain {
+        Domain::from_presentation(text).unwrap()
+    }
+
+    fn add(zone: &mut Zone, owner: &str, r_type: KnownQType, rdata: RData) {
+        let record = ResourceRecord {
+            name: name(owner),
+            r_type: r_type.into(),
+            r_class: KnownQClass::IN.into(),
+            ttl: 3600,
+            rdata,
+        };
+        zone.insert(record).unwrap();
+    }
+
+    fn zone() -> Zone {
+        let mut zone = Zone::new(name("example.com"), KnownQClass::IN.into());
+        let soa = Soa {
+            m_name: name("ns1.example.com"),
+            r_name: name("hostmaster.example.com"),
+            serial: 1,
+            refresh: 7200,
+            retry: 3600,
+            expire: 1209600,
+            minimum: 300,
+        };
+        let mx = Mx {
+            preference: 10,
+            exchange: name("mail.example.com"),
+        };
+        add(&mut zone, "example.com", KnownQType::SOA, RData::SOA(soa));
+        add(
+            &mut zone,
+            "example.com",
+            KnownQType::NS,
+            RData::NS(name("ns1.example.com")),
+        );
+        add(&mut zone, "example.com", KnownQType::MX, RData::MX(mx));
+        add(
+            &mut zone,
+            "ns1.example.com",
+            KnownQType::A,
+            RData::A(Ipv4Addr::new(192, 0, 2, 1)),
+        );
+        add(
+            &mut zone,
+            "mail.example.com",
+            KnownQType::A,
+            RData::A(Ipv4Addr::new(192, 0, 2, 2)),
+        );
+        add(
+            &mut zone,
+            "a.b.example.com",
+            KnownQType::A,
+            RData::A(Ipv4Addr::new(192, 0, 2, 3)),
+        );
+        add(
+            &mut zone,
+            "insecure.example.com",
+            KnownQType::NS,
+            RData::NS(name("ns.insecure.example.com")),
+        );
+        add(
+            &mut zone,
+            "ns.insecure.example.com",
+            KnownQType::A,
+            RData::A(Ipv4Addr::new(192, 0, 2, 4)),
+        );
+        add(
+            &mut zone,
+            "secure.example.com",
+            KnownQType::NS,
+            RData::NS(name("ns1.example.com")),
+        );
+        let ds = Ds {
+            key_tag: 1,
+            algorithm: 15,
+            digest_type: 2,
+            digest: vec![0; 32],
+        };
+        add(
+            &mut zone,
+            "secure.example.com",
+            KnownQType::DS,
+            RData::DS(ds),
+        );
+        zone
+    }
+
+    // Example from RFC 8080, Section 6.1.
+    fn ksk() -> SigningKey {
+        let private_key = data_encoding::BASE64
+            .decode(b"ODIyNjAzODQ2MjgwODAxMjI2NDUxOTAyMDQxNDIyNjI=")
+            .unwrap();
+        SigningKey::from_private_key(name("example.com"), Algorithm::Ed25519, 257, &private_key)
+            .unwrap()
+    }
+
+    fn zsk() -> SigningKey {
+        SigningKey::generate(name("example.com"), Algorithm::EcdsaP256Sha256, 256).unwrap()
+    }
+
+    fn rrsigs(zone: &Zone, owner: &str) -> Vec<Rrsig> {
+        match zone.rrset(&name(owner), KnownQType::RRSIG.into()) {
+            Some(rrset) => rrset
+                .rdatas
+                .into_iter()
+                .filter_map(|rdata| match rdata {
+                    RData::RRSIG(rrsig) => Some(rrsig),
+                    _ => None,
+                })
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Verifies every signature in `zone`, returning the signed record sets
+    /// as `(owner, type, key tag)`.
+    fn verify_zone(zone: &Zone, keys: &[&SigningKey]) -> Vec<(String, QType, u16)> {
+        let mut signed = Vec::new();
+        for owner in zone.names() {
+            for rrsig in rrsigs(zone, &owner.to_string()) {
+                let rrset = zone.rrset(owner, rrsig.type_covered).unwrap();
+                let key = keys.iter().find(|k| k.key_tag() == rrsig.key_tag).unwrap();
+                assert_eq!(
+                    verify_rrsig(&rrset, &rrsig, key.dnskey(), INCEPTION),
+                    Ok(())
+                );
+                signed.push((owner.to_string(), rrsig.type_covered, rrsig.key_tag));
+            }
+        }
+        signed
+    }
+
+    #[test]
+    fn sign_zone_matches_rfc_8080_example() {
+        let config = SigningConfig::new(INCEPTION, EXPIRATION);
+        let signed = sign_zone(&zone(), &[ksk()], &[], &config).unwrap();
+
+        let mx = rrsigs(&signed, "example.com")
+            .into_iter()
+            .find(|rrsig| rrsig.type_covered == KnownQType::MX)
+            .unwrap();
+        assert_eq!(
+            mx.to_string(),
+            "MX 15 2 3600 20150819220000 20150729220000 3613 example.com. \
+             oL9krJun7xfBOIWcGHi7mag5/hdZrKWw15jPGrHpjQeRAvTdszaPD+QLs3fx8A4M3e23mRZ9VrbpMngwcrqNAg=="
+        );
+    }
+
+    #[test]
+    fn sign_zone_with_nsec() {
+        let (ksk, zsk) = (ksk(), zsk());
+        let config = SigningConfig::new(INCEPTION, EXPIRATION);
+        let signed = sign_zone(
+            &zone(),
+            std::slice::from_ref(&ksk),
+            std::slice::from_ref(&zsk),
+            &config,
+        )
+        .unwrap();
+
+        let chain: Vec<(String, String)> = signed
+            .records()
+            .filter_map(|record| match &record.rdata {
+                RData::NSEC(nsec) => Some((record.name.to_string(), nsec.to_string())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            chain,
+            [
+                (
+                    "example.com",
+                    "a.b.example.com. NS SOA MX RRSIG NSEC DNSKEY"
+                ),
+                ("a.b.example.com", "insecure.example.com. A RRSIG NSEC"),
+                ("insecure.example.com", "mail.example.com. NS RRSIG NSEC"),
+                ("mail.example.com", "ns1.example.com. A RRSIG NSEC"),
+                ("ns1.example.com", "secure.example.com. A RRSIG NSEC"),
+                ("secure.example.com", "example.com. NS DS RRSIG NSEC"),
+            ]
+            .map(|(a, b)| (a.to_string(), b.to_string()))
+        );
+        assert!(signed
+            .records()
+            .filter(|r| r.r_type == KnownQType::NSEC)
+            .all(|r| r.ttl == 300));
+
+        let signatures = verify_zone(&signed, &[&ksk, &zsk]);
+        let signed_by = |owner: &str, r_type: KnownQType| -> Vec<u16> {
+            signatures
+                .iter()
+                .filter(|(o, t, _)| o == owner && *t == r_type)
+                .map(|(_, _, tag)| *tag)
+                .collect()
+        };
+        assert_eq!(
+            signed_by("example.com", KnownQType::DNSKEY),
+            [ksk.key_tag()]
+        );
+        assert_eq!(signed_by("example.com", KnownQType::SOA), [zsk.key_tag()]);
+        assert_eq!(
+            signed_by("secure.example.com", KnownQType::DS),
+            [zsk.key_tag()]
+        );
+        assert!(signed_by("secure.example.com", KnownQType::NS).is_empty());
+        assert!(signed_by("insecure.example.com", KnownQType::NS).is_empty());
+        assert!(rrsigs(&signed, "ns.insecure.example.com").is_empty());
+        assert_eq!(
+            signed
+                .rrset(&name("example.com"), KnownQType::DNSKEY.into())
+                .unwrap()
+                .rdatas
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn sign_zone_with_nsec3() {
+        let ksk = ksk();
+        let mut config = SigningConfig::new(INCEPTION, EXPIRATION);
+        config.denial = Denial::Nsec3(Nsec3Config {
+            salt: vec![0xaa, 0xbb],
+            iterations: 0,
+            opt_out: true,
+        });
+        let signed = sign_zone(&zone(), std::slice::from_ref(&ksk), &[], &config).unwrap();
+
+        let param = signed
+            .rrset(&name("example.com"), KnownQType::NSEC3PARAM.into())
+            .unwrap();
+        assert_eq!(param.rdatas[0].to_string(), "1 0 0 AABB");
+        assert_eq!(param.ttl, 0);
+
+        let mut chain: BTreeMap<Vec<u8>, Nsec3> = BTreeMap::new();
+        for record in signed.records() {
+            if let RData::NSEC3(nsec3) = &record.rdata {
+                let label = record.name.labels()[0].to_ascii_uppercase();
+                let hash = data_encoding::BASE32HEX_NOPAD
+                    .decode(label.as_bytes())
+                    .unwrap();
+                assert_eq!(record.name.parent().unwrap(), name("example.com"));
+                assert!(nsec3.is_opt_out());
+                chain.insert(hash, nsec3.clone());
+            }
+        }
+
+        // Apex, the empty non-terminal b, a.b, mail, ns1 and secure, but not
+        // the unsigned delegation.
+        assert_eq!(chain.len(), 6);
+        let hashes: Vec<&Vec<u8>> = chain.keys().collect();
+        for (i, nsec3) in chain.values().enumerate() {
+            assert_eq!(
+                &nsec3.next_hashed_owner_name,
+                hashes[(i + 1) % hashes.len()]
+            );
+        }
+
+        let bitmap_of = |owner: &str| {
+            let hash = nsec3_hash(&name(owner), &[0xaa, 0xbb], 0);
+            chain.get(&hash).map(|nsec3| nsec3.type_bitmap.to_string())
+        };
+        assert_eq!(
+            bitmap_of("example.com").unwrap(),
+            "NS SOA MX RRSIG DNSKEY NSEC3PARAM"
+        );
+        assert_eq!(bitmap_of("b.example.com").unwrap(), "");
+        assert_eq!(bitmap_of("secure.example.com").unwrap(), "NS DS RRSIG");
+        assert_eq!(bitmap_of("insecure.example.com"), None);
+
+        let signatures = verify_zone(&signed, &[&ksk]);
+        let nsec3_signatures = signatures
+            .iter()
+            .filter(|(_, t, _)| *t == KnownQType::NSEC3)
+            .count();
+        assert_eq!(nsec3_signatures, 6);
+    }
+
+    #[test]
+    fn sign_zone_replaces_previous_signatures() {
+        let config = SigningConfig::new(INCEPTION, EXPIRATION);
+        let signed = sign_zone(&zone(), &[ksk()], &[], &config).unwrap();
+        let resigned = sign_zone(&signed, &[ksk()], &[], &config).unwrap();
+        assert_eq!(signed, resigned);
+
+        let mut nsec3_config = config.clone();
+        nsec3_config.denial = Denial::Nsec3(Nsec3Config::default());
+        let nsec3 = sign_zone(&signed, &[ksk()], &[], &nsec3_config).unwrap();
+        assert!(!nsec3.records().any(|r| r.r_type == KnownQType::NSEC));
+        let back = sign_zone(&nsec3, &[ksk()], &[], &config).unwrap();
+        assert_eq!(signed, back);
+    }
+
+    #[test]
+    fn sign_zone_applies_jitter() {
+        let mut config = SigningConfig::new(INCEPTION, EXPIRATION);
+        config.jitter = 86_400;
+        let signed = sign_zone(&zone(), &[ksk()], &[], &config).unwrap();
+
+        let expirations: BTreeSet<u32> = signed
+            .records()
+            .filter_map(|record| match &record.rdata {
+                RData::RRSIG(rrsig) => Some(rrsig.signature_expiration),
+                _ => None,
+            })
+            .collect();
+        assert!(expirations.len() > 1);
+        assert!(expirations
+            .iter()
+            .all(|e| (EXPIRATION - 86_400..=EXPIRATION).contains(e)));
+        assert_eq!(signed, sign_zone(&zone(), &[ksk()], &[], &config).unwrap());
+    }
+
+    #[test]
+    fn sign_zone_fails() {
+        let config = SigningConfig::new(INCEPTION, EXPIRATION);
+        assert_eq!(
+            sign_zone(&zone(), &[], &[], &config),
+            Err(SignError::NoSigningKeys)
+        );
+
+        let other = SigningKey::generate(name("example.org"), Algorithm::Ed25519, 256).unwrap();
+        assert_eq!(
+            sign_zone(&zone(), &[ksk()], &[other], &config),
+            Err(SignError::KeyOwnerMismatch(name("example.org")))
+        );
+
+        let mut no_soa = zone();
+        no_soa.remove_rrset(&name("example.com"), KnownQType::SOA.into());
+        assert_eq!(
+            sign_zone(&no_soa, &[ksk()], &[], &config),
+            Err(SignError::MissingSoa)
+        );
+    }
+}
@@ -0,0 +1,1030 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dnssec::algorithm::DigestType;
+use crate::dnssec::ds::ds_from_dnskey;
+use crate::dnssec::error::TrustAnchorError;
+use crate::dnssec::verify_rrsig;
+use crate::domain::Domain;
+use crate::message::KnownQType;
+use crate::record::rdata::{Dnskey, Ds, Rrsig};
+use crate::record::{RData, RecordSet};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Time a new key must be seen before being trusted, see
+/// [RFC 5011, Section 2.4.1].
+///
+/// [RFC 5011, Section 2.4.1]: https://datatracker.ietf.org/doc/html/rfc5011#section-2.4.1
+pub const ADD_HOLD_DOWN: u32 = 30 * 86_400;
+
+/// Time a revoked key is remembered before being removed, see
+/// [RFC 5011, Section 2.4.2].
+///
+/// [RFC 5011, Section 2.4.2]: https://datatracker.ietf.org/doc/html/rfc5011#section-2.4.2
+pub const REMOVE_HOLD_DOWN: u32 = 30 * 86_400;
+
+const HOUR: u32 = 3_600;
+const DAY: u32 = 86_400;
+
+/// State of a key tracked at a trust point.
+///
+/// Keys that are not tracked are in the implicit `Start` state.
+///
+/// For more details, see [RFC 5011, Section 4].
+///
+/// [RFC 5011, Section 4]: https://datatracker.ietf.org/doc/html/rfc5011#section-4
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnchorState {
+    /// The key has been seen and waits for the add hold-down to expire.
+    AddPend,
+    /// The key is a trust anchor.
+    Valid,
+    /// The key is a trust anchor but is no longer in the `DNSKEY` set.
+    Missing,
+    /// The key has revoked itself and waits for the remove hold-down to
+    /// expire.
+    Revoked,
+    /// The key has been revoked for good and is never trusted again.
+    Removed,
+}
+
+impl AnchorState {
+    /// Checks if keys in this state can validate the trust point.
+    pub fn is_trusted(&self) -> bool {
+        matches!(self, Self::Valid | Self::Missing)
+    }
+}
+
+impl fmt::Display for AnchorState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let state = match self {
+            Self::AddPend => "ADDPEND",
+            Self::Valid => "VALID",
+            Self::Missing => "MISSING",
+            Self::Revoked => "REVOKED",
+            Self::Removed => "REMOVED",
+        };
+        write!(f, "{}", state)
+    }
+}
+
+impl FromStr for AnchorState {
+    type Err = TrustAnchorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ADDPEND" => Ok(Self::AddPend),
+            "VALID" => Ok(Self::Valid),
+            "MISSING" => Ok(Self::Missing),
+            "REVOKED" => Ok(Self::Revoked),
+            "REMOVED" => Ok(Self::Removed),
+            _ => Err(TrustAnchorError::InvalidState(s.to_string())),
+        }
+    }
+}
+
+/// Key material of a trust anchor. Anchors configured from a digest are
+/// replaced by the matching key the first time it is seen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnchorKey {
+    Ds(Ds),
+    Dnskey(Dnskey),
+}
+
+/// A key tracked at a trust point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrustAnchor {
+    pub key: AnchorKey,
+    pub state: AnchorState,
+    /// Time of the last state change.
+    pub last_change: u32,
+    /// End of the add or remove hold-down, in the `AddPend` and `Revoked`
+    /// states.
+    pub hold_down: Option<u32>,
+}
+
+impl TrustAnchor {
+    /// Checks if `dnskey` is the anchored key, regardless of its `REVOKE`
+    /// flag.
+    fn matches(&self, owner: &Domain, dnskey: &Dnskey) -> bool {
+        let unrevoked = Dnskey {
+            flags: dnskey.flags & !Dnskey::REVOKE_FLAG,
+            ..dnskey.clone()
+        };
+        match &self.key {
+            AnchorKey::Dnskey(anchor) => {
+                anchor.algorithm == unrevoked.algorithm && anchor.public_key == unrevoked.public_key
+            }
+            AnchorKey::Ds(ds) => match DigestType::try_from(ds.digest_type) {
+                Ok(digest_type) => ds_from_dnskey(owner, &unrevoked, digest_type) == *ds,
+                Err(_) => false,
+            },
+        }
+    }
+
+    fn transition(&mut self, state: AnchorState, hold_down: Option<u32>, now: u32) {
+        self.state = state;
+        self.hold_down = hold_down;
+        self.last_change = now;
+    }
+}
+
+/// The set of keys tracked for a zone whose `DNSKEY` set is trusted
+/// directly, such as the root, updated automatically as the zone rolls its
+/// key-signing keys.
+///
+/// For more details, see [RFC 5011].
+///
+/// [RFC 5011]: https://datatracker.ietf.org/doc/html/rfc5011
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrustPoint {
+    owner: Domain,
+    anchors: Vec<TrustAnchor>,
+}
+
+impl TrustPoint {
+    /// Creates a trust point for `owner` with no anchors.
+    pub fn new(owner: Domain) -> Self {
+        Self {
+            owner,
+            anchors: Vec::new(),
+        }
+    }
+
+    /// Adds a configured trust anchor, trusted right away.
+    pub fn add_anchor(&mut self, key: AnchorKey, now: u32) {
+        self.anchors.push(TrustAnchor {
+            key,
+            state: AnchorState::Valid,
+            last_change: now,
+            hold_down: None,
+        });
+    }
+
+    /// Builds a trust point from a `root-anchors.xml` document published by
+    /// IANA, keeping the key digests valid at `now`.
+    ///
+    /// For more details, see [RFC 9718].
+    ///
+    /// [RFC 9718]: https://datatracker.ietf.org/doc/html/rfc9718
+    pub fn from_root_anchors(xml: &str, now: u32) -> Result<Self, TrustAnchorError> {
+        let zone = element(xml, "Zone").ok_or(TrustAnchorError::InvalidRootAnchors("Zone"))?;
+        let owner = Domain::from_presentation(zone)
+            .map_err(|_| TrustAnchorError::InvalidRootAnchors("Zone"))?;
+        let mut trust_point = Self::new(owner);
+
+        let mut rest = xml;
+        while let Some(start) = rest.find("<KeyDigest") {
+            let end = rest[start..]
+                .find("</KeyDigest>")
+                .ok_or(TrustAnchorError::InvalidRootAnchors("KeyDigest"))?;
+            let key_digest = &rest[start..start + end];
+            rest = &rest[start + end..];
+
+            let open_tag = &key_digest[..key_digest.find('>').unwrap_or(key_digest.len())];
+            let valid_from = match attribute(open_tag, "validFrom") {
+                Some(time) => parse_xml_datetime(time)
+                    .ok_or(TrustAnchorError::InvalidRootAnchors("validFrom"))?,
+                None => 0,
+            };
+            let valid_until = match attribute(open_tag, "validUntil") {
+                Some(time) => Some(
+                    parse_xml_datetime(time)
+                        .ok_or(TrustAnchorError::InvalidRootAnchors("validUntil"))?,
+                ),
+                None => None,
+            };
+            if now < valid_from || valid_until.is_some_and(|until| until <= now) {
+                continue;
+            }
+
+            let key = trust_point.parse_key_digest(key_digest)?;
+            trust_point.add_anchor(key, now);
+        }
+
+        match trust_point.anchors.is_empty() {
+            true => Err(TrustAnchorError::NoAnchors),
+            false => Ok(trust_point),
+        }
+    }
+
+    fn parse_key_digest(&self, key_digest: &str) -> Result<AnchorKey, TrustAnchorError> {
+        fn field<T: FromStr>(xml: &str, name: &'static str) -> Result<T, TrustAnchorError> {
+            element(xml, name)
+                .and_then(|value| value.parse().ok())
+                .ok_or(TrustAnchorError::InvalidRootAnchors(name))
+        }
+
+        let ds = Ds {
+            key_tag: field(key_digest, "KeyTag")?,
+            algorithm: field(key_digest, "Algorithm")?,
+            digest_type: field(key_digest, "DigestType")?,
+            digest: element(key_digest, "Digest")
+                .and_then(|digest| {
+                    data_encoding::HEXUPPER_PERMISSIVE
+                        .decode(digest.as_bytes())
+                        .ok()
+                })
+                .ok_or(TrustAnchorError::InvalidRootAnchors("Digest"))?,
+        };
+
+        // Documents published since 2024 also carry the key itself.
+        let Some(public_key) = element(key_digest, "PublicKey") else {
+            return Ok(AnchorKey::Ds(ds));
+        };
+        let dnskey = Dnskey {
+            flags: field(key_digest, "Flags")?,
+            protocol: Dnskey::PROTOCOL,
+            algorithm: ds.algorithm,
+            public_key: data_encoding::BASE64
+                .decode(public_key.as_bytes())
+                .map_err(|_| TrustAnchorError::InvalidRootAnchors("PublicKey"))?,
+        };
+        let anchor = TrustAnchor {
+            key: AnchorKey::Ds(ds.clone()),
+            state: AnchorState::Valid,
+            last_change: 0,
+            hold_down: None,
+        };
+        match anchor.matches(&self.owner, &dnskey) {
+            true => Ok(AnchorKey::Dnskey(dnskey)),
+            false => Err(TrustAnchorError::InvalidRootAnchors("PublicKey")),
+        }
+    }
+
+    pub fn owner(&self) -> &Domain {
+        &self.owner
+    }
+
+    pub fn anchors(&self) -> &[TrustAnchor] {
+        &self.anchors
+    }
+
+    /// Checks if `dnskey` is a trust anchor. Revoked keys never are.
+    pub fn is_trusted(&self, dnskey: &Dnskey) -> bool {
+        !dnskey.is_revoked()
+            && self
+                .anchors
+                .iter()
+                .any(|anchor| anchor.state.is_trusted() && anchor.matches(&self.owner, dnskey))
+    }
+
+    /// Processes a `DNSKEY` set of the trust point fetched at `now`, along
+    /// with the `RRSIG` records covering it.
+    ///
+    /// Keys revoking themselves are processed first, as the revocation is
+    /// authenticated by the revoked key alone. The rest of the update is
+    /// only applied if the set is signed by a trust anchor, otherwise
+    /// [`TrustAnchorError::NotValidated`] is returned.
+    ///
+    /// For more details, see [RFC 5011, Section 2].
+    ///
+    /// [RFC 5011, Section 2]: https://datatracker.ietf.org/doc/html/rfc5011#section-2
+    pub fn update(
+        &mut self,
+        rrset: &RecordSet,
+        rrsigs: &[Rrsig],
+        now: u32,
+    ) -> Result<(), TrustAnchorError> {
+        if rrset.name != self.owner || rrset.r_type != KnownQType::DNSKEY {
+            return Err(TrustAnchorError::NotTrustPoint(rrset.name.clone()));
+        }
+        let dnskeys: Vec<&Dnskey> = rrset
+            .rdatas
+            .iter()
+            .filter_map(|rdata| match rdata {
+                RData::DNSKEY(dnskey) if dnskey.is_secure_entry_point() => Some(dnskey),
+                _ => None,
+            })
+            .collect();
+        let is_signed_by = |dnskey: &Dnskey| {
+            rrsigs
+                .iter()
+                .any(|rrsig| verify_rrsig(rrset, rrsig, dnskey, now).is_ok())
+        };
+
+        for dnskey in dnskeys.iter().filter(|dnskey| dnskey.is_revoked()) {
+            let owner = &self.owner;
+            let anchor = self.anchors.iter_mut().find(|anchor| {
+                matches!(anchor.state, AnchorState::Valid | AnchorState::Missing)
+                    && anchor.matches(owner, dnskey)
+            });
+            if let Some(anchor) = anchor {
+                if is_signed_by(dnskey) {
+                    anchor.key = AnchorKey::Dnskey(Dnskey {
+                        flags: dnskey.flags & !Dnskey::REVOKE_FLAG,
+                        ..(*dnskey).clone()
+                    });
+                    anchor.transition(AnchorState::Revoked, Some(now + REMOVE_HOLD_DOWN), now);
+                }
+            }
+        }
+        for anchor in &mut self.anchors {
+            if anchor.state == AnchorState::Revoked
+                && anchor.hold_down.is_some_and(|end| end <= now)
+            {
+                anchor.transition(AnchorState::Removed, None, now);
+            }
+        }
+
+        let validated = dnskeys
+            .iter()
+            .any(|dnskey| self.is_trusted(dnskey) && is_signed_by(dnskey));
+        if !validated {
+            return Err(TrustAnchorError::NotValidated);
+        }
+
+        let add_hold_down = ADD_HOLD_DOWN.max(rrset.ttl);
+        let mut seen = vec![false; self.anchors.len()];
+        for dnskey in dnskeys.iter().filter(|dnskey| !dnskey.is_revoked()) {
+            let owner = &self.owner;
+            let index = self
+                .anchors
+                .iter()
+                .position(|anchor| anchor.matches(owner, dnskey));
+            let Some(index) = index else {
+                self.anchors.push(TrustAnchor {
+                    key: AnchorKey::Dnskey((*dnskey).clone()),
+                    state: AnchorState::AddPend,
+                    last_change: now,
+                    hold_down: Some(now + add_hold_down),
+                });
+                seen.push(true);
+                continue;
+            };
+
+            seen[index] = true;
+            let anchor = &mut self.anchors[index];
+            match anchor.state {
+                AnchorState::AddPend if anchor.hold_down.is_some_and(|end| end <= now) => {
+                    anchor.transition(AnchorState::Valid, None, now)
+                }
+                AnchorState::Missing => anchor.transition(AnchorState::Valid, None, now),
+                _ => (),
+            }
+            if anchor.state.is_trusted() {
+                anchor.key = AnchorKey::Dnskey((*dnskey).clone());
+            }
+        }
+
+        // Revoked keys still count as present: they must not go missing.
+        // Pending keys can only be revoked by going back to the start.
+        for dnskey in dnskeys.iter().filter(|dnskey| dnskey.is_revoked()) {
+            for (anchor, seen) in self.anchors.iter().zip(seen.iter_mut()) {
+                *seen |=
+                    anchor.state != AnchorState::AddPend && anchor.matches(&self.owner, dnskey);
+            }
+        }
+        let mut seen = seen.into_iter();
+        self.anchors.retain_mut(|anchor| {
+            let seen = seen.next().unwrap_or(true);
+            match anchor.state {
+                AnchorState::AddPend if !seen => false,
+                AnchorState::Valid if !seen => {
+                    anchor.transition(AnchorState::Missing, None, now);
+                    true
+                }
+                _ => true,
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Formats the trust point as the contents of a state file, one key per
+    /// line followed by its state.
+    pub fn to_state_file(&self) -> String {
+        let mut state_file = format!("; Trust anchors for {}\n", self.owner.to_fqdn_string());
+        for anchor in &self.anchors {
+            let record = match &anchor.key {
+                AnchorKey::Ds(ds) => format!("DS {}", ds),
+                AnchorKey::Dnskey(dnskey) => format!("DNSKEY {}", dnskey),
+            };
+            state_file.push_str(&format!(
+                "{} IN {} ;state={} ;last_change={}",
+                self.owner.to_fqdn_string(),
+                record,
+                anchor.state,
+                anchor.last_change
+            ));
+            if let Some(hold_down) = anchor.hold_down {
+                state_file.push_str(&format!(" ;hold_down={}", hold_down));
+            }
+            state_file.push('\n');
+        }
+        state_file
+    }
+
+    /// Parses the contents of a state file written by
+    /// [`TrustPoint::to_state_file`].
+    pub fn from_state_file(state_file: &str) -> Result<Self, TrustAnchorError> {
+        let mut trust_point: Option<TrustPoint> = None;
+        for (index, line) in state_file.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(';') {
+                continue;
+            }
+            let invalid = || TrustAnchorError::InvalidStateLine(index + 1);
+
+            let mut parts = line.split(';');
+            let record = parts.next().unwrap_or_default();
+            let (owner, key) = parse_anchor_record(record).ok_or_else(invalid)?;
+            let mut anchor = TrustAnchor {
+                key,
+                state: AnchorState::Valid,
+                last_change: 0,
+                hold_down: None,
+            };
+            for part in parts {
+                let (name, value) = part.trim().split_once('=').ok_or_else(invalid)?;
+                match name {
+                    "state" => anchor.state = value.parse()?,
+                    "last_change" => anchor.last_change = value.parse().map_err(|_| invalid())?,
+                    "hold_down" => anchor.hold_down = Some(value.parse().map_err(|_| invalid())?),
+                    _ => return Err(invalid()),
+                }
+            }
+
+            let trust_point = trust_point.get_or_insert_with(|| TrustPoint::new(owner.clone()));
+            if trust_point.owner != owner {
+                return Err(invalid());
+            }
+            trust_point.anchors.push(anchor);
+        }
+        trust_point.ok_or(TrustAnchorError::NoAnchors)
+    }
+
+    /// Writes the state file to `path`, replacing it atomically.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), TrustAnchorError> {
+        let path = path.as_ref();
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        fs::write(&temporary, self.to_state_file())?;
+        fs::rename(&temporary, path)?;
+        Ok(())
+    }
+
+    /// Reads a state file written by [`TrustPoint::save`].
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, TrustAnchorError> {
+        Self::from_state_file(&fs::read_to_string(path)?)
+    }
+}
+
+/// Returns how long to wait before fetching the `DNSKEY` set of a trust
+/// point again, given a `DNSKEY` set signature valid at `now`.
+///
+/// For more details, see [RFC 5011, Section 2.3].
+///
+/// [RFC 5011, Section 2.3]: https://datatracker.ietf.org/doc/html/rfc5011#section-2.3
+pub fn refresh_interval(rrsig: &Rrsig, now: u32) -> u32 {
+    let remaining = rrsig.signature_expiration.saturating_sub(now);
+    (15 * DAY)
+        .min(rrsig.original_ttl / 2)
+        .min(remaining / 2)
+        .max(HOUR)
+}
+
+/// Returns how long to wait before fetching the `DNSKEY` set again after a
+/// failed attempt.
+///
+/// For more details, see [RFC 5011, Section 2.3].
+///
+/// [RFC 5011, Section 2.3]: https://datatracker.ietf.org/doc/html/rfc5011#section-2.3
+pub fn retry_interval(rrsig: &Rrsig, now: u32) -> u32 {
+    let remaining = rrsig.signature_expiration.saturating_sub(now);
+    DAY.min(rrsig.original_ttl / 10)
+        .min(remaining / 10)
+        .max(HOUR)
+}
+
+fn parse_anchor_record(record: &str) -> Option<(Domain, AnchorKey)> {
+    let mut tokens = record.split_whitespace();
+    let owner = Domain::from_presentation(tokens.next()?).ok()?;
+    if tokens.next()? != "IN" {
+        return None;
+    }
+    let r_type = tokens.next()?;
+    let (first, second, third) = (
+        tokens.next()?.parse().ok()?,
+        tokens.next()?.parse().ok()?,
+        tokens.next()?.parse().ok()?,
+    );
+    let data: String = tokens.collect();
+    let key = match r_type {
+        "DS" => AnchorKey::Ds(Ds {
+            key_tag: first,
+            algorithm: second,
+            digest_type: third,
+            digest: data_encoding::HEXUPPER_PERMISSIVE
+                .decode(data.as_bytes())
+                .ok()?,
+        }),
+        "DNSKEY" => AnchorKey::Dnskey(Dnskey {
+            flags: first,
+            protocol: second,
+            algorithm: third,
+            public_key: data_encoding::BASE64.decode(data.as_bytes()).ok()?,
+        }),
+        _ => return None,
+    };
+    Some((owner, key))
+}
+
+/// Returns the trimmed text of the first `<name>` element in `xml`.
+fn element<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    let open = format!("<{}>", name);
+    let close = format!("</{}>", name);
+    let start = xml.find(&open)? + open.len();
+    let end = xml[start..].find(&close)? + start;
+    Some(xml[start..end].trim())
+}
+
+/// Returns the value of the attribute `name` in the opening tag `tag`.
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let prefix = format!(" {}=\"", name);
+    let start = tag.find(&prefix)? + prefix.len();
+    let end = tag[start..].find('"')? + start;
+    Some(&tag[start..end])
+}
+
+/// Parses an XML Schema `dateTime` such as `2017-02-02T00:00:00+00:00`
+/// into seconds since the epoch.
+fn parse_xml_datetime(datetime: &str) -> Option<u32> {
+    let (date, time) = datetime.split_once('T')?;
+    let mut date = date.split('-').map(str::parse::<i64>);
+    let (year, month, day) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+
+    let (time, offset) = match time.find(['+', '-', 'Z']) {
+        Some(index) => time.split_at(index),
+        None => (time, ""),
+    };
+    let mut time = time
+        .split(':')
+        .map(|part| part.split('.').next()?.parse::<i64>().ok());
+    let (hours, minutes, seconds) = (time.next()??, time.next()??, time.next()??);
+    let offset = match offset {
+        "" | "Z" => 0,
+        _ => {
+            let sign = if offset.starts_with('-') { -1 } else { 1 };
+            let (offset_hours, offset_minutes) = offset[1..].split_once(':')?;
+            sign * (offset_hours.parse::<i64>().ok()? * 3_600
+                + offset_minutes.parse::<i64>().ok()? * 60)
+        }
+    };
+
+    // Days since 1970-01-01, in the proleptic Gregorian calendar, with eras
+    // of 400 years starting on March 1st.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    u32::try_from(days * 86_400 + hours * 3_600 + minutes * 60 + seconds - offset).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dnssec::signer::sign_rrset;
+    use crate::dnssec::{Algorithm, SigningConfig, SigningKey};
+    use crate::message::KnownQClass;
+    use rstest::rstest;
+
+    const ROOT_ANCHORS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<TrustAnchor id="380DC50D-484E-40D0-A3AE-68F2B18F61C7" source="http://data.iana.org/root-anchors/root-anchors.xml">
+<Zone>.</Zone>
+<KeyDigest id="Kjqmt7v" validFrom="2010-07-15T00:00:00+00:00" validUntil="2019-01-11T00:00:00+00:00">
+<KeyTag>19036</KeyTag>
+<Algorithm>8</Algorithm>
+<DigestType>2</DigestType>
+<Digest>49AAC11D7B6F6446702E54A1607371607A1A41855200FD2CE1CDDE32F24E8FB5</Digest>
+</KeyDigest>
+<KeyDigest id="Klajeyz" validFrom="2017-02-02T00:00:00+00:00">
+<KeyTag>20326</KeyTag>
+<Algorithm>8</Algorithm>
+<DigestType>2</DigestType>
+<Digest>E06D44B80B8F1D39A95C0B0D7C65D08458E880409BBC683457104237C7F8EC8D</Digest>
+</KeyDigest>
+</TrustAnchor>
+"#;
+
+    // 2018-01-01T00:00:00Z and 2020-01-01T00:00:00Z.
+    const IN_2018: u32 = 1514764800;
+    const IN_2020: u32 = 1577836800;
+
+    fn root() -> Domain {
+        Domain::from_presentation(".").unwrap()
+    }
+
+    fn ksk() -> SigningKey {
+        SigningKey::generate(root(), Algorithm::Ed25519, 257).unwrap()
+    }
+
+    /// Builds the root `DNSKEY` set out of `keys`, signed by `signers`.
+    fn dnskey_set(keys: &[&SigningKey], signers: &[&SigningKey]) -> (RecordSet, Vec<Rrsig>) {
+        let rrset = RecordSet {
+            name: root(),
+            r_type: KnownQType::DNSKEY.into(),
+            r_class: KnownQClass::IN.into(),
+            ttl: 172800,
+            rdatas: keys
+                .iter()
+                .map(|key| RData::DNSKEY(key.dnskey().clone()))
+                .collect(),
+        };
+        let config = SigningConfig::new(0, u32::MAX);
+        let rrsigs = signers
+            .iter()
+            .map(|key| match sign_rrset(&rrset, key, &config).rdata {
+                RData::RRSIG(rrsig) => rrsig,
+                _ => unreachable!(),
+            })
+            .collect();
+        (rrset, rrsigs)
+    }
+
+    fn update(
+        trust_point: &mut TrustPoint,
+        keys: &[&SigningKey],
+        signers: &[&SigningKey],
+        now: u32,
+    ) {
+        let (rrset, rrsigs) = dnskey_set(keys, signers);
+        trust_point.update(&rrset, &rrsigs, now).unwrap();
+    }
+
+    fn states(trust_point: &TrustPoint) -> Vec<AnchorState> {
+        trust_point
+            .anchors()
+            .iter()
+            .map(|anchor| anchor.state)
+            .collect()
+    }
+
+    fn trust_point_for(key: &SigningKey) -> TrustPoint {
+        let mut trust_point = TrustPoint::new(root());
+        let ds = ds_from_dnskey(&root(), key.dnskey(), DigestType::Sha256);
+        trust_point.add_anchor(AnchorKey::Ds(ds), 0);
+        trust_point
+    }
+
+    #[rstest]
+    #[case(IN_2018, vec![19036, 20326])]
+    #[case(IN_2020, vec![20326])]
+    fn trust_point_from_root_anchors(#[case] now: u32, #[case] key_tags: Vec<u16>) {
+        let trust_point = TrustPoint::from_root_anchors(ROOT_ANCHORS, now).unwrap();
+
+        assert!(trust_point.owner().is_root());
+        let anchors: Vec<u16> = trust_point
+            .anchors()
+            .iter()
+            .map(|anchor| match &anchor.key {
+                AnchorKey::Ds(ds) => ds.key_tag,
+                AnchorKey::Dnskey(_) => panic!("unexpected key"),
+            })
+            .collect();
+        assert_eq!(anchors, key_tags);
+        assert_eq!(
+            states(&trust_point),
+            vec![AnchorState::Valid; key_tags.len()]
+        );
+    }
+
+    #[test]
+    fn trust_point_from_root_anchors_with_public_key() {
+        let key = ksk();
+        let ds = ds_from_dnskey(&root(), key.dnskey(), DigestType::Sha256);
+        let xml = format!(
+            "<TrustAnchor><Zone>.</Zone><KeyDigest id=\"K\" validFrom=\"2024-07-18T00:00:00+00:00\">\
+             <KeyTag>{}</KeyTag><Algorithm>15</Algorithm><DigestType>2</DigestType>\
+             <Digest>{}</Digest><Flags>257</Flags><PublicKey>{}</PublicKey></KeyDigest></TrustAnchor>",
+            ds.key_tag,
+            data_encoding::HEXUPPER.encode(&ds.digest),
+            data_encoding::BASE64.encode(&key.dnskey().public_key),
+        );
+
+        let trust_point = TrustPoint::from_root_anchors(&xml, u32::MAX).unwrap();
+        assert_eq!(
+            trust_point.anchors()[0].key,
+            AnchorKey::Dnskey(key.dnskey().clone())
+        );
+        assert!(trust_point.is_trusted(key.dnskey()));
+
+        let forged = xml.replace("<Flags>257", "<Flags>256");
+        assert!(matches!(
+            TrustPoint::from_root_anchors(&forged, u32::MAX),
+            Err(TrustAnchorError::InvalidRootAnchors("PublicKey"))
+        ));
+    }
+
+    #[rstest]
+    #[case("<TrustAnchor></TrustAnchor>", "Zone")]
+    #[case(
+        "<Zone>.</Zone><KeyDigest id=\"K\"><KeyTag>x</KeyTag></KeyDigest>",
+        "KeyTag"
+    )]
+    #[case(
+        "<Zone>.</Zone><KeyDigest id=\"K\" validFrom=\"yesterday\"></KeyDigest>",
+        "validFrom"
+    )]
+    #[case("<Zone>.</Zone><KeyDigest id=\"K\">", "KeyDigest")]
+    fn trust_point_from_root_anchors_fails(#[case] xml: &str, #[case] field: &str) {
+        assert!(matches!(
+            TrustPoint::from_root_anchors(xml, 0),
+            Err(TrustAnchorError::InvalidRootAnchors(f)) if f == field
+        ));
+    }
+
+    #[test]
+    fn trust_point_from_root_anchors_fails_when_all_expired() {
+        let xml = ROOT_ANCHORS.replace(
+            "<KeyDigest id=\"Klajeyz\"",
+            "<KeyDigest validUntil=\"2019-01-11T00:00:00Z\"",
+        );
+        assert!(matches!(
+            TrustPoint::from_root_anchors(&xml, IN_2020),
+            Err(TrustAnchorError::NoAnchors)
+        ));
+    }
+
+    #[test]
+    fn trust_point_replays_key_rollover() {
+        let (old, new) = (ksk(), ksk());
+        let mut trust_point = trust_point_for(&old);
+        let mut clock = 1_000_000;
+
+        // The configured digest is resolved to the key on first sight.
+        update(&mut trust_point, &[&old], &[&old], clock);
+        assert_eq!(states(&trust_point), [AnchorState::Valid]);
+        assert_eq!(
+            trust_point.anchors()[0].key,
+            AnchorKey::Dnskey(old.dnskey().clone())
+        );
+
+        // The new key is published and waits for the add hold-down.
+        clock += DAY;
+        update(&mut trust_point, &[&old, &new], &[&old], clock);
+        assert_eq!(
+            states(&trust_point),
+            [AnchorState::Valid, AnchorState::AddPend]
+        );
+        assert_eq!(
+            trust_point.anchors()[1].hold_down,
+            Some(clock + ADD_HOLD_DOWN)
+        );
+        assert!(!trust_point.is_trusted(new.dnskey()));
+
+        clock += ADD_HOLD_DOWN - 1;
+        update(&mut trust_point, &[&old, &new], &[&old], clock);
+        assert_eq!(
+            states(&trust_point),
+            [AnchorState::Valid, AnchorState::AddPend]
+        );
+
+        clock += 1;
+        update(&mut trust_point, &[&old, &new], &[&old], clock);
+        assert_eq!(
+            states(&trust_point),
+            [AnchorState::Valid, AnchorState::Valid]
+        );
+        assert!(trust_point.is_trusted(new.dnskey()));
+
+        // The old key revokes itself, the new one signs from now on.
+        clock += DAY;
+        let revoked = old.revoke();
+        update(
+            &mut trust_point,
+            &[&revoked, &new],
+            &[&revoked, &new],
+            clock,
+        );
+        assert_eq!(
+            states(&trust_point),
+            [AnchorState::Revoked, AnchorState::Valid]
+        );
+        assert!(!trust_point.is_trusted(old.dnskey()));
+        assert!(!trust_point.is_trusted(revoked.dnskey()));
+
+        clock += REMOVE_HOLD_DOWN;
+        update(&mut trust_point, &[&new], &[&new], clock);
+        assert_eq!(
+            states(&trust_point),
+            [AnchorState::Removed, AnchorState::Valid]
+        );
+
+        // A removed key is never trusted again.
+        clock += DAY;
+        update(&mut trust_point, &[&old, &new], &[&new], clock);
+        assert_eq!(
+            states(&trust_point),
+            [AnchorState::Removed, AnchorState::Valid]
+        );
+        assert!(!trust_point.is_trusted(old.dnskey()));
+    }
+
+    #[test]
+    fn trust_point_tracks_missing_keys() {
+        let (first, second) = (ksk(), ksk());
+        let mut trust_point = trust_point_for(&first);
+        trust_point.add_anchor(AnchorKey::Dnskey(second.dnskey().clone()), 0);
+
+        update(&mut trust_point, &[&second], &[&second], 10);
+        assert_eq!(
+            states(&trust_point),
+            [AnchorState::Missing, AnchorState::Valid]
+        );
+        assert!(trust_point.is_trusted(first.dnskey()));
+
+        update(&mut trust_point, &[&first, &second], &[&first], 20);
+        assert_eq!(
+            states(&trust_point),
+            [AnchorState::Valid, AnchorState::Valid]
+        );
+        assert_eq!(trust_point.anchors()[0].last_change, 20);
+    }
+
+    #[test]
+    fn trust_point_forgets_pending_keys_that_disappear() {
+        let (trusted, pending) = (ksk(), ksk());
+        let mut trust_point = trust_point_for(&trusted);
+
+        update(&mut trust_point, &[&trusted, &pending], &[&trusted], 10);
+        assert_eq!(
+            states(&trust_point),
+            [AnchorState::Valid, AnchorState::AddPend]
+        );
+
+        update(&mut trust_point, &[&trusted], &[&trusted], 20);
+        assert_eq!(states(&trust_point), [AnchorState::Valid]);
+
+        // Seeing the key again restarts the hold-down.
+        update(&mut trust_point, &[&trusted, &pending], &[&trusted], 30);
+        assert_eq!(trust_point.anchors()[1].hold_down, Some(30 + ADD_HOLD_DOWN));
+    }
+
+    #[test]
+    fn trust_point_forgets_pending_keys_that_are_revoked() {
+        let (trusted, pending) = (ksk(), ksk());
+        let mut trust_point = trust_point_for(&trusted);
+
+        update(&mut trust_point, &[&trusted, &pending], &[&trusted], 10);
+        assert_eq!(
+            states(&trust_point),
+            [AnchorState::Valid, AnchorState::AddPend]
+        );
+
+        let revoked = pending.revoke();
+        update(
+            &mut trust_point,
+            &[&trusted, &revoked],
+            &[&trusted, &revoked],
+            20,
+        );
+        assert_eq!(states(&trust_point), [AnchorState::Valid]);
+    }
+
+    #[test]
+    fn trust_point_ignores_unauthenticated_revocation() {
+        let (trusted, other) = (ksk(), ksk());
+        let mut trust_point = trust_point_for(&trusted);
+        trust_point.add_anchor(AnchorKey::Dnskey(other.dnskey().clone()), 0);
+
+        // The revoked key does not sign the set itself.
+        let revoked = trusted.revoke();
+        update(&mut trust_point, &[&revoked, &other], &[&other], 10);
+        assert_eq!(
+            states(&trust_point),
+            [AnchorState::Valid, AnchorState::Valid]
+        );
+    }
+
+    #[test]
+    fn trust_point_update_fails_without_trusted_signature() {
+        let (trusted, attacker) = (ksk(), ksk());
+        let mut trust_point = trust_point_for(&trusted);
+        let before = trust_point.clone();
+
+        let (rrset, rrsigs) = dnskey_set(&[&trusted, &attacker], &[&attacker]);
+        assert!(matches!(
+            trust_point.update(&rrset, &rrsigs, 10),
+            Err(TrustAnchorError::NotValidated)
+        ));
+        assert_eq!(trust_point, before);
+
+        let mut rrset = rrset;
+        rrset.name = Domain::from_presentation("example.").unwrap();
+        assert!(matches!(
+            trust_point.update(&rrset, &rrsigs, 10),
+            Err(TrustAnchorError::NotTrustPoint(_))
+        ));
+    }
+
+    #[test]
+    fn trust_point_state_file_round_trip() {
+        let (old, new) = (ksk(), ksk());
+        let mut trust_point = TrustPoint::from_root_anchors(ROOT_ANCHORS, IN_2020).unwrap();
+        trust_point.add_anchor(AnchorKey::Dnskey(old.dnskey().clone()), 0);
+        update(&mut trust_point, &[&old, &new], &[&old], IN_2020);
+
+        let state_file = trust_point.to_state_file();
+        assert!(state_file.starts_with("; Trust anchors for .\n"));
+        assert!(state_file.contains(
+            ". IN DS 20326 8 2 E06D44B80B8F1D39A95C0B0D7C65D08458E880409BBC683457104237C7F8EC8D ;state=MISSING"
+        ));
+        assert!(state_file.contains(&format!(
+            " ;state=ADDPEND ;last_change={} ;hold_down={}\n",
+            IN_2020,
+            IN_2020 + ADD_HOLD_DOWN
+        )));
+        assert_eq!(
+            TrustPoint::from_state_file(&state_file).unwrap(),
+            trust_point
+        );
+
+        let path = std::env::temp_dir().join(format!("dns_lib-{}.anchors", std::process::id()));
+        trust_point.save(&path).unwrap();
+        let loaded = TrustPoint::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, trust_point);
+    }
+
+    #[rstest]
+    #[case("", TrustAnchorError::NoAnchors)]
+    #[case(". IN A 1.2.3.4", TrustAnchorError::InvalidStateLine(1))]
+    #[case("; comment\n. IN DS 1 8 2 AA ;state=GONE", TrustAnchorError::InvalidState("GONE".to_string()))]
+    #[case(
+        ". IN DS 1 8 2 AA ;hold_down=soon",
+        TrustAnchorError::InvalidStateLine(1)
+    )]
+    #[case(
+        ". IN DS 1 8 2 AA\nexample. IN DS 1 8 2 AA",
+        TrustAnchorError::InvalidStateLine(2)
+    )]
+    fn trust_point_from_state_file_fails(
+        #[case] state_file: &str,
+        #[case] expected: TrustAnchorError,
+    ) {
+        let error = TrustPoint::from_state_file(state_file).unwrap_err();
+        assert_eq!(error.to_string(), expected.to_string());
+    }
+
+    #[rstest]
+    #[case(172800, u32::MAX, 86400)]
+    #[case(172800, 1000 + 7200, 3600)]
+    #[case(1, u32::MAX, 3600)]
+    #[case(86400 * 60, u32::MAX, 15 * 86400)]
+    fn refresh_interval_works(
+        #[case] original_ttl: u32,
+        #[case] expiration: u32,
+        #[case] expected: u32,
+    ) {
+        let (_, mut rrsigs) = dnskey_set(&[&ksk()], &[&ksk()]);
+        let mut rrsig = rrsigs.remove(0);
+        rrsig.original_ttl = original_ttl;
+        rrsig.signature_expiration = expiration;
+        assert_eq!(refresh_interval(&rrsig, 1000), expected);
+    }
+
+    #[rstest]
+    #[case(172800, u32::MAX, 17280)]
+    #[case(86400 * 30, u32::MAX, 86400)]
+    #[case(600, u32::MAX, 3600)]
+    fn retry_interval_works(
+        #[case] original_ttl: u32,
+        #[case] expiration: u32,
+        #[case] expected: u32,
+    ) {
+        let (_, mut rrsigs) = dnskey_set(&[&ksk()], &[&ksk()]);
+        let mut rrsig = rrsigs.remove(0);
+        rrsig.original_ttl = original_ttl;
+        rrsig.signature_expiration = expiration;
+        assert_eq!(retry_interval(&rrsig, 1000), expected);
+    }
+
+    #[rstest]
+    #[case("2017-02-02T00:00:00+00:00", Some(1485993600))]
+    #[case("2010-07-15T00:00:00Z", Some(1279152000))]
+    #[case("2024-07-18T02:00:00+02:00", Some(1721260800))]
+    #[case("2024-02-29T12:30:15", Some(1709209815))]
+    #[case("2024-02-29", None)]
+    fn parse_xml_datetime_works(#[case] datetime: &str, #[case] expected: Option<u32>) {
+        assert_eq!(parse_xml_datetime(datetime), expected);
+    }
+}
@@ -0,0 +1,189 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dnssec::error::{KeyError, ValidationError};
+use crate::dnssec::key::verify_signature;
+use crate::record::rdata::{Dnskey, Rrsig};
+use crate::record::RecordSet;
+
+/// Builds the data covered by `rrsig` over `rrset`: the `RRSIG` fields but
+/// the signature itself, followed by every record of the set in canonical
+/// form and order, using the original TTL of the signature.
+///
+/// When the signature label count is lower than that of the owner name,
+/// the record set was synthesized from a wildcard and the wildcard owner
+/// name is used instead.
+///
+/// For more details, see [RFC 4034, Section 3.1.8.1] and
+/// [RFC 4035, Section 5.3.2].
+///
+/// [RFC 4034, Section 3.1.8.1]: https://datatracker.ietf.org/doc/html/rfc4034#section-3.1.8.1
+/// [RFC 4035, Section 5.3.2]: https://datatracker.ietf.org/doc/html/rfc4035#section-5.3.2
+pub fn signed_data(rrset: &RecordSet, rrsig: &Rrsig) -> Vec<u8> {
+    let labels = rrset.name.label_count();
+    let signed_labels = rrsig.labels as usize;
+    let mut owner = rrset.name.to_canonical_bytes();
+    if signed_labels < labels {
+        let mut suffix = rrset.name.clone();
+        for _ in signed_labels..labels {
+            suffix = suffix.parent().unwrap_or_default();
+        }
+        owner = vec![1, b'*'];
+        owner.extend_from_slice(&suffix.to_canonical_bytes());
+    }
+
+    let mut data = rrsig.to_signed_prefix();
+    for rdata in rrset.canonical_rdatas() {
+        data.extend_from_slice(&owner);
+        data.extend_from_slice(&rrset.r_type.value.to_be_bytes());
+        data.extend_from_slice(&rrset.r_class.value.to_be_bytes());
+        data.extend_from_slice(&rrsig.original_ttl.to_be_bytes());
+        data.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        data.extend_from_slice(&rdata);
+    }
+    data
+}
+
+/// Verifies that `rrsig` is a valid signature of `rrset` made with
+/// `dnskey`, at time `now` expressed in seconds since the UNIX epoch.
+///
+/// For more details, see [RFC 4035, Section 5.3].
+///
+/// [RFC 4035, Section 5.3]: https://datatracker.ietf.org/doc/html/rfc4035#section-5.3
+pub fn verify_rrsig(
+    rrset: &RecordSet,
+    rrsig: &Rrsig,
+    dnskey: &Dnskey,
+    now: u32,
+) -> Result<(), ValidationError> {
+    if rrsig.type_covered != rrset.r_type {
+        return Err(ValidationError::TypeMismatch);
+    }
+
+    let labels = match rrset.name.is_wildcard() {
+        true => rrset.name.label_count() - 1,
+        false => rrset.name.label_count(),
+    };
+    if rrsig.labels as usize > labels {
+        return Err(ValidationError::InvalidLabelCount(rrsig.labels));
+    }
+
+    if !dnskey.is_zone_key()
+        || dnskey.protocol != Dnskey::PROTOCOL
+        || dnskey.algorithm != rrsig.algorithm
+        || dnskey.key_tag() != rrsig.key_tag
+    {
+        return Err(ValidationError::KeyMismatch);
+    }
+
+    if now < rrsig.signature_inception {
+        return Err(ValidationError::NotYetValid(rrsig.signature_inception));
+    }
+    if now > rrsig.signature_expiration {
+        return Err(ValidationError::Expired(rrsig.signature_expiration));
+    }
+
+    let data = signed_data(rrset, rrsig);
+    match verify_signature(dnskey, &data, &rrsig.signature) {
+        Ok(true) => Ok(()),
+        Ok(false) | Err(KeyError::InvalidKeyMaterial) => Err(ValidationError::InvalidSignature),
+        Err(_) => Err(ValidationError::UnsupportedAlgorithm(dnskey.algorithm)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Domain;
+    use crate::message::{KnownQClass, KnownQType};
+    use crate::record::rdata::Mx;
+    use crate::record::RData;
+    use rstest::rstest;
+
+    // Example from RFC 8080, Section 6.1.
+    fn example() -> (RecordSet, Rrsig, Dnskey) {
+        let rrset = RecordSet {
+            name: Domain::from_presentation("example.com").unwrap(),
+            r_type: KnownQType::MX.into(),
+            r_class: KnownQClass::IN.into(),
+            ttl: 3600,
+            rdatas: vec![RData::MX(Mx {
+                preference: 10,
+                exchange: Domain::from_presentation("mail.example.com").unwrap(),
+            })],
+        };
+        let rrsig = Rrsig {
+            type_covered: KnownQType::MX.into(),
+            algorithm: 15,
+            labels: 2,
+            original_ttl: 3600,
+            signature_expiration: 1440021600,
+            signature_inception: 1438207200,
+            key_tag: 3613,
+            signer_name: Domain::from_presentation("example.com").unwrap(),
+            signature: data_encoding::BASE64
+                .decode(b"oL9krJun7xfBOIWcGHi7mag5/hdZrKWw15jPGrHpjQeRAvTdszaPD+QLs3fx8A4M3e23mRZ9VrbpMngwcrqNAg==")
+                .unwrap(),
+        };
+        let dnskey = Dnskey {
+            flags: 257,
+            protocol: 3,
+            algorithm: 15,
+            public_key: data_encoding::BASE64
+                .decode(b"l02Woi0iS8Aa25FQkUd9RMzZHJpBoRQwAQEX1SxZJA4=")
+                .unwrap(),
+        };
+        (rrset, rrsig, dnskey)
+    }
+
+    #[test]
+    fn verify_rrsig_succeeds() {
+        let (rrset, rrsig, dnskey) = example();
+        assert_eq!(verify_rrsig(&rrset, &rrsig, &dnskey, 1439000000), Ok(()));
+    }
+
+    #[rstest]
+    #[case(|_: &mut RecordSet, s: &mut Rrsig, _: &mut Dnskey| s.type_covered = KnownQType::A.into(), ValidationError::TypeMismatch)]
+    #[case(|_: &mut RecordSet, s: &mut Rrsig, _: &mut Dnskey| s.labels = 3, ValidationError::InvalidLabelCount(3))]
+    #[case(|_: &mut RecordSet, s: &mut Rrsig, _: &mut Dnskey| s.key_tag = 1, ValidationError::KeyMismatch)]
+    #[case(|_: &mut RecordSet, _: &mut Rrsig, k: &mut Dnskey| k.flags = 1, ValidationError::KeyMismatch)]
+    #[case(|_: &mut RecordSet, s: &mut Rrsig, _: &mut Dnskey| s.signature_inception = 1439000001, ValidationError::NotYetValid(1439000001))]
+    #[case(|_: &mut RecordSet, s: &mut Rrsig, _: &mut Dnskey| s.signature_expiration = 1438999999, ValidationError::Expired(1438999999))]
+    #[case(|r: &mut RecordSet, _: &mut Rrsig, _: &mut Dnskey| r.name = Domain::from_presentation("example.net").unwrap(), ValidationError::InvalidSignature)]
+    #[case(|r: &mut RecordSet, _: &mut Rrsig, _: &mut Dnskey| r.rdatas.push(RData::MX(Mx { preference: 20, exchange: Domain::new() })), ValidationError::InvalidSignature)]
+    fn verify_rrsig_fails(
+        #[case] tamper: fn(&mut RecordSet, &mut Rrsig, &mut Dnskey),
+        #[case] expected: ValidationError,
+    ) {
+        let (mut rrset, mut rrsig, mut dnskey) = example();
+        tamper(&mut rrset, &mut rrsig, &mut dnskey);
+        assert_eq!(
+            verify_rrsig(&rrset, &rrsig, &dnskey, 1439000000),
+            Err(expected)
+        );
+    }
+
+    #[test]
+    fn signed_data_uses_wildcard_owner() {
+        let (mut rrset, mut rrsig, _) = example();
+        rrset.name = Domain::from_presentation("a.b.example.com").unwrap();
+        rrsig.labels = 2;
+        let data = signed_data(&rrset, &rrsig);
+        let prefix_len = rrsig.to_signed_prefix().len();
+        assert_eq!(
+            data[prefix_len..prefix_len + 15],
+            *b"\x01*\x07example\x03com\x00"
+        );
+    }
+}
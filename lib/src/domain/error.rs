@@ -45,6 +45,35 @@ impl fmt::Display for TryFromError {
     }
 }
 
+/// Errors found while replacing the suffix of a domain name, see
+/// [`Domain::substitute`](crate::Domain::substitute).
+#[derive(Debug, PartialEq)]
+pub enum SubstitutionError {
+    NotBelowOwner,
+    /// The resulting name has the given length in wire format, more than the
+    /// maximum of 255 octets. A server answers such queries with
+    /// `YXDOMAIN`, see [RFC 6672, Section 2.2].
+    ///
+    /// [RFC 6672, Section 2.2]: https://datatracker.ietf.org/doc/html/rfc6672#section-2.2
+    TooLong(usize),
+}
+
+impl fmt::Display for SubstitutionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotBelowOwner => write!(f, "name is not below the owner of the DNAME"),
+            Self::TooLong(len) => write!(
+                f,
+                "substituted name has {} octets, more than the maximum of {}",
+                len,
+                domain::name::MAX_NAME_LENGTH
+            ),
+        }
+    }
+}
+
+impl Error for SubstitutionError {}
+
 impl Error for TryFromError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
@@ -12,10 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::domain::error::TryFromError;
+use crate::domain::error::{SubstitutionError, TryFromError};
+use std::cmp::Ordering;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 
 pub const MAX_LABEL_LENGTH: usize = 63;
+/// Maximum length of a domain name in wire format, see [RFC 1035, Section 2.3.4].
+///
+/// [RFC 1035, Section 2.3.4]: https://datatracker.ietf.org/doc/html/rfc1035#section-2.3.4
+pub const MAX_NAME_LENGTH: usize = 255;
 const LABEL_SEPARATOR: char = '.';
 
 /// Representation of a DNS domain name.
@@ -31,7 +37,7 @@ const LABEL_SEPARATOR: char = '.';
 /// For more details, see [RFC 1034, Section 3.5].
 ///
 /// [RFC 1034, Section 3.5]: https://datatracker.ietf.org/doc/html/rfc1034#section-3.5
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Default)]
 pub struct Domain {
     labels: Vec<String>,
 }
@@ -50,6 +56,349 @@ impl Domain {
             Err(e) => Err(e),
         }
     }
+
+    /// Adds a label without enforcing the preferred name syntax.
+    ///
+    /// Owner names found in zone data routinely contain labels that are not
+    /// valid host names, such as `*`, `_tcp` or NSEC3 hashes. The DNS itself
+    /// places no restriction on label contents other than length, so this
+    /// method only checks that the label is not empty and does not exceed
+    /// [`MAX_LABEL_LENGTH`].
+    ///
+    /// For more details, see [RFC 2181, Section 11].
+    ///
+    /// [RFC 2181, Section 11]: https://datatracker.ietf.org/doc/html/rfc2181#section-11
+    pub fn add_raw_label(&mut self, bytes: &[u8]) -> Result<(), TryFromError> {
+        self.labels.push(parse_raw_label(bytes)?);
+        Ok(())
+    }
+
+    /// Parses a domain name written in presentation format.
+    ///
+    /// Unlike [`Domain::try_from`], labels are not required to follow the
+    /// preferred name syntax, and the `\X` and `\DDD` escapes described in
+    /// [RFC 1035, Section 5.1] are honoured. A trailing dot is optional, and
+    /// a lone `.` denotes the root.
+    ///
+    /// # Example
+    /// ```
+    /// use dns_lib::Domain;
+    ///
+    /// let srv = Domain::from_presentation("_sip._tcp.example.com.").unwrap();
+    /// assert_eq!(srv.label_count(), 4);
+    ///
+    /// assert!(Domain::from_presentation(".").unwrap().is_root());
+    /// ```
+    ///
+    /// [RFC 1035, Section 5.1]: https://datatracker.ietf.org/doc/html/rfc1035#section-5.1
+    pub fn from_presentation(text: &str) -> Result<Self, TryFromError> {
+        if text.is_empty() {
+            return Err(TryFromError::DomainEmpty);
+        }
+
+        let mut domain = Domain::new();
+        if text == "." {
+            return Ok(domain);
+        }
+
+        let bytes = text.as_bytes();
+        let mut label = Vec::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\\' => {
+                    let digits = bytes
+                        .get(i + 1..i + 4)
+                        .filter(|d| d.iter().all(u8::is_ascii_digit));
+                    match digits {
+                        Some(d) => {
+                            let value = (d[0] - b'0') as u16 * 100
+                                + (d[1] - b'0') as u16 * 10
+                                + (d[2] - b'0') as u16;
+                            if value > 255 {
+                                return Err(TryFromError::LabelInvalidFormat(text.to_string()));
+                            }
+                            label.push(value as u8);
+                            i += 4;
+                        }
+                        None => match bytes.get(i + 1) {
+                            Some(&escaped) => {
+                                label.push(escaped);
+                                i += 2;
+                            }
+                            None => return Err(TryFromError::LabelInvalidFormat(text.to_string())),
+                        },
+                    }
+                }
+                b'.' => {
+                    domain.add_raw_label(&label)?;
+                    label.clear();
+                    i += 1;
+                    if i == bytes.len() {
+                        return Ok(domain);
+                    }
+                }
+                byte => {
+                    label.push(byte);
+                    i += 1;
+                }
+            }
+        }
+
+        domain.add_raw_label(&label)?;
+        Ok(domain)
+    }
+
+    /// Parses a domain name written in presentation format, as found in
+    /// master files.
+    ///
+    /// A lone `@` denotes `origin`, and names that do not end with an
+    /// unescaped dot are relative to it, see [RFC 1035, Section 5.1].
+    ///
+    /// # Example
+    /// ```
+    /// use dns_lib::Domain;
+    ///
+    /// let origin = Domain::from_presentation("example.com.").unwrap();
+    /// let www = Domain::from_presentation_relative("www", &origin).unwrap();
+    /// assert_eq!(www.to_string(), "www.example.com");
+    ///
+    /// let other = Domain::from_presentation_relative("www.example.org.", &origin).unwrap();
+    /// assert_eq!(other.to_string(), "www.example.org");
+    /// ```
+    ///
+    /// [RFC 1035, Section 5.1]: https://datatracker.ietf.org/doc/html/rfc1035#section-5.1
+    pub fn from_presentation_relative(text: &str, origin: &Domain) -> Result<Self, TryFromError> {
+        if text == "@" {
+            return Ok(origin.clone());
+        }
+
+        let mut domain = Domain::from_presentation(text)?;
+        // The final dot is escaped if preceded by an odd number of `\`.
+        let absolute = text
+            .strip_suffix('.')
+            .is_some_and(|rest| rest.bytes().rev().take_while(|&b| b == b'\\').count() % 2 == 0);
+        if !absolute {
+            domain.labels.extend(origin.labels.iter().cloned());
+        }
+        Ok(domain)
+    }
+
+    /// Returns the labels of the domain, from the leftmost one to the
+    /// rightmost one. The root domain has no labels.
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    pub fn label_count(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// Checks if the leftmost label is the asterisk label (`*`).
+    pub fn is_wildcard(&self) -> bool {
+        self.labels.first().is_some_and(|label| label == "*")
+    }
+
+    /// Returns the domain obtained by removing the leftmost label, or `None`
+    /// for the root domain.
+    pub fn parent(&self) -> Option<Domain> {
+        self.labels.split_first().map(|(_, rest)| Domain {
+            labels: rest.to_vec(),
+        })
+    }
+
+    /// Returns a new domain with `label` prepended to the current one.
+    ///
+    /// The label is validated as with [`Domain::add_raw_label`].
+    pub fn prepend_label(&self, label: &[u8]) -> Result<Domain, TryFromError> {
+        let mut labels = Vec::with_capacity(self.labels.len() + 1);
+        labels.push(parse_raw_label(label)?);
+        labels.extend(self.labels.iter().cloned());
+        Ok(Domain { labels })
+    }
+
+    /// Checks if the domain is equal to or below `other` in the DNS tree.
+    ///
+    /// # Example
+    /// ```
+    /// use dns_lib::Domain;
+    ///
+    /// let zone = Domain::try_from("example.com".to_string()).unwrap();
+    /// let host = Domain::try_from("www.example.com".to_string()).unwrap();
+    /// assert!(host.is_subdomain_of(&zone));
+    /// assert!(zone.is_subdomain_of(&zone));
+    /// assert!(!zone.is_subdomain_of(&host));
+    /// ```
+    pub fn is_subdomain_of(&self, other: &Domain) -> bool {
+        self.labels.len() >= other.labels.len()
+            && self
+                .labels
+                .iter()
+                .rev()
+                .zip(other.labels.iter().rev())
+                .all(|(a, b)| a.eq_ignore_ascii_case(b))
+    }
+
+    /// Replaces the `owner` suffix of the domain with `target`, as done when
+    /// a query name is below the owner of a `DNAME` record.
+    ///
+    /// The domain must be strictly below `owner`: a `DNAME` record does not
+    /// redirect its own owner name.
+    ///
+    /// For more details, see [RFC 6672, Section 2.2].
+    ///
+    /// # Example
+    /// ```
+    /// use dns_lib::domain::error::SubstitutionError;
+    /// use dns_lib::Domain;
+    ///
+    /// let owner = Domain::from_presentation("example.com").unwrap();
+    /// let target = Domain::from_presentation("example.net").unwrap();
+    /// let qname = Domain::from_presentation("www.Example.com").unwrap();
+    /// assert_eq!(qname.substitute(&owner, &target).unwrap().to_string(), "www.example.net");
+    /// assert_eq!(owner.substitute(&owner, &target), Err(SubstitutionError::NotBelowOwner));
+    /// ```
+    ///
+    /// [RFC 6672, Section 2.2]: https://datatracker.ietf.org/doc/html/rfc6672#section-2.2
+    pub fn substitute(&self, owner: &Domain, target: &Domain) -> Result<Domain, SubstitutionError> {
+        if self.labels.len() <= owner.labels.len() || !self.is_subdomain_of(owner) {
+            return Err(SubstitutionError::NotBelowOwner);
+        }
+        let prefix = &self.labels[..self.labels.len() - owner.labels.len()];
+        let domain = Domain {
+            labels: prefix.iter().chain(&target.labels).cloned().collect(),
+        };
+        match domain.wire_len() {
+            len if len > MAX_NAME_LENGTH => Err(SubstitutionError::TooLong(len)),
+            _ => Ok(domain),
+        }
+    }
+
+    /// Returns a copy of the domain with all ASCII letters lowercased.
+    pub fn to_lowercase(&self) -> Domain {
+        Domain {
+            labels: self.labels.iter().map(|l| l.to_ascii_lowercase()).collect(),
+        }
+    }
+
+    /// Formats the domain as a fully qualified name, that is, in presentation
+    /// format and with a trailing dot.
+    pub fn to_fqdn_string(&self) -> String {
+        match self.is_root() {
+            true => self.to_string(),
+            false => format!("{}.", self),
+        }
+    }
+
+    /// Formats the domain relative to `origin`, as written in master files:
+    /// `@` for the origin itself, the leading labels for names below it,
+    /// and a fully qualified name otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// use dns_lib::Domain;
+    ///
+    /// let origin = Domain::from_presentation("example.com").unwrap();
+    /// let www = Domain::from_presentation("www.example.com").unwrap();
+    /// assert_eq!(www.to_relative_string(&origin), "www");
+    /// assert_eq!(origin.to_relative_string(&origin), "@");
+    /// ```
+    pub fn to_relative_string(&self, origin: &Domain) -> String {
+        if !self.is_subdomain_of(origin) {
+            return self.to_fqdn_string();
+        }
+        match self.labels.len() - origin.labels.len() {
+            0 => "@".to_string(),
+            count => {
+                let labels: Vec<String> = self.labels[..count]
+                    .iter()
+                    .map(|l| escape_label(l))
+                    .collect();
+                labels.join(&LABEL_SEPARATOR.to_string())
+            }
+        }
+    }
+
+    /// Encodes the domain in uncompressed wire format, as a sequence of
+    /// length-prefixed labels terminated by the zero-length root label.
+    ///
+    /// For more details, see [RFC 1035, Section 3.1].
+    ///
+    /// [RFC 1035, Section 3.1]: https://datatracker.ietf.org/doc/html/rfc1035#section-3.1
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.wire_len());
+        for label in &self.labels {
+            bytes.push(label.len() as u8);
+            bytes.extend_from_slice(label.as_bytes());
+        }
+        bytes.push(0);
+        bytes
+    }
+
+    /// Encodes the domain in the canonical wire format used by DNSSEC, that
+    /// is, uncompressed and with all uppercase US-ASCII letters lowercased.
+    ///
+    /// For more details, see [RFC 4034, Section 6.2].
+    ///
+    /// [RFC 4034, Section 6.2]: https://datatracker.ietf.org/doc/html/rfc4034#section-6.2
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        self.to_lowercase().to_bytes()
+    }
+
+    /// Length in bytes of the domain in uncompressed wire format.
+    pub fn wire_len(&self) -> usize {
+        self.labels.iter().map(|l| l.len() + 1).sum::<usize>() + 1
+    }
+}
+
+impl PartialEq for Domain {
+    /// Domain names are compared without regard to the case of ASCII
+    /// letters, as required by [RFC 4343].
+    ///
+    /// [RFC 4343]: https://datatracker.ietf.org/doc/html/rfc4343
+    fn eq(&self, other: &Self) -> bool {
+        self.labels.len() == other.labels.len()
+            && self
+                .labels
+                .iter()
+                .zip(other.labels.iter())
+                .all(|(a, b)| a.eq_ignore_ascii_case(b))
+    }
+}
+
+impl Eq for Domain {}
+
+impl Hash for Domain {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for label in &self.labels {
+            label.to_ascii_lowercase().hash(state);
+        }
+    }
+}
+
+impl Ord for Domain {
+    /// Orders domain names using the canonical DNS name order: labels are
+    /// compared from the rightmost one as case-insensitive byte strings, and
+    /// a name sorts before all of its subdomains.
+    ///
+    /// For more details, see [RFC 4034, Section 6.1].
+    ///
+    /// [RFC 4034, Section 6.1]: https://datatracker.ietf.org/doc/html/rfc4034#section-6.1
+    fn cmp(&self, other: &Self) -> Ordering {
+        let lhs = self.labels.iter().rev().map(|l| l.to_ascii_lowercase());
+        let rhs = other.labels.iter().rev().map(|l| l.to_ascii_lowercase());
+        lhs.map(String::into_bytes).cmp(rhs.map(String::into_bytes))
+    }
+}
+
+impl PartialOrd for Domain {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 impl TryFrom<String> for Domain {
@@ -121,9 +470,55 @@ impl TryFrom<&[u8]> for Domain {
 }
 
 impl fmt::Display for Domain {
+    /// Formats the domain in presentation format, without the trailing dot.
+    ///
+    /// The root domain is written as a single `.`. Characters with a special
+    /// meaning in master files are escaped as described in
+    /// [RFC 1035, Section 5.1].
+    ///
+    /// [RFC 1035, Section 5.1]: https://datatracker.ietf.org/doc/html/rfc1035#section-5.1
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.labels.join(&LABEL_SEPARATOR.to_string()))
+        if self.labels.is_empty() {
+            return write!(f, "{}", LABEL_SEPARATOR);
+        }
+
+        let labels: Vec<String> = self.labels.iter().map(|l| escape_label(l)).collect();
+        write!(f, "{}", labels.join(&LABEL_SEPARATOR.to_string()))
+    }
+}
+
+fn escape_label(label: &str) -> String {
+    let mut escaped = String::with_capacity(label.len());
+    for byte in label.bytes() {
+        match byte {
+            b'.' | b'\\' | b'"' | b';' | b'(' | b')' | b'@' | b'$' => {
+                escaped.push('\\');
+                escaped.push(byte as char);
+            }
+            0x21..=0x7e => escaped.push(byte as char),
+            _ => escaped.push_str(&format!("\\{:03}", byte)),
+        }
+    }
+    escaped
+}
+
+/// Tries to convert a slice `&[u8]` into a label [`String`] without enforcing
+/// the preferred name syntax.
+fn parse_raw_label(bytes: &[u8]) -> Result<String, TryFromError> {
+    let label = match std::string::String::from_utf8(bytes.to_vec()) {
+        Ok(str) => str,
+        Err(e) => return Err(TryFromError::LabelInvalidEncoding(e)),
+    };
+
+    if bytes.is_empty() {
+        return Err(TryFromError::LabelEmpty);
+    }
+
+    if bytes.len() > MAX_LABEL_LENGTH {
+        return Err(TryFromError::LabelTooLong(label));
     }
+
+    Ok(label)
 }
 
 /// Tries to convert a slice `&[u8]` into a label [`String`].
@@ -139,7 +534,7 @@ pub fn parse_label(bytes: &[u8]) -> Result<String, TryFromError> {
         Err(e) => return Err(TryFromError::LabelInvalidEncoding(e)),
     };
 
-    if bytes.len() == 0 {
+    if bytes.is_empty() {
         return Err(TryFromError::LabelEmpty);
     }
 
@@ -148,7 +543,7 @@ pub fn parse_label(bytes: &[u8]) -> Result<String, TryFromError> {
     }
 
     let (first_byte, remaining_bytes) = bytes.split_at(1);
-    if remaining_bytes.len() == 0 {
+    if remaining_bytes.is_empty() {
         match first_byte[0].is_ascii_alphabetic() {
             true => return Ok(label),
             false => return Err(TryFromError::LabelInvalidFormat(label)),
@@ -158,8 +553,8 @@ pub fn parse_label(bytes: &[u8]) -> Result<String, TryFromError> {
     let (middle_bytes, last_byte) = remaining_bytes.split_at(remaining_bytes.len() - 1);
 
     let first_byte_letter = first_byte[0].is_ascii_alphabetic();
-    let last_byte_letter_digit = last_byte.len() == 0 || last_byte[0].is_ascii_alphanumeric();
-    let middle_bytes_are_ldh_str = middle_bytes.len() == 0 || bytes_are_ldh_str(middle_bytes);
+    let last_byte_letter_digit = last_byte.is_empty() || last_byte[0].is_ascii_alphanumeric();
+    let middle_bytes_are_ldh_str = middle_bytes.is_empty() || bytes_are_ldh_str(middle_bytes);
 
     match first_byte_letter && middle_bytes_are_ldh_str && last_byte_letter_digit {
         true => Ok(label),
@@ -284,4 +679,202 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap().to_string(), input);
     }
+
+    #[test]
+    fn domain_root_to_string() {
+        assert_eq!(Domain::new().to_string(), ".");
+    }
+
+    #[rstest]
+    #[case("example.com", vec!["example", "com"])]
+    #[case("example.com.", vec!["example", "com"])]
+    #[case("_25._tcp.mx.example.com.", vec!["_25", "_tcp", "mx", "example", "com"])]
+    #[case("*.example.com", vec!["*", "example", "com"])]
+    #[case("0p9mhaveqvm6t7vbl5lop2u3t2rp3tom.example", vec!["0p9mhaveqvm6t7vbl5lop2u3t2rp3tom", "example"])]
+    #[case(r"a\.b.example", vec!["a.b", "example"])]
+    #[case(r"\065bc.example", vec!["Abc", "example"])]
+    #[case(".", vec![])]
+    fn domain_from_presentation_succeeds(#[case] input: &str, #[case] labels: Vec<&str>) {
+        let result = Domain::from_presentation(input);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().labels(), labels);
+    }
+
+    #[rstest]
+    #[case("", "domain is empty".to_string())]
+    #[case("cdn..com", "label is empty".to_string())]
+    #[case(r"foo\", r"label 'foo\' has invalid format".to_string())]
+    #[case(r"\256.com", r"label '\256.com' has invalid format".to_string())]
+    fn domain_from_presentation_fails(#[case] input: &str, #[case] error_msg: String) {
+        let result = Domain::from_presentation(input);
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().to_string(), error_msg);
+    }
+
+    #[rstest]
+    #[case("example.com", "@")]
+    #[case("www.Example.com", "www")]
+    #[case("a\\.b.c.example.com", "a\\.b.c")]
+    #[case("example.org", "example.org.")]
+    #[case(".", ".")]
+    fn domain_to_relative_string(#[case] input: &str, #[case] expected: &str) {
+        let origin = Domain::from_presentation("example.com").unwrap();
+        let domain = Domain::from_presentation(input).unwrap();
+        assert_eq!(domain.to_relative_string(&origin), expected);
+    }
+
+    #[rstest]
+    #[case("@", vec!["example", "com"])]
+    #[case("www", vec!["www", "example", "com"])]
+    #[case("a.b", vec!["a", "b", "example", "com"])]
+    #[case("www.example.org.", vec!["www", "example", "org"])]
+    #[case(r"a\.", vec!["a.", "example", "com"])]
+    #[case(r"a\\.", vec![r"a\"])]
+    #[case(".", vec![])]
+    fn domain_from_presentation_relative(#[case] input: &str, #[case] labels: Vec<&str>) {
+        let origin = Domain::from_presentation("example.com.").unwrap();
+        let domain = Domain::from_presentation_relative(input, &origin).unwrap();
+        assert_eq!(domain.labels(), labels);
+    }
+
+    #[rstest]
+    #[case(".", ".")]
+    #[case("_sip._udp.example.com.", "_sip._udp.example.com")]
+    #[case(r"a\.b.example", r"a\.b.example")]
+    #[case(r"semi\;colon.example", r"semi\;colon.example")]
+    #[case(r"\009tab.example", r"\009tab.example")]
+    fn domain_to_string_escapes(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(
+            Domain::from_presentation(input).unwrap().to_string(),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(".", ".")]
+    #[case("example.com", "example.com.")]
+    fn domain_to_fqdn_string(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(
+            Domain::from_presentation(input).unwrap().to_fqdn_string(),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case("example.com", "EXAMPLE.com", true)]
+    #[case("Www.Example.Com", "www.example.com", true)]
+    #[case("www.example.com", "example.com", false)]
+    fn domain_eq_ignores_case(#[case] lhs: &str, #[case] rhs: &str, #[case] expected: bool) {
+        let lhs = Domain::from_presentation(lhs).unwrap();
+        let rhs = Domain::from_presentation(rhs).unwrap();
+        assert_eq!(lhs == rhs, expected);
+    }
+
+    #[test]
+    fn domain_canonical_order() {
+        // Example from RFC 4034, Section 6.1, minus the non-UTF-8 label.
+        let expected = [
+            "example",
+            "a.example",
+            "yljkjljk.a.example",
+            "Z.a.example",
+            "zABC.a.EXAMPLE",
+            "z.example",
+            r"\001.z.example",
+            "*.z.example",
+        ];
+        let mut names: Vec<Domain> = expected
+            .iter()
+            .rev()
+            .map(|name| Domain::from_presentation(name).unwrap())
+            .collect();
+        names.sort();
+
+        let sorted: Vec<String> = names.iter().map(|d| d.to_string()).collect();
+        assert_eq!(sorted, expected);
+    }
+
+    #[rstest]
+    #[case("www.example.com", "example.com", true)]
+    #[case("example.com", "example.com", true)]
+    #[case("WWW.EXAMPLE.COM", "example.com", true)]
+    #[case("example.com", "www.example.com", false)]
+    #[case("wwwexample.com", "example.com", false)]
+    #[case("example.com", ".", true)]
+    fn domain_is_subdomain_of(#[case] name: &str, #[case] parent: &str, #[case] expected: bool) {
+        let name = Domain::from_presentation(name).unwrap();
+        let parent = Domain::from_presentation(parent).unwrap();
+        assert_eq!(name.is_subdomain_of(&parent), expected);
+    }
+
+    #[rstest]
+    #[case("www.example.com", Some("example.com"))]
+    #[case("com", Some("."))]
+    #[case(".", None)]
+    fn domain_parent(#[case] name: &str, #[case] expected: Option<&str>) {
+        let parent = Domain::from_presentation(name).unwrap().parent();
+        assert_eq!(
+            parent,
+            expected.map(|p| Domain::from_presentation(p).unwrap())
+        );
+    }
+
+    #[rstest]
+    #[case(".", &[0])]
+    #[case("Example.COM", &[7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 3, b'c', b'o', b'm', 0])]
+    fn domain_to_canonical_bytes(#[case] input: &str, #[case] expected: &[u8]) {
+        let domain = Domain::from_presentation(input).unwrap();
+        assert_eq!(domain.to_canonical_bytes(), expected);
+        assert_eq!(domain.wire_len(), expected.len());
+    }
+
+    #[rstest]
+    #[case("www.example.com", "example.com", "example.net", "www.example.net")]
+    #[case("a.b.EXAMPLE.com", "example.com", "net", "a.b.net")]
+    #[case("www.example.com", ".", "example.org", "www.example.com.example.org")]
+    #[case("www.example.com", "example.com", ".", "www")]
+    fn domain_substitute(
+        #[case] name: &str,
+        #[case] owner: &str,
+        #[case] target: &str,
+        #[case] expected: &str,
+    ) {
+        let name = Domain::from_presentation(name).unwrap();
+        let owner = Domain::from_presentation(owner).unwrap();
+        let target = Domain::from_presentation(target).unwrap();
+        assert_eq!(
+            name.substitute(&owner, &target),
+            Ok(Domain::from_presentation(expected).unwrap())
+        );
+    }
+
+    #[rstest]
+    #[case("example.com", "example.com")]
+    #[case("example.com", "www.example.com")]
+    #[case("www.example.org", "example.com")]
+    fn domain_substitute_not_below_owner(#[case] name: &str, #[case] owner: &str) {
+        let name = Domain::from_presentation(name).unwrap();
+        let owner = Domain::from_presentation(owner).unwrap();
+        let target = Domain::from_presentation("example.net").unwrap();
+        assert_eq!(
+            name.substitute(&owner, &target),
+            Err(SubstitutionError::NotBelowOwner)
+        );
+    }
+
+    #[test]
+    fn domain_substitute_too_long() {
+        let label = "a".repeat(60);
+        let target = Domain::from_presentation(&[label.as_str(); 4].join(".")).unwrap();
+        let owner = Domain::from_presentation("example.com").unwrap();
+
+        let name = Domain::from_presentation("b.c.d.e.f.example.com").unwrap();
+        assert_eq!(name.substitute(&owner, &target).unwrap().wire_len(), 255);
+
+        let name = Domain::from_presentation("a.b.c.d.e.f.example.com").unwrap();
+        assert_eq!(
+            name.substitute(&owner, &target),
+            Err(SubstitutionError::TooLong(257))
+        );
+    }
 }
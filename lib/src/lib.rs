@@ -12,8 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod caa;
+pub mod dane;
+pub mod ddds;
+pub mod dnssec;
 pub mod domain;
 pub mod message;
+pub mod record;
+pub mod sshfp;
+pub mod transport;
+pub mod tsig;
+pub mod zone;
 
 pub use domain::Domain;
 pub use message::{Header, Message};
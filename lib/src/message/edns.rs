@@ -0,0 +1,157 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::Domain;
+use crate::message::{KnownQType, QClass};
+use crate::record::rdata::{EdnsOption, Opt};
+use crate::record::{RData, ResourceRecord};
+use std::time::Duration;
+
+/// Typed view of the `OPT` pseudo-record of a message, which advertises
+/// the EDNS(0) capabilities of its sender.
+///
+/// The fixed fields of the record are repurposed: the class holds the UDP
+/// payload size and the TTL holds the extended `RCODE`, the version and
+/// the flags.
+///
+/// For more details, see [RFC 6891, Section 6.1.3].
+///
+/// [RFC 6891, Section 6.1.3]: https://datatracker.ietf.org/doc/html/rfc6891#section-6.1.3
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edns {
+    pub udp_payload_size: u16,
+    /// Upper 8 bits of the 12-bit extended `RCODE`.
+    pub extended_r_code: u8,
+    pub version: u8,
+    pub dnssec_ok: bool,
+    pub options: Vec<EdnsOption>,
+}
+
+impl Edns {
+    /// UDP payload size that avoids IP fragmentation on most paths.
+    pub const DEFAULT_UDP_PAYLOAD_SIZE: u16 = 1232;
+
+    const DNSSEC_OK_FLAG: u32 = 0x8000;
+
+    /// Creates an EDNS(0) version 0 record advertising `udp_payload_size`,
+    /// with all flags cleared and no options.
+    pub fn new(udp_payload_size: u16) -> Self {
+        Edns {
+            udp_payload_size,
+            extended_r_code: 0,
+            version: 0,
+            dnssec_ok: false,
+            options: Vec::new(),
+        }
+    }
+
+    /// Decodes the EDNS(0) fields of `record`, if it is an `OPT` record.
+    pub fn from_record(record: &ResourceRecord) -> Option<Self> {
+        let options = match &record.rdata {
+            RData::OPT(opt) => opt.options.clone(),
+            _ => return None,
+        };
+        let [extended_r_code, version, ..] = record.ttl.to_be_bytes();
+        Some(Edns {
+            udp_payload_size: record.r_class.value,
+            extended_r_code,
+            version,
+            dnssec_ok: record.ttl & Self::DNSSEC_OK_FLAG != 0,
+            options,
+        })
+    }
+
+    /// Encodes the fields as an `OPT` record owned by the root.
+    pub fn to_record(&self) -> ResourceRecord {
+        let mut ttl = u32::from_be_bytes([self.extended_r_code, self.version, 0, 0]);
+        if self.dnssec_ok {
+            ttl |= Self::DNSSEC_OK_FLAG;
+        }
+        ResourceRecord {
+            name: Domain::new(),
+            r_type: KnownQType::OPT.into(),
+            r_class: QClass::new(self.udp_payload_size),
+            ttl,
+            rdata: RData::OPT(Opt {
+                options: self.options.clone(),
+            }),
+        }
+    }
+
+    /// Returns the data of the first option with `code`.
+    pub fn option(&self, code: u16) -> Option<&[u8]> {
+        self.options
+            .iter()
+            .find(|option| option.code == code)
+            .map(|option| option.data.as_slice())
+    }
+
+    /// Returns the idle timeout announced by a server in the
+    /// `edns-tcp-keepalive` option, see [RFC 7828, Section 3.3.1].
+    ///
+    /// [RFC 7828, Section 3.3.1]: https://datatracker.ietf.org/doc/html/rfc7828#section-3.3.1
+    pub fn tcp_keepalive(&self) -> Option<Duration> {
+        let data: [u8; 2] = self.option(EdnsOption::TCP_KEEPALIVE)?.try_into().ok()?;
+        Some(Duration::from_millis(
+            u64::from(u16::from_be_bytes(data)) * 100,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn edns_round_trips_through_record() {
+        let edns = Edns {
+            udp_payload_size: 4096,
+            extended_r_code: 1,
+            version: 0,
+            dnssec_ok: true,
+            options: vec![EdnsOption::tcp_keepalive(None)],
+        };
+        let record = edns.to_record();
+        assert!(record.name.is_root());
+        assert_eq!(record.r_class.value, 4096);
+        assert_eq!(record.ttl, 0x0100_8000);
+        assert_eq!(Edns::from_record(&record), Some(edns));
+    }
+
+    #[test]
+    fn edns_from_record_ignores_other_types() {
+        let mut record = Edns::new(1232).to_record();
+        record.rdata = RData::Unknown(Vec::new());
+        assert_eq!(Edns::from_record(&record), None);
+    }
+
+    #[rstest]
+    #[case(Some(Duration::from_secs(30)), &[1, 44], Some(Duration::from_secs(30)))]
+    #[case(Some(Duration::from_millis(150)), &[0, 1], Some(Duration::from_millis(100)))]
+    #[case(Some(Duration::from_secs(86_400)), &[255, 255], Some(Duration::from_millis(6_553_500)))]
+    #[case(None, &[], None)]
+    fn edns_tcp_keepalive(
+        #[case] timeout: Option<Duration>,
+        #[case] data: &[u8],
+        #[case] expected: Option<Duration>,
+    ) {
+        let option = EdnsOption::tcp_keepalive(timeout);
+        assert_eq!(option.data, data);
+
+        let mut edns = Edns::new(1232);
+        edns.options.push(option);
+        assert_eq!(edns.tcp_keepalive(), expected);
+    }
+}
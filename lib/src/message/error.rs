@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::domain::error::TryFromError;
+use crate::domain::Domain;
+use crate::message::QType;
 use std::error::Error;
 use std::fmt;
 
@@ -48,6 +51,28 @@ impl fmt::Display for RCodeTryFromError {
 
 impl Error for RCodeTryFromError {}
 
+#[derive(Debug, PartialEq)]
+pub struct QTypeFromStrError(pub String);
+
+impl fmt::Display for QTypeFromStrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}' is not a known record type", self.0)
+    }
+}
+
+impl Error for QTypeFromStrError {}
+
+#[derive(Debug, PartialEq)]
+pub struct QClassFromStrError(pub String);
+
+impl fmt::Display for QClassFromStrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}' is not a known class", self.0)
+    }
+}
+
+impl Error for QClassFromStrError {}
+
 #[derive(Debug, PartialEq)]
 pub enum HeaderTryFromError {
     InsufficientHeaderBytes(usize),
@@ -98,6 +123,169 @@ impl Error for HeaderTryFromError {
     }
 }
 
+/// Errors found while decoding the wire format of names, records and
+/// messages.
+#[derive(Debug, PartialEq)]
+pub enum WireError {
+    UnexpectedEnd(usize),
+    InvalidPointer(usize),
+    InvalidLabel(TryFromError),
+    InvalidRData(QType),
+    TrailingBytes(usize),
+}
+
+impl From<TryFromError> for WireError {
+    fn from(error: TryFromError) -> WireError {
+        WireError::InvalidLabel(error)
+    }
+}
+
+impl fmt::Display for WireError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnexpectedEnd(offset) => {
+                write!(f, "message ends unexpectedly at offset {}", offset)
+            }
+            Self::InvalidPointer(offset) => {
+                write!(f, "invalid compression pointer at offset {}", offset)
+            }
+            Self::InvalidLabel(e) => e.fmt(f),
+            Self::InvalidRData(r_type) => write!(f, "invalid RDATA for type {}", r_type),
+            Self::TrailingBytes(count) => write!(f, "{} unexpected bytes after the message", count),
+        }
+    }
+}
+
+impl Error for WireError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::InvalidLabel(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum MessageTryFromError {
+    Header(HeaderTryFromError),
+    Wire(WireError),
+}
+
+impl From<HeaderTryFromError> for MessageTryFromError {
+    fn from(error: HeaderTryFromError) -> MessageTryFromError {
+        MessageTryFromError::Header(error)
+    }
+}
+
+impl From<WireError> for MessageTryFromError {
+    fn from(error: WireError) -> MessageTryFromError {
+        MessageTryFromError::Wire(error)
+    }
+}
+
+impl fmt::Display for MessageTryFromError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Header(e) => e.fmt(f),
+            Self::Wire(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for MessageTryFromError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Header(e) => Some(e),
+            Self::Wire(e) => Some(e),
+        }
+    }
+}
+
+/// Errors found while interpreting a message as a dynamic update.
+///
+/// They correspond to the `FORMERR` conditions of [RFC 2136, Section 3].
+///
+/// [RFC 2136, Section 3]: https://datatracker.ietf.org/doc/html/rfc2136#section-3
+#[derive(Debug, PartialEq)]
+pub enum UpdateError {
+    Message(MessageTryFromError),
+    NotUpdate,
+    InvalidZoneCount(u16),
+    InvalidZoneType(QType),
+    InvalidPrerequisite(Domain),
+    InvalidUpdate(Domain),
+}
+
+impl From<MessageTryFromError> for UpdateError {
+    fn from(error: MessageTryFromError) -> UpdateError {
+        UpdateError::Message(error)
+    }
+}
+
+impl fmt::Display for UpdateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Message(e) => e.fmt(f),
+            Self::NotUpdate => write!(f, "OPCODE is not UPDATE"),
+            Self::InvalidZoneCount(count) => {
+                write!(f, "zone section has {} records instead of 1", count)
+            }
+            Self::InvalidZoneType(z_type) => write!(f, "zone type {} is not SOA", z_type),
+            Self::InvalidPrerequisite(name) => write!(f, "invalid prerequisite for '{}'", name),
+            Self::InvalidUpdate(name) => write!(f, "invalid update for '{}'", name),
+        }
+    }
+}
+
+impl Error for UpdateError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Message(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Errors found while interpreting a message as a zone change
+/// notification.
+#[derive(Debug, PartialEq)]
+pub enum NotifyError {
+    Message(MessageTryFromError),
+    NotNotify,
+    InvalidZoneCount(u16),
+    InvalidZoneType(QType),
+    InvalidAnswer(Domain),
+}
+
+impl From<MessageTryFromError> for NotifyError {
+    fn from(error: MessageTryFromError) -> NotifyError {
+        NotifyError::Message(error)
+    }
+}
+
+impl fmt::Display for NotifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Message(e) => e.fmt(f),
+            Self::NotNotify => write!(f, "OPCODE is not NOTIFY"),
+            Self::InvalidZoneCount(count) => {
+                write!(f, "question section has {} records instead of 1", count)
+            }
+            Self::InvalidZoneType(q_type) => write!(f, "notified type {} is not SOA", q_type),
+            Self::InvalidAnswer(name) => write!(f, "invalid SOA answer for '{}'", name),
+        }
+    }
+}
+
+impl Error for NotifyError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Message(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -111,4 +299,46 @@ mod tests {
     fn header_try_from_error_display(#[case] err: HeaderTryFromError, #[case] msg: String) {
         assert_eq!(err.to_string(), msg);
     }
+
+    #[rstest]
+    #[case(WireError::UnexpectedEnd(12), "message ends unexpectedly at offset 12")]
+    #[case(
+        WireError::InvalidPointer(40),
+        "invalid compression pointer at offset 40"
+    )]
+    #[case(WireError::InvalidRData(QType::new(15)), "invalid RDATA for type MX")]
+    #[case(WireError::TrailingBytes(3), "3 unexpected bytes after the message")]
+    fn wire_error_display(#[case] err: WireError, #[case] msg: &str) {
+        assert_eq!(err.to_string(), msg);
+        assert_eq!(MessageTryFromError::from(err).to_string(), msg);
+    }
+
+    #[rstest]
+    #[case(UpdateError::NotUpdate, "OPCODE is not UPDATE")]
+    #[case(
+        UpdateError::InvalidZoneCount(2),
+        "zone section has 2 records instead of 1"
+    )]
+    #[case(UpdateError::InvalidZoneType(QType::new(1)), "zone type A is not SOA")]
+    #[case(
+        UpdateError::InvalidUpdate(Domain::from_presentation("a.example").unwrap()),
+        "invalid update for 'a.example'"
+    )]
+    fn update_error_display(#[case] err: UpdateError, #[case] msg: &str) {
+        assert_eq!(err.to_string(), msg);
+    }
+
+    #[rstest]
+    #[case(NotifyError::NotNotify, "OPCODE is not NOTIFY")]
+    #[case(
+        NotifyError::InvalidZoneCount(0),
+        "question section has 0 records instead of 1"
+    )]
+    #[case(
+        NotifyError::InvalidAnswer(Domain::from_presentation("example").unwrap()),
+        "invalid SOA answer for 'example'"
+    )]
+    fn notify_error_display(#[case] err: NotifyError, #[case] msg: &str) {
+        assert_eq!(err.to_string(), msg);
+    }
 }
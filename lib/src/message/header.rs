@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+// Flag masks are grouped by header field rather than by nibble.
+#![allow(clippy::unusual_byte_groupings)]
+
 use crate::message::error::{
     HeaderTryFromError, OpCodeTryFromError, RCodeTryFromError, ZTryFromError,
 };
@@ -26,7 +29,7 @@ use crate::message::error::{
 /// For more details, see [RFC 1035, Section 4.1.1].
 ///
 /// [RFC 1035, Section 4.1.1]: https://datatracker.ietf.org/doc/html/rfc1035#section-4.1.1
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Header {
     pub id: u16,
 
@@ -45,6 +48,56 @@ pub struct Header {
     pub ar_count: u16,
 }
 
+impl Header {
+    /// Creates the header of a standard query with the identifier `id`, all
+    /// flags cleared and empty sections.
+    pub fn new(id: u16) -> Self {
+        Header {
+            id,
+            qr: QR::Query,
+            op_code: OpCode::Query,
+            aa: false,
+            tc: false,
+            rd: false,
+            ra: false,
+            z: Z::AllZeros,
+            r_code: RCode::NoError,
+            qd_count: 0,
+            an_count: 0,
+            ns_count: 0,
+            ar_count: 0,
+        }
+    }
+
+    /// Encodes the header in wire format.
+    pub fn to_bytes(&self) -> [u8; 12] {
+        let flags = (self.qr as u16) << 15
+            | (self.op_code as u16) << 11
+            | u16::from(self.aa) << 10
+            | u16::from(self.tc) << 9
+            | u16::from(self.rd) << 8
+            | u16::from(self.ra) << 7
+            | (self.z as u16) << 4
+            | self.r_code as u16;
+
+        let mut bytes = [0; 12];
+        for (i, value) in [
+            self.id,
+            flags,
+            self.qd_count,
+            self.an_count,
+            self.ns_count,
+            self.ar_count,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            bytes[2 * i..2 * i + 2].copy_from_slice(&value.to_be_bytes());
+        }
+        bytes
+    }
+}
+
 impl TryFrom<&[u8]> for Header {
     type Error = HeaderTryFromError;
 
@@ -82,9 +135,9 @@ impl TryFrom<&[u8]> for Header {
 
         let flags = u16::from_be_bytes([value[2], value[3]]);
 
-        let op_code = OpCode::try_from(flags).map_err(|e| Self::Error::from(e))?;
-        let z = Z::try_from(flags).map_err(|e| Self::Error::from(e))?;
-        let r_code = RCode::try_from(flags).map_err(|e| Self::Error::from(e))?;
+        let op_code = OpCode::try_from(flags).map_err(Self::Error::from)?;
+        let z = Z::try_from(flags).map_err(Self::Error::from)?;
+        let r_code = RCode::try_from(flags).map_err(Self::Error::from)?;
 
         Ok(Header {
             id: u16::from_be_bytes([value[0], value[1]]),
@@ -120,7 +173,7 @@ fn parse_ra_flag(value: u16) -> bool {
     (value & 0b0_0000_0_0_0_1_000_0000) >> 7 == 1
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum QR {
     Query = 0,
     Response = 1,
@@ -135,11 +188,13 @@ impl From<u16> for QR {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum OpCode {
     Query = 0,
     InverseQuery = 1,
     Status = 2,
+    Notify = 4,
+    Update = 5,
 }
 
 impl TryFrom<u16> for OpCode {
@@ -162,14 +217,15 @@ impl TryFrom<u16> for OpCode {
     /// ```
     ///
     /// With 4 bits available, `OPCODE` _can_ have 16 possible values, but only
-    /// 3 are supported:
+    /// 5 are supported:
     ///
     ///  - `0` a standard query (QUERY)
     ///  - `1` an inverse query (IQUERY)
     ///  - `2` a server status request (STATUS)
+    ///  - `4` a zone change notification (NOTIFY), see [RFC 1996, Section 3.1]
+    ///  - `5` a dynamic update (UPDATE), see [RFC 2136, Section 1.3]
     ///
-    /// Unsupported values in range `3-15` will result in an
-    /// `OpCodeTryFromError`.
+    /// Other values will result in an `OpCodeTryFromError`.
     ///
     /// For more details, see [RFC 1035, Section 4.1.1].
     ///
@@ -180,22 +236,26 @@ impl TryFrom<u16> for OpCode {
     /// let valid_opcode = 0b0_0000_0_0_0_0_000_0000; // 0, QUERY
     /// assert!(OpCode::try_from(valid_opcode).is_ok());
     ///
-    /// let invalid_opcode = 0b0_0100_0_0_0_0_000_0000; // 4, RESERVED
+    /// let invalid_opcode = 0b0_0011_0_0_0_0_000_0000; // 3, RESERVED
     /// assert!(OpCode::try_from(invalid_opcode).is_err());
     /// ```
     ///
     /// [RFC 1035, Section 4.1.1]: https://datatracker.ietf.org/doc/html/rfc1035#section-4.1.1
+    /// [RFC 1996, Section 3.1]: https://datatracker.ietf.org/doc/html/rfc1996#section-3.1
+    /// [RFC 2136, Section 1.3]: https://datatracker.ietf.org/doc/html/rfc2136#section-1.3
     fn try_from(value: u16) -> Result<Self, Self::Error> {
         match (value & 0b0_1111_0_0_0_0_000_0000) >> 11 {
             0 => Ok(Self::Query),
             1 => Ok(Self::InverseQuery),
             2 => Ok(Self::Status),
+            4 => Ok(Self::Notify),
+            5 => Ok(Self::Update),
             unsupported => Err(OpCodeTryFromError(unsupported)),
         }
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Z {
     AllZeros = 0,
 }
@@ -244,7 +304,7 @@ impl TryFrom<u16> for Z {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum RCode {
     NoError = 0,
     FormatError = 1,
@@ -252,6 +312,11 @@ pub enum RCode {
     NameError = 3,
     NotImplemented = 4,
     Refused = 5,
+    YxDomain = 6,
+    YxRrSet = 7,
+    NxRrSet = 8,
+    NotAuth = 9,
+    NotZone = 10,
 }
 
 impl TryFrom<u16> for RCode {
@@ -274,7 +339,7 @@ impl TryFrom<u16> for RCode {
     /// ```
     ///
     /// With 4 bits available, `RCODE` _can_ have 16 possible values, but only
-    /// 6 are supported:
+    /// 11 are supported:
     ///
     ///  - `0` No error condition
     ///  - `1` Format error
@@ -282,9 +347,15 @@ impl TryFrom<u16> for RCode {
     ///  - `3` Name error
     ///  - `4` Not implemented
     ///  - `5` Refused
+    ///  - `6` Name exists when it should not (YXDOMAIN)
+    ///  - `7` RR set exists when it should not (YXRRSET)
+    ///  - `8` RR set that should exist does not (NXRRSET)
+    ///  - `9` Not authorized (NOTAUTH)
+    ///  - `10` Name not contained in zone (NOTZONE)
     ///
-    /// Unsupported values in range `6-15` will result in an
-    /// `RCodeTryFromError`.
+    /// Codes `6-10` are defined by [RFC 2136, Section 2.2], and `NOTAUTH` is
+    /// also used by [RFC 8945] for TSIG failures. Unsupported values in range
+    /// `11-15` will result in an `RCodeTryFromError`.
     ///
     /// For more details, see [RFC 1035, Section 4.1.1].
     ///
@@ -295,11 +366,13 @@ impl TryFrom<u16> for RCode {
     /// let valid_rcode = 0b0_0000_0_0_0_0_000_0001; // 1, Format error
     /// assert!(RCode::try_from(valid_rcode).is_ok());
     ///
-    /// let invalid_rcode = 0b0_0100_0_0_0_0_000_1000; // 8, Reserved
+    /// let invalid_rcode = 0b0_0100_0_0_0_0_000_1100; // 12, Reserved
     /// assert!(RCode::try_from(invalid_rcode).is_err());
     /// ```
     ///
     /// [RFC 1035, Section 4.1.1]: https://datatracker.ietf.org/doc/html/rfc1035#section-4.1.1
+    /// [RFC 2136, Section 2.2]: https://datatracker.ietf.org/doc/html/rfc2136#section-2.2
+    /// [RFC 8945]: https://datatracker.ietf.org/doc/html/rfc8945
     fn try_from(value: u16) -> Result<Self, Self::Error> {
         match value & 0b0_0000_0_0_0_0_000_1111 {
             0 => Ok(Self::NoError),
@@ -308,6 +381,11 @@ impl TryFrom<u16> for RCode {
             3 => Ok(Self::NameError),
             4 => Ok(Self::NotImplemented),
             5 => Ok(Self::Refused),
+            6 => Ok(Self::YxDomain),
+            7 => Ok(Self::YxRrSet),
+            8 => Ok(Self::NxRrSet),
+            9 => Ok(Self::NotAuth),
+            10 => Ok(Self::NotZone),
             unspported => Err(RCodeTryFromError(unspported)),
         }
     }
@@ -330,6 +408,8 @@ mod tests {
     #[case(0b0_0000_0_0_0_0_000_0000, OpCode::Query)]
     #[case(0b0_0001_0_0_0_0_000_0000, OpCode::InverseQuery)]
     #[case(0b0_0010_0_0_0_0_000_0000, OpCode::Status)]
+    #[case(0b0_0100_0_0_0_0_000_0000, OpCode::Notify)]
+    #[case(0b0_0101_0_0_0_0_000_0000, OpCode::Update)]
     fn op_code_try_from_u16_succeeds(#[case] input: u16, #[case] expected: OpCode) {
         let result = OpCode::try_from(input);
         assert!(result.is_ok());
@@ -371,6 +451,8 @@ mod tests {
     #[case(0b0_0000_0_0_0_0_000_0011, RCode::NameError)]
     #[case(0b0_0001_0_0_0_0_000_0100, RCode::NotImplemented)]
     #[case(0b0_0010_0_0_0_0_000_0101, RCode::Refused)]
+    #[case(0b0_0000_0_0_0_0_000_1001, RCode::NotAuth)]
+    #[case(0b0_0000_0_0_0_0_000_1010, RCode::NotZone)]
     fn r_code_try_from_u16_succeeds(#[case] input: u16, #[case] expected: RCode) {
         let result = RCode::try_from(input);
         assert!(result.is_ok());
@@ -378,7 +460,7 @@ mod tests {
     }
 
     #[rstest]
-    #[case(0b0_0000_0_0_0_0_000_0110, RCodeTryFromError(6))]
+    #[case(0b0_0000_0_0_0_0_000_1011, RCodeTryFromError(11))]
     #[case(0b0_0000_0_0_0_0_000_1101, RCodeTryFromError(13))]
     #[case(0b0_0000_0_0_0_0_000_1111, RCodeTryFromError(15))]
     fn r_code_try_from_u16_fails(#[case] input: u16, #[case] err: RCodeTryFromError) {
@@ -407,6 +489,7 @@ mod tests {
         let result = Header::try_from(input);
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), expected);
+        assert_eq!(expected.to_bytes(), input);
     }
 
     #[rstest]
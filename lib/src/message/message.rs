@@ -12,15 +12,435 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::message::Header;
+use crate::message::error::{MessageTryFromError, WireError};
+use crate::message::reader::Reader;
 use crate::message::Question;
+use crate::message::{Edns, Header, QR};
+use crate::record::rdata::EdnsOption;
+use crate::record::{RData, ResourceRecord};
 
 /// `Message` format used by the DNS protocol.
 ///
 /// For more details, see [RFC 1035, Section 4].
 ///
 /// [RFC 1035, Section 4]: https://datatracker.ietf.org/doc/html/rfc1035#section-4
+#[derive(Debug, Clone, PartialEq)]
 pub struct Message {
     pub header: Header,
     pub questions: Vec<Question>,
+    pub answers: Vec<ResourceRecord>,
+    pub authorities: Vec<ResourceRecord>,
+    pub additionals: Vec<ResourceRecord>,
+}
+
+impl Message {
+    /// Creates a message with `header` and empty sections.
+    pub fn new(header: Header) -> Self {
+        Message {
+            header,
+            questions: Vec::new(),
+            answers: Vec::new(),
+            authorities: Vec::new(),
+            additionals: Vec::new(),
+        }
+    }
+
+    /// Encodes the message in uncompressed wire format. The section counts
+    /// of the header are taken from the sections themselves.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut header = self.header.clone();
+        header.qd_count = self.questions.len() as u16;
+        header.an_count = self.answers.len() as u16;
+        header.ns_count = self.authorities.len() as u16;
+        header.ar_count = self.additionals.len() as u16;
+
+        let mut bytes = header.to_bytes().to_vec();
+        for question in &self.questions {
+            bytes.extend_from_slice(&question.to_bytes());
+        }
+        for record in self.records() {
+            bytes.extend_from_slice(&record.to_bytes());
+        }
+        bytes
+    }
+
+    /// Returns the records of the answer, authority and additional
+    /// sections, in this order.
+    pub fn records(&self) -> impl Iterator<Item = &ResourceRecord> {
+        self.answers
+            .iter()
+            .chain(&self.authorities)
+            .chain(&self.additionals)
+    }
+
+    /// Returns the `TSIG` record of the message, which must be the last
+    /// record of the additional section.
+    ///
+    /// For more details, see [RFC 8945, Section 5.1].
+    ///
+    /// [RFC 8945, Section 5.1]: https://datatracker.ietf.org/doc/html/rfc8945#section-5.1
+    pub fn tsig(&self) -> Option<&ResourceRecord> {
+        self.additionals
+            .last()
+            .filter(|record| matches!(record.rdata, RData::TSIG(_)))
+    }
+
+    /// Returns the EDNS(0) fields of the message, taken from its `OPT`
+    /// record.
+    ///
+    /// For more details, see [RFC 6891, Section 6.1.1].
+    ///
+    /// [RFC 6891, Section 6.1.1]: https://datatracker.ietf.org/doc/html/rfc6891#section-6.1.1
+    pub fn edns(&self) -> Option<Edns> {
+        self.additionals.iter().find_map(Edns::from_record)
+    }
+
+    /// Replaces the `OPT` record of the message with `edns`, keeping a
+    /// `TSIG` record last in the additional section.
+    pub fn set_edns(&mut self, edns: Edns) {
+        self.additionals
+            .retain(|record| !matches!(record.rdata, RData::OPT(_)));
+        let position = match self.tsig() {
+            Some(_) => self.additionals.len() - 1,
+            None => self.additionals.len(),
+        };
+        self.additionals.insert(position, edns.to_record());
+    }
+
+    /// Adds a `Padding` option to the `OPT` record of the message, adding
+    /// the record if missing, so that its length is a multiple of
+    /// `block_size` octets. Messages signed with `TSIG` are left untouched,
+    /// since padding them would invalidate the signature.
+    ///
+    /// For more details, see [RFC 7830, Section 3] and
+    /// [RFC 8467, Section 4.1].
+    ///
+    /// [RFC 7830, Section 3]: https://datatracker.ietf.org/doc/html/rfc7830#section-3
+    /// [RFC 8467, Section 4.1]: https://datatracker.ietf.org/doc/html/rfc8467#section-4.1
+    pub fn pad(&mut self, block_size: usize) {
+        if block_size == 0 || self.tsig().is_some() {
+            return;
+        }
+        let mut edns = self
+            .edns()
+            .unwrap_or_else(|| Edns::new(Edns::DEFAULT_UDP_PAYLOAD_SIZE));
+        edns.options
+            .retain(|option| option.code != EdnsOption::PADDING);
+        edns.options.push(EdnsOption {
+            code: EdnsOption::PADDING,
+            data: Vec::new(),
+        });
+        self.set_edns(edns.clone());
+
+        let len = self.to_bytes().len();
+        let padding = (block_size - len % block_size) % block_size;
+        if let Some(option) = edns.options.last_mut() {
+            option.data = vec![0; padding];
+        }
+        self.set_edns(edns);
+    }
+
+    /// Checks if `response` answers this query: it must be a response with
+    /// the same identifier, opcode and question section. Anything else is
+    /// either stray or forged, and must be discarded.
+    ///
+    /// For more details, see [RFC 5452, Section 4].
+    ///
+    /// [RFC 5452, Section 4]: https://datatracker.ietf.org/doc/html/rfc5452#section-4
+    pub fn is_answered_by(&self, response: &Message) -> bool {
+        response.header.qr == QR::Response
+            && response.header.id == self.header.id
+            && response.header.op_code == self.header.op_code
+            && response.questions == self.questions
+    }
+
+    /// Decodes a message, also returning the offset at which each record of
+    /// the additional section starts.
+    pub(crate) fn from_wire(bytes: &[u8]) -> Result<(Self, Vec<usize>), MessageTryFromError> {
+        let header = Header::try_from(bytes)?;
+        let mut reader = Reader::new(bytes, 12);
+
+        let mut message = Message::new(header);
+        for _ in 0..message.header.qd_count {
+            message.questions.push(Question::from_wire(&mut reader)?);
+        }
+        for _ in 0..message.header.an_count {
+            message
+                .answers
+                .push(ResourceRecord::from_wire(&mut reader)?);
+        }
+        for _ in 0..message.header.ns_count {
+            message
+                .authorities
+                .push(ResourceRecord::from_wire(&mut reader)?);
+        }
+        let mut offsets = Vec::new();
+        for _ in 0..message.header.ar_count {
+            offsets.push(reader.position());
+            message
+                .additionals
+                .push(ResourceRecord::from_wire(&mut reader)?);
+        }
+
+        match reader.remaining() {
+            0 => Ok((message, offsets)),
+            trailing => Err(WireError::TrailingBytes(trailing).into()),
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for Message {
+    type Error = MessageTryFromError;
+
+    /// Tries to decode a DNS `Message` from its wire format, expanding
+    /// compressed domain names.
+    ///
+    /// For more details, see [RFC 1035, Section 4.1].
+    ///
+    /// [RFC 1035, Section 4.1]: https://datatracker.ietf.org/doc/html/rfc1035#section-4.1
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Message::from_wire(bytes).map(|(message, _)| message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Domain;
+    use crate::message::{KnownQClass, KnownQType, QType, QR};
+    use crate::record::rdata::{Dnskey, EdnsOption, Mx, Nsec, Nsec3, Opt, Soa, Tsig, TypeBitmap};
+    use rstest::rstest;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    fn name(text: &str) -> Domain {
+        Domain::from_presentation(text).unwrap()
+    }
+
+    fn record(owner: &str, r_type: KnownQType, rdata: RData) -> ResourceRecord {
+        ResourceRecord {
+            name: name(owner),
+            r_type: r_type.into(),
+            r_class: KnownQClass::IN.into(),
+            ttl: 3600,
+            rdata,
+        }
+    }
+
+    fn response() -> Message {
+        let mut header = Header::new(0xbeef);
+        header.qr = QR::Response;
+        header.aa = true;
+        let mut message = Message::new(header);
+        message.questions.push(Question {
+            q_name: name("example.com"),
+            q_type: KnownQType::MX.into(),
+            q_class: KnownQClass::IN.into(),
+        });
+        message.answers.push(record(
+            "example.com",
+            KnownQType::MX,
+            RData::MX(Mx {
+                preference: 10,
+                exchange: name("mail.example.com"),
+            }),
+        ));
+        message.authorities.push(record(
+            "example.com",
+            KnownQType::SOA,
+            RData::SOA(Soa {
+                m_name: name("ns1.example.com"),
+                r_name: name("hostmaster.example.com"),
+                serial: 2024010101,
+                refresh: 7200,
+                retry: 3600,
+                expire: 1209600,
+                minimum: 300,
+            }),
+        ));
+        message.additionals.push(record(
+            "mail.example.com",
+            KnownQType::A,
+            RData::A(Ipv4Addr::new(192, 0, 2, 1)),
+        ));
+        message.additionals.push(record(
+            "mail.example.com",
+            KnownQType::AAAA,
+            RData::AAAA(Ipv6Addr::LOCALHOST),
+        ));
+        message
+    }
+
+    #[test]
+    fn message_round_trip() {
+        let message = response();
+        let bytes = message.to_bytes();
+        assert_eq!(&bytes[4..12], [0, 1, 0, 1, 0, 1, 0, 2]);
+
+        let mut decoded = Message::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(decoded.header.qd_count, 1);
+        assert_eq!(decoded.header.ar_count, 2);
+        decoded.header = message.header.clone();
+        assert_eq!(decoded, message);
+    }
+
+    #[rstest]
+    #[case(RData::NSEC(Nsec {
+        next_domain_name: name("b.example.com"),
+        type_bitmap: [1, 46, 47].into_iter().map(QType::new).collect::<TypeBitmap>(),
+    }))]
+    #[case(RData::NSEC3(Nsec3 {
+        hash_algorithm: 1,
+        flags: 1,
+        iterations: 12,
+        salt: vec![0xaa, 0xbb],
+        next_hashed_owner_name: vec![7; 20],
+        type_bitmap: TypeBitmap::new(),
+    }))]
+    #[case(RData::TSIG(Tsig {
+        algorithm: name("hmac-sha256"),
+        time_signed: 1_700_000_000,
+        fudge: 300,
+        mac: vec![1; 32],
+        original_id: 7,
+        error: 0,
+        other_data: vec![],
+    }))]
+    #[case(RData::KEY(Dnskey {
+        flags: 512,
+        protocol: 3,
+        algorithm: 15,
+        public_key: vec![1; 32],
+    }))]
+    #[case(RData::OPT(Opt::default()))]
+    #[case(RData::OPT(Opt {
+        options: vec![
+            EdnsOption::tcp_keepalive(None),
+            EdnsOption { code: 12, data: vec![0; 3] },
+        ],
+    }))]
+    #[case(RData::Unknown(vec![1, 2, 3]))]
+    fn message_round_trips_rdata(#[case] rdata: RData) {
+        let r_type = match &rdata {
+            RData::NSEC(_) => QType::from(KnownQType::NSEC),
+            RData::NSEC3(_) => KnownQType::NSEC3.into(),
+            RData::TSIG(_) => KnownQType::TSIG.into(),
+            RData::KEY(_) => KnownQType::KEY.into(),
+            RData::OPT(_) => KnownQType::OPT.into(),
+            _ => QType::new(65280),
+        };
+        let mut message = Message::new(Header::new(1));
+        message.answers.push(ResourceRecord {
+            r_type,
+            ..record("a.example.com", KnownQType::A, rdata)
+        });
+        message.header.an_count = 1;
+
+        let decoded = Message::try_from(message.to_bytes().as_slice()).unwrap();
+        assert_eq!(decoded, message);
+    }
+
+    #[test]
+    fn message_try_from_expands_compressed_names() {
+        let mut bytes = vec![0, 1, 0x84, 0, 0, 1, 0, 1, 0, 0, 0, 0];
+        // Question: example.com. IN MX, name at offset 12.
+        bytes.extend_from_slice(b"\x07example\x03com\x00\x00\x0f\x00\x01");
+        // Answer: pointer to 12, MX 10 mail + pointer to 12.
+        bytes.extend_from_slice(&[0xc0, 12, 0, 15, 0, 1, 0, 0, 0x0e, 0x10, 0, 9]);
+        bytes.extend_from_slice(b"\x00\x0a\x04mail\xc0\x0c");
+
+        let message = Message::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(
+            message.answers,
+            [record(
+                "example.com",
+                KnownQType::MX,
+                RData::MX(Mx {
+                    preference: 10,
+                    exchange: name("mail.example.com"),
+                })
+            )]
+        );
+    }
+
+    #[rstest]
+    #[case(&[0, 1], MessageTryFromError::Header(crate::message::error::HeaderTryFromError::InsufficientHeaderBytes(2)))]
+    #[case(&[0, 1, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, b'a'], WireError::UnexpectedEnd(14).into())]
+    #[case(&[0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], WireError::TrailingBytes(1).into())]
+    // An A record with 3 bytes of RDATA.
+    #[case(
+        &[0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 1, 0, 0, 0, 0, 0, 3, 1, 2, 3],
+        WireError::InvalidRData(QType::new(1)).into()
+    )]
+    // An NS record whose name overflows its RDATA.
+    #[case(
+        &[0, 1, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 2, 0, 1, 0, 0, 0, 0, 0, 1, 1, b'a', 0],
+        WireError::InvalidRData(QType::new(2)).into()
+    )]
+    fn message_try_from_fails(#[case] bytes: &[u8], #[case] expected: MessageTryFromError) {
+        assert_eq!(Message::try_from(bytes), Err(expected));
+    }
+
+    #[test]
+    fn message_set_edns_keeps_tsig_last() {
+        let mut message = response();
+        let tsig = record(
+            "key.example.com",
+            KnownQType::TSIG,
+            RData::TSIG(Tsig {
+                algorithm: name("hmac-sha256"),
+                time_signed: 1_700_000_000,
+                fudge: 300,
+                mac: vec![1; 32],
+                original_id: 0xbeef,
+                error: 0,
+                other_data: vec![],
+            }),
+        );
+        message.additionals.push(tsig.clone());
+        assert_eq!(message.edns(), None);
+
+        message.set_edns(Edns::new(4096));
+        message.set_edns(Edns::new(1232));
+        assert_eq!(message.additionals.len(), 4);
+        assert_eq!(message.edns(), Some(Edns::new(1232)));
+        assert_eq!(message.tsig(), Some(&tsig));
+    }
+
+    #[rstest]
+    #[case(128)]
+    #[case(468)]
+    fn message_pad(#[case] block_size: usize) {
+        let mut message = response();
+        message.pad(block_size);
+        let len = message.to_bytes().len();
+        assert_eq!(len % block_size, 0);
+        assert!(message
+            .edns()
+            .unwrap()
+            .option(EdnsOption::PADDING)
+            .is_some());
+
+        message.pad(block_size);
+        assert_eq!(message.to_bytes().len(), len);
+        assert_eq!(message.edns().unwrap().options.len(), 1);
+    }
+
+    #[test]
+    fn message_is_answered_by() {
+        let response = response();
+        let mut query = Message::new(Header::new(0xbeef));
+        query.questions = response.questions.clone();
+        assert!(query.is_answered_by(&response));
+        assert!(!query.is_answered_by(&query));
+
+        let mut other = response.clone();
+        other.header.id = 0xbeee;
+        assert!(!query.is_answered_by(&other));
+        let mut other = response.clone();
+        other.questions[0].q_type = KnownQType::A.into();
+        assert!(!query.is_answered_by(&other));
+        let mut other = response.clone();
+        other.questions.clear();
+        assert!(!query.is_answered_by(&other));
+    }
 }
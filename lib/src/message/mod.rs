@@ -12,11 +12,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod edns;
 pub mod error;
 mod header;
+#[allow(clippy::module_inception)]
 mod message;
+mod notify;
 mod question;
+pub(crate) mod reader;
+mod update;
 
-pub use header::{Header, OpCode, RCode, Z};
+pub use edns::Edns;
+pub use header::{Header, OpCode, RCode, QR, Z};
 pub use message::Message;
-pub use question::{parse_question, KnownQType, QType, Question};
+pub use notify::NotifyMessage;
+pub use question::{parse_question, KnownQClass, KnownQType, QClass, QType, Question};
+pub use update::{Prerequisite, Update, UpdateBuilder, UpdateMessage};
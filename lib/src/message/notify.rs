@@ -0,0 +1,237 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::Domain;
+use crate::message::error::NotifyError;
+use crate::message::{Header, KnownQType, Message, OpCode, QClass, Question, QR};
+use crate::record::rdata::Soa;
+use crate::record::{RData, ResourceRecord};
+
+/// Typed view of a `NOTIFY` message, sent by a primary server to tell its
+/// secondaries that a zone changed.
+///
+/// The zone is carried in the question section with type `SOA`, and the
+/// answer section may hold the new `SOA` record as a hint.
+///
+/// For more details, see [RFC 1996, Section 3].
+///
+/// [RFC 1996, Section 3]: https://datatracker.ietf.org/doc/html/rfc1996#section-3
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotifyMessage {
+    pub header: Header,
+    pub zone: Domain,
+    pub class: QClass,
+    pub soa: Option<ResourceRecord>,
+}
+
+impl NotifyMessage {
+    /// Creates a notification for `zone`, with the `AA` bit set as required
+    /// by [RFC 1996, Section 3.7].
+    ///
+    /// [RFC 1996, Section 3.7]: https://datatracker.ietf.org/doc/html/rfc1996#section-3.7
+    pub fn new(id: u16, zone: Domain, class: QClass) -> Self {
+        let mut header = Header::new(id);
+        header.op_code = OpCode::Notify;
+        header.aa = true;
+        NotifyMessage {
+            header,
+            zone,
+            class,
+            soa: None,
+        }
+    }
+
+    /// Adds the new `SOA` record of the zone to the answer section.
+    pub fn with_soa(mut self, ttl: u32, soa: Soa) -> Self {
+        self.soa = Some(ResourceRecord {
+            name: self.zone.clone(),
+            r_type: KnownQType::SOA.into(),
+            r_class: self.class,
+            ttl,
+            rdata: RData::SOA(soa),
+        });
+        self
+    }
+
+    /// Returns the serial of the `SOA` record carried by the notification.
+    pub fn serial(&self) -> Option<u32> {
+        match &self.soa.as_ref()?.rdata {
+            RData::SOA(soa) => Some(soa.serial),
+            _ => None,
+        }
+    }
+
+    /// Converts the notification into a plain message, with `OPCODE`
+    /// `NOTIFY`.
+    pub fn to_message(&self) -> Message {
+        let mut header = self.header.clone();
+        header.op_code = OpCode::Notify;
+        let mut message = Message::new(header);
+        message.questions.push(self.question());
+        message.answers.extend(self.soa.clone());
+        message
+    }
+
+    /// Encodes the notification in uncompressed wire format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_message().to_bytes()
+    }
+
+    /// Builds the response a secondary sends to acknowledge the
+    /// notification.
+    ///
+    /// For more details, see [RFC 1996, Section 4.7].
+    ///
+    /// [RFC 1996, Section 4.7]: https://datatracker.ietf.org/doc/html/rfc1996#section-4.7
+    pub fn acknowledgement(&self) -> Message {
+        let mut header = self.header.clone();
+        header.qr = QR::Response;
+        header.op_code = OpCode::Notify;
+        let mut message = Message::new(header);
+        message.questions.push(self.question());
+        message
+    }
+
+    /// Checks whether `response` answers this notification: it must be a
+    /// `NOTIFY` response with the same identifier and question.
+    pub fn is_answered_by(&self, response: &Message) -> bool {
+        response.header.qr == QR::Response
+            && response.header.id == self.header.id
+            && response.header.op_code == OpCode::Notify
+            && response.questions == [self.question()]
+    }
+
+    fn question(&self) -> Question {
+        Question {
+            q_name: self.zone.clone(),
+            q_type: KnownQType::SOA.into(),
+            q_class: self.class,
+        }
+    }
+}
+
+impl TryFrom<&Message> for NotifyMessage {
+    type Error = NotifyError;
+
+    /// Interprets `message` as a notification. The answer section must be
+    /// empty or hold the `SOA` record of the zone.
+    fn try_from(message: &Message) -> Result<Self, Self::Error> {
+        if message.header.op_code != OpCode::Notify {
+            return Err(NotifyError::NotNotify);
+        }
+        let [question] = message.questions.as_slice() else {
+            return Err(NotifyError::InvalidZoneCount(message.questions.len() as u16));
+        };
+        if question.q_type != KnownQType::SOA {
+            return Err(NotifyError::InvalidZoneType(question.q_type));
+        }
+        let soa = match message.answers.as_slice() {
+            [] => None,
+            [record]
+                if record.name == question.q_name
+                    && record.r_class == question.q_class
+                    && matches!(record.rdata, RData::SOA(_)) =>
+            {
+                Some(record.clone())
+            }
+            [record, ..] => return Err(NotifyError::InvalidAnswer(record.name.clone())),
+        };
+
+        Ok(NotifyMessage {
+            header: message.header.clone(),
+            zone: question.q_name.clone(),
+            class: question.q_class,
+            soa,
+        })
+    }
+}
+
+impl TryFrom<&[u8]> for NotifyMessage {
+    type Error = NotifyError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        NotifyMessage::try_from(&Message::try_from(bytes)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::KnownQClass;
+    use rstest::rstest;
+
+    fn name(text: &str) -> Domain {
+        Domain::from_presentation(text).unwrap()
+    }
+
+    fn soa(serial: u32) -> Soa {
+        Soa {
+            m_name: name("ns1.example.com"),
+            r_name: name("hostmaster.example.com"),
+            serial,
+            refresh: 3600,
+            retry: 600,
+            expire: 604800,
+            minimum: 300,
+        }
+    }
+
+    #[rstest]
+    #[case(NotifyMessage::new(7, name("example.com"), KnownQClass::IN.into()), None)]
+    #[case(
+        NotifyMessage::new(7, name("example.com"), KnownQClass::IN.into()).with_soa(3600, soa(2024010101)),
+        Some(2024010101)
+    )]
+    fn notify_round_trip(#[case] notify: NotifyMessage, #[case] serial: Option<u32>) {
+        let bytes = notify.to_bytes();
+        let message = Message::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(message.header.op_code, OpCode::Notify);
+        assert!(message.header.aa);
+
+        let decoded = NotifyMessage::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(decoded.serial(), serial);
+        assert_eq!(decoded.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn notify_acknowledgement() {
+        let notify = NotifyMessage::new(7, name("example.com"), KnownQClass::IN.into())
+            .with_soa(3600, soa(1));
+        let ack = notify.acknowledgement();
+        assert!(notify.is_answered_by(&ack));
+        assert!(ack.answers.is_empty());
+
+        let mut other = ack.clone();
+        other.header.id = 8;
+        assert!(!notify.is_answered_by(&other));
+        let mut other = ack.clone();
+        other.questions[0].q_name = name("example.net");
+        assert!(!notify.is_answered_by(&other));
+        assert!(!notify.is_answered_by(&notify.to_message()));
+    }
+
+    #[rstest]
+    #[case(|m: &mut Message| m.header.op_code = OpCode::Query, NotifyError::NotNotify)]
+    #[case(|m: &mut Message| m.questions.clear(), NotifyError::InvalidZoneCount(0))]
+    #[case(|m: &mut Message| m.questions[0].q_type = KnownQType::A.into(), NotifyError::InvalidZoneType(KnownQType::A.into()))]
+    #[case(|m: &mut Message| m.answers[0].name = name("example.net"), NotifyError::InvalidAnswer(name("example.net")))]
+    #[case(|m: &mut Message| m.answers.push(m.answers[0].clone()), NotifyError::InvalidAnswer(name("example.com")))]
+    fn notify_try_from_fails(#[case] change: fn(&mut Message), #[case] expected: NotifyError) {
+        let mut message = NotifyMessage::new(7, name("example.com"), KnownQClass::IN.into())
+            .with_soa(3600, soa(1))
+            .to_message();
+        change(&mut message);
+        assert_eq!(NotifyMessage::try_from(&message), Err(expected));
+    }
+}
@@ -13,14 +13,37 @@
 // limitations under the License.
 
 use crate::domain::{error, Domain};
+use crate::message::error::{QClassFromStrError, QTypeFromStrError, WireError};
+use crate::message::reader::Reader;
+use std::fmt;
+use std::str::FromStr;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Question {
     pub q_name: Domain,
     pub q_type: QType,
     pub q_class: QClass,
 }
 
+impl Question {
+    /// Encodes the question in uncompressed wire format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.q_name.to_bytes();
+        bytes.extend_from_slice(&self.q_type.value.to_be_bytes());
+        bytes.extend_from_slice(&self.q_class.value.to_be_bytes());
+        bytes
+    }
+
+    /// Decodes a question from `reader`, expanding compressed names.
+    pub(crate) fn from_wire(reader: &mut Reader) -> Result<Self, WireError> {
+        Ok(Question {
+            q_name: reader.name()?,
+            q_type: QType::new(reader.u16()?),
+            q_class: QClass::new(reader.u16()?),
+        })
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct QuestionParseData {
     pub question: Question,
@@ -107,7 +130,7 @@ pub fn parse_question(
     })
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct QType {
     pub value: u16,
 }
@@ -118,55 +141,61 @@ impl QType {
     }
 
     pub fn to_known_type(&self) -> Option<KnownQType> {
-        match self.value {
-            1 => Some(KnownQType::A),
-            2 => Some(KnownQType::NS),
-            3 => Some(KnownQType::MD),
-            4 => Some(KnownQType::MF),
-            5 => Some(KnownQType::CNAME),
-            6 => Some(KnownQType::SOA),
-            7 => Some(KnownQType::MB),
-            8 => Some(KnownQType::MG),
-            9 => Some(KnownQType::MR),
-            10 => Some(KnownQType::NULL),
-            11 => Some(KnownQType::WKS),
-            12 => Some(KnownQType::PTR),
-            13 => Some(KnownQType::HINFO),
-            14 => Some(KnownQType::MINFO),
-            15 => Some(KnownQType::MX),
-            16 => Some(KnownQType::TXT),
-            252 => Some(KnownQType::AXFR),
-            253 => Some(KnownQType::MAILB),
-            254 => Some(KnownQType::MAILA),
-            255 => Some(KnownQType::ANY),
-            _ => None,
+        KnownQType::from_value(self.value)
+    }
+
+    /// Returns whether the type is a meta type. Unassigned values from 128
+    /// to 255 are considered meta types, since that range is reserved for
+    /// meta and question types, see [RFC 6895, Section 3.1].
+    ///
+    /// [RFC 6895, Section 3.1]: https://datatracker.ietf.org/doc/html/rfc6895#section-3.1
+    pub fn is_meta(&self) -> bool {
+        match self.to_known_type() {
+            Some(known) => known.is_meta(),
+            None => (128..=255).contains(&self.value),
         }
     }
+
+    /// Returns whether the type may only appear in questions, such as
+    /// `AXFR` and `ANY`.
+    pub fn is_question_only(&self) -> bool {
+        self.to_known_type()
+            .is_some_and(|known| known.is_question_only())
+    }
+
+    /// Returns whether the type is marked as obsolete or deprecated.
+    pub fn is_obsolete(&self) -> bool {
+        self.to_known_type()
+            .is_some_and(|known| known.is_obsolete())
+    }
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
-#[repr(u16)]
-pub enum KnownQType {
-    A = 1,
-    NS = 2,
-    MD = 3,
-    MF = 4,
-    CNAME = 5,
-    SOA = 6,
-    MB = 7,
-    MG = 8,
-    MR = 9,
-    NULL = 10,
-    WKS = 11,
-    PTR = 12,
-    HINFO = 13,
-    MINFO = 14,
-    MX = 15,
-    TXT = 16,
-    AXFR = 252,
-    MAILB = 253,
-    MAILA = 254,
-    ANY = 255,
+include!(concat!(env!("OUT_DIR"), "/registry.rs"));
+
+impl fmt::Display for QType {
+    /// Formats the type using its mnemonic, or the generic `TYPEnnn` form
+    /// for types without one.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.to_known_type() {
+            Some(known) => write!(f, "{}", known.mnemonic()),
+            None => write!(f, "TYPE{}", self.value),
+        }
+    }
+}
+
+impl FromStr for QType {
+    type Err = QTypeFromStrError;
+
+    /// Parses a type from its mnemonic, regardless of case, or from the
+    /// generic `TYPEnnn` form described in [RFC 3597, Section 5].
+    ///
+    /// [RFC 3597, Section 5]: https://datatracker.ietf.org/doc/html/rfc3597#section-5
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        KnownQType::from_mnemonic(s)
+            .map(QType::from)
+            .or_else(|| parse_generic(s, "TYPE").map(QType::new))
+            .ok_or_else(|| QTypeFromStrError(s.to_string()))
+    }
 }
 
 impl From<KnownQType> for QType {
@@ -201,13 +230,7 @@ impl PartialEq<QType> for KnownQType {
     }
 }
 
-impl PartialEq for QType {
-    fn eq(&self, other: &Self) -> bool {
-        self.value == other.value
-    }
-}
-
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct QClass {
     pub value: u16,
 }
@@ -218,25 +241,48 @@ impl QClass {
     }
 
     pub fn to_known_class(&self) -> Option<KnownQClass> {
-        match self.value {
-            1 => Some(KnownQClass::IN),
-            2 => Some(KnownQClass::CS),
-            3 => Some(KnownQClass::CH),
-            4 => Some(KnownQClass::HS),
-            255 => Some(KnownQClass::ANY),
-            _ => None,
+        KnownQClass::from_value(self.value)
+    }
+
+    /// Returns whether the class is obsolete, such as `CS`.
+    pub fn is_obsolete(&self) -> bool {
+        self.to_known_class()
+            .is_some_and(|known| known.is_obsolete())
+    }
+}
+
+impl fmt::Display for QClass {
+    /// Formats the class using its mnemonic, or the generic `CLASSnnn` form
+    /// for classes without one.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.to_known_class() {
+            Some(known) => write!(f, "{}", known.mnemonic()),
+            None => write!(f, "CLASS{}", self.value),
         }
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
-#[repr(u16)]
-pub enum KnownQClass {
-    IN = 1,
-    CS = 2,
-    CH = 3,
-    HS = 4,
-    ANY = 255,
+impl FromStr for QClass {
+    type Err = QClassFromStrError;
+
+    /// Parses a class from its mnemonic, regardless of case, or from the
+    /// generic `CLASSnnn` form described in [RFC 3597, Section 5].
+    ///
+    /// [RFC 3597, Section 5]: https://datatracker.ietf.org/doc/html/rfc3597#section-5
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        KnownQClass::from_mnemonic(s)
+            .map(QClass::from)
+            .or_else(|| parse_generic(s, "CLASS").map(QClass::new))
+            .ok_or_else(|| QClassFromStrError(s.to_string()))
+    }
+}
+
+/// Parses the generic `<prefix>nnn` form of a type or class, regardless of
+/// case.
+fn parse_generic(s: &str, prefix: &str) -> Option<u16> {
+    s.get(..prefix.len())
+        .filter(|start| start.eq_ignore_ascii_case(prefix))
+        .and_then(|_| s[prefix.len()..].parse().ok())
 }
 
 impl From<KnownQClass> for QClass {
@@ -271,12 +317,6 @@ impl PartialEq<QClass> for KnownQClass {
     }
 }
 
-impl PartialEq for QClass {
-    fn eq(&self, other: &Self) -> bool {
-        self.value == other.value
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -311,6 +351,21 @@ mod tests {
     #[case(14, Some(KnownQType::MINFO))]
     #[case(15, Some(KnownQType::MX))]
     #[case(16, Some(KnownQType::TXT))]
+    #[case(24, Some(KnownQType::SIG))]
+    #[case(25, Some(KnownQType::KEY))]
+    #[case(28, Some(KnownQType::AAAA))]
+    #[case(43, Some(KnownQType::DS))]
+    #[case(46, Some(KnownQType::RRSIG))]
+    #[case(47, Some(KnownQType::NSEC))]
+    #[case(48, Some(KnownQType::DNSKEY))]
+    #[case(50, Some(KnownQType::NSEC3))]
+    #[case(51, Some(KnownQType::NSEC3PARAM))]
+    #[case(59, Some(KnownQType::CDS))]
+    #[case(60, Some(KnownQType::CDNSKEY))]
+    #[case(63, Some(KnownQType::ZONEMD))]
+    #[case(99, Some(KnownQType::SPF))]
+    #[case(250, Some(KnownQType::TSIG))]
+    #[case(251, Some(KnownQType::IXFR))]
     #[case(252, Some(KnownQType::AXFR))]
     #[case(253, Some(KnownQType::MAILB))]
     #[case(254, Some(KnownQType::MAILA))]
@@ -341,6 +396,89 @@ mod tests {
         assert_eq!(input, QType::new(input));
     }
 
+    #[rstest]
+    #[case(1, "A")]
+    #[case(48, "DNSKEY")]
+    #[case(51, "NSEC3PARAM")]
+    #[case(23, "NSAP-PTR")]
+    #[case(65, "HTTPS")]
+    #[case(255, "ANY")]
+    #[case(257, "CAA")]
+    #[case(32769, "DLV")]
+    #[case(54, "TYPE54")]
+    #[case(65280, "TYPE65280")]
+    fn qtype_to_string(#[case] input: u16, #[case] expected: &str) {
+        assert_eq!(QType::new(input).to_string(), expected);
+    }
+
+    #[rstest]
+    #[case(KnownQType::A, false, false, false)]
+    #[case(KnownQType::OPT, true, false, false)]
+    #[case(KnownQType::TSIG, true, false, false)]
+    #[case(KnownQType::AXFR, false, true, false)]
+    #[case(KnownQType::ANY, false, true, false)]
+    #[case(KnownQType::MAILA, false, true, true)]
+    #[case(KnownQType::A6, false, false, true)]
+    #[case(KnownQType::NSAP, false, false, true)]
+    #[case(KnownQType::SPF, false, false, true)]
+    fn known_qtype_status(
+        #[case] r_type: KnownQType,
+        #[case] meta: bool,
+        #[case] question_only: bool,
+        #[case] obsolete: bool,
+    ) {
+        assert_eq!(r_type.is_meta(), meta);
+        assert_eq!(r_type.is_question_only(), question_only);
+        assert_eq!(r_type.is_obsolete(), obsolete);
+        assert_eq!(QType::from(r_type).is_meta(), meta);
+        assert_eq!(QType::from(r_type).is_question_only(), question_only);
+        assert_eq!(QType::from(r_type).is_obsolete(), obsolete);
+    }
+
+    #[rstest]
+    #[case(129, true)]
+    #[case(127, false)]
+    #[case(65280, false)]
+    fn qtype_unassigned_is_meta(#[case] value: u16, #[case] expected: bool) {
+        assert_eq!(QType::new(value).is_meta(), expected);
+    }
+
+    #[rstest]
+    #[case(1, "IN")]
+    #[case(3, "CH")]
+    #[case(254, "NONE")]
+    #[case(1024, "CLASS1024")]
+    fn qclass_to_string(#[case] input: u16, #[case] expected: &str) {
+        assert_eq!(QClass::new(input).to_string(), expected);
+    }
+
+    #[rstest]
+    #[case("A", Ok(QType::new(1)))]
+    #[case("nsec3param", Ok(QType::new(51)))]
+    #[case("TYPE65280", Ok(QType::new(65280)))]
+    #[case("type1", Ok(QType::new(1)))]
+    #[case("type65", Ok(QType::new(65)))]
+    #[case("caa", Ok(QType::new(257)))]
+    #[case("NSAP-PTR", Ok(QType::new(23)))]
+    #[case("*", Err(QTypeFromStrError("*".to_string())))]
+    #[case("FOO", Err(QTypeFromStrError("FOO".to_string())))]
+    #[case("TYPE", Err(QTypeFromStrError("TYPE".to_string())))]
+    #[case("TYPE70000", Err(QTypeFromStrError("TYPE70000".to_string())))]
+    fn qtype_from_str(#[case] input: &str, #[case] expected: Result<QType, QTypeFromStrError>) {
+        assert_eq!(input.parse::<QType>(), expected);
+    }
+
+    #[rstest]
+    #[case("IN", Ok(QClass::new(1)))]
+    #[case("ch", Ok(QClass::new(3)))]
+    #[case("CLASS32", Ok(QClass::new(32)))]
+    #[case("none", Ok(QClass::new(254)))]
+    #[case("CS", Ok(QClass::new(2)))]
+    #[case("A", Err(QClassFromStrError("A".to_string())))]
+    fn qclass_from_str(#[case] input: &str, #[case] expected: Result<QClass, QClassFromStrError>) {
+        assert_eq!(input.parse::<QClass>(), expected);
+    }
+
     #[rstest]
     #[case(1, KnownQClass::IN)]
     #[case(2, KnownQClass::CS)]
@@ -357,6 +495,7 @@ mod tests {
     #[case(2, Some(KnownQClass::CS))]
     #[case(3, Some(KnownQClass::CH))]
     #[case(4, Some(KnownQClass::HS))]
+    #[case(254, Some(KnownQClass::NONE))]
     #[case(255, Some(KnownQClass::ANY))]
     #[case(1024, None)]
     fn qclass_to_known_type(#[case] input: u16, #[case] expected: Option<KnownQClass>) {
@@ -373,6 +512,15 @@ mod tests {
         assert_eq!(QClass::from(input), input);
     }
 
+    #[rstest]
+    #[case(1, false)]
+    #[case(2, true)]
+    #[case(3, false)]
+    #[case(1024, false)]
+    fn qclass_is_obsolete(#[case] input: u16, #[case] expected: bool) {
+        assert_eq!(QClass::new(input).is_obsolete(), expected);
+    }
+
     #[rstest]
     #[case(1)]
     #[case(2)]
@@ -0,0 +1,167 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::Domain;
+use crate::message::error::WireError;
+
+/// Cursor over the wire format of a DNS message.
+///
+/// The whole message is kept around, as compressed names may point to any
+/// earlier offset in it.
+pub(crate) struct Reader<'a> {
+    message: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(message: &'a [u8], pos: usize) -> Self {
+        Reader { message, pos }
+    }
+
+    pub(crate) fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub(crate) fn remaining(&self) -> usize {
+        self.message.len().saturating_sub(self.pos)
+    }
+
+    pub(crate) fn bytes(&mut self, len: usize) -> Result<&'a [u8], WireError> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.message.len())
+            .ok_or(WireError::UnexpectedEnd(self.message.len()))?;
+        let bytes = &self.message[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, WireError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub(crate) fn u16(&mut self) -> Result<u16, WireError> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, WireError> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Reads a 48-bit unsigned integer, as used by TSIG timestamps.
+    pub(crate) fn u48(&mut self) -> Result<u64, WireError> {
+        let bytes = self.bytes(6)?;
+        Ok(bytes.iter().fold(0, |acc, &byte| acc << 8 | byte as u64))
+    }
+
+    /// Reads a domain name, following compression pointers.
+    ///
+    /// Pointers must refer to an earlier offset than the label they
+    /// replace, which rules out loops.
+    ///
+    /// For more details, see [RFC 1035, Section 4.1.4].
+    ///
+    /// [RFC 1035, Section 4.1.4]: https://datatracker.ietf.org/doc/html/rfc1035#section-4.1.4
+    pub(crate) fn name(&mut self) -> Result<Domain, WireError> {
+        let mut domain = Domain::new();
+        let mut pos = self.pos;
+        let mut resume_at = None;
+        loop {
+            let len = *self
+                .message
+                .get(pos)
+                .ok_or(WireError::UnexpectedEnd(self.message.len()))?;
+            match len & 0b1100_0000 {
+                0b1100_0000 => {
+                    let low = *self
+                        .message
+                        .get(pos + 1)
+                        .ok_or(WireError::UnexpectedEnd(self.message.len()))?;
+                    let pointer = usize::from(u16::from_be_bytes([len & 0b0011_1111, low]));
+                    if pointer >= pos {
+                        return Err(WireError::InvalidPointer(pos));
+                    }
+                    resume_at.get_or_insert(pos + 2);
+                    pos = pointer;
+                }
+                0b0000_0000 if len == 0 => {
+                    self.pos = resume_at.unwrap_or(pos + 1);
+                    return Ok(domain);
+                }
+                0b0000_0000 => {
+                    let start = pos + 1;
+                    let end = start + len as usize;
+                    let label = self
+                        .message
+                        .get(start..end)
+                        .ok_or(WireError::UnexpectedEnd(self.message.len()))?;
+                    domain.add_raw_label(label)?;
+                    pos = end;
+                }
+                // Extended label types are obsolete, see RFC 6891.
+                _ => return Err(WireError::InvalidPointer(pos)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn reader_reads_integers() {
+        let bytes = [1, 0, 2, 0, 0, 0, 3, 0, 0, 0, 0, 1, 0];
+        let mut reader = Reader::new(&bytes, 0);
+        assert_eq!(reader.u8(), Ok(1));
+        assert_eq!(reader.u16(), Ok(2));
+        assert_eq!(reader.u32(), Ok(3));
+        assert_eq!(reader.u48(), Ok(256));
+        assert_eq!(reader.remaining(), 0);
+        assert_eq!(reader.u8(), Err(WireError::UnexpectedEnd(13)));
+    }
+
+    #[rstest]
+    // "example.com" at 0, "www" pointing to it at 13.
+    #[case(13, "www.example.com", 19)]
+    #[case(0, "example.com", 13)]
+    // A bare pointer.
+    #[case(19, "example.com", 21)]
+    #[case(21, ".", 22)]
+    fn reader_reads_names(#[case] offset: usize, #[case] expected: &str, #[case] end: usize) {
+        let bytes = [
+            7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 3, b'c', b'o', b'm', 0, 3, b'w', b'w',
+            b'w', 0xc0, 0, 0xc0, 0, 0,
+        ];
+        let mut reader = Reader::new(&bytes, offset);
+        let name = reader.name().unwrap();
+        assert_eq!(name, Domain::from_presentation(expected).unwrap());
+        assert_eq!(reader.position(), end);
+    }
+
+    #[rstest]
+    #[case(&[3, b'c', b'o'], WireError::UnexpectedEnd(3))]
+    #[case(&[3, b'c', b'o', b'm'], WireError::UnexpectedEnd(4))]
+    #[case(&[0xc0, 0], WireError::InvalidPointer(0))]
+    #[case(&[1, b'a', 0xc0, 2], WireError::InvalidPointer(2))]
+    #[case(&[0xc0], WireError::UnexpectedEnd(1))]
+    #[case(&[0x40, 0], WireError::InvalidPointer(0))]
+    fn reader_rejects_invalid_names(#[case] bytes: &[u8], #[case] expected: WireError) {
+        assert_eq!(Reader::new(bytes, 0).name(), Err(expected));
+    }
+}
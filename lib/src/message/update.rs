@@ -0,0 +1,528 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::Domain;
+use crate::message::error::UpdateError;
+use crate::message::{Header, KnownQClass, KnownQType, Message, OpCode, QClass, QType, Question};
+use crate::record::{RData, ResourceRecord};
+
+/// Condition that must hold in the zone for a dynamic update to be applied.
+///
+/// For more details, see [RFC 2136, Section 2.4].
+///
+/// [RFC 2136, Section 2.4]: https://datatracker.ietf.org/doc/html/rfc2136#section-2.4
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Prerequisite {
+    /// At least one record set is owned by the name.
+    NameInUse(Domain),
+    /// No record set is owned by the name.
+    NameNotInUse(Domain),
+    /// A record set of the type exists, whatever its records.
+    RrsetExists { name: Domain, r_type: QType },
+    /// No record set of the type exists.
+    RrsetDoesNotExist { name: Domain, r_type: QType },
+    /// A record set of the type exists with exactly these records. Note
+    /// that RFC 2136 defines no negation of this form.
+    RrsetEquals {
+        name: Domain,
+        r_type: QType,
+        rdatas: Vec<RData>,
+    },
+}
+
+/// Change requested by a dynamic update.
+///
+/// For more details, see [RFC 2136, Section 2.5].
+///
+/// [RFC 2136, Section 2.5]: https://datatracker.ietf.org/doc/html/rfc2136#section-2.5
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Update {
+    /// Adds a record to its record set. Its class is the class of the zone.
+    Add(ResourceRecord),
+    /// Deletes the record set of the type owned by the name.
+    DeleteRrset { name: Domain, r_type: QType },
+    /// Deletes all the record sets owned by the name.
+    DeleteAllRrsets(Domain),
+    /// Deletes a single record from its record set.
+    DeleteRecord {
+        name: Domain,
+        r_type: QType,
+        rdata: RData,
+    },
+}
+
+/// Typed view of a dynamic update message.
+///
+/// The sections of an `UPDATE` message reuse those of a standard query:
+/// the zone section is the question section, while the prerequisite and
+/// update sections are the answer and authority sections. The meaning of
+/// each record then depends on its class, which is either the class of
+/// the zone, `ANY` or `NONE`.
+///
+/// For more details, see [RFC 2136, Section 2].
+///
+/// [RFC 2136, Section 2]: https://datatracker.ietf.org/doc/html/rfc2136#section-2
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateMessage {
+    pub header: Header,
+    pub zone: Domain,
+    pub class: QClass,
+    pub prerequisites: Vec<Prerequisite>,
+    pub updates: Vec<Update>,
+    pub additionals: Vec<ResourceRecord>,
+}
+
+impl UpdateMessage {
+    /// Converts the update into a plain message, with `OPCODE` `UPDATE`.
+    pub fn to_message(&self) -> Message {
+        let mut header = self.header.clone();
+        header.op_code = OpCode::Update;
+        let mut message = Message::new(header);
+        message.questions.push(Question {
+            q_name: self.zone.clone(),
+            q_type: KnownQType::SOA.into(),
+            q_class: self.class,
+        });
+        message.answers = self
+            .prerequisites
+            .iter()
+            .flat_map(|prerequisite| self.prerequisite_records(prerequisite))
+            .collect();
+        message.authorities = self
+            .updates
+            .iter()
+            .map(|update| self.update_record(update))
+            .collect();
+        message.additionals = self.additionals.clone();
+        message
+    }
+
+    /// Encodes the update in uncompressed wire format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_message().to_bytes()
+    }
+
+    fn prerequisite_records(&self, prerequisite: &Prerequisite) -> Vec<ResourceRecord> {
+        let any = QClass::from(KnownQClass::ANY);
+        let none = QClass::from(KnownQClass::NONE);
+        match prerequisite {
+            Prerequisite::NameInUse(name) => vec![meta_record(name, KnownQType::ANY.into(), any)],
+            Prerequisite::NameNotInUse(name) => {
+                vec![meta_record(name, KnownQType::ANY.into(), none)]
+            }
+            Prerequisite::RrsetExists { name, r_type } => vec![meta_record(name, *r_type, any)],
+            Prerequisite::RrsetDoesNotExist { name, r_type } => {
+                vec![meta_record(name, *r_type, none)]
+            }
+            Prerequisite::RrsetEquals {
+                name,
+                r_type,
+                rdatas,
+            } => rdatas
+                .iter()
+                .map(|rdata| ResourceRecord {
+                    rdata: rdata.clone(),
+                    ..meta_record(name, *r_type, self.class)
+                })
+                .collect(),
+        }
+    }
+
+    fn update_record(&self, update: &Update) -> ResourceRecord {
+        match update {
+            Update::Add(record) => ResourceRecord {
+                r_class: self.class,
+                ..record.clone()
+            },
+            Update::DeleteRrset { name, r_type } => {
+                meta_record(name, *r_type, KnownQClass::ANY.into())
+            }
+            Update::DeleteAllRrsets(name) => {
+                meta_record(name, KnownQType::ANY.into(), KnownQClass::ANY.into())
+            }
+            Update::DeleteRecord {
+                name,
+                r_type,
+                rdata,
+            } => ResourceRecord {
+                rdata: rdata.clone(),
+                ..meta_record(name, *r_type, KnownQClass::NONE.into())
+            },
+        }
+    }
+}
+
+/// Record with a zero TTL and empty `RDATA`, as used by most prerequisites
+/// and deletions.
+fn meta_record(name: &Domain, r_type: QType, r_class: QClass) -> ResourceRecord {
+    ResourceRecord {
+        name: name.clone(),
+        r_type,
+        r_class,
+        ttl: 0,
+        rdata: RData::Unknown(Vec::new()),
+    }
+}
+
+fn is_empty(record: &ResourceRecord) -> bool {
+    record.ttl == 0 && record.rdata == RData::Unknown(Vec::new())
+}
+
+impl TryFrom<&Message> for UpdateMessage {
+    type Error = UpdateError;
+
+    /// Interprets `message` as a dynamic update, checking the forms of its
+    /// prerequisites and updates as described in [RFC 2136, Section 3].
+    ///
+    /// Value-dependent prerequisites on the same record set are merged into
+    /// a single [`Prerequisite::RrsetEquals`].
+    ///
+    /// [RFC 2136, Section 3]: https://datatracker.ietf.org/doc/html/rfc2136#section-3
+    fn try_from(message: &Message) -> Result<Self, Self::Error> {
+        if message.header.op_code != OpCode::Update {
+            return Err(UpdateError::NotUpdate);
+        }
+        let [zone] = message.questions.as_slice() else {
+            return Err(UpdateError::InvalidZoneCount(message.questions.len() as u16));
+        };
+        if zone.q_type != KnownQType::SOA {
+            return Err(UpdateError::InvalidZoneType(zone.q_type));
+        }
+        let class = zone.q_class;
+
+        let mut prerequisites = Vec::new();
+        for record in &message.answers {
+            let invalid = || UpdateError::InvalidPrerequisite(record.name.clone());
+            let name = record.name.clone();
+            let r_type = record.r_type;
+            // Meta types, such as `OPT` or `AXFR`, cannot be added, deleted
+            // or required to exist as records.
+            let data_type = !r_type.is_meta() && !r_type.is_question_only();
+            let prerequisite = match record.r_class.to_known_class() {
+                Some(KnownQClass::ANY) if is_empty(record) => match r_type == KnownQType::ANY {
+                    true => Prerequisite::NameInUse(name),
+                    false if data_type => Prerequisite::RrsetExists { name, r_type },
+                    false => return Err(invalid()),
+                },
+                Some(KnownQClass::NONE) if is_empty(record) => match r_type == KnownQType::ANY {
+                    true => Prerequisite::NameNotInUse(name),
+                    false if data_type => Prerequisite::RrsetDoesNotExist { name, r_type },
+                    false => return Err(invalid()),
+                },
+                _ if record.r_class == class && record.ttl == 0 && data_type => {
+                    let existing = prerequisites.iter_mut().find_map(|p| match p {
+                        Prerequisite::RrsetEquals {
+                            name: n,
+                            r_type: t,
+                            rdatas,
+                        } if *n == name && *t == r_type => Some(rdatas),
+                        _ => None,
+                    });
+                    if let Some(rdatas) = existing {
+                        rdatas.push(record.rdata.clone());
+                        continue;
+                    }
+                    Prerequisite::RrsetEquals {
+                        name,
+                        r_type,
+                        rdatas: vec![record.rdata.clone()],
+                    }
+                }
+                _ => return Err(invalid()),
+            };
+            prerequisites.push(prerequisite);
+        }
+
+        let mut updates = Vec::new();
+        for record in &message.authorities {
+            let invalid = || UpdateError::InvalidUpdate(record.name.clone());
+            let name = record.name.clone();
+            let r_type = record.r_type;
+            let data_type = !r_type.is_meta() && !r_type.is_question_only();
+            let update = match record.r_class.to_known_class() {
+                Some(KnownQClass::ANY) if is_empty(record) => match r_type == KnownQType::ANY {
+                    true => Update::DeleteAllRrsets(name),
+                    false if data_type => Update::DeleteRrset { name, r_type },
+                    false => return Err(invalid()),
+                },
+                Some(KnownQClass::NONE) if record.ttl == 0 && data_type => Update::DeleteRecord {
+                    name,
+                    r_type,
+                    rdata: record.rdata.clone(),
+                },
+                _ if record.r_class == class && data_type => Update::Add(record.clone()),
+                _ => return Err(invalid()),
+            };
+            updates.push(update);
+        }
+
+        Ok(UpdateMessage {
+            header: message.header.clone(),
+            zone: zone.q_name.clone(),
+            class,
+            prerequisites,
+            updates,
+            additionals: message.additionals.clone(),
+        })
+    }
+}
+
+impl TryFrom<&[u8]> for UpdateMessage {
+    type Error = UpdateError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        UpdateMessage::try_from(&Message::try_from(bytes)?)
+    }
+}
+
+/// Builds an [`UpdateMessage`] for a zone, one prerequisite or update at a
+/// time.
+///
+/// # Example
+/// ```
+/// use dns_lib::message::{KnownQClass, KnownQType, UpdateBuilder};
+/// use dns_lib::Domain;
+///
+/// let zone = Domain::from_presentation("example.com").unwrap();
+/// let host = Domain::from_presentation("host.example.com").unwrap();
+/// let update = UpdateBuilder::new(zone, KnownQClass::IN.into())
+///     .id(42)
+///     .name_in_use(host.clone())
+///     .delete_rrset(host, KnownQType::A.into())
+///     .build();
+/// assert_eq!(update.prerequisites.len(), 1);
+/// assert_eq!(update.updates.len(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct UpdateBuilder {
+    update: UpdateMessage,
+}
+
+impl UpdateBuilder {
+    pub fn new(zone: Domain, class: QClass) -> Self {
+        let mut header = Header::new(0);
+        header.op_code = OpCode::Update;
+        UpdateBuilder {
+            update: UpdateMessage {
+                header,
+                zone,
+                class,
+                prerequisites: Vec::new(),
+                updates: Vec::new(),
+                additionals: Vec::new(),
+            },
+        }
+    }
+
+    pub fn id(mut self, id: u16) -> Self {
+        self.update.header.id = id;
+        self
+    }
+
+    pub fn name_in_use(self, name: Domain) -> Self {
+        self.prerequisite(Prerequisite::NameInUse(name))
+    }
+
+    pub fn name_not_in_use(self, name: Domain) -> Self {
+        self.prerequisite(Prerequisite::NameNotInUse(name))
+    }
+
+    pub fn rrset_exists(self, name: Domain, r_type: QType) -> Self {
+        self.prerequisite(Prerequisite::RrsetExists { name, r_type })
+    }
+
+    pub fn rrset_does_not_exist(self, name: Domain, r_type: QType) -> Self {
+        self.prerequisite(Prerequisite::RrsetDoesNotExist { name, r_type })
+    }
+
+    pub fn rrset_equals(self, name: Domain, r_type: QType, rdatas: Vec<RData>) -> Self {
+        self.prerequisite(Prerequisite::RrsetEquals {
+            name,
+            r_type,
+            rdatas,
+        })
+    }
+
+    /// Adds `record` to the zone. Its class is replaced by that of the zone.
+    pub fn add_record(self, record: ResourceRecord) -> Self {
+        let class = self.update.class;
+        self.update(Update::Add(ResourceRecord {
+            r_class: class,
+            ..record
+        }))
+    }
+
+    pub fn delete_rrset(self, name: Domain, r_type: QType) -> Self {
+        self.update(Update::DeleteRrset { name, r_type })
+    }
+
+    pub fn delete_all_rrsets(self, name: Domain) -> Self {
+        self.update(Update::DeleteAllRrsets(name))
+    }
+
+    pub fn delete_record(self, name: Domain, r_type: QType, rdata: RData) -> Self {
+        self.update(Update::DeleteRecord {
+            name,
+            r_type,
+            rdata,
+        })
+    }
+
+    /// Appends `record` to the additional section, for instance glue for
+    /// added `NS` records.
+    pub fn additional(mut self, record: ResourceRecord) -> Self {
+        self.update.additionals.push(record);
+        self
+    }
+
+    pub fn build(self) -> UpdateMessage {
+        self.update
+    }
+
+    fn prerequisite(mut self, prerequisite: Prerequisite) -> Self {
+        self.update.prerequisites.push(prerequisite);
+        self
+    }
+
+    fn update(mut self, update: Update) -> Self {
+        self.update.updates.push(update);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::rdata::Mx;
+    use rstest::rstest;
+    use std::net::Ipv4Addr;
+
+    fn name(text: &str) -> Domain {
+        Domain::from_presentation(text).unwrap()
+    }
+
+    fn a(owner: &str, ttl: u32, addr: [u8; 4]) -> ResourceRecord {
+        ResourceRecord {
+            name: name(owner),
+            r_type: KnownQType::A.into(),
+            r_class: KnownQClass::IN.into(),
+            ttl,
+            rdata: RData::A(Ipv4Addr::from(addr)),
+        }
+    }
+
+    fn full_update(class: KnownQClass) -> UpdateMessage {
+        UpdateBuilder::new(name("example.com"), class.into())
+            .id(4321)
+            .name_in_use(name("www.example.com"))
+            .name_not_in_use(name("new.example.com"))
+            .rrset_exists(name("example.com"), KnownQType::MX.into())
+            .rrset_does_not_exist(name("www.example.com"), KnownQType::CNAME.into())
+            .rrset_equals(
+                name("www.example.com"),
+                KnownQType::A.into(),
+                vec![
+                    RData::A(Ipv4Addr::new(192, 0, 2, 1)),
+                    RData::A(Ipv4Addr::new(192, 0, 2, 2)),
+                ],
+            )
+            .add_record(a("new.example.com", 300, [192, 0, 2, 3]))
+            .delete_rrset(name("www.example.com"), KnownQType::AAAA.into())
+            .delete_all_rrsets(name("old.example.com"))
+            .delete_record(
+                name("example.com"),
+                KnownQType::MX.into(),
+                RData::MX(Mx {
+                    preference: 10,
+                    exchange: name("mail.example.com"),
+                }),
+            )
+            .build()
+    }
+
+    #[rstest]
+    #[case(KnownQClass::IN)]
+    #[case(KnownQClass::CH)]
+    fn update_round_trip(#[case] class: KnownQClass) {
+        let update = full_update(class);
+        let bytes = update.to_bytes();
+
+        let message = Message::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(message.header.op_code, OpCode::Update);
+        assert_eq!(message.questions.len(), 1);
+        assert_eq!(message.answers.len(), 6);
+        assert_eq!(message.authorities.len(), 4);
+
+        let decoded = UpdateMessage::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(decoded.prerequisites, update.prerequisites);
+        assert_eq!(decoded.updates, update.updates);
+        assert_eq!(decoded.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn update_record_forms() {
+        let message = full_update(KnownQClass::IN).to_message();
+        let forms: Vec<(u16, u16, u32, usize)> = message
+            .answers
+            .iter()
+            .chain(&message.authorities)
+            .map(|r| {
+                (
+                    r.r_class.value,
+                    r.r_type.value,
+                    r.ttl,
+                    r.rdata.to_bytes().len(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            forms,
+            [
+                // Prerequisites.
+                (255, 255, 0, 0),
+                (254, 255, 0, 0),
+                (255, 15, 0, 0),
+                (254, 5, 0, 0),
+                (1, 1, 0, 4),
+                (1, 1, 0, 4),
+                // Updates.
+                (1, 1, 300, 4),
+                (255, 28, 0, 0),
+                (255, 255, 0, 0),
+                (254, 15, 0, 20),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case(|m: &mut Message| m.header.op_code = OpCode::Query, UpdateError::NotUpdate)]
+    #[case(|m: &mut Message| m.questions.clear(), UpdateError::InvalidZoneCount(0))]
+    #[case(|m: &mut Message| m.questions[0].q_type = KnownQType::A.into(), UpdateError::InvalidZoneType(KnownQType::A.into()))]
+    #[case(|m: &mut Message| m.answers[0].ttl = 1, UpdateError::InvalidPrerequisite(name("www.example.com")))]
+    #[case(|m: &mut Message| m.answers[4].r_class = KnownQClass::CH.into(), UpdateError::InvalidPrerequisite(name("www.example.com")))]
+    #[case(|m: &mut Message| m.answers[2].r_type = KnownQType::OPT.into(), UpdateError::InvalidPrerequisite(name("example.com")))]
+    #[case(|m: &mut Message| m.answers[2].r_type = KnownQType::AXFR.into(), UpdateError::InvalidPrerequisite(name("example.com")))]
+    #[case(|m: &mut Message| m.answers[3].r_type = KnownQType::TSIG.into(), UpdateError::InvalidPrerequisite(name("www.example.com")))]
+    #[case(|m: &mut Message| m.answers[3].r_type = KnownQType::MAILB.into(), UpdateError::InvalidPrerequisite(name("www.example.com")))]
+    #[case(|m: &mut Message| m.authorities[1].rdata = RData::A(Ipv4Addr::LOCALHOST), UpdateError::InvalidUpdate(name("www.example.com")))]
+    #[case(|m: &mut Message| m.authorities[0].r_type = KnownQType::AXFR.into(), UpdateError::InvalidUpdate(name("new.example.com")))]
+    #[case(|m: &mut Message| m.authorities[0].r_type = KnownQType::OPT.into(), UpdateError::InvalidUpdate(name("new.example.com")))]
+    #[case(|m: &mut Message| m.authorities[1].r_type = KnownQType::OPT.into(), UpdateError::InvalidUpdate(name("www.example.com")))]
+    #[case(|m: &mut Message| m.authorities[3].ttl = 60, UpdateError::InvalidUpdate(name("example.com")))]
+    fn update_try_from_fails(#[case] change: fn(&mut Message), #[case] expected: UpdateError) {
+        let mut message = full_update(KnownQClass::IN).to_message();
+        change(&mut message);
+        assert_eq!(UpdateMessage::try_from(&message), Err(expected));
+    }
+}
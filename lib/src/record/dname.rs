@@ -0,0 +1,169 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Redirection of a subtree of the domain name space with `DNAME` records.
+//!
+//! For more details, see [RFC 6672].
+//!
+//! [RFC 6672]: https://datatracker.ietf.org/doc/html/rfc6672
+
+use crate::domain::Domain;
+use crate::message::KnownQType;
+use crate::record::error::DnameError;
+use crate::record::{RData, ResourceRecord};
+
+/// Synthesizes the `CNAME` record that accompanies `dname` in answers to
+/// queries for `qname`, a name below the owner of the `DNAME` record.
+///
+/// The `CNAME` record has `qname` as owner and the class and TTL of the
+/// `DNAME` record, see [RFC 6672, Section 3.1].
+///
+/// # Example
+/// ```
+/// use dns_lib::record::dname;
+/// use dns_lib::record::{RData, ResourceRecord};
+/// use dns_lib::Domain;
+///
+/// let dname = ResourceRecord {
+///     name: Domain::from_presentation("example.com").unwrap(),
+///     r_type: "DNAME".parse().unwrap(),
+///     r_class: "IN".parse().unwrap(),
+///     ttl: 3600,
+///     rdata: RData::DNAME(Domain::from_presentation("example.net").unwrap()),
+/// };
+/// let qname = Domain::from_presentation("www.example.com").unwrap();
+///
+/// let cname = dname::synthesize_cname(&dname, &qname).unwrap();
+/// assert_eq!(cname.to_string(), "www.example.com. 3600 IN CNAME www.example.net.");
+/// ```
+///
+/// [RFC 6672, Section 3.1]: https://datatracker.ietf.org/doc/html/rfc6672#section-3.1
+pub fn synthesize_cname(
+    dname: &ResourceRecord,
+    qname: &Domain,
+) -> Result<ResourceRecord, DnameError> {
+    let RData::DNAME(target) = &dname.rdata else {
+        return Err(DnameError::NotDname);
+    };
+    Ok(ResourceRecord {
+        name: qname.clone(),
+        r_type: KnownQType::CNAME.into(),
+        r_class: dname.r_class,
+        ttl: dname.ttl,
+        rdata: RData::CNAME(qname.substitute(&dname.name, target)?),
+    })
+}
+
+/// Verifies that `cname` is the record synthesized from `dname`, as a
+/// resolver does with the `CNAME` record received alongside a `DNAME` record.
+///
+/// The TTL is not compared, since caches decrement the TTLs of the two
+/// records independently.
+///
+/// For more details, see [RFC 6672, Section 3.4].
+///
+/// [RFC 6672, Section 3.4]: https://datatracker.ietf.org/doc/html/rfc6672#section-3.4
+pub fn verify_cname(dname: &ResourceRecord, cname: &ResourceRecord) -> Result<(), DnameError> {
+    let RData::DNAME(target) = &dname.rdata else {
+        return Err(DnameError::NotDname);
+    };
+    let RData::CNAME(actual) = &cname.rdata else {
+        return Err(DnameError::NotCname);
+    };
+    let expected = cname.name.substitute(&dname.name, target)?;
+    if cname.r_class != dname.r_class || *actual != expected {
+        return Err(DnameError::CnameMismatch(expected));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::error::SubstitutionError;
+    use crate::message::KnownQClass;
+    use rstest::rstest;
+
+    fn name(text: &str) -> Domain {
+        Domain::from_presentation(text).unwrap()
+    }
+
+    fn record(owner: &str, r_type: KnownQType, rdata: RData) -> ResourceRecord {
+        ResourceRecord {
+            name: name(owner),
+            r_type: r_type.into(),
+            r_class: KnownQClass::IN.into(),
+            ttl: 3600,
+            rdata,
+        }
+    }
+
+    fn dname() -> ResourceRecord {
+        record(
+            "example.com",
+            KnownQType::DNAME,
+            RData::DNAME(name("example.net")),
+        )
+    }
+
+    #[rstest]
+    #[case("www.example.com", Ok("www.example.net"))]
+    #[case("a.b.example.com", Ok("a.b.example.net"))]
+    #[case(
+        "example.com",
+        Err(DnameError::Substitution(SubstitutionError::NotBelowOwner))
+    )]
+    #[case(
+        "www.example.org",
+        Err(DnameError::Substitution(SubstitutionError::NotBelowOwner))
+    )]
+    fn synthesize_cname_works(#[case] qname: &str, #[case] expected: Result<&str, DnameError>) {
+        let expected =
+            expected.map(|target| record(qname, KnownQType::CNAME, RData::CNAME(name(target))));
+        assert_eq!(synthesize_cname(&dname(), &name(qname)), expected);
+    }
+
+    #[test]
+    fn synthesize_cname_rejects_other_records() {
+        let ns = record(
+            "example.com",
+            KnownQType::NS,
+            RData::NS(name("ns.example.com")),
+        );
+        assert_eq!(
+            synthesize_cname(&ns, &name("www.example.com")),
+            Err(DnameError::NotDname)
+        );
+    }
+
+    #[rstest]
+    #[case(RData::CNAME(name("www.example.net")), Ok(()))]
+    #[case(RData::CNAME(name("WWW.Example.NET")), Ok(()))]
+    #[case(
+        RData::CNAME(name("www.example.org")),
+        Err(DnameError::CnameMismatch(name("www.example.net")))
+    )]
+    #[case(RData::NS(name("www.example.net")), Err(DnameError::NotCname))]
+    fn verify_cname_works(#[case] rdata: RData, #[case] expected: Result<(), DnameError>) {
+        let cname = record("www.example.com", KnownQType::CNAME, rdata);
+        assert_eq!(verify_cname(&dname(), &cname), expected);
+    }
+
+    #[test]
+    fn verify_cname_ignores_ttl() {
+        let mut cname = synthesize_cname(&dname(), &name("www.example.com")).unwrap();
+        cname.ttl = 0;
+        assert_eq!(verify_cname(&dname(), &cname), Ok(()));
+    }
+}
@@ -0,0 +1,162 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::error::SubstitutionError;
+use crate::domain::Domain;
+use crate::message::QType;
+use std::error::Error;
+use std::fmt;
+
+/// Errors found while parsing `RDATA` written in presentation format.
+#[derive(Debug, PartialEq)]
+pub enum RDataParseError {
+    MissingField(&'static str),
+    InvalidField(&'static str, String),
+    TrailingField(String),
+    /// The type has no presentation format, and must be written using the
+    /// generic `\#` form.
+    UnsupportedType(QType),
+    /// The length of generic `RDATA` does not match its hexadecimal data.
+    InvalidLength {
+        expected: usize,
+        actual: usize,
+    },
+}
+
+impl fmt::Display for RDataParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingField(field) => write!(f, "missing {}", field),
+            Self::InvalidField(field, value) => write!(f, "invalid {} '{}'", field, value),
+            Self::TrailingField(value) => write!(f, "unexpected field '{}'", value),
+            Self::UnsupportedType(r_type) => {
+                write!(f, "type {} must use the generic RDATA format", r_type)
+            }
+            Self::InvalidLength { expected, actual } => write!(
+                f,
+                "generic RDATA has {} bytes instead of {}",
+                actual, expected
+            ),
+        }
+    }
+}
+
+impl Error for RDataParseError {}
+
+/// Errors found while building a `<character-string>` or parsing it from
+/// presentation format.
+#[derive(Debug, PartialEq)]
+pub enum CharacterStringError {
+    /// The string is longer than the 255 octets allowed by its one octet
+    /// length prefix.
+    TooLong(usize),
+    InvalidEscape(String),
+    UnterminatedQuote,
+}
+
+impl fmt::Display for CharacterStringError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::TooLong(len) => write!(
+                f,
+                "character string has {} octets, more than the maximum of 255",
+                len
+            ),
+            Self::InvalidEscape(escape) => write!(f, "invalid escape sequence '{}'", escape),
+            Self::UnterminatedQuote => write!(f, "unterminated quoted string"),
+        }
+    }
+}
+
+impl Error for CharacterStringError {}
+
+/// Errors found while synthesizing or verifying the `CNAME` record that
+/// accompanies a `DNAME` record.
+#[derive(Debug, PartialEq)]
+pub enum DnameError {
+    NotDname,
+    NotCname,
+    Substitution(SubstitutionError),
+    /// The `CNAME` record does not point to the given name obtained from
+    /// the `DNAME` record, or has a different class.
+    CnameMismatch(Domain),
+}
+
+impl fmt::Display for DnameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotDname => write!(f, "record is not a DNAME"),
+            Self::NotCname => write!(f, "record is not a CNAME"),
+            Self::Substitution(err) => write!(f, "DNAME substitution failed: {}", err),
+            Self::CnameMismatch(expected) => write!(
+                f,
+                "CNAME does not match the DNAME, expected target {}",
+                expected.to_fqdn_string()
+            ),
+        }
+    }
+}
+
+impl Error for DnameError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Substitution(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<SubstitutionError> for DnameError {
+    fn from(value: SubstitutionError) -> Self {
+        Self::Substitution(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(RDataParseError::MissingField("exchange"), "missing exchange")]
+    #[case(
+        RDataParseError::InvalidField("address", "192.0.2".to_string()),
+        "invalid address '192.0.2'"
+    )]
+    #[case(RDataParseError::TrailingField("x".to_string()), "unexpected field 'x'")]
+    #[case(
+        RDataParseError::UnsupportedType(QType::new(250)),
+        "type TSIG must use the generic RDATA format"
+    )]
+    #[case(
+        RDataParseError::InvalidLength { expected: 4, actual: 2 },
+        "generic RDATA has 2 bytes instead of 4"
+    )]
+    fn rdata_parse_error_display(#[case] err: RDataParseError, #[case] msg: &str) {
+        assert_eq!(err.to_string(), msg);
+    }
+
+    #[rstest]
+    #[case(
+        CharacterStringError::TooLong(256),
+        "character string has 256 octets, more than the maximum of 255"
+    )]
+    #[case(
+        CharacterStringError::InvalidEscape("\\256".to_string()),
+        "invalid escape sequence '\\256'"
+    )]
+    fn character_string_error_display(#[case] err: CharacterStringError, #[case] msg: &str) {
+        assert_eq!(err.to_string(), msg);
+    }
+}
@@ -0,0 +1,50 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::Domain;
+use std::fmt;
+
+/// `RDATA` of an `AFSDB` record, naming a server of the AFS cell or DCE
+/// cell named by the owner. Subtype 1 is an AFS volume location server,
+/// subtype 2 a DCE authenticated name server.
+///
+/// For more details, see [RFC 1183, Section 1] and [RFC 5864].
+///
+/// [RFC 1183, Section 1]: https://datatracker.ietf.org/doc/html/rfc1183#section-1
+/// [RFC 5864]: https://datatracker.ietf.org/doc/html/rfc5864
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Afsdb {
+    pub subtype: u16,
+    pub hostname: Domain,
+}
+
+impl Afsdb {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.subtype.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&self.hostname.to_bytes());
+        bytes
+    }
+
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.subtype.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&self.hostname.to_canonical_bytes());
+        bytes
+    }
+}
+
+impl fmt::Display for Afsdb {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.subtype, self.hostname.to_fqdn_string())
+    }
+}
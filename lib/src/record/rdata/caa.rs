@@ -0,0 +1,88 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::character_string::write_quoted;
+use std::fmt;
+
+/// `RDATA` of a `CAA` record, a property restricting which certification
+/// authorities may issue certificates for the owner name.
+///
+/// For more details, see [RFC 8659, Section 4.1].
+///
+/// [RFC 8659, Section 4.1]: https://datatracker.ietf.org/doc/html/rfc8659#section-4.1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Caa {
+    pub flags: u8,
+    pub tag: String,
+    pub value: Vec<u8>,
+}
+
+impl Caa {
+    /// Issuer Critical flag, telling CAs that do not understand the tag not
+    /// to issue.
+    pub const CRITICAL: u8 = 0x80;
+
+    pub fn is_critical(&self) -> bool {
+        self.flags & Self::CRITICAL != 0
+    }
+
+    /// Returns whether `tag` is a valid property tag, made of 1 to 15 ASCII
+    /// letters and digits.
+    pub fn is_valid_tag(tag: &str) -> bool {
+        (1..=15).contains(&tag.len()) && tag.bytes().all(|b| b.is_ascii_alphanumeric())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![self.flags, self.tag.len() as u8];
+        bytes.extend_from_slice(self.tag.as_bytes());
+        bytes.extend_from_slice(&self.value);
+        bytes
+    }
+}
+
+impl fmt::Display for Caa {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} ", self.flags, self.tag)?;
+        write_quoted(f, &self.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn caa_to_bytes() {
+        let caa = Caa {
+            flags: Caa::CRITICAL,
+            tag: "issue".to_string(),
+            value: b"ca.example".to_vec(),
+        };
+        assert!(caa.is_critical());
+        assert_eq!(caa.to_bytes()[..7], [128, 5, b'i', b's', b's', b'u', b'e']);
+        assert_eq!(caa.to_bytes().len(), 17);
+        assert_eq!(caa.to_string(), "128 issue \"ca.example\"");
+    }
+
+    #[rstest]
+    #[case("issue", true)]
+    #[case("tbs1", true)]
+    #[case("", false)]
+    #[case("issue-wild", false)]
+    #[case("abcdefghijklmnop", false)]
+    fn caa_is_valid_tag(#[case] tag: &str, #[case] expected: bool) {
+        assert_eq!(Caa::is_valid_tag(tag), expected);
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+/// Certificate types with a mnemonic, see [RFC 4398, Section 2.1].
+const TYPE_MNEMONICS: [(u16, &str); 10] = [
+    (1, "PKIX"),
+    (2, "SPKI"),
+    (3, "PGP"),
+    (4, "IPKIX"),
+    (5, "ISPKI"),
+    (6, "IPGP"),
+    (7, "ACPKIX"),
+    (8, "IACPKIX"),
+    (253, "URI"),
+    (254, "OID"),
+];
+
+/// `RDATA` of a `CERT` record, storing a certificate or a certificate
+/// revocation list, or for the indirect types the URL of one.
+///
+/// In presentation format the type is written with its mnemonic when it
+/// has one, and the algorithm, which uses the DNSSEC algorithm numbers, as
+/// a number.
+///
+/// For more details, see [RFC 4398, Section 2].
+///
+/// [RFC 4398, Section 2]: https://datatracker.ietf.org/doc/html/rfc4398#section-2
+/// [RFC 4398, Section 2.1]: https://datatracker.ietf.org/doc/html/rfc4398#section-2.1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cert {
+    pub cert_type: u16,
+    pub key_tag: u16,
+    pub algorithm: u8,
+    pub certificate: Vec<u8>,
+}
+
+impl Cert {
+    pub const PKIX: u16 = 1;
+    pub const PGP: u16 = 3;
+    pub const IPKIX: u16 = 4;
+
+    /// Returns the mnemonic of a certificate type, if it has one.
+    pub fn type_mnemonic(cert_type: u16) -> Option<&'static str> {
+        TYPE_MNEMONICS
+            .iter()
+            .find(|(value, _)| *value == cert_type)
+            .map(|(_, mnemonic)| *mnemonic)
+    }
+
+    /// Parses a certificate type written as a mnemonic, in any case, or as a
+    /// number.
+    pub fn parse_type(text: &str) -> Option<u16> {
+        TYPE_MNEMONICS
+            .iter()
+            .find(|(_, mnemonic)| mnemonic.eq_ignore_ascii_case(text))
+            .map(|(value, _)| *value)
+            .or_else(|| text.parse().ok())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.cert_type.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&self.key_tag.to_be_bytes());
+        bytes.push(self.algorithm);
+        bytes.extend_from_slice(&self.certificate);
+        bytes
+    }
+}
+
+impl fmt::Display for Cert {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match Cert::type_mnemonic(self.cert_type) {
+            Some(mnemonic) => write!(f, "{} ", mnemonic)?,
+            None => write!(f, "{} ", self.cert_type)?,
+        }
+        write!(
+            f,
+            "{} {} {}",
+            self.key_tag,
+            self.algorithm,
+            data_encoding::BASE64.encode(&self.certificate)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("PKIX", Some(1))]
+    #[case("ipgp", Some(6))]
+    #[case("65280", Some(65280))]
+    #[case("X509", None)]
+    fn cert_parse_type(#[case] text: &str, #[case] expected: Option<u16>) {
+        assert_eq!(Cert::parse_type(text), expected);
+    }
+
+    #[test]
+    fn cert_to_bytes() {
+        let cert = Cert {
+            cert_type: 65280,
+            key_tag: 12345,
+            algorithm: 8,
+            certificate: vec![0xde, 0xad],
+        };
+        assert_eq!(cert.to_bytes(), [0xff, 0, 0x30, 0x39, 8, 0xde, 0xad]);
+        assert_eq!(cert.to_string(), "65280 12345 8 3q0=");
+    }
+}
@@ -0,0 +1,232 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::message::error::WireError;
+use crate::message::reader::Reader;
+use crate::record::error::CharacterStringError;
+use std::fmt;
+use std::str::FromStr;
+
+/// Opaque string of at most 255 octets, encoded on the wire with a one
+/// octet length prefix.
+///
+/// In presentation format it is written either as a single token or
+/// between double quotes, with `\X` escaping any character and `\DDD`
+/// giving the decimal value of an octet. It is always written back quoted.
+///
+/// For more details, see [RFC 1035, Section 3.3] and [RFC 1035, Section 5.1].
+///
+/// [RFC 1035, Section 3.3]: https://datatracker.ietf.org/doc/html/rfc1035#section-3.3
+/// [RFC 1035, Section 5.1]: https://datatracker.ietf.org/doc/html/rfc1035#section-5.1
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CharacterString(Vec<u8>);
+
+impl CharacterString {
+    /// Maximum length of a character string, in octets.
+    pub const MAX_LEN: usize = 255;
+
+    pub fn new(bytes: impl Into<Vec<u8>>) -> Result<Self, CharacterStringError> {
+        let bytes = bytes.into();
+        match bytes.len() > Self::MAX_LEN {
+            true => Err(CharacterStringError::TooLong(bytes.len())),
+            false => Ok(CharacterString(bytes)),
+        }
+    }
+
+    /// Splits `value` into as many character strings as needed to hold
+    /// it, each but the last one being 255 octets long. An empty value
+    /// gives a single empty string.
+    ///
+    /// # Example
+    /// ```
+    /// use dns_lib::record::rdata::CharacterString;
+    ///
+    /// let strings = CharacterString::split(&[b'a'; 300]);
+    /// assert_eq!(strings.len(), 2);
+    /// assert_eq!(strings[1].len(), 45);
+    /// ```
+    pub fn split(value: &[u8]) -> Vec<CharacterString> {
+        match value.is_empty() {
+            true => vec![CharacterString::default()],
+            false => value
+                .chunks(Self::MAX_LEN)
+                .map(|chunk| CharacterString(chunk.to_vec()))
+                .collect(),
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.0.len() + 1);
+        bytes.push(self.0.len() as u8);
+        bytes.extend_from_slice(&self.0);
+        bytes
+    }
+
+    /// Decodes a length prefixed character string from `reader`.
+    pub(crate) fn from_wire(reader: &mut Reader) -> Result<Self, WireError> {
+        let len = reader.u8()? as usize;
+        Ok(CharacterString(reader.bytes(len)?.to_vec()))
+    }
+}
+
+impl FromStr for CharacterString {
+    type Err = CharacterStringError;
+
+    /// Parses a character string from a quoted or unquoted token of a
+    /// master file.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CharacterString::new(unescape(s)?)
+    }
+}
+
+impl fmt::Display for CharacterString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_quoted(f, &self.0)
+    }
+}
+
+/// Decodes a quoted or unquoted token of a master file, resolving the
+/// `\X` and `\DDD` escapes, without limiting its length.
+pub(crate) fn unescape(s: &str) -> Result<Vec<u8>, CharacterStringError> {
+    let text = match s.strip_prefix('"') {
+        Some(quoted) => match quoted.strip_suffix('"') {
+            Some(inner) if !ends_with_escape(inner) => inner,
+            _ => return Err(CharacterStringError::UnterminatedQuote),
+        },
+        None => s,
+    };
+
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+        let invalid = |escape: &str| CharacterStringError::InvalidEscape(format!("\\{}", escape));
+        match chars.next() {
+            Some(d) if d.is_ascii_digit() => {
+                let digits: String = std::iter::once(d).chain(chars.by_ref().take(2)).collect();
+                let value = match digits.len() == 3 && digits.bytes().all(|b| b.is_ascii_digit()) {
+                    true => digits.parse::<u8>().map_err(|_| invalid(&digits))?,
+                    false => return Err(invalid(&digits)),
+                };
+                bytes.push(value);
+            }
+            Some(c) => {
+                let mut buf = [0; 4];
+                bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            }
+            None => return Err(invalid("")),
+        }
+    }
+    Ok(bytes)
+}
+
+/// Returns whether `text` ends with a backslash escaping the character
+/// after it, which for a quoted string means the closing quote.
+fn ends_with_escape(text: &str) -> bool {
+    text.bytes().rev().take_while(|b| *b == b'\\').count() % 2 == 1
+}
+
+/// Writes `bytes` between double quotes, escaping quotes, backslashes and
+/// non-printable octets.
+pub(crate) fn write_quoted(f: &mut fmt::Formatter, bytes: &[u8]) -> fmt::Result {
+    write!(f, "\"")?;
+    for &b in bytes {
+        match b {
+            b'"' | b'\\' => write!(f, "\\{}", b as char)?,
+            0x20..=0x7e => write!(f, "{}", b as char)?,
+            _ => write!(f, "\\{:03}", b)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("hello", b"hello", "\"hello\"")]
+    #[case("\"hello world\"", b"hello world", "\"hello world\"")]
+    #[case("\"\"", b"", "\"\"")]
+    #[case("\"a\\\"b\\\\c\"", b"a\"b\\c", "\"a\\\"b\\\\c\"")]
+    #[case("a\\;b", b"a;b", "\"a;b\"")]
+    #[case("\\009tab\\255", b"\ttab\xff", "\"\\009tab\\255\"")]
+    #[case("caf\u{e9}", b"caf\xc3\xa9", "\"caf\\195\\169\"")]
+    fn character_string_from_str(
+        #[case] text: &str,
+        #[case] bytes: &[u8],
+        #[case] presentation: &str,
+    ) {
+        let string: CharacterString = text.parse().unwrap();
+        assert_eq!(string.as_bytes(), bytes);
+        assert_eq!(string.to_string(), presentation);
+        assert_eq!(presentation.parse(), Ok(string));
+    }
+
+    #[rstest]
+    #[case("\"open", CharacterStringError::UnterminatedQuote)]
+    #[case("\"open\\\"", CharacterStringError::UnterminatedQuote)]
+    #[case("a\\25", CharacterStringError::InvalidEscape("\\25".to_string()))]
+    #[case("a\\256", CharacterStringError::InvalidEscape("\\256".to_string()))]
+    #[case("a\\", CharacterStringError::InvalidEscape("\\".to_string()))]
+    fn character_string_from_str_fails(#[case] text: &str, #[case] expected: CharacterStringError) {
+        assert_eq!(text.parse::<CharacterString>(), Err(expected));
+    }
+
+    #[test]
+    fn character_string_length() {
+        assert_eq!(
+            CharacterString::new(vec![0; 256]),
+            Err(CharacterStringError::TooLong(256))
+        );
+        assert_eq!(
+            "x".repeat(256).parse::<CharacterString>(),
+            Err(CharacterStringError::TooLong(256))
+        );
+
+        let string = CharacterString::new(vec![b'x'; 255]).unwrap();
+        let bytes = string.to_bytes();
+        assert_eq!((bytes.len(), bytes[0]), (256, 255));
+        let mut reader = Reader::new(&bytes, 0);
+        assert_eq!(CharacterString::from_wire(&mut reader), Ok(string));
+    }
+
+    #[rstest]
+    #[case(0, &[0])]
+    #[case(255, &[255])]
+    #[case(256, &[255, 1])]
+    #[case(600, &[255, 255, 90])]
+    fn character_string_split(#[case] len: usize, #[case] expected: &[usize]) {
+        let strings = CharacterString::split(&vec![b'x'; len]);
+        let lens: Vec<usize> = strings.iter().map(CharacterString::len).collect();
+        assert_eq!(lens, expected);
+    }
+}
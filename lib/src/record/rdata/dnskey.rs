@@ -0,0 +1,147 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+/// `RDATA` of a `DNSKEY` record, holding a public key used by DNSSEC.
+///
+/// For more details, see [RFC 4034, Section 2].
+///
+/// [RFC 4034, Section 2]: https://datatracker.ietf.org/doc/html/rfc4034#section-2
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dnskey {
+    pub flags: u16,
+    pub protocol: u8,
+    pub algorithm: u8,
+    pub public_key: Vec<u8>,
+}
+
+impl Dnskey {
+    /// Flag set on keys allowed to sign zone data.
+    pub const ZONE_KEY_FLAG: u16 = 0b0000_0001_0000_0000;
+
+    /// Flag marking a key as revoked, see [RFC 5011, Section 3].
+    ///
+    /// [RFC 5011, Section 3]: https://datatracker.ietf.org/doc/html/rfc5011#section-3
+    pub const REVOKE_FLAG: u16 = 0b0000_0000_1000_0000;
+
+    /// Flag marking a key as a secure entry point, usually a key-signing key.
+    pub const SECURE_ENTRY_POINT_FLAG: u16 = 0b0000_0000_0000_0001;
+
+    /// The only protocol value allowed by [RFC 4034, Section 2.1.2].
+    ///
+    /// [RFC 4034, Section 2.1.2]: https://datatracker.ietf.org/doc/html/rfc4034#section-2.1.2
+    pub const PROTOCOL: u8 = 3;
+
+    pub fn is_zone_key(&self) -> bool {
+        self.flags & Self::ZONE_KEY_FLAG != 0
+    }
+
+    pub fn is_revoked(&self) -> bool {
+        self.flags & Self::REVOKE_FLAG != 0
+    }
+
+    pub fn is_secure_entry_point(&self) -> bool {
+        self.flags & Self::SECURE_ENTRY_POINT_FLAG != 0
+    }
+
+    /// Computes the key tag identifying this key in `RRSIG` and `DS`
+    /// records.
+    ///
+    /// For more details, see [RFC 4034, Appendix B].
+    ///
+    /// [RFC 4034, Appendix B]: https://datatracker.ietf.org/doc/html/rfc4034#appendix-B
+    pub fn key_tag(&self) -> u16 {
+        let mut accumulator: u32 = 0;
+        for (i, &byte) in self.to_bytes().iter().enumerate() {
+            accumulator += match i & 1 {
+                0 => (byte as u32) << 8,
+                _ => byte as u32,
+            };
+        }
+        accumulator += (accumulator >> 16) & 0xFFFF;
+        (accumulator & 0xFFFF) as u16
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.flags.to_be_bytes().to_vec();
+        bytes.push(self.protocol);
+        bytes.push(self.algorithm);
+        bytes.extend_from_slice(&self.public_key);
+        bytes
+    }
+}
+
+impl fmt::Display for Dnskey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.flags,
+            self.protocol,
+            self.algorithm,
+            data_encoding::BASE64.encode(&self.public_key)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn dnskey(flags: u16, algorithm: u8, public_key: &str) -> Dnskey {
+        Dnskey {
+            flags,
+            protocol: Dnskey::PROTOCOL,
+            algorithm,
+            public_key: data_encoding::BASE64.decode(public_key.as_bytes()).unwrap(),
+        }
+    }
+
+    #[rstest]
+    // Example from RFC 8080, Section 6.1.
+    #[case(dnskey(257, 15, "l02Woi0iS8Aa25FQkUd9RMzZHJpBoRQwAQEX1SxZJA4="), 3613)]
+    // Example from RFC 6605, Section 6.1.
+    #[case(dnskey(257, 13, "GojIhhXUN/u4v54ZQqGSnyhWJwaubCvTmeexv7bR6edbkrSqQpF64cYbcB7wNcP+e+MAnLr+Wi9xMWyQLc8NAA=="), 55648)]
+    fn dnskey_key_tag(#[case] dnskey: Dnskey, #[case] expected: u16) {
+        assert_eq!(dnskey.key_tag(), expected);
+    }
+
+    #[rstest]
+    #[case(256, true, false, false)]
+    #[case(257, true, false, true)]
+    #[case(385, true, true, true)]
+    #[case(0, false, false, false)]
+    fn dnskey_flags(
+        #[case] flags: u16,
+        #[case] zone_key: bool,
+        #[case] revoked: bool,
+        #[case] sep: bool,
+    ) {
+        let key = dnskey(flags, 15, "");
+        assert_eq!(key.is_zone_key(), zone_key);
+        assert_eq!(key.is_revoked(), revoked);
+        assert_eq!(key.is_secure_entry_point(), sep);
+    }
+
+    #[test]
+    fn dnskey_to_string() {
+        let key = dnskey(257, 15, "l02Woi0iS8Aa25FQkUd9RMzZHJpBoRQwAQEX1SxZJA4=");
+        assert_eq!(
+            key.to_string(),
+            "257 3 15 l02Woi0iS8Aa25FQkUd9RMzZHJpBoRQwAQEX1SxZJA4="
+        );
+    }
+}
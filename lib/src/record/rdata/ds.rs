@@ -0,0 +1,69 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+/// `RDATA` of a `DS` record, referring to a `DNSKEY` of a child zone by
+/// means of a digest.
+///
+/// For more details, see [RFC 4034, Section 5].
+///
+/// [RFC 4034, Section 5]: https://datatracker.ietf.org/doc/html/rfc4034#section-5
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ds {
+    pub key_tag: u16,
+    pub algorithm: u8,
+    pub digest_type: u8,
+    pub digest: Vec<u8>,
+}
+
+impl Ds {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.key_tag.to_be_bytes().to_vec();
+        bytes.push(self.algorithm);
+        bytes.push(self.digest_type);
+        bytes.extend_from_slice(&self.digest);
+        bytes
+    }
+}
+
+impl fmt::Display for Ds {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.key_tag,
+            self.algorithm,
+            self.digest_type,
+            data_encoding::HEXUPPER.encode(&self.digest)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ds_to_bytes() {
+        let ds = Ds {
+            key_tag: 3613,
+            algorithm: 15,
+            digest_type: 2,
+            digest: vec![0x3a, 0xa5],
+        };
+        assert_eq!(ds.to_bytes(), [0x0e, 0x1d, 15, 2, 0x3a, 0xa5]);
+        assert_eq!(ds.to_string(), "3613 15 2 3AA5");
+    }
+}
@@ -0,0 +1,44 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CharacterString;
+use std::fmt;
+
+/// `RDATA` of an `HINFO` record, describing the hardware and operating
+/// system of a host.
+///
+/// For more details, see [RFC 1035, Section 3.3.2] and [RFC 8482,
+/// Section 4.2], which reuses it to answer `ANY` queries.
+///
+/// [RFC 1035, Section 3.3.2]: https://datatracker.ietf.org/doc/html/rfc1035#section-3.3.2
+/// [RFC 8482, Section 4.2]: https://datatracker.ietf.org/doc/html/rfc8482#section-4.2
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hinfo {
+    pub cpu: CharacterString,
+    pub os: CharacterString,
+}
+
+impl Hinfo {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.cpu.to_bytes();
+        bytes.extend_from_slice(&self.os.to_bytes());
+        bytes
+    }
+}
+
+impl fmt::Display for Hinfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.cpu, self.os)
+    }
+}
@@ -0,0 +1,325 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+/// Value of the latitude and longitude at the equator and the prime
+/// meridian, in thousandths of a second of arc.
+const EQUATOR: i64 = 1 << 31;
+/// Value of the altitude at the reference level of the WGS 84 spheroid, in
+/// centimeters, which puts the lowest altitude 100 km below it.
+const REFERENCE_ALTITUDE: i64 = 10_000_000;
+const MAX_LATITUDE: i64 = 90 * 3_600_000;
+const MAX_LONGITUDE: i64 = 180 * 3_600_000;
+
+/// `RDATA` of a `LOC` record, giving the location of the owner as a
+/// latitude, longitude and altitude with the size of the object and the
+/// precision of the measures.
+///
+/// The fields hold their wire values: the latitude and longitude are
+/// thousandths of a second of arc offset by 2^31, the altitude centimeters
+/// offset by 100 km, and the size and precisions a mantissa and a power of
+/// ten of centimeters in the high and low nibble of an octet.
+///
+/// In presentation format the coordinates are written in degrees, minutes
+/// and seconds, followed by distances in meters:
+/// `42 21 54 N 71 06 18 W -24m 30m`. The size defaults to 1 m, the
+/// horizontal precision to 10 km and the vertical one to 10 m.
+///
+/// For more details, see [RFC 1876, Section 2] and [RFC 1876, Section 3].
+///
+/// [RFC 1876, Section 2]: https://datatracker.ietf.org/doc/html/rfc1876#section-2
+/// [RFC 1876, Section 3]: https://datatracker.ietf.org/doc/html/rfc1876#section-3
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Loc {
+    pub size: u8,
+    pub horizontal_precision: u8,
+    pub vertical_precision: u8,
+    pub latitude: u32,
+    pub longitude: u32,
+    pub altitude: u32,
+}
+
+impl Loc {
+    /// Only version of the format.
+    pub const VERSION: u8 = 0;
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![
+            Self::VERSION,
+            self.size,
+            self.horizontal_precision,
+            self.vertical_precision,
+        ];
+        bytes.extend_from_slice(&self.latitude.to_be_bytes());
+        bytes.extend_from_slice(&self.longitude.to_be_bytes());
+        bytes.extend_from_slice(&self.altitude.to_be_bytes());
+        bytes
+    }
+
+    /// Checks if a size or precision octet has a mantissa and an exponent
+    /// that are both decimal digits.
+    pub fn is_valid_precision(value: u8) -> bool {
+        value >> 4 <= 9 && value & 0x0f <= 9
+    }
+
+    /// Parses the fields of a `LOC` record in presentation format.
+    pub(crate) fn from_presentation(fields: &[&str]) -> Option<Loc> {
+        let mut fields = fields.iter().copied().peekable();
+        let latitude = parse_coordinate(&mut fields, ('N', 'S'), MAX_LATITUDE)?;
+        let longitude = parse_coordinate(&mut fields, ('E', 'W'), MAX_LONGITUDE)?;
+
+        let altitude = parse_meters(fields.next()?)? + REFERENCE_ALTITUDE;
+        let altitude = u32::try_from(altitude).ok()?;
+        let mut precisions = [100, 1_000_000, 1_000].map(encode_precision);
+        for precision in precisions.iter_mut() {
+            if let Some(text) = fields.next() {
+                let centimeters = parse_meters(text)?;
+                *precision = (0..=9_000_000_000)
+                    .contains(&centimeters)
+                    .then(|| encode_precision(centimeters))?;
+            }
+        }
+        if fields.next().is_some() {
+            return None;
+        }
+
+        let [size, horizontal_precision, vertical_precision] = precisions;
+        Some(Loc {
+            size,
+            horizontal_precision,
+            vertical_precision,
+            latitude,
+            longitude,
+            altitude,
+        })
+    }
+}
+
+impl fmt::Display for Loc {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_coordinate(f, self.latitude, ('N', 'S'))?;
+        write!(f, " ")?;
+        write_coordinate(f, self.longitude, ('E', 'W'))?;
+        write!(f, " ")?;
+        write_centimeters(f, self.altitude as i64 - REFERENCE_ALTITUDE)?;
+        for precision in [
+            self.size,
+            self.horizontal_precision,
+            self.vertical_precision,
+        ] {
+            write!(f, " ")?;
+            write_centimeters(f, decode_precision(precision))?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses degrees, optional minutes and seconds and a hemisphere into the
+/// wire value of a coordinate.
+fn parse_coordinate<'a>(
+    fields: &mut std::iter::Peekable<impl Iterator<Item = &'a str>>,
+    (positive, negative): (char, char),
+    max: i64,
+) -> Option<u32> {
+    let degrees = i64::from(fields.next()?.parse::<u32>().ok()?);
+    let mut minutes = 0;
+    let mut thousandths = 0;
+    if let Some(text) = fields.next_if(|text| text.starts_with(|c: char| c.is_ascii_digit())) {
+        minutes = text.parse().ok().filter(|m| *m < 60)?;
+        if let Some(text) = fields.next_if(|text| text.starts_with(|c: char| c.is_ascii_digit())) {
+            thousandths = parse_fixed(text, 3).filter(|s| (0..60_000).contains(s))?;
+        }
+    }
+    let value = (degrees * 60 + minutes) * 60_000 + thousandths;
+    if value > max {
+        return None;
+    }
+    let hemisphere = fields.next()?;
+    let value = match hemisphere {
+        _ if hemisphere.eq_ignore_ascii_case(&positive.to_string()) => EQUATOR + value,
+        _ if hemisphere.eq_ignore_ascii_case(&negative.to_string()) => EQUATOR - value,
+        _ => return None,
+    };
+    u32::try_from(value).ok()
+}
+
+fn write_coordinate(
+    f: &mut fmt::Formatter,
+    value: u32,
+    (positive, negative): (char, char),
+) -> fmt::Result {
+    let offset = value as i64 - EQUATOR;
+    let hemisphere = match offset < 0 {
+        true => negative,
+        false => positive,
+    };
+    let thousandths = offset.abs();
+    write!(
+        f,
+        "{} {} {}.{:03} {}",
+        thousandths / 3_600_000,
+        thousandths / 60_000 % 60,
+        thousandths / 1000 % 60,
+        thousandths % 1000,
+        hemisphere
+    )
+}
+
+/// Parses a distance in meters, with an optional `m` suffix, into
+/// centimeters.
+fn parse_meters(text: &str) -> Option<i64> {
+    parse_fixed(text.strip_suffix(['m', 'M']).unwrap_or(text), 2)
+}
+
+fn write_centimeters(f: &mut fmt::Formatter, centimeters: i64) -> fmt::Result {
+    let sign = if centimeters < 0 { "-" } else { "" };
+    let centimeters = centimeters.abs();
+    match centimeters % 100 {
+        0 => write!(f, "{}{}m", sign, centimeters / 100),
+        cm => write!(f, "{}{}.{:02}m", sign, centimeters / 100, cm),
+    }
+}
+
+/// Parses a decimal number with at most `decimals` digits after the point,
+/// scaled by 10 to the power of `decimals`.
+fn parse_fixed(text: &str, decimals: u32) -> Option<i64> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text),
+    };
+    let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if integer.is_empty() || !all_digits(integer) || !all_digits(fraction) {
+        return None;
+    }
+    if fraction.len() > decimals as usize {
+        return None;
+    }
+    let scale = 10_i64.pow(decimals);
+    let integer: i64 = integer.parse().ok()?;
+    let fraction: i64 = match fraction.is_empty() {
+        true => 0,
+        false => fraction.parse::<i64>().ok()? * 10_i64.pow(decimals - fraction.len() as u32),
+    };
+    let value = integer.checked_mul(scale)?.checked_add(fraction)?;
+    Some(if negative { -value } else { value })
+}
+
+/// Encodes centimeters as a mantissa and a power of ten, rounding down.
+fn encode_precision(centimeters: i64) -> u8 {
+    let mut exponent = 0;
+    while exponent < 9 && centimeters >= 10_i64.pow(exponent + 1) {
+        exponent += 1;
+    }
+    let mantissa = (centimeters / 10_i64.pow(exponent)).min(9);
+    (mantissa as u8) << 4 | exponent as u8
+}
+
+fn decode_precision(value: u8) -> i64 {
+    (value >> 4) as i64 * 10_i64.pow((value & 0x0f) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(
+        "42 21 54 N 71 06 18 W -24m 30m",
+        (0x33, 0x16, 0x13, 2_299_997_648, 1_891_505_648, 9_997_600),
+        "42 21 54.000 N 71 6 18.000 W -24m 30m 10000m 10m"
+    )]
+    #[case(
+        "42 21 43.952 N 71 5 6.344 W -24m 1m 200m",
+        (0x12, 0x24, 0x13, 2_299_987_600, 1_891_577_304, 9_997_600),
+        "42 21 43.952 N 71 5 6.344 W -24m 1m 200m 10m"
+    )]
+    #[case(
+        "52 14 05 N 00 08 50 E 10m",
+        (0x12, 0x16, 0x13, 2_335_528_648, 2_148_013_648, 10_001_000),
+        "52 14 5.000 N 0 8 50.000 E 10m 1m 10000m 10m"
+    )]
+    #[case(
+        "32 7 19 S 116 2 25 E 10m",
+        (0x12, 0x16, 0x13, 2_031_844_648, 2_565_228_648, 10_001_000),
+        "32 7 19.000 S 116 2 25.000 E 10m 1m 10000m 10m"
+    )]
+    #[case(
+        "90 S 180 W -0.5 0.05m 90000000m 0",
+        (0x50, 0x99, 0x00, 1_823_483_648, 1_499_483_648, 9_999_950),
+        "90 0 0.000 S 180 0 0.000 W -0.50m 0.05m 90000000m 0m"
+    )]
+    fn loc_from_presentation(
+        #[case] text: &str,
+        #[case] expected: (u8, u8, u8, u32, u32, u32),
+        #[case] presentation: &str,
+    ) {
+        let fields: Vec<&str> = text.split_whitespace().collect();
+        let loc = Loc::from_presentation(&fields).unwrap();
+        assert_eq!(
+            (
+                loc.size,
+                loc.horizontal_precision,
+                loc.vertical_precision,
+                loc.latitude,
+                loc.longitude,
+                loc.altitude
+            ),
+            expected
+        );
+        assert_eq!(loc.to_string(), presentation);
+
+        let fields: Vec<&str> = presentation.split_whitespace().collect();
+        assert_eq!(Loc::from_presentation(&fields), Some(loc));
+    }
+
+    #[rstest]
+    #[case("91 N 0 E 0m")]
+    #[case("90 1 N 0 E 0m")]
+    #[case("0 60 N 0 E 0m")]
+    #[case("0 0 60 N 0 E 0m")]
+    #[case("0 0 1.2345 N 0 E 0m")]
+    #[case("0 N 181 E 0m")]
+    #[case("-1 N 0 E 0m")]
+    #[case("0 N -1 E 0m")]
+    #[case("0 X 0 E 0m")]
+    #[case("0 N 0 N 0m")]
+    #[case("0 N 0 E")]
+    #[case("0 N 0 E -100000.01m")]
+    #[case("0 N 0 E 42849672.96m")]
+    #[case("0 N 0 E 0m 90000000.01m")]
+    #[case("0 N 0 E 0m 1m 1m 1m 1m")]
+    #[case("0 N 0 E 1km")]
+    fn loc_from_presentation_fails(#[case] text: &str) {
+        let fields: Vec<&str> = text.split_whitespace().collect();
+        assert_eq!(Loc::from_presentation(&fields), None);
+    }
+
+    #[test]
+    fn loc_to_bytes() {
+        let fields: Vec<&str> = "42 21 54 N 71 06 18 W -24m 30m"
+            .split_whitespace()
+            .collect();
+        let loc = Loc::from_presentation(&fields).unwrap();
+        assert_eq!(
+            loc.to_bytes(),
+            [
+                0, 0x33, 0x16, 0x13, 0x89, 0x17, 0x2d, 0xd0, 0x70, 0xbe, 0x15, 0xf0, 0x00, 0x98,
+                0x8d, 0x20
+            ]
+        );
+    }
+}
@@ -0,0 +1,584 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod afsdb;
+mod caa;
+mod cert;
+mod character_string;
+mod dnskey;
+mod ds;
+mod hinfo;
+mod loc;
+mod mx;
+mod naptr;
+mod nsec;
+mod nsec3;
+mod openpgpkey;
+mod opt;
+mod rp;
+mod rrsig;
+mod soa;
+mod sshfp;
+mod text;
+mod tlsa;
+mod tsig;
+mod txt;
+mod type_bitmap;
+mod uri;
+mod zonemd;
+
+pub use afsdb::Afsdb;
+pub use caa::Caa;
+pub use cert::Cert;
+pub use character_string::CharacterString;
+pub use dnskey::Dnskey;
+pub use ds::Ds;
+pub use hinfo::Hinfo;
+pub use loc::Loc;
+pub use mx::Mx;
+pub use naptr::Naptr;
+pub use nsec::Nsec;
+pub use nsec3::{Nsec3, Nsec3Param};
+pub use openpgpkey::Openpgpkey;
+pub use opt::{EdnsOption, Opt};
+pub use rp::Rp;
+pub use rrsig::Rrsig;
+pub use soa::Soa;
+pub use sshfp::Sshfp;
+pub use tlsa::Tlsa;
+pub use tsig::Tsig;
+pub use txt::Txt;
+pub use type_bitmap::TypeBitmap;
+pub use uri::Uri;
+pub use zonemd::Zonemd;
+
+pub(crate) use text::parse_ttl;
+
+use crate::domain::Domain;
+use crate::message::error::WireError;
+use crate::message::reader::Reader;
+use crate::message::{KnownQType, QType};
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// Typed `RDATA` of a resource record.
+///
+/// Record types without a dedicated representation are kept as opaque
+/// bytes in [`RData::Unknown`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum RData {
+    A(Ipv4Addr),
+    NS(Domain),
+    CNAME(Domain),
+    SOA(Soa),
+    PTR(Domain),
+    HINFO(Hinfo),
+    MX(Mx),
+    TXT(Txt),
+    RP(Rp),
+    AFSDB(Afsdb),
+    SIG(Rrsig),
+    KEY(Dnskey),
+    AAAA(Ipv6Addr),
+    LOC(Loc),
+    NAPTR(Naptr),
+    CERT(Cert),
+    DNAME(Domain),
+    OPT(Opt),
+    DS(Ds),
+    SSHFP(Sshfp),
+    RRSIG(Rrsig),
+    NSEC(Nsec),
+    DNSKEY(Dnskey),
+    NSEC3(Nsec3),
+    NSEC3PARAM(Nsec3Param),
+    TLSA(Tlsa),
+    SMIMEA(Tlsa),
+    CDS(Ds),
+    CDNSKEY(Dnskey),
+    OPENPGPKEY(Openpgpkey),
+    ZONEMD(Zonemd),
+    SPF(Txt),
+    TSIG(Tsig),
+    URI(Uri),
+    CAA(Caa),
+    Unknown(Vec<u8>),
+}
+
+impl RData {
+    /// Encodes the `RDATA` in uncompressed wire format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::A(addr) => addr.octets().to_vec(),
+            Self::NS(name) | Self::CNAME(name) | Self::PTR(name) | Self::DNAME(name) => {
+                name.to_bytes()
+            }
+            Self::SOA(soa) => soa.to_bytes(),
+            Self::HINFO(hinfo) => hinfo.to_bytes(),
+            Self::MX(mx) => mx.to_bytes(),
+            Self::TXT(txt) | Self::SPF(txt) => txt.to_bytes(),
+            Self::RP(rp) => rp.to_bytes(),
+            Self::AFSDB(afsdb) => afsdb.to_bytes(),
+            Self::AAAA(addr) => addr.octets().to_vec(),
+            Self::LOC(loc) => loc.to_bytes(),
+            Self::DS(ds) | Self::CDS(ds) => ds.to_bytes(),
+            Self::RRSIG(rrsig) | Self::SIG(rrsig) => rrsig.to_bytes(),
+            Self::NSEC(nsec) => nsec.to_bytes(),
+            Self::DNSKEY(dnskey) | Self::CDNSKEY(dnskey) | Self::KEY(dnskey) => dnskey.to_bytes(),
+            Self::NAPTR(naptr) => naptr.to_bytes(),
+            Self::CERT(cert) => cert.to_bytes(),
+            Self::OPT(opt) => opt.to_bytes(),
+            Self::SSHFP(sshfp) => sshfp.to_bytes(),
+            Self::NSEC3(nsec3) => nsec3.to_bytes(),
+            Self::NSEC3PARAM(param) => param.to_bytes(),
+            Self::TLSA(tlsa) | Self::SMIMEA(tlsa) => tlsa.to_bytes(),
+            Self::OPENPGPKEY(key) => key.to_bytes(),
+            Self::ZONEMD(zonemd) => zonemd.to_bytes(),
+            Self::TSIG(tsig) => tsig.to_bytes(),
+            Self::URI(uri) => uri.to_bytes(),
+            Self::CAA(caa) => caa.to_bytes(),
+            Self::Unknown(bytes) => bytes.clone(),
+        }
+    }
+
+    /// Encodes the `RDATA` in the canonical form used by DNSSEC, in which
+    /// the domain names embedded in well-known record types are lowercased.
+    ///
+    /// For more details, see [RFC 4034, Section 6.2] and [RFC 6840, Section 5.1].
+    ///
+    /// [RFC 4034, Section 6.2]: https://datatracker.ietf.org/doc/html/rfc4034#section-6.2
+    /// [RFC 6840, Section 5.1]: https://datatracker.ietf.org/doc/html/rfc6840#section-5.1
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::NS(name) | Self::CNAME(name) | Self::PTR(name) | Self::DNAME(name) => {
+                name.to_canonical_bytes()
+            }
+            Self::SOA(soa) => soa.to_canonical_bytes(),
+            Self::MX(mx) => mx.to_canonical_bytes(),
+            Self::RP(rp) => rp.to_canonical_bytes(),
+            Self::AFSDB(afsdb) => afsdb.to_canonical_bytes(),
+            Self::NAPTR(naptr) => naptr.to_canonical_bytes(),
+            Self::RRSIG(rrsig) | Self::SIG(rrsig) => rrsig.to_canonical_bytes(),
+            _ => self.to_bytes(),
+        }
+    }
+
+    /// Formats the `RDATA` in the generic `\# <length> <hex>` form, which
+    /// any implementation can read regardless of the type.
+    ///
+    /// For more details, see [RFC 3597, Section 5].
+    ///
+    /// # Example
+    /// ```
+    /// use dns_lib::record::RData;
+    /// use std::net::Ipv4Addr;
+    ///
+    /// let rdata = RData::A(Ipv4Addr::new(192, 0, 2, 1));
+    /// assert_eq!(rdata.to_generic_string(), "\\# 4 C0000201");
+    /// ```
+    ///
+    /// [RFC 3597, Section 5]: https://datatracker.ietf.org/doc/html/rfc3597#section-5
+    pub fn to_generic_string(&self) -> String {
+        let bytes = self.to_bytes();
+        match bytes.is_empty() {
+            true => "\\# 0".to_string(),
+            false => format!(
+                "\\# {} {}",
+                bytes.len(),
+                data_encoding::HEXUPPER.encode(&bytes)
+            ),
+        }
+    }
+
+    /// Decodes `len` bytes of `RDATA` of type `r_type` from `reader`,
+    /// expanding compressed domain names.
+    pub(crate) fn from_wire(
+        r_type: QType,
+        reader: &mut Reader,
+        len: usize,
+    ) -> Result<RData, WireError> {
+        let end = reader.position() + len;
+        if reader.remaining() < len {
+            return Err(WireError::UnexpectedEnd(end));
+        }
+        // Empty RDATA only appears in the prerequisites and deletions of
+        // dynamic updates, see RFC 2136, Section 2.4, and in OPT records
+        // without options.
+        if len == 0 && r_type != KnownQType::OPT {
+            return Ok(Self::Unknown(Vec::new()));
+        }
+        let invalid = |_| WireError::InvalidRData(r_type);
+        let rest = |reader: &mut Reader| -> Result<Vec<u8>, WireError> {
+            let remaining = end
+                .checked_sub(reader.position())
+                .ok_or(WireError::InvalidRData(r_type))?;
+            Ok(reader.bytes(remaining)?.to_vec())
+        };
+
+        let rdata = match r_type.to_known_type() {
+            Some(KnownQType::A) => {
+                let octets: [u8; 4] = reader.bytes(len)?.try_into().map_err(invalid)?;
+                Self::A(Ipv4Addr::from(octets))
+            }
+            Some(KnownQType::AAAA) => {
+                let octets: [u8; 16] = reader.bytes(len)?.try_into().map_err(invalid)?;
+                Self::AAAA(Ipv6Addr::from(octets))
+            }
+            Some(KnownQType::NS) => Self::NS(reader.name()?),
+            Some(KnownQType::CNAME) => Self::CNAME(reader.name()?),
+            Some(KnownQType::PTR) => Self::PTR(reader.name()?),
+            Some(KnownQType::SOA) => Self::SOA(Soa {
+                m_name: reader.name()?,
+                r_name: reader.name()?,
+                serial: reader.u32()?,
+                refresh: reader.u32()?,
+                retry: reader.u32()?,
+                expire: reader.u32()?,
+                minimum: reader.u32()?,
+            }),
+            Some(KnownQType::HINFO) => Self::HINFO(Hinfo {
+                cpu: CharacterString::from_wire(reader)?,
+                os: CharacterString::from_wire(reader)?,
+            }),
+            Some(KnownQType::MX) => Self::MX(Mx {
+                preference: reader.u16()?,
+                exchange: reader.name()?,
+            }),
+            Some(r_type @ (KnownQType::TXT | KnownQType::SPF)) => {
+                let mut strings = Vec::new();
+                while reader.position() < end {
+                    strings.push(CharacterString::from_wire(reader)?);
+                }
+                let txt = Txt { strings };
+                match r_type {
+                    KnownQType::TXT => Self::TXT(txt),
+                    _ => Self::SPF(txt),
+                }
+            }
+            Some(KnownQType::RP) => Self::RP(Rp {
+                mailbox: reader.name()?,
+                text: reader.name()?,
+            }),
+            Some(KnownQType::AFSDB) => Self::AFSDB(Afsdb {
+                subtype: reader.u16()?,
+                hostname: reader.name()?,
+            }),
+            Some(KnownQType::LOC) => {
+                if reader.u8()? != Loc::VERSION {
+                    return Err(WireError::InvalidRData(r_type));
+                }
+                let loc = Loc {
+                    size: reader.u8()?,
+                    horizontal_precision: reader.u8()?,
+                    vertical_precision: reader.u8()?,
+                    latitude: reader.u32()?,
+                    longitude: reader.u32()?,
+                    altitude: reader.u32()?,
+                };
+                let precisions = [loc.size, loc.horizontal_precision, loc.vertical_precision];
+                if !precisions.into_iter().all(Loc::is_valid_precision) {
+                    return Err(WireError::InvalidRData(r_type));
+                }
+                Self::LOC(loc)
+            }
+            Some(KnownQType::CERT) => Self::CERT(Cert {
+                cert_type: reader.u16()?,
+                key_tag: reader.u16()?,
+                algorithm: reader.u8()?,
+                certificate: rest(reader)?,
+            }),
+            Some(KnownQType::DNAME) => Self::DNAME(reader.name()?),
+            Some(KnownQType::OPT) => {
+                let mut options = Vec::new();
+                while reader.position() < end {
+                    let code = reader.u16()?;
+                    let len = reader.u16()? as usize;
+                    options.push(EdnsOption {
+                        code,
+                        data: reader.bytes(len)?.to_vec(),
+                    });
+                }
+                Self::OPT(Opt { options })
+            }
+            Some(r_type @ (KnownQType::DS | KnownQType::CDS)) => {
+                let ds = Ds {
+                    key_tag: reader.u16()?,
+                    algorithm: reader.u8()?,
+                    digest_type: reader.u8()?,
+                    digest: rest(reader)?,
+                };
+                match r_type {
+                    KnownQType::DS => Self::DS(ds),
+                    _ => Self::CDS(ds),
+                }
+            }
+            Some(r_type @ (KnownQType::DNSKEY | KnownQType::CDNSKEY | KnownQType::KEY)) => {
+                let dnskey = Dnskey {
+                    flags: reader.u16()?,
+                    protocol: reader.u8()?,
+                    algorithm: reader.u8()?,
+                    public_key: rest(reader)?,
+                };
+                match r_type {
+                    KnownQType::DNSKEY => Self::DNSKEY(dnskey),
+                    KnownQType::KEY => Self::KEY(dnskey),
+                    _ => Self::CDNSKEY(dnskey),
+                }
+            }
+            Some(r_type @ (KnownQType::RRSIG | KnownQType::SIG)) => {
+                let rrsig = Rrsig {
+                    type_covered: QType::new(reader.u16()?),
+                    algorithm: reader.u8()?,
+                    labels: reader.u8()?,
+                    original_ttl: reader.u32()?,
+                    signature_expiration: reader.u32()?,
+                    signature_inception: reader.u32()?,
+                    key_tag: reader.u16()?,
+                    signer_name: reader.name()?,
+                    signature: rest(reader)?,
+                };
+                match r_type {
+                    KnownQType::RRSIG => Self::RRSIG(rrsig),
+                    _ => Self::SIG(rrsig),
+                }
+            }
+            Some(KnownQType::NSEC) => Self::NSEC(Nsec {
+                next_domain_name: reader.name()?,
+                type_bitmap: TypeBitmap::from_bytes(&rest(reader)?)
+                    .ok_or(WireError::InvalidRData(r_type))?,
+            }),
+            Some(KnownQType::NSEC3) => {
+                let (hash_algorithm, flags, iterations) =
+                    (reader.u8()?, reader.u8()?, reader.u16()?);
+                let salt_len = reader.u8()? as usize;
+                let salt = reader.bytes(salt_len)?.to_vec();
+                let hash_len = reader.u8()? as usize;
+                Self::NSEC3(Nsec3 {
+                    hash_algorithm,
+                    flags,
+                    iterations,
+                    salt,
+                    next_hashed_owner_name: reader.bytes(hash_len)?.to_vec(),
+                    type_bitmap: TypeBitmap::from_bytes(&rest(reader)?)
+                        .ok_or(WireError::InvalidRData(r_type))?,
+                })
+            }
+            Some(KnownQType::NSEC3PARAM) => {
+                let (hash_algorithm, flags, iterations) =
+                    (reader.u8()?, reader.u8()?, reader.u16()?);
+                let salt_len = reader.u8()? as usize;
+                Self::NSEC3PARAM(Nsec3Param {
+                    hash_algorithm,
+                    flags,
+                    iterations,
+                    salt: reader.bytes(salt_len)?.to_vec(),
+                })
+            }
+            Some(KnownQType::NAPTR) => Self::NAPTR(Naptr {
+                order: reader.u16()?,
+                preference: reader.u16()?,
+                flags: CharacterString::from_wire(reader)?,
+                services: CharacterString::from_wire(reader)?,
+                regexp: CharacterString::from_wire(reader)?,
+                replacement: reader.name()?,
+            }),
+            Some(KnownQType::SSHFP) => Self::SSHFP(Sshfp {
+                algorithm: reader.u8()?,
+                fingerprint_type: reader.u8()?,
+                fingerprint: rest(reader)?,
+            }),
+            Some(r_type @ (KnownQType::TLSA | KnownQType::SMIMEA)) => {
+                let tlsa = Tlsa {
+                    certificate_usage: reader.u8()?,
+                    selector: reader.u8()?,
+                    matching_type: reader.u8()?,
+                    certificate_association_data: rest(reader)?,
+                };
+                match r_type {
+                    KnownQType::TLSA => Self::TLSA(tlsa),
+                    _ => Self::SMIMEA(tlsa),
+                }
+            }
+            Some(KnownQType::OPENPGPKEY) => Self::OPENPGPKEY(Openpgpkey {
+                public_key: rest(reader)?,
+            }),
+            Some(KnownQType::ZONEMD) => Self::ZONEMD(Zonemd {
+                serial: reader.u32()?,
+                scheme: reader.u8()?,
+                hash_algorithm: reader.u8()?,
+                digest: rest(reader)?,
+            }),
+            Some(KnownQType::TSIG) => {
+                let algorithm = reader.name()?;
+                let (time_signed, fudge) = (reader.u48()?, reader.u16()?);
+                let mac_len = reader.u16()? as usize;
+                let mac = reader.bytes(mac_len)?.to_vec();
+                let (original_id, error) = (reader.u16()?, reader.u16()?);
+                let other_len = reader.u16()? as usize;
+                Self::TSIG(Tsig {
+                    algorithm,
+                    time_signed,
+                    fudge,
+                    mac,
+                    original_id,
+                    error,
+                    other_data: reader.bytes(other_len)?.to_vec(),
+                })
+            }
+            Some(KnownQType::URI) => Self::URI(Uri {
+                priority: reader.u16()?,
+                weight: reader.u16()?,
+                target: rest(reader)?,
+            }),
+            Some(KnownQType::CAA) => {
+                let flags = reader.u8()?;
+                let tag_len = reader.u8()? as usize;
+                let tag = std::str::from_utf8(reader.bytes(tag_len)?)
+                    .ok()
+                    .filter(|tag| Caa::is_valid_tag(tag))
+                    .ok_or(WireError::InvalidRData(r_type))?
+                    .to_string();
+                Self::CAA(Caa {
+                    flags,
+                    tag,
+                    value: rest(reader)?,
+                })
+            }
+            _ => Self::Unknown(reader.bytes(len)?.to_vec()),
+        };
+
+        match reader.position() == end {
+            true => Ok(rdata),
+            false => Err(WireError::InvalidRData(r_type)),
+        }
+    }
+}
+
+impl fmt::Display for RData {
+    /// Formats the `RDATA` in master file format.
+    ///
+    /// [`RData::Unknown`] is written using the generic `\# <length> <hex>`
+    /// form described in [RFC 3597, Section 5].
+    ///
+    /// [RFC 3597, Section 5]: https://datatracker.ietf.org/doc/html/rfc3597#section-5
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::A(addr) => write!(f, "{}", addr),
+            Self::NS(name) | Self::CNAME(name) | Self::PTR(name) | Self::DNAME(name) => {
+                write!(f, "{}", name.to_fqdn_string())
+            }
+            Self::SOA(soa) => write!(f, "{}", soa),
+            Self::HINFO(hinfo) => write!(f, "{}", hinfo),
+            Self::MX(mx) => write!(f, "{}", mx),
+            Self::TXT(txt) | Self::SPF(txt) => write!(f, "{}", txt),
+            Self::RP(rp) => write!(f, "{}", rp),
+            Self::AFSDB(afsdb) => write!(f, "{}", afsdb),
+            Self::AAAA(addr) => write!(f, "{}", addr),
+            Self::LOC(loc) => write!(f, "{}", loc),
+            Self::DS(ds) | Self::CDS(ds) => write!(f, "{}", ds),
+            Self::RRSIG(rrsig) | Self::SIG(rrsig) => write!(f, "{}", rrsig),
+            Self::NSEC(nsec) => write!(f, "{}", nsec),
+            Self::DNSKEY(dnskey) | Self::CDNSKEY(dnskey) | Self::KEY(dnskey) => {
+                write!(f, "{}", dnskey)
+            }
+            Self::NAPTR(naptr) => write!(f, "{}", naptr),
+            Self::CERT(cert) => write!(f, "{}", cert),
+            Self::OPT(opt) => write!(f, "{}", opt),
+            Self::SSHFP(sshfp) => write!(f, "{}", sshfp),
+            Self::NSEC3(nsec3) => write!(f, "{}", nsec3),
+            Self::NSEC3PARAM(param) => write!(f, "{}", param),
+            Self::TLSA(tlsa) | Self::SMIMEA(tlsa) => write!(f, "{}", tlsa),
+            Self::OPENPGPKEY(key) => write!(f, "{}", key),
+            Self::ZONEMD(zonemd) => write!(f, "{}", zonemd),
+            Self::TSIG(tsig) => write!(f, "{}", tsig),
+            Self::URI(uri) => write!(f, "{}", uri),
+            Self::CAA(caa) => write!(f, "{}", caa),
+            Self::Unknown(_) => write!(f, "{}", self.to_generic_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn name(text: &str) -> Domain {
+        Domain::from_presentation(text).unwrap()
+    }
+
+    fn hinfo(cpu: &str, os: &str) -> Hinfo {
+        Hinfo {
+            cpu: cpu.parse().unwrap(),
+            os: os.parse().unwrap(),
+        }
+    }
+
+    #[rstest]
+    #[case(RData::A(Ipv4Addr::new(192, 0, 2, 1)), &[192, 0, 2, 1])]
+    #[case(RData::NS(name("Ns.Example")), &[2, b'N', b's', 7, b'E', b'x', b'a', b'm', b'p', b'l', b'e', 0])]
+    #[case(RData::AAAA(Ipv6Addr::LOCALHOST), &[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1])]
+    #[case(RData::TXT(Txt::from_value(b"ab")), &[2, b'a', b'b'])]
+    #[case(RData::HINFO(hinfo("x86", "")), &[3, b'x', b'8', b'6', 0])]
+    #[case(RData::Unknown(vec![1, 2, 3]), &[1, 2, 3])]
+    fn rdata_to_bytes(#[case] rdata: RData, #[case] expected: &[u8]) {
+        assert_eq!(rdata.to_bytes(), expected);
+    }
+
+    #[rstest]
+    #[case(RData::NS(name("Ns.Example")), &[2, b'n', b's', 7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 0])]
+    #[case(RData::CNAME(name("A.b")), &[1, b'a', 1, b'b', 0])]
+    #[case(RData::AFSDB(Afsdb { subtype: 1, hostname: name("A") }), &[0, 1, 1, b'a', 0])]
+    #[case(RData::RP(Rp { mailbox: name("A"), text: name(".") }), &[1, b'a', 0, 0])]
+    #[case(RData::DNAME(name("Example.NET")), &[7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 3, b'n', b'e', b't', 0])]
+    #[case(RData::Unknown(b"A".to_vec()), b"A")]
+    fn rdata_to_canonical_bytes(#[case] rdata: RData, #[case] expected: &[u8]) {
+        assert_eq!(rdata.to_canonical_bytes(), expected);
+    }
+
+    #[rstest]
+    #[case(RData::A(Ipv4Addr::new(192, 0, 2, 1)), "192.0.2.1")]
+    #[case(RData::AAAA("2001:db8::1".parse().unwrap()), "2001:db8::1")]
+    #[case(RData::CNAME(name("www.example.com")), "www.example.com.")]
+    #[case(RData::TXT(Txt::from_value(b"a \"b\"")), "\"a \\\"b\\\"\"")]
+    #[case(RData::SPF(Txt::from_value(b"v=spf1 -all")), "\"v=spf1 -all\"")]
+    #[case(RData::HINFO(hinfo("x86", "Linux")), "\"x86\" \"Linux\"")]
+    #[case(RData::Unknown(vec![0x0a, 0xff]), "\\# 2 0AFF")]
+    #[case(RData::Unknown(vec![]), "\\# 0")]
+    fn rdata_to_string(#[case] rdata: RData, #[case] expected: &str) {
+        assert_eq!(rdata.to_string(), expected);
+    }
+
+    #[rstest]
+    #[case(&[0, 0x12, 0x16, 0x13, 0x89, 0x17, 0x2d, 0xd0, 0x70, 0xbe, 0x15, 0xf0, 0, 0x98, 0x8d, 0x20], true)]
+    #[case(&[1, 0x12, 0x16, 0x13, 0x89, 0x17, 0x2d, 0xd0, 0x70, 0xbe, 0x15, 0xf0, 0, 0x98, 0x8d, 0x20], false)]
+    #[case(&[0, 0x1a, 0x16, 0x13, 0x89, 0x17, 0x2d, 0xd0, 0x70, 0xbe, 0x15, 0xf0, 0, 0x98, 0x8d, 0x20], false)]
+    fn rdata_from_wire_loc(#[case] bytes: &[u8], #[case] valid: bool) {
+        let r_type = KnownQType::LOC.into();
+        let mut reader = Reader::new(bytes, 0);
+        match RData::from_wire(r_type, &mut reader, bytes.len()) {
+            Ok(rdata) => assert_eq!((rdata.to_bytes(), valid), (bytes.to_vec(), true)),
+            Err(e) => assert_eq!((e, valid), (WireError::InvalidRData(r_type), false)),
+        }
+    }
+
+    #[rstest]
+    #[case(RData::MX(Mx { preference: 10, exchange: name("a") }), "\\# 5 000A016100")]
+    #[case(RData::TXT(Txt::from_value(b"")), "\\# 1 00")]
+    #[case(RData::Unknown(vec![]), "\\# 0")]
+    fn rdata_to_generic_string(#[case] rdata: RData, #[case] expected: &str) {
+        assert_eq!(rdata.to_generic_string(), expected);
+    }
+}
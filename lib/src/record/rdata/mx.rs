@@ -0,0 +1,64 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::Domain;
+use std::fmt;
+
+/// `RDATA` of an `MX` record, naming a host willing to act as a mail
+/// exchange for the owner name.
+///
+/// For more details, see [RFC 1035, Section 3.3.9].
+///
+/// [RFC 1035, Section 3.3.9]: https://datatracker.ietf.org/doc/html/rfc1035#section-3.3.9
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mx {
+    pub preference: u16,
+    pub exchange: Domain,
+}
+
+impl Mx {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.preference.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&self.exchange.to_bytes());
+        bytes
+    }
+
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.preference.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&self.exchange.to_canonical_bytes());
+        bytes
+    }
+}
+
+impl fmt::Display for Mx {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.preference, self.exchange.to_fqdn_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mx_to_canonical_bytes() {
+        let mx = Mx {
+            preference: 10,
+            exchange: Domain::from_presentation("MX.a").unwrap(),
+        };
+        assert_eq!(mx.to_bytes(), [0, 10, 2, b'M', b'X', 1, b'a', 0]);
+        assert_eq!(mx.to_canonical_bytes(), [0, 10, 2, b'm', b'x', 1, b'a', 0]);
+        assert_eq!(mx.to_string(), "10 MX.a.");
+    }
+}
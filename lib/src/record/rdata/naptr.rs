@@ -0,0 +1,98 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CharacterString;
+use crate::domain::Domain;
+use std::fmt;
+
+/// `RDATA` of a `NAPTR` record, a rule of a Dynamic Delegation Discovery
+/// System application rewriting a string into a domain name or a URI.
+///
+/// For more details, see [RFC 3403, Section 4.1].
+///
+/// [RFC 3403, Section 4.1]: https://datatracker.ietf.org/doc/html/rfc3403#section-4.1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Naptr {
+    pub order: u16,
+    pub preference: u16,
+    pub flags: CharacterString,
+    pub services: CharacterString,
+    pub regexp: CharacterString,
+    pub replacement: Domain,
+}
+
+impl Naptr {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.fixed_bytes();
+        bytes.extend_from_slice(&self.replacement.to_bytes());
+        bytes
+    }
+
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.fixed_bytes();
+        bytes.extend_from_slice(&self.replacement.to_canonical_bytes());
+        bytes
+    }
+
+    fn fixed_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.order.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&self.preference.to_be_bytes());
+        bytes.extend_from_slice(&self.flags.to_bytes());
+        bytes.extend_from_slice(&self.services.to_bytes());
+        bytes.extend_from_slice(&self.regexp.to_bytes());
+        bytes
+    }
+}
+
+impl fmt::Display for Naptr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {}",
+            self.order,
+            self.preference,
+            self.flags,
+            self.services,
+            self.regexp,
+            self.replacement.to_fqdn_string()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn naptr_to_bytes() {
+        let naptr = Naptr {
+            order: 100,
+            preference: 10,
+            flags: CharacterString::new("u").unwrap(),
+            services: CharacterString::new("E2U+sip").unwrap(),
+            regexp: CharacterString::new("!^.*$!sip:a@b!").unwrap(),
+            replacement: Domain::from_presentation(".").unwrap(),
+        };
+        let mut expected = vec![0, 100, 0, 10, 1, b'u', 7];
+        expected.extend_from_slice(b"E2U+sip");
+        expected.push(14);
+        expected.extend_from_slice(b"!^.*$!sip:a@b!");
+        expected.push(0);
+        assert_eq!(naptr.to_bytes(), expected);
+        assert_eq!(
+            naptr.to_string(),
+            "100 10 \"u\" \"E2U+sip\" \"!^.*$!sip:a@b!\" ."
+        );
+    }
+}
@@ -0,0 +1,73 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::Domain;
+use crate::record::rdata::TypeBitmap;
+use std::fmt;
+
+/// `RDATA` of an `NSEC` record, linking an owner name to the next one in
+/// canonical order to provide authenticated denial of existence.
+///
+/// For more details, see [RFC 4034, Section 4].
+///
+/// [RFC 4034, Section 4]: https://datatracker.ietf.org/doc/html/rfc4034#section-4
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Nsec {
+    pub next_domain_name: Domain,
+    pub type_bitmap: TypeBitmap,
+}
+
+impl Nsec {
+    /// Encodes the record. The next domain name is never lowercased, not
+    /// even in canonical form, as clarified by [RFC 6840, Section 5.1].
+    ///
+    /// [RFC 6840, Section 5.1]: https://datatracker.ietf.org/doc/html/rfc6840#section-5.1
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.next_domain_name.to_bytes();
+        bytes.extend_from_slice(&self.type_bitmap.to_bytes());
+        bytes
+    }
+}
+
+impl fmt::Display for Nsec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.next_domain_name.to_fqdn_string())?;
+        if !self.type_bitmap.is_empty() {
+            write!(f, " {}", self.type_bitmap)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::QType;
+
+    #[test]
+    fn nsec_to_bytes() {
+        // Example from RFC 4034, Section 4.3.
+        let nsec = Nsec {
+            next_domain_name: Domain::from_presentation("host.example.com").unwrap(),
+            type_bitmap: [1, 15, 46, 47, 1234].into_iter().map(QType::new).collect(),
+        };
+        let bytes = nsec.to_bytes();
+        assert_eq!(bytes[..18], *b"\x04host\x07example\x03com\x00");
+        assert_eq!(bytes.len(), 18 + 37);
+        assert_eq!(
+            nsec.to_string(),
+            "host.example.com. A MX RRSIG NSEC TYPE1234"
+        );
+    }
+}
@@ -0,0 +1,166 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::record::rdata::TypeBitmap;
+use std::fmt;
+
+/// `RDATA` of an `NSEC3` record, linking the hash of an owner name to the
+/// next hash in the zone to provide hashed authenticated denial of
+/// existence.
+///
+/// For more details, see [RFC 5155, Section 3].
+///
+/// [RFC 5155, Section 3]: https://datatracker.ietf.org/doc/html/rfc5155#section-3
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Nsec3 {
+    pub hash_algorithm: u8,
+    pub flags: u8,
+    pub iterations: u16,
+    pub salt: Vec<u8>,
+    pub next_hashed_owner_name: Vec<u8>,
+    pub type_bitmap: TypeBitmap,
+}
+
+impl Nsec3 {
+    /// Flag indicating that the record may cover unsigned delegations.
+    pub const OPT_OUT_FLAG: u8 = 0b0000_0001;
+
+    pub fn is_opt_out(&self) -> bool {
+        self.flags & Self::OPT_OUT_FLAG != 0
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes =
+            encode_parameters(self.hash_algorithm, self.flags, self.iterations, &self.salt);
+        bytes.push(self.next_hashed_owner_name.len() as u8);
+        bytes.extend_from_slice(&self.next_hashed_owner_name);
+        bytes.extend_from_slice(&self.type_bitmap.to_bytes());
+        bytes
+    }
+}
+
+impl fmt::Display for Nsec3 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {}",
+            self.hash_algorithm,
+            self.flags,
+            self.iterations,
+            fmt_salt(&self.salt),
+            data_encoding::BASE32HEX_NOPAD
+                .encode(&self.next_hashed_owner_name)
+                .to_ascii_lowercase()
+        )?;
+        if !self.type_bitmap.is_empty() {
+            write!(f, " {}", self.type_bitmap)?;
+        }
+        Ok(())
+    }
+}
+
+/// `RDATA` of an `NSEC3PARAM` record, holding the parameters used by
+/// authoritative servers to compute hashed owner names.
+///
+/// For more details, see [RFC 5155, Section 4].
+///
+/// [RFC 5155, Section 4]: https://datatracker.ietf.org/doc/html/rfc5155#section-4
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Nsec3Param {
+    pub hash_algorithm: u8,
+    pub flags: u8,
+    pub iterations: u16,
+    pub salt: Vec<u8>,
+}
+
+impl Nsec3Param {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode_parameters(self.hash_algorithm, self.flags, self.iterations, &self.salt)
+    }
+}
+
+impl fmt::Display for Nsec3Param {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.hash_algorithm,
+            self.flags,
+            self.iterations,
+            fmt_salt(&self.salt)
+        )
+    }
+}
+
+fn encode_parameters(hash_algorithm: u8, flags: u8, iterations: u16, salt: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![hash_algorithm, flags];
+    bytes.extend_from_slice(&iterations.to_be_bytes());
+    bytes.push(salt.len() as u8);
+    bytes.extend_from_slice(salt);
+    bytes
+}
+
+fn fmt_salt(salt: &[u8]) -> String {
+    match salt.is_empty() {
+        true => "-".to_string(),
+        false => data_encoding::HEXUPPER.encode(salt),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::QType;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(vec![0xaa, 0xbb, 0xcc, 0xdd], "1 0 12 AABBCCDD", &[1, 0, 0, 12, 4, 0xaa, 0xbb, 0xcc, 0xdd])]
+    #[case(vec![], "1 0 0 -", &[1, 0, 0, 0, 0])]
+    fn nsec3param_encoding(#[case] salt: Vec<u8>, #[case] text: &str, #[case] bytes: &[u8]) {
+        let param = Nsec3Param {
+            hash_algorithm: 1,
+            flags: 0,
+            iterations: u16::from(bytes[3]),
+            salt,
+        };
+        assert_eq!(param.to_string(), text);
+        assert_eq!(param.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn nsec3_encoding() {
+        let next = data_encoding::BASE32HEX_NOPAD
+            .decode(b"2T7B4G4VSA5SMI47K61MV5BV1A22BOJR")
+            .unwrap();
+        let nsec3 = Nsec3 {
+            hash_algorithm: 1,
+            flags: Nsec3::OPT_OUT_FLAG,
+            iterations: 12,
+            salt: vec![0xaa, 0xbb, 0xcc, 0xdd],
+            next_hashed_owner_name: next.clone(),
+            type_bitmap: [1, 46].into_iter().map(QType::new).collect(),
+        };
+        assert!(nsec3.is_opt_out());
+        assert_eq!(
+            nsec3.to_string(),
+            "1 1 12 AABBCCDD 2t7b4g4vsa5smi47k61mv5bv1a22bojr A RRSIG"
+        );
+
+        let bytes = nsec3.to_bytes();
+        assert_eq!(bytes[..9], [1, 1, 0, 12, 4, 0xaa, 0xbb, 0xcc, 0xdd]);
+        assert_eq!(bytes[9], 20);
+        assert_eq!(bytes[10..30], next[..]);
+        assert_eq!(bytes[30..], [0, 6, 0x40, 0, 0, 0, 0, 0x02]);
+    }
+}
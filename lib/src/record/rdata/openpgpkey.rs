@@ -0,0 +1,73 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::error::TryFromError;
+use crate::domain::Domain;
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+/// `RDATA` of an `OPENPGPKEY` record, holding the OpenPGP transferable
+/// public key of the email address the owner name is derived from, see
+/// [`Openpgpkey::owner_name`].
+///
+/// For more details, see [RFC 7929, Section 2].
+///
+/// [RFC 7929, Section 2]: https://datatracker.ietf.org/doc/html/rfc7929#section-2
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Openpgpkey {
+    pub public_key: Vec<u8>,
+}
+
+impl Openpgpkey {
+    /// Returns the owner name of the `OPENPGPKEY` record of the address
+    /// `local_part@domain`: the SHA2-256 digest of the local part,
+    /// truncated to 28 octets and hex encoded, followed by `_openpgpkey`
+    /// and the domain.
+    ///
+    /// The local part is hashed as given, without any canonicalization.
+    ///
+    /// For more details, see [RFC 7929, Section 3].
+    ///
+    /// # Example
+    /// ```
+    /// use dns_lib::record::rdata::Openpgpkey;
+    /// use dns_lib::Domain;
+    ///
+    /// let domain = Domain::from_presentation("example.com.").unwrap();
+    /// let owner = Openpgpkey::owner_name("hugh", &domain).unwrap();
+    /// assert_eq!(
+    ///     owner.to_string(),
+    ///     "c93f1e400f26708f98cb19d936620da35eec8f72e57f9eec01c1afd6._openpgpkey.example.com"
+    /// );
+    /// ```
+    ///
+    /// [RFC 7929, Section 3]: https://datatracker.ietf.org/doc/html/rfc7929#section-3
+    pub fn owner_name(local_part: &str, domain: &Domain) -> Result<Domain, TryFromError> {
+        let digest = Sha256::digest(local_part.as_bytes());
+        let label = data_encoding::HEXLOWER.encode(&digest[..28]);
+        domain
+            .prepend_label(b"_openpgpkey")?
+            .prepend_label(label.as_bytes())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.public_key.clone()
+    }
+}
+
+impl fmt::Display for Openpgpkey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", data_encoding::BASE64.encode(&self.public_key))
+    }
+}
@@ -0,0 +1,100 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::time::Duration;
+
+/// `RDATA` of the `OPT` pseudo-record, carrying the options of the EDNS(0)
+/// extension mechanism.
+///
+/// For more details, see [RFC 6891, Section 6.1.2].
+///
+/// [RFC 6891, Section 6.1.2]: https://datatracker.ietf.org/doc/html/rfc6891#section-6.1.2
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Opt {
+    pub options: Vec<EdnsOption>,
+}
+
+impl Opt {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for option in &self.options {
+            bytes.extend_from_slice(&option.code.to_be_bytes());
+            bytes.extend_from_slice(&(option.data.len() as u16).to_be_bytes());
+            bytes.extend_from_slice(&option.data);
+        }
+        bytes
+    }
+}
+
+impl fmt::Display for Opt {
+    /// Formats the options as pairs of code and hexadecimal data. The
+    /// `OPT` record has no presentation format, so this is only meant for
+    /// diagnostics.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let options: Vec<String> = self
+            .options
+            .iter()
+            .map(|option| {
+                format!(
+                    "{} {}",
+                    option.code,
+                    data_encoding::HEXUPPER.encode(&option.data)
+                )
+            })
+            .collect();
+        write!(f, "{}", options.join(" "))
+    }
+}
+
+/// Option carried by an `OPT` record, see [RFC 6891, Section 6.1.2].
+///
+/// [RFC 6891, Section 6.1.2]: https://datatracker.ietf.org/doc/html/rfc6891#section-6.1.2
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdnsOption {
+    pub code: u16,
+    pub data: Vec<u8>,
+}
+
+impl EdnsOption {
+    /// Code of the `edns-tcp-keepalive` option, see [RFC 7828, Section 3.1].
+    ///
+    /// [RFC 7828, Section 3.1]: https://datatracker.ietf.org/doc/html/rfc7828#section-3.1
+    pub const TCP_KEEPALIVE: u16 = 11;
+    /// Code of the `Padding` option, see [RFC 7830, Section 3].
+    ///
+    /// [RFC 7830, Section 3]: https://datatracker.ietf.org/doc/html/rfc7830#section-3
+    pub const PADDING: u16 = 12;
+
+    /// Creates an `edns-tcp-keepalive` option. Clients send it without a
+    /// timeout, while servers send the time they keep idle connections
+    /// open, in units of 100 milliseconds.
+    ///
+    /// For more details, see [RFC 7828, Section 3.1].
+    ///
+    /// [RFC 7828, Section 3.1]: https://datatracker.ietf.org/doc/html/rfc7828#section-3.1
+    pub fn tcp_keepalive(timeout: Option<Duration>) -> Self {
+        let data = match timeout {
+            Some(timeout) => {
+                let units = u16::try_from(timeout.as_millis() / 100).unwrap_or(u16::MAX);
+                units.to_be_bytes().to_vec()
+            }
+            None => Vec::new(),
+        };
+        EdnsOption {
+            code: Self::TCP_KEEPALIVE,
+            data,
+        }
+    }
+}
@@ -0,0 +1,55 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::Domain;
+use std::fmt;
+
+/// `RDATA` of an `RP` record, naming the person responsible for the owner
+/// name: their mailbox, written like the `RNAME` of an `SOA` record, and a
+/// name with `TXT` records giving more details. The root name stands for
+/// either when it is not available.
+///
+/// For more details, see [RFC 1183, Section 2.2].
+///
+/// [RFC 1183, Section 2.2]: https://datatracker.ietf.org/doc/html/rfc1183#section-2.2
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rp {
+    pub mailbox: Domain,
+    pub text: Domain,
+}
+
+impl Rp {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.mailbox.to_bytes();
+        bytes.extend_from_slice(&self.text.to_bytes());
+        bytes
+    }
+
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.mailbox.to_canonical_bytes();
+        bytes.extend_from_slice(&self.text.to_canonical_bytes());
+        bytes
+    }
+}
+
+impl fmt::Display for Rp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.mailbox.to_fqdn_string(),
+            self.text.to_fqdn_string()
+        )
+    }
+}
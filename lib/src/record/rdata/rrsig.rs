@@ -0,0 +1,206 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::Domain;
+use crate::message::QType;
+use std::fmt;
+
+/// `RDATA` of an `RRSIG` record, holding the signature of a record set.
+///
+/// For more details, see [RFC 4034, Section 3].
+///
+/// [RFC 4034, Section 3]: https://datatracker.ietf.org/doc/html/rfc4034#section-3
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rrsig {
+    pub type_covered: QType,
+    pub algorithm: u8,
+    pub labels: u8,
+    pub original_ttl: u32,
+    pub signature_expiration: u32,
+    pub signature_inception: u32,
+    pub key_tag: u16,
+    pub signer_name: Domain,
+    pub signature: Vec<u8>,
+}
+
+impl Rrsig {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.encode_fields(self.signer_name.to_bytes());
+        bytes.extend_from_slice(&self.signature);
+        bytes
+    }
+
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.to_signed_prefix();
+        bytes.extend_from_slice(&self.signature);
+        bytes
+    }
+
+    /// Encodes every field but the signature, with the signer name in
+    /// canonical form. This is the first part of the data covered by the
+    /// signature itself.
+    ///
+    /// For more details, see [RFC 4034, Section 3.1.8.1].
+    ///
+    /// [RFC 4034, Section 3.1.8.1]: https://datatracker.ietf.org/doc/html/rfc4034#section-3.1.8.1
+    pub fn to_signed_prefix(&self) -> Vec<u8> {
+        self.encode_fields(self.signer_name.to_canonical_bytes())
+    }
+
+    fn encode_fields(&self, signer_name: Vec<u8>) -> Vec<u8> {
+        let mut bytes = self.type_covered.value.to_be_bytes().to_vec();
+        bytes.push(self.algorithm);
+        bytes.push(self.labels);
+        bytes.extend_from_slice(&self.original_ttl.to_be_bytes());
+        bytes.extend_from_slice(&self.signature_expiration.to_be_bytes());
+        bytes.extend_from_slice(&self.signature_inception.to_be_bytes());
+        bytes.extend_from_slice(&self.key_tag.to_be_bytes());
+        bytes.extend_from_slice(&signer_name);
+        bytes
+    }
+}
+
+impl fmt::Display for Rrsig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {} {} {} {}",
+            self.type_covered,
+            self.algorithm,
+            self.labels,
+            self.original_ttl,
+            format_timestamp(self.signature_expiration),
+            format_timestamp(self.signature_inception),
+            self.key_tag,
+            self.signer_name.to_fqdn_string(),
+            data_encoding::BASE64.encode(&self.signature)
+        )
+    }
+}
+
+/// Formats seconds since the UNIX epoch in the `YYYYMMDDHHmmSS` form used
+/// by `RRSIG` records, see [RFC 4034, Section 3.2].
+///
+/// [RFC 4034, Section 3.2]: https://datatracker.ietf.org/doc/html/rfc4034#section-3.2
+fn format_timestamp(timestamp: u32) -> String {
+    let days = (timestamp / 86_400) as i64;
+    let seconds = timestamp % 86_400;
+
+    // Civil date from days since 1970-01-01, in the proleptic Gregorian
+    // calendar, with eras of 400 years starting on March 1st.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}{:02}{:02}{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        seconds / 3_600,
+        (seconds % 3_600) / 60,
+        seconds % 60
+    )
+}
+
+/// Parses a timestamp written either in the `YYYYMMDDHHmmSS` form or as a
+/// decimal number of seconds since the UNIX epoch, see [RFC 4034,
+/// Section 3.2].
+///
+/// [RFC 4034, Section 3.2]: https://datatracker.ietf.org/doc/html/rfc4034#section-3.2
+pub(crate) fn parse_timestamp(text: &str) -> Option<u32> {
+    if !text.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    if text.len() != 14 {
+        return text.parse().ok();
+    }
+
+    let field = |range: std::ops::Range<usize>| text[range].parse::<i64>().ok();
+    let (year, month, day) = (field(0..4)?, field(4..6)?, field(6..8)?);
+    let (hours, minutes, seconds) = (field(8..10)?, field(10..12)?, field(12..14)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    if hours > 23 || minutes > 59 || seconds > 59 {
+        return None;
+    }
+
+    // Inverse of the computation done by `format_timestamp`.
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * mp + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    u32::try_from(days * 86_400 + hours * 3_600 + minutes * 60 + seconds).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::KnownQType;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, "19700101000000")]
+    #[case(1438207200, "20150729220000")]
+    #[case(1440021600, "20150819220000")]
+    #[case(951782400, "20000229000000")]
+    #[case(u32::MAX, "21060207062815")]
+    fn format_timestamp_works(#[case] timestamp: u32, #[case] expected: &str) {
+        assert_eq!(format_timestamp(timestamp), expected);
+        assert_eq!(parse_timestamp(expected), Some(timestamp));
+    }
+
+    #[rstest]
+    #[case("1438207200", Some(1438207200))]
+    #[case("19691231235959", None)]
+    #[case("20151319220000", None)]
+    #[case("2015081922000a", None)]
+    #[case("21060207062816", None)]
+    fn parse_timestamp_works(#[case] text: &str, #[case] expected: Option<u32>) {
+        assert_eq!(parse_timestamp(text), expected);
+    }
+
+    #[test]
+    fn rrsig_to_string() {
+        let rrsig = Rrsig {
+            type_covered: KnownQType::MX.into(),
+            algorithm: 15,
+            labels: 2,
+            original_ttl: 3600,
+            signature_expiration: 1440021600,
+            signature_inception: 1438207200,
+            key_tag: 3613,
+            signer_name: Domain::from_presentation("Example.com").unwrap(),
+            signature: vec![0xa0, 0xbf],
+        };
+        assert_eq!(
+            rrsig.to_string(),
+            "MX 15 2 3600 20150819220000 20150729220000 3613 Example.com. oL8="
+        );
+        assert_eq!(rrsig.to_bytes().len(), 18 + 13 + 2);
+        assert_eq!(rrsig.to_signed_prefix()[18..20], [7, b'e']);
+    }
+}
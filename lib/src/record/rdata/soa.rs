@@ -0,0 +1,103 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::Domain;
+use std::fmt;
+
+/// `RDATA` of a `SOA` record, marking the start of a zone of authority.
+///
+/// For more details, see [RFC 1035, Section 3.3.13].
+///
+/// [RFC 1035, Section 3.3.13]: https://datatracker.ietf.org/doc/html/rfc1035#section-3.3.13
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Soa {
+    pub m_name: Domain,
+    pub r_name: Domain,
+    pub serial: u32,
+    pub refresh: u32,
+    pub retry: u32,
+    pub expire: u32,
+    pub minimum: u32,
+}
+
+impl Soa {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.encode(self.m_name.to_bytes(), self.r_name.to_bytes())
+    }
+
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        self.encode(
+            self.m_name.to_canonical_bytes(),
+            self.r_name.to_canonical_bytes(),
+        )
+    }
+
+    fn encode(&self, m_name: Vec<u8>, r_name: Vec<u8>) -> Vec<u8> {
+        let mut bytes = m_name;
+        bytes.extend_from_slice(&r_name);
+        for value in [
+            self.serial,
+            self.refresh,
+            self.retry,
+            self.expire,
+            self.minimum,
+        ] {
+            bytes.extend_from_slice(&value.to_be_bytes());
+        }
+        bytes
+    }
+}
+
+impl fmt::Display for Soa {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {} {}",
+            self.m_name.to_fqdn_string(),
+            self.r_name.to_fqdn_string(),
+            self.serial,
+            self.refresh,
+            self.retry,
+            self.expire,
+            self.minimum
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn soa_to_canonical_bytes() {
+        let soa = Soa {
+            m_name: Domain::from_presentation("NS").unwrap(),
+            r_name: Domain::from_presentation("h.M").unwrap(),
+            serial: 1,
+            refresh: 2,
+            retry: 3,
+            expire: 4,
+            minimum: 5,
+        };
+        assert_eq!(
+            soa.to_canonical_bytes(),
+            [
+                &[2, b'n', b's', 0, 1, b'h', 1, b'm', 0][..],
+                &[0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0, 4, 0, 0, 0, 5],
+            ]
+            .concat()
+        );
+        assert_eq!(soa.to_string(), "NS. h.M. 1 2 3 4 5");
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+/// `RDATA` of an `SSHFP` record, holding the fingerprint of an SSH host key
+/// of the host named by the owner of the record.
+///
+/// For more details, see [RFC 4255, Section 3.1] and [RFC 6594].
+///
+/// [RFC 4255, Section 3.1]: https://datatracker.ietf.org/doc/html/rfc4255#section-3.1
+/// [RFC 6594]: https://datatracker.ietf.org/doc/html/rfc6594
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sshfp {
+    pub algorithm: u8,
+    pub fingerprint_type: u8,
+    pub fingerprint: Vec<u8>,
+}
+
+impl Sshfp {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![self.algorithm, self.fingerprint_type];
+        bytes.extend_from_slice(&self.fingerprint);
+        bytes
+    }
+}
+
+impl fmt::Display for Sshfp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.algorithm,
+            self.fingerprint_type,
+            data_encoding::HEXUPPER.encode(&self.fingerprint)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sshfp_to_bytes() {
+        let sshfp = Sshfp {
+            algorithm: 4,
+            fingerprint_type: 2,
+            fingerprint: vec![0x3e, 0xf2],
+        };
+        assert_eq!(sshfp.to_bytes(), [4, 2, 0x3e, 0xf2]);
+        assert_eq!(sshfp.to_string(), "4 2 3EF2");
+    }
+}
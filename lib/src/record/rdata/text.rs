@@ -0,0 +1,587 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::character_string::unescape;
+use super::rrsig::parse_timestamp;
+use super::{
+    Afsdb, Caa, Cert, CharacterString, Dnskey, Ds, Hinfo, Loc, Mx, Naptr, Nsec, Nsec3, Nsec3Param,
+    Openpgpkey, RData, Rp, Rrsig, Soa, Sshfp, Tlsa, Txt, TypeBitmap, Uri, Zonemd,
+};
+use crate::dnssec::Algorithm;
+use crate::domain::Domain;
+use crate::message::reader::Reader;
+use crate::message::{KnownQType, QType};
+use crate::record::error::RDataParseError;
+use std::str::FromStr;
+
+impl RData {
+    /// Parses `RDATA` of type `r_type` from the whitespace separated
+    /// `fields` of a master file entry. Relative domain names are completed
+    /// with `origin`.
+    ///
+    /// Any type may also be written in the generic `\# <length> <hex>` form
+    /// described in [RFC 3597, Section 5].
+    ///
+    /// # Example
+    /// ```
+    /// use dns_lib::message::KnownQType;
+    /// use dns_lib::record::RData;
+    /// use dns_lib::Domain;
+    ///
+    /// let origin = Domain::from_presentation("example.com.").unwrap();
+    /// let rdata = RData::from_presentation(KnownQType::MX.into(), &["10", "mail"], &origin);
+    /// assert_eq!(rdata.unwrap().to_string(), "10 mail.example.com.");
+    /// ```
+    ///
+    /// [RFC 3597, Section 5]: https://datatracker.ietf.org/doc/html/rfc3597#section-5
+    pub fn from_presentation(
+        r_type: QType,
+        fields: &[&str],
+        origin: &Domain,
+    ) -> Result<RData, RDataParseError> {
+        let mut fields = Fields {
+            fields: fields.iter(),
+            origin,
+        };
+        if fields.peek() == Some("\\#") {
+            fields.next("generic RDATA")?;
+            return parse_generic(r_type, fields);
+        }
+
+        let rdata = match r_type.to_known_type() {
+            Some(KnownQType::A) => Self::A(fields.parse("address")?),
+            Some(KnownQType::AAAA) => Self::AAAA(fields.parse("address")?),
+            Some(KnownQType::NS) => Self::NS(fields.name("name server")?),
+            Some(KnownQType::CNAME) => Self::CNAME(fields.name("canonical name")?),
+            Some(KnownQType::PTR) => Self::PTR(fields.name("pointer")?),
+            Some(KnownQType::SOA) => Self::SOA(Soa {
+                m_name: fields.name("primary name server")?,
+                r_name: fields.name("responsible mailbox")?,
+                serial: fields.parse("serial")?,
+                refresh: fields.ttl("refresh")?,
+                retry: fields.ttl("retry")?,
+                expire: fields.ttl("expire")?,
+                minimum: fields.ttl("minimum")?,
+            }),
+            Some(KnownQType::HINFO) => Self::HINFO(Hinfo {
+                cpu: fields.character_string("CPU")?,
+                os: fields.character_string("OS")?,
+            }),
+            Some(KnownQType::MX) => Self::MX(Mx {
+                preference: fields.parse("preference")?,
+                exchange: fields.name("exchange")?,
+            }),
+            Some(r_type @ (KnownQType::TXT | KnownQType::SPF)) => {
+                let mut strings = vec![fields.character_string("text")?];
+                while fields.peek().is_some() {
+                    strings.push(fields.character_string("text")?);
+                }
+                let txt = Txt { strings };
+                match r_type {
+                    KnownQType::TXT => Self::TXT(txt),
+                    _ => Self::SPF(txt),
+                }
+            }
+            Some(KnownQType::RP) => Self::RP(Rp {
+                mailbox: fields.name("mailbox")?,
+                text: fields.name("text name")?,
+            }),
+            Some(KnownQType::AFSDB) => Self::AFSDB(Afsdb {
+                subtype: fields.parse("subtype")?,
+                hostname: fields.name("hostname")?,
+            }),
+            Some(KnownQType::LOC) => Self::LOC(fields.loc()?),
+            Some(KnownQType::CERT) => Self::CERT(Cert {
+                cert_type: fields.cert_type()?,
+                key_tag: fields.parse("key tag")?,
+                algorithm: fields.cert_algorithm()?,
+                certificate: fields.base64("certificate")?,
+            }),
+            Some(KnownQType::DNAME) => Self::DNAME(fields.name("target")?),
+            Some(r_type @ (KnownQType::DS | KnownQType::CDS)) => {
+                let ds = Ds {
+                    key_tag: fields.parse("key tag")?,
+                    algorithm: fields.parse("algorithm")?,
+                    digest_type: fields.parse("digest type")?,
+                    digest: fields.hex("digest")?,
+                };
+                match r_type {
+                    KnownQType::DS => Self::DS(ds),
+                    _ => Self::CDS(ds),
+                }
+            }
+            Some(r_type @ (KnownQType::DNSKEY | KnownQType::CDNSKEY | KnownQType::KEY)) => {
+                let dnskey = Dnskey {
+                    flags: fields.parse("flags")?,
+                    protocol: fields.parse("protocol")?,
+                    algorithm: fields.parse("algorithm")?,
+                    public_key: fields.base64("public key")?,
+                };
+                match r_type {
+                    KnownQType::DNSKEY => Self::DNSKEY(dnskey),
+                    KnownQType::KEY => Self::KEY(dnskey),
+                    _ => Self::CDNSKEY(dnskey),
+                }
+            }
+            Some(r_type @ (KnownQType::RRSIG | KnownQType::SIG)) => {
+                let rrsig = Rrsig {
+                    type_covered: fields.parse("type covered")?,
+                    algorithm: fields.parse("algorithm")?,
+                    labels: fields.parse("labels")?,
+                    original_ttl: fields.ttl("original TTL")?,
+                    signature_expiration: fields.timestamp("signature expiration")?,
+                    signature_inception: fields.timestamp("signature inception")?,
+                    key_tag: fields.parse("key tag")?,
+                    signer_name: fields.name("signer name")?,
+                    signature: fields.base64("signature")?,
+                };
+                match r_type {
+                    KnownQType::RRSIG => Self::RRSIG(rrsig),
+                    _ => Self::SIG(rrsig),
+                }
+            }
+            Some(KnownQType::NSEC) => Self::NSEC(Nsec {
+                next_domain_name: fields.name("next domain name")?,
+                type_bitmap: fields.type_bitmap()?,
+            }),
+            Some(KnownQType::NSEC3) => Self::NSEC3(Nsec3 {
+                hash_algorithm: fields.parse("hash algorithm")?,
+                flags: fields.parse("flags")?,
+                iterations: fields.parse("iterations")?,
+                salt: fields.salt()?,
+                next_hashed_owner_name: fields.base32hex("next hashed owner name")?,
+                type_bitmap: fields.type_bitmap()?,
+            }),
+            Some(KnownQType::NSEC3PARAM) => Self::NSEC3PARAM(Nsec3Param {
+                hash_algorithm: fields.parse("hash algorithm")?,
+                flags: fields.parse("flags")?,
+                iterations: fields.parse("iterations")?,
+                salt: fields.salt()?,
+            }),
+            Some(KnownQType::NAPTR) => Self::NAPTR(Naptr {
+                order: fields.parse("order")?,
+                preference: fields.parse("preference")?,
+                flags: fields.character_string("flags")?,
+                services: fields.character_string("services")?,
+                regexp: fields.character_string("regexp")?,
+                replacement: fields.name("replacement")?,
+            }),
+            Some(KnownQType::SSHFP) => Self::SSHFP(Sshfp {
+                algorithm: fields.parse("algorithm")?,
+                fingerprint_type: fields.parse("fingerprint type")?,
+                fingerprint: fields.hex("fingerprint")?,
+            }),
+            Some(r_type @ (KnownQType::TLSA | KnownQType::SMIMEA)) => {
+                let tlsa = Tlsa {
+                    certificate_usage: fields.parse("certificate usage")?,
+                    selector: fields.parse("selector")?,
+                    matching_type: fields.parse("matching type")?,
+                    certificate_association_data: fields.hex("certificate association data")?,
+                };
+                match r_type {
+                    KnownQType::TLSA => Self::TLSA(tlsa),
+                    _ => Self::SMIMEA(tlsa),
+                }
+            }
+            Some(KnownQType::OPENPGPKEY) => Self::OPENPGPKEY(Openpgpkey {
+                public_key: fields.base64("public key")?,
+            }),
+            Some(KnownQType::ZONEMD) => Self::ZONEMD(Zonemd {
+                serial: fields.parse("serial")?,
+                scheme: fields.parse("scheme")?,
+                hash_algorithm: fields.parse("hash algorithm")?,
+                digest: fields.hex("digest")?,
+            }),
+            Some(KnownQType::URI) => Self::URI(Uri {
+                priority: fields.parse("priority")?,
+                weight: fields.parse("weight")?,
+                target: fields.unescaped("target")?,
+            }),
+            Some(KnownQType::CAA) => Self::CAA(Caa {
+                flags: fields.parse("flags")?,
+                tag: fields.caa_tag()?,
+                value: fields.unescaped("value")?,
+            }),
+            _ => return Err(RDataParseError::UnsupportedType(r_type)),
+        };
+        fields.finish()?;
+        Ok(rdata)
+    }
+}
+
+/// Parses a TTL written either as a number of seconds or, as accepted by
+/// BIND, as a sequence of values followed by one of the `w`, `d`, `h`, `m`
+/// and `s` units, such as `1h30m`.
+pub(crate) fn parse_ttl(text: &str) -> Option<u32> {
+    if text.is_empty() {
+        return None;
+    }
+
+    let mut ttl: u32 = 0;
+    let mut value: Option<u32> = None;
+    for c in text.chars() {
+        match c.to_digit(10) {
+            Some(digit) => {
+                value = Some(value.unwrap_or(0).checked_mul(10)?.checked_add(digit)?);
+            }
+            None => {
+                let unit = match c.to_ascii_lowercase() {
+                    'w' => 604_800,
+                    'd' => 86_400,
+                    'h' => 3_600,
+                    'm' => 60,
+                    's' => 1,
+                    _ => return None,
+                };
+                ttl = ttl.checked_add(value.take()?.checked_mul(unit)?)?;
+            }
+        }
+    }
+    ttl.checked_add(value.unwrap_or(0))
+}
+
+fn parse_generic(r_type: QType, mut fields: Fields) -> Result<RData, RDataParseError> {
+    let len: usize = fields.parse("RDATA length")?;
+    let bytes = match len {
+        0 => Vec::new(),
+        _ => fields.hex("RDATA")?,
+    };
+    fields.finish()?;
+    if bytes.len() != len {
+        return Err(RDataParseError::InvalidLength {
+            expected: len,
+            actual: bytes.len(),
+        });
+    }
+
+    let mut reader = Reader::new(&bytes, 0);
+    let rdata = RData::from_wire(r_type, &mut reader, len)
+        .map_err(|e| RDataParseError::InvalidField("RDATA", e.to_string()))?;
+    // Generic RDATA must not use name compression, see RFC 3597, Section 4,
+    // so that it always encodes back to the same bytes.
+    match rdata.to_bytes() == bytes {
+        true => Ok(rdata),
+        false => Err(RDataParseError::InvalidField(
+            "RDATA",
+            data_encoding::HEXUPPER.encode(&bytes),
+        )),
+    }
+}
+
+/// Cursor over the fields of `RDATA` in presentation format.
+struct Fields<'a> {
+    fields: std::slice::Iter<'a, &'a str>,
+    origin: &'a Domain,
+}
+
+impl<'a> Fields<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        self.fields.clone().next().copied()
+    }
+
+    fn next(&mut self, field: &'static str) -> Result<&'a str, RDataParseError> {
+        self.fields
+            .next()
+            .copied()
+            .ok_or(RDataParseError::MissingField(field))
+    }
+
+    fn parse<T: FromStr>(&mut self, field: &'static str) -> Result<T, RDataParseError> {
+        let text = self.next(field)?;
+        text.parse()
+            .map_err(|_| RDataParseError::InvalidField(field, text.to_string()))
+    }
+
+    fn name(&mut self, field: &'static str) -> Result<Domain, RDataParseError> {
+        let text = self.next(field)?;
+        Domain::from_presentation_relative(text, self.origin)
+            .map_err(|_| RDataParseError::InvalidField(field, text.to_string()))
+    }
+
+    fn character_string(
+        &mut self,
+        field: &'static str,
+    ) -> Result<CharacterString, RDataParseError> {
+        let text = self.next(field)?;
+        text.parse()
+            .map_err(|_| RDataParseError::InvalidField(field, text.to_string()))
+    }
+
+    /// Parses a quoted or unquoted string of any length.
+    fn unescaped(&mut self, field: &'static str) -> Result<Vec<u8>, RDataParseError> {
+        let text = self.next(field)?;
+        unescape(text).map_err(|_| RDataParseError::InvalidField(field, text.to_string()))
+    }
+
+    fn caa_tag(&mut self) -> Result<String, RDataParseError> {
+        let text = self.next("tag")?;
+        match Caa::is_valid_tag(text) {
+            true => Ok(text.to_string()),
+            false => Err(RDataParseError::InvalidField("tag", text.to_string())),
+        }
+    }
+
+    /// Parses the remaining fields as the location of a `LOC` record.
+    fn loc(&mut self) -> Result<Loc, RDataParseError> {
+        let fields: Vec<&str> = self.fields.by_ref().copied().collect();
+        match fields.is_empty() {
+            true => Err(RDataParseError::MissingField("latitude")),
+            false => Loc::from_presentation(&fields)
+                .ok_or_else(|| RDataParseError::InvalidField("location", fields.join(" "))),
+        }
+    }
+
+    fn cert_type(&mut self) -> Result<u16, RDataParseError> {
+        let text = self.next("certificate type")?;
+        Cert::parse_type(text)
+            .ok_or_else(|| RDataParseError::InvalidField("certificate type", text.to_string()))
+    }
+
+    /// Parses a DNSSEC algorithm written as a number or a mnemonic, see
+    /// RFC 4398, Section 2.2.
+    fn cert_algorithm(&mut self) -> Result<u8, RDataParseError> {
+        let text = self.next("algorithm")?;
+        text.parse()
+            .ok()
+            .or_else(|| {
+                (0..=u8::MAX).find(|value| {
+                    Algorithm::try_from(*value)
+                        .is_ok_and(|algorithm| algorithm.mnemonic().eq_ignore_ascii_case(text))
+                })
+            })
+            .ok_or_else(|| RDataParseError::InvalidField("algorithm", text.to_string()))
+    }
+
+    fn ttl(&mut self, field: &'static str) -> Result<u32, RDataParseError> {
+        let text = self.next(field)?;
+        parse_ttl(text).ok_or_else(|| RDataParseError::InvalidField(field, text.to_string()))
+    }
+
+    fn timestamp(&mut self, field: &'static str) -> Result<u32, RDataParseError> {
+        let text = self.next(field)?;
+        parse_timestamp(text).ok_or_else(|| RDataParseError::InvalidField(field, text.to_string()))
+    }
+
+    /// Joins all the remaining fields, since binary data in presentation
+    /// format may be split by whitespace.
+    fn rest(&mut self, field: &'static str) -> Result<String, RDataParseError> {
+        let text = self.fields.by_ref().copied().collect::<String>();
+        match text.is_empty() {
+            true => Err(RDataParseError::MissingField(field)),
+            false => Ok(text),
+        }
+    }
+
+    fn hex(&mut self, field: &'static str) -> Result<Vec<u8>, RDataParseError> {
+        let text = self.rest(field)?;
+        data_encoding::HEXUPPER_PERMISSIVE
+            .decode(text.as_bytes())
+            .map_err(|_| RDataParseError::InvalidField(field, text))
+    }
+
+    fn base64(&mut self, field: &'static str) -> Result<Vec<u8>, RDataParseError> {
+        let text = self.rest(field)?;
+        data_encoding::BASE64
+            .decode(text.as_bytes())
+            .map_err(|_| RDataParseError::InvalidField(field, text))
+    }
+
+    fn base32hex(&mut self, field: &'static str) -> Result<Vec<u8>, RDataParseError> {
+        let text = self.next(field)?;
+        data_encoding::BASE32HEX_NOPAD
+            .decode(text.to_ascii_uppercase().as_bytes())
+            .map_err(|_| RDataParseError::InvalidField(field, text.to_string()))
+    }
+
+    /// Parses an NSEC3 salt, where `-` denotes an empty salt.
+    fn salt(&mut self) -> Result<Vec<u8>, RDataParseError> {
+        let text = self.next("salt")?;
+        match text {
+            "-" => Ok(Vec::new()),
+            _ => data_encoding::HEXUPPER_PERMISSIVE
+                .decode(text.as_bytes())
+                .map_err(|_| RDataParseError::InvalidField("salt", text.to_string())),
+        }
+    }
+
+    fn type_bitmap(&mut self) -> Result<TypeBitmap, RDataParseError> {
+        self.fields
+            .by_ref()
+            .map(|text| {
+                QType::from_str(text)
+                    .map_err(|_| RDataParseError::InvalidField("type", text.to_string()))
+            })
+            .collect()
+    }
+
+    fn finish(mut self) -> Result<(), RDataParseError> {
+        match self.fields.next() {
+            Some(text) => Err(RDataParseError::TrailingField(text.to_string())),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn parse(r_type: KnownQType, text: &str) -> Result<RData, RDataParseError> {
+        let origin = Domain::from_presentation("example.com.").unwrap();
+        let fields: Vec<&str> = text.split_whitespace().collect();
+        RData::from_presentation(r_type.into(), &fields, &origin)
+    }
+
+    #[rstest]
+    #[case(KnownQType::A, "192.0.2.1", "192.0.2.1")]
+    #[case(KnownQType::AAAA, "2001:DB8::1", "2001:db8::1")]
+    #[case(KnownQType::NS, "ns1", "ns1.example.com.")]
+    #[case(KnownQType::CNAME, "@", "example.com.")]
+    #[case(KnownQType::PTR, "host.example.org.", "host.example.org.")]
+    #[case(
+        KnownQType::SOA,
+        "ns1 hostmaster 2024010101 2h 1h 2w 5m",
+        "ns1.example.com. hostmaster.example.com. 2024010101 7200 3600 1209600 300"
+    )]
+    #[case(KnownQType::HINFO, "\"x86\" Linux", "\"x86\" \"Linux\"")]
+    #[case(KnownQType::MX, "10 mail", "10 mail.example.com.")]
+    #[case(KnownQType::RP, "louie.trantor.umd.edu. .", "louie.trantor.umd.edu. .")]
+    #[case(KnownQType::AFSDB, "1 afs1", "1 afs1.example.com.")]
+    #[case(
+        KnownQType::LOC,
+        "42 21 54 N 71 06 18 W -24m 30m",
+        "42 21 54.000 N 71 6 18.000 W -24m 30m 10000m 10m"
+    )]
+    #[case(KnownQType::CERT, "pgp 0 rsasha256 3q 0=", "PGP 0 8 3q0=")]
+    #[case(KnownQType::CERT, "65280 12345 253 3q0=", "65280 12345 253 3q0=")]
+    #[case(KnownQType::DNAME, "example.net.", "example.net.")]
+    #[case(KnownQType::TXT, "v=spf1 \"-all\"", "\"v=spf1\" \"-all\"")]
+    #[case(KnownQType::TXT, "\"\"", "\"\"")]
+    #[case(KnownQType::SPF, "a\\032b", "\"a b\"")]
+    #[case(
+        KnownQType::DS,
+        "60485 5 1 2bb183af5f22588179a5 3b0a98631fad1a292118",
+        "60485 5 1 2BB183AF5F22588179A53B0A98631FAD1A292118"
+    )]
+    #[case(
+        KnownQType::DNSKEY,
+        "256 3 15 l02Woi0iS8Aa 25FQkUd9RMzZHJpBoRQwAQEX1SxZJA4=",
+        "256 3 15 l02Woi0iS8Aa25FQkUd9RMzZHJpBoRQwAQEX1SxZJA4="
+    )]
+    #[case(
+        KnownQType::RRSIG,
+        "MX 15 2 1h 20150819220000 1438207200 3613 @ oL8=",
+        "MX 15 2 3600 20150819220000 20150729220000 3613 example.com. oL8="
+    )]
+    #[case(
+        KnownQType::NSEC,
+        "host A MX RRSIG NSEC TYPE1234",
+        "host.example.com. A MX RRSIG NSEC TYPE1234"
+    )]
+    #[case(
+        KnownQType::NSEC3,
+        "1 1 12 AABBCCDD 2T7B4G4VSA5SMI47K61MV5BV1A22BOJR A RRSIG",
+        "1 1 12 AABBCCDD 2t7b4g4vsa5smi47k61mv5bv1a22bojr A RRSIG"
+    )]
+    #[case(KnownQType::NSEC3PARAM, "1 0 0 -", "1 0 0 -")]
+    #[case(
+        KnownQType::NAPTR,
+        "100 10 S SIP+D2U \"\" _sip._udp",
+        "100 10 \"S\" \"SIP+D2U\" \"\" _sip._udp.example.com."
+    )]
+    #[case(
+        KnownQType::SSHFP,
+        "4 1 60f65734e9070591cbf426ac9591f99c5c86d641",
+        "4 1 60F65734E9070591CBF426AC9591F99C5C86D641"
+    )]
+    #[case(KnownQType::TLSA, "3 1 1 af77 b4d1", "3 1 1 AF77B4D1")]
+    #[case(KnownQType::SMIMEA, "3 0 0 3082", "3 0 0 3082")]
+    #[case(KnownQType::OPENPGPKEY, "mQIN BFXS", "mQINBFXS")]
+    #[case(
+        KnownQType::URI,
+        "10 1 \"ftp://ftp1.example.com/public\"",
+        "10 1 \"ftp://ftp1.example.com/public\""
+    )]
+    #[case(
+        KnownQType::ZONEMD,
+        "2018031900 1 1 c680 90d9",
+        "2018031900 1 1 C68090D9"
+    )]
+    #[case(
+        KnownQType::CAA,
+        "0 issue \"ca.example.net;account=1\"",
+        "0 issue \"ca.example.net;account=1\""
+    )]
+    #[case(KnownQType::CAA, "128 tbs Unknown", "128 tbs \"Unknown\"")]
+    #[case(KnownQType::A, "\\# 4 C0000201", "192.0.2.1")]
+    #[case(KnownQType::MX, "\\# 5 000A 01 61 00", "10 a.")]
+    fn rdata_from_presentation(
+        #[case] r_type: KnownQType,
+        #[case] text: &str,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(parse(r_type, text).unwrap().to_string(), expected);
+    }
+
+    #[test]
+    fn rdata_from_presentation_generic_unknown_type() {
+        let origin = Domain::new();
+        let rdata = RData::from_presentation(QType::new(65280), &["\\#", "3", "0a0b0c"], &origin);
+        assert_eq!(rdata, Ok(RData::Unknown(vec![10, 11, 12])));
+
+        let empty = RData::from_presentation(QType::new(65280), &["\\#", "0"], &origin);
+        assert_eq!(empty, Ok(RData::Unknown(vec![])));
+    }
+
+    #[rstest]
+    #[case(KnownQType::A, "", RDataParseError::MissingField("address"))]
+    #[case(KnownQType::A, "192.0.2", RDataParseError::InvalidField("address", "192.0.2".to_string()))]
+    #[case(KnownQType::A, "192.0.2.1 x", RDataParseError::TrailingField("x".to_string()))]
+    #[case(KnownQType::MX, "10", RDataParseError::MissingField("exchange"))]
+    #[case(KnownQType::HINFO, "x86", RDataParseError::MissingField("OS"))]
+    #[case(KnownQType::CAA, "0 is-sue x", RDataParseError::InvalidField("tag", "is-sue".to_string()))]
+    #[case(KnownQType::TXT, "", RDataParseError::MissingField("text"))]
+    #[case(KnownQType::LOC, "", RDataParseError::MissingField("latitude"))]
+    #[case(KnownQType::LOC, "91 N 0 E 0m", RDataParseError::InvalidField("location", "91 N 0 E 0m".to_string()))]
+    #[case(KnownQType::CERT, "X509 0 8 3q0=", RDataParseError::InvalidField("certificate type", "X509".to_string()))]
+    #[case(KnownQType::CERT, "PKIX 0 RSA 3q0=", RDataParseError::InvalidField("algorithm", "RSA".to_string()))]
+    #[case(KnownQType::TXT, "a\\300", RDataParseError::InvalidField("text", "a\\300".to_string()))]
+    #[case(KnownQType::SOA, "a b 1 2 3 4 1x", RDataParseError::InvalidField("minimum", "1x".to_string()))]
+    #[case(KnownQType::NSEC, "a A BOGUS", RDataParseError::InvalidField("type", "BOGUS".to_string()))]
+    #[case(KnownQType::TSIG, "hmac-sha256", RDataParseError::UnsupportedType(KnownQType::TSIG.into()))]
+    #[case(KnownQType::A, "\\# 4 C00002", RDataParseError::InvalidLength { expected: 4, actual: 3 })]
+    #[case(KnownQType::MX, "\\# 4 000A C000", RDataParseError::InvalidField("RDATA", "000AC000".to_string()))]
+    fn rdata_from_presentation_fails(
+        #[case] r_type: KnownQType,
+        #[case] text: &str,
+        #[case] expected: RDataParseError,
+    ) {
+        assert_eq!(parse(r_type, text), Err(expected));
+    }
+
+    #[rstest]
+    #[case("3600", Some(3600))]
+    #[case("1h30m", Some(5400))]
+    #[case("1W2D", Some(777600))]
+    #[case("1h30", Some(3630))]
+    #[case("0", Some(0))]
+    #[case("", None)]
+    #[case("h", None)]
+    #[case("1y", None)]
+    #[case("4294967296", None)]
+    fn parse_ttl_works(#[case] text: &str, #[case] expected: Option<u32>) {
+        assert_eq!(parse_ttl(text), expected);
+    }
+}
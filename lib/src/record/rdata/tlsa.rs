@@ -0,0 +1,67 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+/// `RDATA` of a `TLSA` record, associating a TLS server certificate or
+/// public key with the name where the record is found.
+///
+/// For more details, see [RFC 6698, Section 2.1].
+///
+/// [RFC 6698, Section 2.1]: https://datatracker.ietf.org/doc/html/rfc6698#section-2.1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tlsa {
+    pub certificate_usage: u8,
+    pub selector: u8,
+    pub matching_type: u8,
+    pub certificate_association_data: Vec<u8>,
+}
+
+impl Tlsa {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![self.certificate_usage, self.selector, self.matching_type];
+        bytes.extend_from_slice(&self.certificate_association_data);
+        bytes
+    }
+}
+
+impl fmt::Display for Tlsa {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.certificate_usage,
+            self.selector,
+            self.matching_type,
+            data_encoding::HEXUPPER.encode(&self.certificate_association_data)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tlsa_to_bytes() {
+        let tlsa = Tlsa {
+            certificate_usage: 3,
+            selector: 1,
+            matching_type: 1,
+            certificate_association_data: vec![0xaf, 0x77],
+        };
+        assert_eq!(tlsa.to_bytes(), [3, 1, 1, 0xaf, 0x77]);
+        assert_eq!(tlsa.to_string(), "3 1 1 AF77");
+    }
+}
@@ -0,0 +1,97 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::Domain;
+use std::fmt;
+
+/// `RDATA` of a `TSIG` record, authenticating a message with a shared
+/// secret.
+///
+/// For more details, see [RFC 8945, Section 4.2].
+///
+/// [RFC 8945, Section 4.2]: https://datatracker.ietf.org/doc/html/rfc8945#section-4.2
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tsig {
+    pub algorithm: Domain,
+    /// Seconds since the epoch, on 48 bits.
+    pub time_signed: u64,
+    pub fudge: u16,
+    pub mac: Vec<u8>,
+    pub original_id: u16,
+    pub error: u16,
+    pub other_data: Vec<u8>,
+}
+
+impl Tsig {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.algorithm.to_bytes();
+        bytes.extend_from_slice(&self.time_signed.to_be_bytes()[2..]);
+        bytes.extend_from_slice(&self.fudge.to_be_bytes());
+        bytes.extend_from_slice(&(self.mac.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(&self.mac);
+        bytes.extend_from_slice(&self.original_id.to_be_bytes());
+        bytes.extend_from_slice(&self.error.to_be_bytes());
+        bytes.extend_from_slice(&(self.other_data.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(&self.other_data);
+        bytes
+    }
+}
+
+impl fmt::Display for Tsig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {} {} {}",
+            self.algorithm.to_fqdn_string(),
+            self.time_signed,
+            self.fudge,
+            self.mac.len(),
+            data_encoding::BASE64.encode(&self.mac),
+            self.original_id,
+            self.error,
+            self.other_data.len()
+        )?;
+        if !self.other_data.is_empty() {
+            write!(f, " {}", data_encoding::BASE64.encode(&self.other_data))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tsig_to_bytes() {
+        let tsig = Tsig {
+            algorithm: Domain::from_presentation("hmac-md5.sig-alg.reg.int").unwrap(),
+            time_signed: 0x0102_0304_0506,
+            fudge: 300,
+            mac: vec![0xaa, 0xbb],
+            original_id: 0x1234,
+            error: 18,
+            other_data: vec![0, 0, 0, 0, 0, 1],
+        };
+        let mut expected = tsig.algorithm.to_bytes();
+        expected.extend_from_slice(&[1, 2, 3, 4, 5, 6, 0x01, 0x2c, 0, 2, 0xaa, 0xbb]);
+        expected.extend_from_slice(&[0x12, 0x34, 0, 18, 0, 6, 0, 0, 0, 0, 0, 1]);
+
+        assert_eq!(tsig.to_bytes(), expected);
+        assert_eq!(
+            tsig.to_string(),
+            "hmac-md5.sig-alg.reg.int. 1108152157446 300 2 qrs= 4660 18 6 AAAAAAAB"
+        );
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CharacterString;
+use std::fmt;
+
+/// `RDATA` of a `TXT` or `SPF` record, made of one or more character
+/// strings.
+///
+/// Consumers such as SPF and DKIM treat the strings as a single value, see
+/// [`Txt::value`]. For more details, see [RFC 1035, Section 3.3.14] and
+/// [RFC 7208, Section 3.3].
+///
+/// [RFC 1035, Section 3.3.14]: https://datatracker.ietf.org/doc/html/rfc1035#section-3.3.14
+/// [RFC 7208, Section 3.3]: https://datatracker.ietf.org/doc/html/rfc7208#section-3.3
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Txt {
+    pub strings: Vec<CharacterString>,
+}
+
+impl Txt {
+    /// Builds the `RDATA` holding `value`, split into character strings
+    /// of at most 255 octets.
+    ///
+    /// # Example
+    /// ```
+    /// use dns_lib::record::rdata::Txt;
+    ///
+    /// let value = format!("v=DKIM1; k=rsa; p={}", "A".repeat(400));
+    /// let txt = Txt::from_value(value.as_bytes());
+    /// assert_eq!(txt.strings.len(), 2);
+    /// assert_eq!(txt.value(), value.as_bytes());
+    /// ```
+    pub fn from_value(value: &[u8]) -> Txt {
+        Txt {
+            strings: CharacterString::split(value),
+        }
+    }
+
+    /// Returns the concatenation of all the strings, without any separator.
+    pub fn value(&self) -> Vec<u8> {
+        self.strings
+            .iter()
+            .flat_map(|string| string.as_bytes())
+            .copied()
+            .collect()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.strings
+            .iter()
+            .flat_map(CharacterString::to_bytes)
+            .collect()
+    }
+}
+
+impl fmt::Display for Txt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let strings: Vec<String> = self.strings.iter().map(|s| s.to_string()).collect();
+        write!(f, "{}", strings.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn txt_value() {
+        let txt = Txt {
+            strings: vec![
+                "v=spf1 ".parse().unwrap(),
+                "include:example.net".parse().unwrap(),
+                " -all".parse().unwrap(),
+            ],
+        };
+        assert_eq!(txt.value(), b"v=spf1 include:example.net -all");
+        assert_eq!(
+            txt.to_string(),
+            "\"v=spf1 \" \"include:example.net\" \" -all\""
+        );
+        assert_eq!(
+            txt.to_bytes()[..8],
+            [7, b'v', b'=', b's', b'p', b'f', b'1', b' ']
+        );
+        assert_eq!(txt.to_bytes().len(), 31 + 3);
+    }
+}
@@ -0,0 +1,176 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::message::QType;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// Set of record types present at an owner name, as carried by `NSEC` and
+/// `NSEC3` records.
+///
+/// For more details, see [RFC 4034, Section 4.1.2].
+///
+/// [RFC 4034, Section 4.1.2]: https://datatracker.ietf.org/doc/html/rfc4034#section-4.1.2
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TypeBitmap {
+    types: BTreeSet<QType>,
+}
+
+impl TypeBitmap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, r_type: QType) {
+        self.types.insert(r_type);
+    }
+
+    pub fn contains(&self, r_type: QType) -> bool {
+        self.types.contains(&r_type)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+
+    /// Returns the types in the bitmap, in ascending numeric order.
+    pub fn types(&self) -> impl Iterator<Item = QType> + '_ {
+        self.types.iter().copied()
+    }
+
+    /// Encodes the bitmap as a sequence of windows. Each window holds up to
+    /// 256 types sharing the same high-order byte, and is truncated after
+    /// the last octet with a bit set.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut window: Option<(u8, [u8; 32], usize)> = None;
+
+        for r_type in &self.types {
+            let [high, low] = r_type.value.to_be_bytes();
+            match window {
+                Some((number, _, _)) if number == high => (),
+                _ => {
+                    if let Some((number, bitmap, len)) = window {
+                        push_window(&mut bytes, number, &bitmap[..len]);
+                    }
+                    window = Some((high, [0; 32], 0));
+                }
+            }
+
+            if let Some((_, bitmap, len)) = window.as_mut() {
+                let octet = low as usize / 8;
+                bitmap[octet] |= 0b1000_0000 >> (low % 8);
+                *len = octet + 1;
+            }
+        }
+
+        if let Some((number, bitmap, len)) = window {
+            push_window(&mut bytes, number, &bitmap[..len]);
+        }
+        bytes
+    }
+
+    /// Decodes a sequence of windows. Returns `None` if windows are out of
+    /// order, empty or longer than 32 octets.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut bitmap = TypeBitmap::new();
+        let mut rest = bytes;
+        let mut last_window: Option<u8> = None;
+        while let [number, len, tail @ ..] = rest {
+            let len = *len as usize;
+            if last_window.is_some_and(|last| last >= *number) || len == 0 || len > 32 {
+                return None;
+            }
+            let octets = tail.get(..len)?;
+            for (i, octet) in octets.iter().enumerate() {
+                for bit in 0..8 {
+                    if octet & (0b1000_0000 >> bit) != 0 {
+                        let low = (i * 8 + bit) as u8;
+                        bitmap.insert(QType::new(u16::from_be_bytes([*number, low])));
+                    }
+                }
+            }
+            last_window = Some(*number);
+            rest = &tail[len..];
+        }
+        match rest.is_empty() {
+            true => Some(bitmap),
+            false => None,
+        }
+    }
+}
+
+fn push_window(bytes: &mut Vec<u8>, number: u8, bitmap: &[u8]) {
+    bytes.push(number);
+    bytes.push(bitmap.len() as u8);
+    bytes.extend_from_slice(bitmap);
+}
+
+impl FromIterator<QType> for TypeBitmap {
+    fn from_iter<I: IntoIterator<Item = QType>>(iter: I) -> Self {
+        TypeBitmap {
+            types: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl fmt::Display for TypeBitmap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let types: Vec<String> = self.types.iter().map(|t| t.to_string()).collect();
+        write!(f, "{}", types.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(&[], &[])]
+    // Example from RFC 4034, Section 4.3: A MX RRSIG NSEC TYPE1234.
+    #[case(
+        &[1, 15, 46, 47, 1234],
+        &[
+            0x00, 0x06, 0x40, 0x01, 0x00, 0x00, 0x00, 0x03,
+            0x04, 0x1b, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x20,
+        ]
+    )]
+    fn type_bitmap_to_bytes(#[case] types: &[u16], #[case] expected: &[u8]) {
+        let bitmap: TypeBitmap = types.iter().map(|&t| QType::new(t)).collect();
+        assert_eq!(bitmap.to_bytes(), expected);
+        assert_eq!(TypeBitmap::from_bytes(expected), Some(bitmap));
+    }
+
+    #[rstest]
+    #[case(&[0, 0])]
+    #[case(&[0, 33])]
+    #[case(&[0, 2, 0x40])]
+    #[case(&[1, 1, 0x40, 0, 1, 0x40])]
+    #[case(&[0])]
+    fn type_bitmap_from_bytes_fails(#[case] bytes: &[u8]) {
+        assert_eq!(TypeBitmap::from_bytes(bytes), None);
+    }
+
+    #[test]
+    fn type_bitmap_to_string() {
+        let bitmap: TypeBitmap = [47, 1, 1234, 15].into_iter().map(QType::new).collect();
+        assert_eq!(bitmap.to_string(), "A MX NSEC TYPE1234");
+        assert!(bitmap.contains(QType::new(15)));
+        assert!(!bitmap.contains(QType::new(2)));
+    }
+}
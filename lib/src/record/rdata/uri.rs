@@ -0,0 +1,68 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::character_string::write_quoted;
+use std::fmt;
+
+/// `RDATA` of a `URI` record, mapping the owner name, usually of the form
+/// `_service._proto.name`, to a URI. Like `SRV`, clients pick the target
+/// with the lowest priority and, among equal priorities, at random weighted
+/// by the weight.
+///
+/// The target is not a `<character-string>`: it spans the rest of the
+/// `RDATA` and may be longer than 255 octets.
+///
+/// For more details, see [RFC 7553, Section 4].
+///
+/// [RFC 7553, Section 4]: https://datatracker.ietf.org/doc/html/rfc7553#section-4
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Uri {
+    pub priority: u16,
+    pub weight: u16,
+    pub target: Vec<u8>,
+}
+
+impl Uri {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.priority.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&self.weight.to_be_bytes());
+        bytes.extend_from_slice(&self.target);
+        bytes
+    }
+}
+
+impl fmt::Display for Uri {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} ", self.priority, self.weight)?;
+        write_quoted(f, &self.target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uri_to_bytes() {
+        let uri = Uri {
+            priority: 10,
+            weight: 1,
+            target: b"ftp://ftp1.example.com/public".to_vec(),
+        };
+        let mut expected = vec![0, 10, 0, 1];
+        expected.extend_from_slice(b"ftp://ftp1.example.com/public");
+        assert_eq!(uri.to_bytes(), expected);
+        assert_eq!(uri.to_string(), "10 1 \"ftp://ftp1.example.com/public\"");
+    }
+}
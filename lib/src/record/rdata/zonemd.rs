@@ -0,0 +1,71 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+/// `RDATA` of a `ZONEMD` record, holding a digest of the zone contents.
+///
+/// For more details, see [RFC 8976, Section 2].
+///
+/// [RFC 8976, Section 2]: https://datatracker.ietf.org/doc/html/rfc8976#section-2
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Zonemd {
+    pub serial: u32,
+    pub scheme: u8,
+    pub hash_algorithm: u8,
+    pub digest: Vec<u8>,
+}
+
+impl Zonemd {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.serial.to_be_bytes().to_vec();
+        bytes.push(self.scheme);
+        bytes.push(self.hash_algorithm);
+        bytes.extend_from_slice(&self.digest);
+        bytes
+    }
+}
+
+impl fmt::Display for Zonemd {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.serial,
+            self.scheme,
+            self.hash_algorithm,
+            data_encoding::HEXUPPER.encode(&self.digest)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zonemd_to_bytes() {
+        let zonemd = Zonemd {
+            serial: 2018031900,
+            scheme: 1,
+            hash_algorithm: 1,
+            digest: vec![0xc6, 0x80],
+        };
+        assert_eq!(
+            zonemd.to_bytes(),
+            [0x78, 0x48, 0xb9, 0x1c, 1, 1, 0xc6, 0x80]
+        );
+        assert_eq!(zonemd.to_string(), "2018031900 1 1 C680");
+    }
+}
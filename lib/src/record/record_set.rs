@@ -0,0 +1,126 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::Domain;
+use crate::message::{QClass, QType};
+use crate::record::{RData, ResourceRecord};
+
+/// Set of resource records sharing the same owner name, class and type.
+///
+/// All records in a set are expected to share the same TTL. When they do
+/// not, the lowest TTL found is used for the whole set.
+///
+/// For more details, see [RFC 2181, Section 5].
+///
+/// [RFC 2181, Section 5]: https://datatracker.ietf.org/doc/html/rfc2181#section-5
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordSet {
+    pub name: Domain,
+    pub r_type: QType,
+    pub r_class: QClass,
+    pub ttl: u32,
+    pub rdatas: Vec<RData>,
+}
+
+impl RecordSet {
+    /// Builds a set out of `records`, which must all share the owner name,
+    /// class and type of the first one. Returns `None` if `records` is empty.
+    pub fn from_records<'a, I>(records: I) -> Option<RecordSet>
+    where
+        I: IntoIterator<Item = &'a ResourceRecord>,
+    {
+        let mut records = records.into_iter();
+        let first = records.next()?;
+        let mut set = RecordSet {
+            name: first.name.clone(),
+            r_type: first.r_type,
+            r_class: first.r_class,
+            ttl: first.ttl,
+            rdatas: vec![first.rdata.clone()],
+        };
+        for record in records {
+            set.ttl = set.ttl.min(record.ttl);
+            set.rdatas.push(record.rdata.clone());
+        }
+        Some(set)
+    }
+
+    /// Expands the set into its individual records.
+    pub fn records(&self) -> Vec<ResourceRecord> {
+        self.rdatas
+            .iter()
+            .map(|rdata| ResourceRecord {
+                name: self.name.clone(),
+                r_type: self.r_type,
+                r_class: self.r_class,
+                ttl: self.ttl,
+                rdata: rdata.clone(),
+            })
+            .collect()
+    }
+
+    /// Returns the canonical wire form of each RDATA in the set, sorted in
+    /// canonical order and without duplicates.
+    ///
+    /// For more details, see [RFC 4034, Section 6.3].
+    ///
+    /// [RFC 4034, Section 6.3]: https://datatracker.ietf.org/doc/html/rfc4034#section-6.3
+    pub fn canonical_rdatas(&self) -> Vec<Vec<u8>> {
+        let mut rdatas: Vec<Vec<u8>> = self.rdatas.iter().map(RData::to_canonical_bytes).collect();
+        rdatas.sort();
+        rdatas.dedup();
+        rdatas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::{KnownQClass, KnownQType};
+    use std::net::Ipv4Addr;
+
+    fn a_record(ttl: u32, last_octet: u8) -> ResourceRecord {
+        ResourceRecord {
+            name: Domain::from_presentation("example.com").unwrap(),
+            r_type: KnownQType::A.into(),
+            r_class: KnownQClass::IN.into(),
+            ttl,
+            rdata: RData::A(Ipv4Addr::new(192, 0, 2, last_octet)),
+        }
+    }
+
+    #[test]
+    fn record_set_from_records_uses_lowest_ttl() {
+        let records = [a_record(300, 1), a_record(60, 2)];
+        let set = RecordSet::from_records(&records).unwrap();
+        assert_eq!(set.ttl, 60);
+        assert_eq!(set.rdatas.len(), 2);
+        assert!(set.records().iter().all(|r| r.ttl == 60));
+    }
+
+    #[test]
+    fn record_set_from_no_records() {
+        assert_eq!(RecordSet::from_records(&[]), None);
+    }
+
+    #[test]
+    fn record_set_canonical_rdatas_are_sorted_and_unique() {
+        let records = [a_record(60, 9), a_record(60, 1), a_record(60, 9)];
+        let set = RecordSet::from_records(&records).unwrap();
+        assert_eq!(
+            set.canonical_rdatas(),
+            vec![vec![192, 0, 2, 1], vec![192, 0, 2, 9]]
+        );
+    }
+}
@@ -0,0 +1,182 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::Domain;
+use crate::message::error::WireError;
+use crate::message::reader::Reader;
+use crate::message::{QClass, QType};
+use crate::record::RData;
+use std::fmt;
+
+/// Resource record, the unit of data stored in zones and carried in the
+/// answer, authority and additional sections of a DNS `Message`.
+///
+/// For more details, see [RFC 1035, Section 4.1.3].
+///
+/// [RFC 1035, Section 4.1.3]: https://datatracker.ietf.org/doc/html/rfc1035#section-4.1.3
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceRecord {
+    pub name: Domain,
+    pub r_type: QType,
+    pub r_class: QClass,
+    pub ttl: u32,
+    pub rdata: RData,
+}
+
+impl ResourceRecord {
+    /// Encodes the record in uncompressed wire format.
+    ///
+    /// ```text
+    ///                                 1  1  1  1  1  1
+    ///   0  1  2  3  4  5  6  7  8  9  0  1  2  3  4  5
+    /// +--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+
+    /// |                                               |
+    /// /                      NAME                     /
+    /// |                                               |
+    /// +--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+
+    /// |                      TYPE                     |
+    /// +--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+
+    /// |                     CLASS                     |
+    /// +--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+
+    /// |                      TTL                      |
+    /// |                                               |
+    /// +--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+
+    /// |                   RDLENGTH                    |
+    /// +--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--|
+    /// /                     RDATA                     /
+    /// /                                               /
+    /// +--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode_record(&self.name.to_bytes(), self, self.ttl, self.rdata.to_bytes())
+    }
+
+    /// Encodes the record in the canonical form used to compute and verify
+    /// DNSSEC signatures, using `original_ttl` in place of the record TTL.
+    ///
+    /// For more details, see [RFC 4034, Section 6.2].
+    ///
+    /// [RFC 4034, Section 6.2]: https://datatracker.ietf.org/doc/html/rfc4034#section-6.2
+    pub fn to_canonical_bytes(&self, original_ttl: u32) -> Vec<u8> {
+        encode_record(
+            &self.name.to_canonical_bytes(),
+            self,
+            original_ttl,
+            self.rdata.to_canonical_bytes(),
+        )
+    }
+}
+
+impl ResourceRecord {
+    /// Decodes a record from `reader`, expanding compressed names.
+    pub(crate) fn from_wire(reader: &mut Reader) -> Result<Self, WireError> {
+        let name = reader.name()?;
+        let r_type = QType::new(reader.u16()?);
+        let r_class = QClass::new(reader.u16()?);
+        let ttl = reader.u32()?;
+        let len = reader.u16()? as usize;
+        let rdata = RData::from_wire(r_type, reader, len)?;
+        Ok(ResourceRecord {
+            name,
+            r_type,
+            r_class,
+            ttl,
+            rdata,
+        })
+    }
+}
+
+fn encode_record(name: &[u8], record: &ResourceRecord, ttl: u32, rdata: Vec<u8>) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(name.len() + 10 + rdata.len());
+    bytes.extend_from_slice(name);
+    bytes.extend_from_slice(&record.r_type.value.to_be_bytes());
+    bytes.extend_from_slice(&record.r_class.value.to_be_bytes());
+    bytes.extend_from_slice(&ttl.to_be_bytes());
+    bytes.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+    bytes.extend_from_slice(&rdata);
+    bytes
+}
+
+impl fmt::Display for ResourceRecord {
+    /// Formats the record in master file format, with a fully qualified
+    /// owner name.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {}",
+            self.name.to_fqdn_string(),
+            self.ttl,
+            self.r_class,
+            self.r_type,
+            self.rdata
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::{KnownQClass, KnownQType};
+    use rstest::rstest;
+    use std::net::Ipv4Addr;
+
+    fn a_record(name: &str) -> ResourceRecord {
+        ResourceRecord {
+            name: Domain::from_presentation(name).unwrap(),
+            r_type: KnownQType::A.into(),
+            r_class: KnownQClass::IN.into(),
+            ttl: 3600,
+            rdata: RData::A(Ipv4Addr::new(192, 0, 2, 1)),
+        }
+    }
+
+    #[rstest]
+    #[case(
+        "Ex.com",
+        &[2, b'E', b'x', 3, b'c', b'o', b'm', 0, 0, 1, 0, 1, 0, 0, 14, 16, 0, 4, 192, 0, 2, 1]
+    )]
+    fn resource_record_to_bytes(#[case] name: &str, #[case] expected: &[u8]) {
+        assert_eq!(a_record(name).to_bytes(), expected);
+    }
+
+    #[rstest]
+    #[case(
+        "Ex.com",
+        &[2, b'e', b'x', 3, b'c', b'o', b'm', 0, 0, 1, 0, 1, 0, 0, 0, 60, 0, 4, 192, 0, 2, 1]
+    )]
+    fn resource_record_to_canonical_bytes(#[case] name: &str, #[case] expected: &[u8]) {
+        assert_eq!(a_record(name).to_canonical_bytes(60), expected);
+    }
+
+    #[test]
+    fn resource_record_unknown_type_round_trips() {
+        // Unknown type and class, with RDATA that looks like a compressed
+        // name and must be kept as is.
+        let bytes = [
+            1, b'a', 0, 0xff, 0x00, 0, 32, 0, 0, 0, 60, 0, 3, 0xc0, 0x00, 7,
+        ];
+        let record = ResourceRecord::from_wire(&mut Reader::new(&bytes, 0)).unwrap();
+        assert_eq!(record.rdata, RData::Unknown(vec![0xc0, 0x00, 7]));
+        assert_eq!(record.to_bytes(), bytes);
+        assert_eq!(record.to_string(), "a. 60 CLASS32 TYPE65280 \\# 3 C00007");
+    }
+
+    #[test]
+    fn resource_record_to_string() {
+        assert_eq!(
+            a_record("www.example.com").to_string(),
+            "www.example.com. 3600 IN A 192.0.2.1"
+        );
+    }
+}
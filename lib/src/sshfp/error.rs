@@ -0,0 +1,105 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub struct SshfpAlgorithmTryFromError(pub u8);
+
+impl fmt::Display for SshfpAlgorithmTryFromError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SSHFP algorithm '{}' is not supported", self.0)
+    }
+}
+
+impl Error for SshfpAlgorithmTryFromError {}
+
+#[derive(Debug, PartialEq)]
+pub struct FingerprintTypeTryFromError(pub u8);
+
+impl fmt::Display for FingerprintTypeTryFromError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SSHFP fingerprint type '{}' is not supported", self.0)
+    }
+}
+
+impl Error for FingerprintTypeTryFromError {}
+
+/// Errors found while parsing an OpenSSH public key line.
+#[derive(Debug, PartialEq)]
+pub enum HostKeyFromStrError {
+    MissingKey,
+    UnsupportedKeyType(String),
+    InvalidBase64,
+    /// The key type written before the key does not match the one encoded
+    /// in it.
+    KeyTypeMismatch(String),
+}
+
+impl fmt::Display for HostKeyFromStrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingKey => write!(f, "missing public key"),
+            Self::UnsupportedKeyType(key_type) => {
+                write!(f, "key type '{}' is not supported", key_type)
+            }
+            Self::InvalidBase64 => write!(f, "public key is not valid base64"),
+            Self::KeyTypeMismatch(key_type) => {
+                write!(f, "public key does not have type '{}'", key_type)
+            }
+        }
+    }
+}
+
+impl Error for HostKeyFromStrError {}
+
+/// Reasons why a host key is not verified by an `SSHFP` record set.
+#[derive(Debug, PartialEq)]
+pub enum SshfpError {
+    /// No record uses the algorithm of the key and a supported fingerprint
+    /// type.
+    NoUsableRecords,
+    NoMatch,
+}
+
+impl fmt::Display for SshfpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoUsableRecords => write!(f, "no usable SSHFP records"),
+            Self::NoMatch => write!(f, "no SSHFP record matches the host key"),
+        }
+    }
+}
+
+impl Error for SshfpError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(
+        HostKeyFromStrError::UnsupportedKeyType("ssh-foo".to_string()),
+        "key type 'ssh-foo' is not supported"
+    )]
+    #[case(
+        HostKeyFromStrError::KeyTypeMismatch("ssh-rsa".to_string()),
+        "public key does not have type 'ssh-rsa'"
+    )]
+    fn host_key_from_str_error_display(#[case] err: HostKeyFromStrError, #[case] msg: &str) {
+        assert_eq!(err.to_string(), msg);
+    }
+}
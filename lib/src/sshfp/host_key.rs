@@ -0,0 +1,276 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::record::rdata::Sshfp;
+use crate::record::{RData, RecordSet};
+use crate::sshfp::error::{HostKeyFromStrError, SshfpError};
+use crate::sshfp::parameters::{FingerprintType, SshfpAlgorithm};
+use data_encoding::BASE64;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::str::FromStr;
+
+/// SSH host public key, as written on a line of an OpenSSH `.pub` file:
+/// the key type, the base64 encoded key and an optional comment.
+///
+/// # Example
+/// ```
+/// use dns_lib::sshfp::{FingerprintType, HostKey};
+///
+/// let key: HostKey = "ssh-ed25519 \
+///     AAAAC3NzaC1lZDI1NTE5AAAAICKrNk7obryWhfXjZ/Z2TDs1Ltl/VXBRZYdxsbvu5h8L root@host"
+///     .parse()
+///     .unwrap();
+/// assert_eq!(
+///     key.to_sshfp(FingerprintType::Sha1).to_string(),
+///     "4 1 60F65734E9070591CBF426AC9591F99C5C86D641"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostKey {
+    algorithm: SshfpAlgorithm,
+    key_type: String,
+    key: Vec<u8>,
+    comment: Option<String>,
+}
+
+impl HostKey {
+    pub fn algorithm(&self) -> SshfpAlgorithm {
+        self.algorithm
+    }
+
+    pub fn key_type(&self) -> &str {
+        &self.key_type
+    }
+
+    /// Returns the key in the SSH wire format, which the fingerprints are
+    /// computed over.
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    pub fn fingerprint(&self, fingerprint_type: FingerprintType) -> Vec<u8> {
+        match fingerprint_type {
+            FingerprintType::Sha1 => Sha1::digest(&self.key).to_vec(),
+            FingerprintType::Sha256 => Sha256::digest(&self.key).to_vec(),
+        }
+    }
+
+    pub fn to_sshfp(&self, fingerprint_type: FingerprintType) -> Sshfp {
+        Sshfp {
+            algorithm: self.algorithm as u8,
+            fingerprint_type: fingerprint_type as u8,
+            fingerprint: self.fingerprint(fingerprint_type),
+        }
+    }
+
+    /// Returns the `SSHFP` records of the key, with a SHA-1 and a SHA-256
+    /// fingerprint.
+    pub fn to_sshfp_records(&self) -> Vec<Sshfp> {
+        vec![
+            self.to_sshfp(FingerprintType::Sha1),
+            self.to_sshfp(FingerprintType::Sha256),
+        ]
+    }
+
+    /// Verifies the key against the `SSHFP` records of the host, returning
+    /// the matching one.
+    ///
+    /// Only records with the algorithm of the key and a supported
+    /// fingerprint type are considered, see [RFC 4255, Section 3.1]. The
+    /// record set must have been validated with DNSSEC for the match to be
+    /// trusted, see [RFC 4255, Section 2.4].
+    ///
+    /// [RFC 4255, Section 2.4]: https://datatracker.ietf.org/doc/html/rfc4255#section-2.4
+    /// [RFC 4255, Section 3.1]: https://datatracker.ietf.org/doc/html/rfc4255#section-3.1
+    pub fn verify<'a>(&self, rrset: &'a RecordSet) -> Result<&'a Sshfp, SshfpError> {
+        let usable: Vec<(&Sshfp, FingerprintType)> = rrset
+            .rdatas
+            .iter()
+            .filter_map(|rdata| match rdata {
+                RData::SSHFP(sshfp) if sshfp.algorithm == self.algorithm as u8 => {
+                    let fingerprint_type =
+                        FingerprintType::try_from(sshfp.fingerprint_type).ok()?;
+                    Some((sshfp, fingerprint_type))
+                }
+                _ => None,
+            })
+            .collect();
+        if usable.is_empty() {
+            return Err(SshfpError::NoUsableRecords);
+        }
+        usable
+            .into_iter()
+            .find(|(sshfp, fingerprint_type)| {
+                sshfp.fingerprint == self.fingerprint(*fingerprint_type)
+            })
+            .map(|(sshfp, _)| sshfp)
+            .ok_or(SshfpError::NoMatch)
+    }
+}
+
+impl FromStr for HostKey {
+    type Err = HostKeyFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.split_whitespace();
+        let key_type = fields.next().ok_or(HostKeyFromStrError::MissingKey)?;
+        let algorithm = SshfpAlgorithm::from_key_type(key_type)
+            .ok_or_else(|| HostKeyFromStrError::UnsupportedKeyType(key_type.to_string()))?;
+        let encoded = fields.next().ok_or(HostKeyFromStrError::MissingKey)?;
+        let key = BASE64
+            .decode(encoded.as_bytes())
+            .map_err(|_| HostKeyFromStrError::InvalidBase64)?;
+
+        // The key starts with its type, as an SSH string with a four octet
+        // length prefix, see RFC 4253, Section 6.6.
+        let embedded = key
+            .get(..4)
+            .map(|len| u32::from_be_bytes([len[0], len[1], len[2], len[3]]) as usize)
+            .and_then(|len| key.get(4..4 + len));
+        if embedded != Some(key_type.as_bytes()) {
+            return Err(HostKeyFromStrError::KeyTypeMismatch(key_type.to_string()));
+        }
+
+        let comment: Vec<&str> = fields.collect();
+        Ok(HostKey {
+            algorithm,
+            key_type: key_type.to_string(),
+            key,
+            comment: (!comment.is_empty()).then(|| comment.join(" ")),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Domain;
+    use crate::message::{KnownQClass, KnownQType};
+    use data_encoding::HEXLOWER;
+    use rstest::rstest;
+
+    const RSA: &str = "ssh-rsa AAAAB3NzaC1yc2EAAAADAQABAAAAgQDJm4A7/y48nL7hctE+FVkMCPcAgjKvFWiRQUOgwxNrlfh8vxl72Ykg1yIfLPqKImLKiKZ8MyAmCGWZM79b5IlFai7GdH0xRkDx5lQZhGLQAy+yo8lSAFNCvrSyCNoXTUV3KoXfnS7goSi3UDGqeiSe9OtHr3Mx+dh0lBhYfGGPIQ== test@rsa";
+    const ECDSA: &str = "ecdsa-sha2-nistp256 AAAAE2VjZHNhLXNoYTItbmlzdHAyNTYAAAAIbmlzdHAyNTYAAABBBJ54GmOWMgg5O4FinQ/tU4BOhIdIQXx/VdJNr/p2rx0Z6kmT6VdKxPNfp5hllClUg94hNzV4HgCs17Xthnkiiis=";
+    const ED25519: &str = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAICKrNk7obryWhfXjZ/Z2TDs1Ltl/VXBRZYdxsbvu5h8L test@ed25519";
+
+    fn sshfp(algorithm: u8, fingerprint_type: u8, fingerprint: &str) -> Sshfp {
+        Sshfp {
+            algorithm,
+            fingerprint_type,
+            fingerprint: HEXLOWER.decode(fingerprint.as_bytes()).unwrap(),
+        }
+    }
+
+    fn rrset(records: Vec<Sshfp>) -> RecordSet {
+        RecordSet {
+            name: Domain::from_presentation("host.example.com.").unwrap(),
+            r_type: KnownQType::SSHFP.into(),
+            r_class: KnownQClass::IN.into(),
+            ttl: 3600,
+            rdatas: records.into_iter().map(RData::SSHFP).collect(),
+        }
+    }
+
+    #[rstest]
+    #[case(
+        RSA,
+        SshfpAlgorithm::Rsa,
+        Some("test@rsa"),
+        "8fdf3ca7030459ab6c84de11da499d4552c28a8a",
+        "8d444be3a1a5af4f480a4daafe7461e0e6600aa3c5ea7d75493cff379856b5bd"
+    )]
+    #[case(
+        ECDSA,
+        SshfpAlgorithm::Ecdsa,
+        None,
+        "066543c4f833c534227d3eef21d7914f923d8e34",
+        "ef350729ee0e9e4f02542d02af437a9f22e3efa214fe17db6245cea5c0dd9f41"
+    )]
+    #[case(
+        ED25519,
+        SshfpAlgorithm::Ed25519,
+        Some("test@ed25519"),
+        "60f65734e9070591cbf426ac9591f99c5c86d641",
+        "3ef2798e51b2690a7c52578c68486847e052cafd04920514c43d6e1cbc2217b4"
+    )]
+    fn host_key_fingerprints(
+        #[case] line: &str,
+        #[case] algorithm: SshfpAlgorithm,
+        #[case] comment: Option<&str>,
+        #[case] sha1: &str,
+        #[case] sha256: &str,
+    ) {
+        let key: HostKey = line.parse().unwrap();
+        assert_eq!((key.algorithm(), key.comment()), (algorithm, comment));
+        assert_eq!(
+            key.to_sshfp_records(),
+            [
+                sshfp(algorithm as u8, 1, sha1),
+                sshfp(algorithm as u8, 2, sha256)
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case("", HostKeyFromStrError::MissingKey)]
+    #[case("ssh-ed25519", HostKeyFromStrError::MissingKey)]
+    #[case(
+        "ssh-foo AAAA",
+        HostKeyFromStrError::UnsupportedKeyType("ssh-foo".to_string())
+    )]
+    #[case("ssh-ed25519 AAA!", HostKeyFromStrError::InvalidBase64)]
+    #[case(
+        "ssh-rsa AAAAC3NzaC1lZDI1NTE5AAAAICKrNk7obryWhfXjZ/Z2TDs1Ltl/VXBRZYdxsbvu5h8L",
+        HostKeyFromStrError::KeyTypeMismatch("ssh-rsa".to_string())
+    )]
+    fn host_key_from_str_fails(#[case] line: &str, #[case] expected: HostKeyFromStrError) {
+        assert_eq!(line.parse::<HostKey>(), Err(expected));
+    }
+
+    #[test]
+    fn host_key_verify() {
+        let key: HostKey = ED25519.parse().unwrap();
+        let sha256 = "3ef2798e51b2690a7c52578c68486847e052cafd04920514c43d6e1cbc2217b4";
+
+        let records = rrset(vec![
+            sshfp(
+                1,
+                2,
+                "8d444be3a1a5af4f480a4daafe7461e0e6600aa3c5ea7d75493cff379856b5bd",
+            ),
+            sshfp(4, 1, &"00".repeat(20)),
+            sshfp(4, 2, sha256),
+        ]);
+        assert_eq!(key.verify(&records), Ok(&sshfp(4, 2, sha256)));
+
+        let records = rrset(vec![sshfp(4, 2, &"00".repeat(32))]);
+        assert_eq!(key.verify(&records), Err(SshfpError::NoMatch));
+
+        // The RSA fingerprint and an unknown fingerprint type are ignored.
+        let records = rrset(vec![
+            sshfp(
+                1,
+                2,
+                "8d444be3a1a5af4f480a4daafe7461e0e6600aa3c5ea7d75493cff379856b5bd",
+            ),
+            sshfp(4, 3, sha256),
+        ]);
+        assert_eq!(key.verify(&records), Err(SshfpError::NoUsableRecords));
+    }
+}
@@ -0,0 +1,113 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::sshfp::error::{FingerprintTypeTryFromError, SshfpAlgorithmTryFromError};
+
+/// Public key algorithm of an `SSHFP` record, see [RFC 4255, Section 3.1.1],
+/// [RFC 6594, Section 3.1.1], [RFC 7479, Section 3.1.1] and
+/// [RFC 8709, Section 5].
+///
+/// [RFC 4255, Section 3.1.1]: https://datatracker.ietf.org/doc/html/rfc4255#section-3.1.1
+/// [RFC 6594, Section 3.1.1]: https://datatracker.ietf.org/doc/html/rfc6594#section-3.1.1
+/// [RFC 7479, Section 3.1.1]: https://datatracker.ietf.org/doc/html/rfc7479#section-3.1.1
+/// [RFC 8709, Section 5]: https://datatracker.ietf.org/doc/html/rfc8709#section-5
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum SshfpAlgorithm {
+    Rsa = 1,
+    Dsa = 2,
+    Ecdsa = 3,
+    Ed25519 = 4,
+    Ed448 = 6,
+}
+
+impl SshfpAlgorithm {
+    /// Returns the algorithm of an OpenSSH key type such as `ssh-ed25519`.
+    pub fn from_key_type(key_type: &str) -> Option<SshfpAlgorithm> {
+        match key_type {
+            "ssh-rsa" => Some(Self::Rsa),
+            "ssh-dss" => Some(Self::Dsa),
+            "ecdsa-sha2-nistp256" | "ecdsa-sha2-nistp384" | "ecdsa-sha2-nistp521" => {
+                Some(Self::Ecdsa)
+            }
+            "ssh-ed25519" => Some(Self::Ed25519),
+            "ssh-ed448" => Some(Self::Ed448),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<u8> for SshfpAlgorithm {
+    type Error = SshfpAlgorithmTryFromError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Self::Rsa),
+            2 => Ok(Self::Dsa),
+            3 => Ok(Self::Ecdsa),
+            4 => Ok(Self::Ed25519),
+            6 => Ok(Self::Ed448),
+            unsupported => Err(SshfpAlgorithmTryFromError(unsupported)),
+        }
+    }
+}
+
+/// Hash function used to compute the fingerprint of an `SSHFP` record, see
+/// [RFC 4255, Section 3.1.2] and [RFC 6594, Section 3.1.2].
+///
+/// [RFC 4255, Section 3.1.2]: https://datatracker.ietf.org/doc/html/rfc4255#section-3.1.2
+/// [RFC 6594, Section 3.1.2]: https://datatracker.ietf.org/doc/html/rfc6594#section-3.1.2
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum FingerprintType {
+    Sha1 = 1,
+    Sha256 = 2,
+}
+
+impl TryFrom<u8> for FingerprintType {
+    type Error = FingerprintTypeTryFromError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Self::Sha1),
+            2 => Ok(Self::Sha256),
+            unsupported => Err(FingerprintTypeTryFromError(unsupported)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(3, Ok(SshfpAlgorithm::Ecdsa))]
+    #[case(6, Ok(SshfpAlgorithm::Ed448))]
+    #[case(5, Err(SshfpAlgorithmTryFromError(5)))]
+    fn sshfp_algorithm_try_from(
+        #[case] value: u8,
+        #[case] expected: Result<SshfpAlgorithm, SshfpAlgorithmTryFromError>,
+    ) {
+        assert_eq!(SshfpAlgorithm::try_from(value), expected);
+    }
+
+    #[rstest]
+    #[case(2, Ok(FingerprintType::Sha256))]
+    #[case(0, Err(FingerprintTypeTryFromError(0)))]
+    fn fingerprint_type_try_from(
+        #[case] value: u8,
+        #[case] expected: Result<FingerprintType, FingerprintTypeTryFromError>,
+    ) {
+        assert_eq!(FingerprintType::try_from(value), expected);
+    }
+}
@@ -0,0 +1,128 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::Domain;
+use crate::message::error::MessageTryFromError;
+use crate::message::RCode;
+use crate::transport::DoqErrorCode;
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum TransportError {
+    Io(io::Error),
+    /// No valid response arrived after the given number of attempts.
+    Timeout(u32),
+    /// The server answered with an error `RCODE`.
+    Rejected(RCode),
+    /// The server closed the connection before answering.
+    Closed,
+    /// The message has the given length, more than fits in the two octet
+    /// length prefix of stream transports.
+    TooLong(usize),
+    /// The server sent a message that could not be decoded.
+    Malformed(MessageTryFromError),
+    /// The TLS handshake failed, or the server could not be authenticated.
+    Tls(rustls::Error),
+    /// The name cannot be used to authenticate a TLS server.
+    InvalidServerName(Domain),
+    /// The HTTP exchange failed, or the server answered with something
+    /// other than a DNS message.
+    Http(String),
+    /// The server answered with an HTTP status other than 200 OK.
+    HttpStatus(u16),
+    /// The QUIC connection failed.
+    Quic(String),
+    /// The QUIC connection was closed, or the stream of the query aborted,
+    /// with a DNS over QUIC error code.
+    Doq(DoqErrorCode),
+}
+
+impl From<io::Error> for TransportError {
+    fn from(error: io::Error) -> TransportError {
+        TransportError::Io(error)
+    }
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(e) => e.fmt(f),
+            Self::Timeout(attempts) => write!(f, "no response after {} attempts", attempts),
+            Self::Rejected(r_code) => write!(f, "server answered with RCODE {:?}", r_code),
+            Self::Closed => write!(f, "connection closed by the server"),
+            Self::TooLong(len) => write!(
+                f,
+                "message has {} octets, more than the maximum of 65535",
+                len
+            ),
+            Self::Malformed(e) => write!(f, "malformed response: {}", e),
+            Self::Tls(e) => write!(f, "TLS error: {}", e),
+            Self::InvalidServerName(name) => write!(
+                f,
+                "{} is not a valid TLS server name",
+                name.to_fqdn_string()
+            ),
+            Self::Http(e) => write!(f, "HTTP error: {}", e),
+            Self::HttpStatus(status) => write!(f, "server answered with HTTP status {}", status),
+            Self::Quic(e) => write!(f, "QUIC error: {}", e),
+            Self::Doq(code) => write!(f, "DNS over QUIC error {}", code),
+        }
+    }
+}
+
+impl Error for TransportError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Malformed(e) => Some(e),
+            Self::Tls(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(TransportError::Timeout(3), "no response after 3 attempts")]
+    #[case(
+        TransportError::Rejected(RCode::Refused),
+        "server answered with RCODE Refused"
+    )]
+    #[case(TransportError::Closed, "connection closed by the server")]
+    #[case(
+        TransportError::TooLong(70000),
+        "message has 70000 octets, more than the maximum of 65535"
+    )]
+    #[case(
+        TransportError::HttpStatus(415),
+        "server answered with HTTP status 415"
+    )]
+    #[case(
+        TransportError::Doq(DoqErrorCode::ProtocolError),
+        "DNS over QUIC error DOQ_PROTOCOL_ERROR"
+    )]
+    #[case(
+        TransportError::Doq(DoqErrorCode::Unknown(0xd098ea5e)),
+        "DNS over QUIC error 0xd098ea5e"
+    )]
+    fn transport_error_display(#[case] err: TransportError, #[case] msg: &str) {
+        assert_eq!(err.to_string(), msg);
+    }
+}
@@ -0,0 +1,687 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::message::Message;
+use crate::record::RData;
+use crate::transport::error::TransportError;
+use crate::transport::tls::tls_error;
+use crate::transport::Response;
+use bytes::Bytes;
+use data_encoding::BASE64URL_NOPAD;
+use h2::client::SendRequest;
+use http::header::{ACCEPT, AGE, CACHE_CONTROL, CONTENT_LENGTH, CONTENT_TYPE};
+use http::{HeaderMap, Method, Request, StatusCode, Uri};
+use rustls::pki_types::ServerName;
+use rustls::{ClientConfig, RootCertStore};
+use std::collections::HashMap;
+use std::fmt;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::runtime::Runtime;
+use tokio_rustls::TlsConnector;
+
+/// Media type of DNS messages exchanged over HTTPS, see
+/// [RFC 8484, Section 6].
+///
+/// [RFC 8484, Section 6]: https://datatracker.ietf.org/doc/html/rfc8484#section-6
+pub const DNS_MESSAGE: &str = "application/dns-message";
+
+/// HTTP method used to send queries, see [RFC 8484, Section 4.1].
+///
+/// [RFC 8484, Section 4.1]: https://datatracker.ietf.org/doc/html/rfc8484#section-4.1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DohMethod {
+    /// The query is sent base64url encoded in the `dns` parameter of the
+    /// URI, so that responses can be cached by HTTP caches.
+    Get,
+    /// The query is sent as the request body.
+    Post,
+}
+
+/// Computes the freshness lifetime of an HTTP response carrying
+/// `response`, which must not outlive any of its records: the smallest TTL
+/// of the answer section or, for negative answers, the TTL of the `SOA`
+/// record of the authority section capped by its `MINIMUM` field. Returns
+/// `None` if the response has neither.
+///
+/// For more details, see [RFC 8484, Section 5.1] and
+/// [RFC 2308, Section 5].
+///
+/// [RFC 8484, Section 5.1]: https://datatracker.ietf.org/doc/html/rfc8484#section-5.1
+/// [RFC 2308, Section 5]: https://datatracker.ietf.org/doc/html/rfc2308#section-5
+pub fn max_age(response: &Message) -> Option<u32> {
+    if !response.answers.is_empty() {
+        return response.answers.iter().map(|record| record.ttl).min();
+    }
+    response
+        .authorities
+        .iter()
+        .filter_map(|record| match &record.rdata {
+            RData::SOA(soa) => Some(record.ttl.min(soa.minimum)),
+            _ => None,
+        })
+        .min()
+}
+
+/// Sends queries over HTTPS, as described in [RFC 8484].
+///
+/// All queries share a single HTTP/2 connection, opened on the first query
+/// and reopened if the server closes it, see [RFC 8484, Section 5.2].
+/// Responses to `GET` requests are cached for as long as their
+/// `Cache-Control` header allows, and the TTLs of their records are
+/// decreased by their age, see [RFC 8484, Section 5.1].
+///
+/// [RFC 8484]: https://datatracker.ietf.org/doc/html/rfc8484
+/// [RFC 8484, Section 5.1]: https://datatracker.ietf.org/doc/html/rfc8484#section-5.1
+/// [RFC 8484, Section 5.2]: https://datatracker.ietf.org/doc/html/rfc8484#section-5.2
+pub struct DohClient {
+    /// Time to wait for each exchange, including opening the connection.
+    pub timeout: Duration,
+    /// Method used to send queries.
+    pub method: DohMethod,
+    uri: Uri,
+    server_name: ServerName<'static>,
+    connector: TlsConnector,
+    runtime: Runtime,
+    sender: Option<SendRequest<Bytes>>,
+    cache: HashMap<Uri, CachedResponse>,
+}
+
+impl fmt::Debug for DohClient {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DohClient")
+            .field("timeout", &self.timeout)
+            .field("method", &self.method)
+            .field("uri", &self.uri)
+            .finish_non_exhaustive()
+    }
+}
+
+impl DohClient {
+    /// Creates a client sending queries to `uri`, such as
+    /// `https://dns.example/dns-query`, and checking the certificate of the
+    /// server against the certificate authorities of `roots`.
+    pub fn new(uri: &str, roots: RootCertStore) -> Result<Self, TransportError> {
+        let uri: Uri = uri
+            .parse()
+            .map_err(|e: http::uri::InvalidUri| TransportError::Http(e.to_string()))?;
+        let host = match (uri.scheme_str(), uri.host()) {
+            (Some("https"), Some(host)) => host.trim_start_matches('[').trim_end_matches(']'),
+            _ => return Err(TransportError::Http(format!("{} is not an https URI", uri))),
+        };
+        let server_name = ServerName::try_from(host.to_string())
+            .map_err(|e| TransportError::Http(e.to_string()))?;
+
+        let mut config =
+            ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()
+                .map_err(TransportError::Tls)?
+                .with_root_certificates(roots)
+                .with_no_client_auth();
+        config.alpn_protocols = vec![b"h2".to_vec()];
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_io()
+            .enable_time()
+            .build()?;
+        Ok(DohClient {
+            timeout: Duration::from_secs(5),
+            method: DohMethod::Get,
+            uri,
+            server_name,
+            connector: TlsConnector::from(Arc::new(config)),
+            runtime,
+            sender: None,
+            cache: HashMap::new(),
+        })
+    }
+
+    /// Creates a client trusting the certificate authorities of the Mozilla
+    /// root program.
+    pub fn with_webpki_roots(uri: &str) -> Result<Self, TransportError> {
+        let roots = RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+        Self::new(uri, roots)
+    }
+
+    pub fn uri(&self) -> &Uri {
+        &self.uri
+    }
+
+    /// Sends `query` to `server`, the address the host of the URI resolves
+    /// to, and returns the response whatever its `RCODE`.
+    ///
+    /// The identifier of the query is set to 0, which makes identical
+    /// queries share cached responses, see [RFC 8484, Section 4.1].
+    ///
+    /// [RFC 8484, Section 4.1]: https://datatracker.ietf.org/doc/html/rfc8484#section-4.1
+    pub fn query(
+        &mut self,
+        query: &Message,
+        server: SocketAddr,
+    ) -> Result<Response, TransportError> {
+        let mut query = query.clone();
+        query.header.id = 0;
+        let bytes = query.to_bytes();
+
+        let (uri, body) = match self.method {
+            DohMethod::Get => (self.get_uri(&bytes)?, None),
+            DohMethod::Post => (self.uri.clone(), Some(Bytes::from(bytes))),
+        };
+        let now = Instant::now();
+        self.cache.retain(|_, cached| cached.expires > now);
+        if let Some(cached) = self.cache.get(&uri) {
+            return Ok(Response {
+                message: cached.aged(now),
+                rtt: Duration::ZERO,
+            });
+        }
+
+        let mut request = Request::builder()
+            .uri(uri.clone())
+            .header(ACCEPT, DNS_MESSAGE);
+        request = match &body {
+            Some(body) => request
+                .method(Method::POST)
+                .header(CONTENT_TYPE, DNS_MESSAGE)
+                .header(CONTENT_LENGTH, body.len()),
+            None => request.method(Method::GET),
+        };
+        let request = request
+            .body(())
+            .map_err(|e| TransportError::Http(e.to_string()))?;
+
+        let timeout = self.timeout;
+        let cacheable = body.is_none();
+        let exchange = exchange(
+            &mut self.sender,
+            &self.connector,
+            self.server_name.clone(),
+            server,
+            request,
+            body,
+        );
+        let (headers, bytes) = self
+            .runtime
+            .block_on(async { tokio::time::timeout(timeout, exchange).await })
+            .map_err(|_| TransportError::Timeout(1))??;
+        let rtt = now.elapsed();
+
+        let mut message = Message::try_from(bytes.as_slice()).map_err(TransportError::Malformed)?;
+        if !query.is_answered_by(&message) {
+            return Err(TransportError::Http(
+                "response does not answer the query".to_string(),
+            ));
+        }
+        let age = header_seconds(&headers, AGE).unwrap_or(0);
+        decrease_ttls(&mut message, age);
+
+        if let (Some(max_age), Some(ttl)) = (cache_max_age(&headers), max_age(&message)) {
+            let fresh = max_age.saturating_sub(age).min(ttl);
+            if cacheable && fresh > 0 {
+                self.cache.insert(
+                    uri,
+                    CachedResponse {
+                        message: message.clone(),
+                        stored: now,
+                        expires: now + Duration::from_secs(fresh.into()),
+                    },
+                );
+            }
+        }
+        Ok(Response { message, rtt })
+    }
+
+    /// Builds the URI of a `GET` request for the query `bytes`, adding the
+    /// `dns` parameter to those of the URI template.
+    fn get_uri(&self, bytes: &[u8]) -> Result<Uri, TransportError> {
+        let encoded = BASE64URL_NOPAD.encode(bytes);
+        let path_and_query = match self.uri.query() {
+            Some(query) => format!("{}?{}&dns={}", self.uri.path(), query, encoded),
+            None => format!("{}?dns={}", self.uri.path(), encoded),
+        };
+        let mut parts = self.uri.clone().into_parts();
+        parts.path_and_query = Some(
+            path_and_query
+                .parse()
+                .map_err(|e: http::uri::InvalidUri| TransportError::Http(e.to_string()))?,
+        );
+        Uri::from_parts(parts).map_err(|e| TransportError::Http(e.to_string()))
+    }
+}
+
+/// Response kept until its freshness lifetime runs out.
+#[derive(Debug)]
+struct CachedResponse {
+    message: Message,
+    stored: Instant,
+    expires: Instant,
+}
+
+impl CachedResponse {
+    /// Returns the message with the TTLs of its records decreased by the
+    /// time spent in the cache.
+    fn aged(&self, now: Instant) -> Message {
+        let mut message = self.message.clone();
+        let elapsed = now.duration_since(self.stored).as_secs();
+        decrease_ttls(&mut message, elapsed.try_into().unwrap_or(u32::MAX));
+        message
+    }
+}
+
+/// Sends `request` over the connection of `sender`, opening a new one if
+/// there is none or the server closed it, and returns the headers and body
+/// of a successful response.
+async fn exchange(
+    sender: &mut Option<SendRequest<Bytes>>,
+    connector: &TlsConnector,
+    server_name: ServerName<'static>,
+    server: SocketAddr,
+    request: Request<()>,
+    body: Option<Bytes>,
+) -> Result<(HeaderMap, Vec<u8>), TransportError> {
+    let ready = match sender.take() {
+        Some(sender) => sender.ready().await.ok(),
+        None => None,
+    };
+    let mut ready = match ready {
+        Some(ready) => ready,
+        None => connect(connector, server_name, server).await?,
+    };
+    let (response, mut stream) = ready
+        .send_request(request, body.is_none())
+        .map_err(http_error)?;
+    *sender = Some(ready);
+    if let Some(body) = body {
+        stream.send_data(body, true).map_err(http_error)?;
+    }
+
+    let (parts, mut body) = response.await.map_err(http_error)?.into_parts();
+    if parts.status != StatusCode::OK {
+        return Err(TransportError::HttpStatus(parts.status.as_u16()));
+    }
+    if !is_dns_message(&parts.headers) {
+        return Err(TransportError::Http(
+            "response is not an application/dns-message".to_string(),
+        ));
+    }
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(http_error)?;
+        if bytes.len() + chunk.len() > u16::MAX as usize {
+            return Err(TransportError::TooLong(bytes.len() + chunk.len()));
+        }
+        body.flow_control()
+            .release_capacity(chunk.len())
+            .map_err(http_error)?;
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok((parts.headers, bytes))
+}
+
+/// Opens an HTTP/2 connection to `server`, which must negotiate `h2`
+/// during the TLS handshake, see [RFC 8484, Section 5.2].
+///
+/// [RFC 8484, Section 5.2]: https://datatracker.ietf.org/doc/html/rfc8484#section-5.2
+async fn connect(
+    connector: &TlsConnector,
+    server_name: ServerName<'static>,
+    server: SocketAddr,
+) -> Result<SendRequest<Bytes>, TransportError> {
+    let socket = tokio::net::TcpStream::connect(server).await?;
+    socket.set_nodelay(true)?;
+    let stream = connector
+        .connect(server_name, socket)
+        .await
+        .map_err(tls_error)?;
+    if stream.get_ref().1.alpn_protocol() != Some(b"h2") {
+        return Err(TransportError::Http(
+            "server does not support HTTP/2".to_string(),
+        ));
+    }
+    let (sender, connection) = h2::client::handshake(stream).await.map_err(http_error)?;
+    tokio::spawn(connection);
+    Ok(sender)
+}
+
+fn http_error(error: h2::Error) -> TransportError {
+    match error.is_io() {
+        true => TransportError::Io(error.into_io().unwrap()),
+        false => TransportError::Http(error.to_string()),
+    }
+}
+
+/// Checks if the media type of the `Content-Type` header is
+/// `application/dns-message`, ignoring its parameters, see
+/// [RFC 9110, Section 8.3.1].
+///
+/// [RFC 9110, Section 8.3.1]: https://datatracker.ietf.org/doc/html/rfc9110#section-8.3.1
+fn is_dns_message(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .is_some_and(|media_type| media_type.trim().eq_ignore_ascii_case(DNS_MESSAGE))
+}
+
+/// Parses the `max-age` directive of the `Cache-Control` header, returning
+/// `None` if the response must not be cached, see [RFC 9111, Section 5.2.2].
+///
+/// [RFC 9111, Section 5.2.2]: https://datatracker.ietf.org/doc/html/rfc9111#section-5.2.2
+fn cache_max_age(headers: &HeaderMap) -> Option<u32> {
+    let mut max_age = None;
+    for directive in headers
+        .get_all(CACHE_CONTROL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+    {
+        let directive = directive.trim().to_ascii_lowercase();
+        match directive.split_once('=') {
+            Some(("max-age", seconds)) => max_age = seconds.trim_matches('"').parse().ok(),
+            _ if directive == "no-store" || directive == "no-cache" => return None,
+            _ => {}
+        }
+    }
+    max_age
+}
+
+fn header_seconds(headers: &HeaderMap, name: http::header::HeaderName) -> Option<u32> {
+    headers.get(name)?.to_str().ok()?.trim().parse().ok()
+}
+
+/// Decreases the TTLs of the records of `message` by `seconds`, leaving
+/// the `OPT` record alone since its TTL field holds flags.
+fn decrease_ttls(message: &mut Message, seconds: u32) {
+    for record in message
+        .answers
+        .iter_mut()
+        .chain(&mut message.authorities)
+        .chain(&mut message.additionals)
+        .filter(|record| !matches!(record.rdata, RData::OPT(_)))
+    {
+        record.ttl = record.ttl.saturating_sub(seconds);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Domain;
+    use crate::message::{Header, KnownQClass, KnownQType, Question, QR};
+    use crate::record::rdata::Soa;
+    use crate::record::ResourceRecord;
+    use rstest::rstest;
+    use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+    use rustls::ServerConfig;
+    use std::net::Ipv4Addr;
+    use std::thread;
+    use tokio_rustls::TlsAcceptor;
+
+    /// Request as seen by the server.
+    #[derive(Debug)]
+    struct Seen {
+        method: Method,
+        uri: Uri,
+        content_type: Option<String>,
+        query: Message,
+    }
+
+    struct Server {
+        addr: SocketAddr,
+        certificate: CertificateDer<'static>,
+        handle: thread::JoinHandle<Vec<Seen>>,
+    }
+
+    /// Stands in for a DNS over HTTPS server with a self-signed certificate
+    /// for `dns.example`: accepts a single connection and answers
+    /// `requests` requests on it with `status`, an `A` record with a TTL of
+    /// 300 and `Age: 100`, returning the requests received.
+    fn serve(requests: usize, status: StatusCode) -> Server {
+        let key = rcgen::generate_simple_self_signed(vec!["dns.example".to_string()]).unwrap();
+        let certificate = key.cert.der().clone();
+        let private_key =
+            PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key.key_pair.serialize_der()));
+        let mut config =
+            ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()
+                .unwrap()
+                .with_no_client_auth()
+                .with_single_cert(vec![certificate.clone()], private_key)
+                .unwrap();
+        config.alpn_protocols = vec![b"h2".to_vec()];
+        let acceptor = TlsAcceptor::from(Arc::new(config));
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handle = thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            runtime.block_on(async move {
+                let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+                let socket = listener.accept().await.unwrap().0;
+                let stream = acceptor.accept(socket).await.unwrap();
+                let mut connection = h2::server::handshake(stream).await.unwrap();
+                let mut seen = Vec::new();
+                while seen.len() < requests {
+                    let (request, mut respond) = connection.accept().await.unwrap().unwrap();
+                    let (parts, mut body) = request.into_parts();
+                    let bytes = match parts.method {
+                        Method::GET => {
+                            let encoded = parts.uri.query().unwrap().strip_prefix("dns=").unwrap();
+                            BASE64URL_NOPAD.decode(encoded.as_bytes()).unwrap()
+                        }
+                        _ => {
+                            let mut bytes = Vec::new();
+                            while let Some(chunk) = body.data().await {
+                                bytes.extend_from_slice(&chunk.unwrap());
+                            }
+                            bytes
+                        }
+                    };
+                    let query = Message::try_from(bytes.as_slice()).unwrap();
+
+                    let mut response = query.clone();
+                    response.header.qr = QR::Response;
+                    response.answers.push(ResourceRecord {
+                        name: query.questions[0].q_name.clone(),
+                        r_type: KnownQType::A.into(),
+                        r_class: KnownQClass::IN.into(),
+                        ttl: 300,
+                        rdata: RData::A(Ipv4Addr::new(192, 0, 2, 1)),
+                    });
+                    let head = http::Response::builder()
+                        .status(status)
+                        .header(CONTENT_TYPE, DNS_MESSAGE)
+                        .header(
+                            CACHE_CONTROL,
+                            format!("max-age={}", max_age(&response).unwrap()),
+                        )
+                        .header(AGE, "100")
+                        .body(())
+                        .unwrap();
+                    let mut send = respond.send_response(head, false).unwrap();
+                    send.send_data(Bytes::from(response.to_bytes()), true)
+                        .unwrap();
+
+                    seen.push(Seen {
+                        method: parts.method,
+                        uri: parts.uri,
+                        content_type: parts
+                            .headers
+                            .get(CONTENT_TYPE)
+                            .map(|value| value.to_str().unwrap().to_string()),
+                        query,
+                    });
+                }
+                // Keeps driving the connection until the client is done
+                // reading the last response.
+                let closed = std::future::poll_fn(|cx| connection.poll_closed(cx));
+                let _ = tokio::time::timeout(Duration::from_millis(500), closed).await;
+                seen
+            })
+        });
+        Server {
+            addr,
+            certificate,
+            handle,
+        }
+    }
+
+    fn query(name: &str) -> Message {
+        let mut message = Message::new(Header::new(0xbeef));
+        message.questions.push(Question {
+            q_name: Domain::from_presentation(name).unwrap(),
+            q_type: KnownQType::A.into(),
+            q_class: KnownQClass::IN.into(),
+        });
+        message
+    }
+
+    fn client(server: &Server) -> DohClient {
+        let mut roots = RootCertStore::empty();
+        roots.add(server.certificate.clone()).unwrap();
+        let mut client = DohClient::new("https://dns.example/dns-query", roots).unwrap();
+        client.timeout = Duration::from_millis(500);
+        client
+    }
+
+    #[test]
+    fn get_and_post_share_a_connection() {
+        let server = serve(2, StatusCode::OK);
+        let mut client = client(&server);
+
+        let response = client.query(&query("example.com"), server.addr).unwrap();
+        assert_eq!(response.message.header.id, 0);
+        assert_eq!(response.message.answers[0].ttl, 200);
+        client.method = DohMethod::Post;
+        let response = client.query(&query("example.org"), server.addr).unwrap();
+        assert_eq!(response.message.questions, query("example.org").questions);
+
+        let seen = server.handle.join().unwrap();
+        assert_eq!(seen[0].method, Method::GET);
+        assert_eq!(seen[0].uri.path(), "/dns-query");
+        assert_eq!(seen[0].query.header.id, 0);
+        assert_eq!(seen[0].query.questions, query("example.com").questions);
+        assert_eq!(seen[1].method, Method::POST);
+        assert_eq!(seen[1].content_type.as_deref(), Some(DNS_MESSAGE));
+        assert_eq!(seen[1].query.header.id, 0);
+    }
+
+    #[test]
+    fn get_responses_are_cached() {
+        let server = serve(1, StatusCode::OK);
+        let mut client = client(&server);
+
+        let first = client.query(&query("example.com"), server.addr).unwrap();
+        let cached = client.query(&query("example.com"), server.addr).unwrap();
+        assert_eq!(cached.rtt, Duration::ZERO);
+        assert_eq!(cached.message, first.message);
+        assert_eq!(server.handle.join().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn error_status_is_reported() {
+        let server = serve(1, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        let mut client = client(&server);
+        assert!(matches!(
+            client.query(&query("example.com"), server.addr),
+            Err(TransportError::HttpStatus(415))
+        ));
+        server.handle.join().unwrap();
+    }
+
+    #[test]
+    fn uri_must_be_https() {
+        assert!(matches!(
+            DohClient::new("http://dns.example/dns-query", RootCertStore::empty()),
+            Err(TransportError::Http(_))
+        ));
+    }
+
+    fn record(ttl: u32, rdata: RData) -> ResourceRecord {
+        ResourceRecord {
+            name: Domain::from_presentation("example.com").unwrap(),
+            r_type: KnownQType::A.into(),
+            r_class: KnownQClass::IN.into(),
+            ttl,
+            rdata,
+        }
+    }
+
+    fn soa(ttl: u32, minimum: u32) -> ResourceRecord {
+        record(
+            ttl,
+            RData::SOA(Soa {
+                m_name: Domain::from_presentation("ns.example.com").unwrap(),
+                r_name: Domain::from_presentation("admin.example.com").unwrap(),
+                serial: 1,
+                refresh: 3600,
+                retry: 600,
+                expire: 86400,
+                minimum,
+            }),
+        )
+    }
+
+    #[rstest]
+    #[case(vec![300, 60, 3600], vec![], Some(60))]
+    #[case(vec![], vec![soa(3600, 900)], Some(900))]
+    #[case(vec![], vec![soa(600, 900)], Some(600))]
+    #[case(vec![], vec![], None)]
+    fn max_age_is_the_minimum_ttl(
+        #[case] answers: Vec<u32>,
+        #[case] authorities: Vec<ResourceRecord>,
+        #[case] expected: Option<u32>,
+    ) {
+        let mut message = Message::new(Header::new(0));
+        message.answers = answers
+            .into_iter()
+            .map(|ttl| record(ttl, RData::A(Ipv4Addr::LOCALHOST)))
+            .collect();
+        message.authorities = authorities;
+        assert_eq!(max_age(&message), expected);
+    }
+
+    #[rstest]
+    #[case(Some("application/dns-message"), true)]
+    #[case(Some("Application/DNS-Message"), true)]
+    #[case(Some("application/dns-message; charset=utf-8"), true)]
+    #[case(Some("application/dns-message;q=1"), true)]
+    #[case(Some("application/dns-json"), false)]
+    #[case(Some("text/html; application/dns-message"), false)]
+    #[case(None, false)]
+    fn content_type_is_dns_message(#[case] value: Option<&str>, #[case] expected: bool) {
+        let mut headers = HeaderMap::new();
+        if let Some(value) = value {
+            headers.insert(CONTENT_TYPE, value.parse().unwrap());
+        }
+        assert_eq!(is_dns_message(&headers), expected);
+    }
+
+    #[rstest]
+    #[case("max-age=300", Some(300))]
+    #[case("public, max-age=60", Some(60))]
+    #[case("max-age=300, no-store", None)]
+    #[case("public", None)]
+    fn cache_control_max_age(#[case] value: &str, #[case] expected: Option<u32>) {
+        let mut headers = HeaderMap::new();
+        headers.insert(CACHE_CONTROL, value.parse().unwrap());
+        assert_eq!(cache_max_age(&headers), expected);
+    }
+}
@@ -0,0 +1,32 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub mod error;
+mod https;
+mod notify;
+mod quic;
+mod response;
+mod stub;
+mod tcp;
+mod tls;
+mod udp;
+
+pub use https::{max_age, DohClient, DohMethod, DNS_MESSAGE};
+pub use notify::NotifySender;
+pub use quic::{DoqClient, DoqErrorCode};
+pub use response::Response;
+pub use stub::StubClient;
+pub use tcp::{Stream, StreamConnection, TcpClient, TcpConnection};
+pub use tls::{spki_pin, Authentication, DotClient, PrivacyProfile, TlsConnection, TlsStream};
+pub use udp::UdpClient;
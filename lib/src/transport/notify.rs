@@ -0,0 +1,179 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::message::{Message, NotifyMessage, RCode};
+use crate::transport::error::TransportError;
+use crate::transport::udp::is_timeout;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+
+/// Sends `NOTIFY` messages over UDP, retransmitting them until they are
+/// acknowledged.
+///
+/// Datagrams from other addresses and responses that do not answer the
+/// notification are ignored.
+///
+/// For more details, see [RFC 1996, Section 3.6].
+///
+/// [RFC 1996, Section 3.6]: https://datatracker.ietf.org/doc/html/rfc1996#section-3.6
+#[derive(Debug, Clone)]
+pub struct NotifySender {
+    /// Time to wait for an acknowledgement after each transmission.
+    pub timeout: Duration,
+    /// Number of transmissions before giving up.
+    pub attempts: u32,
+}
+
+impl Default for NotifySender {
+    fn default() -> Self {
+        NotifySender {
+            timeout: Duration::from_secs(2),
+            attempts: 5,
+        }
+    }
+}
+
+impl NotifySender {
+    /// Sends `notify` to `server`, returning the acknowledgement.
+    pub fn send(
+        &self,
+        notify: &NotifyMessage,
+        server: SocketAddr,
+    ) -> Result<Message, TransportError> {
+        let local: SocketAddr = match server {
+            SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+            SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+        };
+        let socket = UdpSocket::bind(local)?;
+        let request = notify.to_bytes();
+        let mut buffer = [0; 65535];
+
+        for _ in 0..self.attempts {
+            socket.send_to(&request, server)?;
+            let deadline = Instant::now() + self.timeout;
+            while let Some(remaining) = deadline
+                .checked_duration_since(Instant::now())
+                .filter(|remaining| !remaining.is_zero())
+            {
+                socket.set_read_timeout(Some(remaining))?;
+                let (len, from) = match socket.recv_from(&mut buffer) {
+                    Ok(received) => received,
+                    Err(e) if is_timeout(&e) => break,
+                    Err(e) => return Err(e.into()),
+                };
+                if from != server {
+                    continue;
+                }
+                let Ok(response) = Message::try_from(&buffer[..len]) else {
+                    continue;
+                };
+                if !notify.is_answered_by(&response) {
+                    continue;
+                }
+                return match response.header.r_code {
+                    RCode::NoError => Ok(response),
+                    r_code => Err(TransportError::Rejected(r_code)),
+                };
+            }
+        }
+        Err(TransportError::Timeout(self.attempts))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Domain;
+    use crate::message::KnownQClass;
+    use std::thread;
+
+    fn notify() -> NotifyMessage {
+        NotifyMessage::new(
+            4242,
+            Domain::from_presentation("example.com").unwrap(),
+            KnownQClass::IN.into(),
+        )
+    }
+
+    fn sender() -> NotifySender {
+        NotifySender {
+            timeout: Duration::from_millis(200),
+            attempts: 3,
+        }
+    }
+
+    /// Stands in for a secondary: ignores the first `dropped` notifications,
+    /// answers the next one with a stray response and then `r_code`, and
+    /// returns the number of notifications received.
+    fn secondary(dropped: usize, r_code: RCode) -> (SocketAddr, thread::JoinHandle<usize>) {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = socket.local_addr().unwrap();
+        let handle = thread::spawn(move || {
+            let mut buffer = [0; 512];
+            for received in 1.. {
+                let (len, from) = socket.recv_from(&mut buffer).unwrap();
+                if received <= dropped {
+                    continue;
+                }
+                let notify = NotifyMessage::try_from(&buffer[..len]).unwrap();
+                let mut stray = notify.acknowledgement();
+                stray.header.id = stray.header.id.wrapping_add(1);
+                socket.send_to(&stray.to_bytes(), from).unwrap();
+
+                let mut ack = notify.acknowledgement();
+                ack.header.r_code = r_code;
+                socket.send_to(&ack.to_bytes(), from).unwrap();
+                return received;
+            }
+            unreachable!()
+        });
+        (addr, handle)
+    }
+
+    #[test]
+    fn send_retries_until_acknowledged() {
+        let (addr, secondary) = secondary(1, RCode::NoError);
+        let ack = sender().send(&notify(), addr).unwrap();
+        assert!(notify().is_answered_by(&ack));
+        assert_eq!(secondary.join().unwrap(), 2);
+    }
+
+    #[test]
+    fn send_reports_rejection() {
+        let (addr, secondary) = secondary(0, RCode::NotAuth);
+        assert!(matches!(
+            sender().send(&notify(), addr),
+            Err(TransportError::Rejected(RCode::NotAuth))
+        ));
+        secondary.join().unwrap();
+    }
+
+    #[test]
+    fn send_times_out() {
+        let silent = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = silent.local_addr().unwrap();
+        assert!(matches!(
+            sender().send(&notify(), addr),
+            Err(TransportError::Timeout(3))
+        ));
+
+        let mut buffer = [0; 512];
+        silent.set_nonblocking(true).unwrap();
+        let mut received = 0;
+        while silent.recv_from(&mut buffer).is_ok() {
+            received += 1;
+        }
+        assert_eq!(received, 3);
+    }
+}
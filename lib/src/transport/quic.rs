@@ -0,0 +1,727 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::message::{KnownQType, Message, OpCode};
+use crate::record::rdata::EdnsOption;
+use crate::transport::error::TransportError;
+use crate::transport::tls::{TlsSettings, QUERY_BLOCK_SIZE};
+use crate::transport::{Authentication, PrivacyProfile, Response};
+use quinn::crypto::rustls::QuicClientConfig;
+use quinn::{
+    ConnectionError, Endpoint, ReadError, ReadToEndError, VarInt, WriteError, ZeroRttAccepted,
+};
+use rustls::pki_types::{CertificateDer, ServerName};
+use rustls::RootCertStore;
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::runtime::Runtime;
+use tokio::time::timeout_at;
+
+/// Error codes used to close DNS over QUIC connections and abort their
+/// streams.
+///
+/// For more details, see [RFC 9250, Section 4.3].
+///
+/// [RFC 9250, Section 4.3]: https://datatracker.ietf.org/doc/html/rfc9250#section-4.3
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DoqErrorCode {
+    /// No error, used when closing idle connections.
+    NoError,
+    /// The endpoint encountered an internal error.
+    InternalError,
+    /// The peer violated the protocol, for instance by sending a message
+    /// with a nonzero identifier.
+    ProtocolError,
+    /// The client cancelled an outstanding query.
+    RequestCancelled,
+    /// The server is overloaded.
+    ExcessiveLoad,
+    /// No more specific error code applies.
+    UnspecifiedError,
+    /// Code not defined by RFC 9250.
+    Unknown(u64),
+}
+
+impl DoqErrorCode {
+    pub fn code(&self) -> u64 {
+        match self {
+            Self::NoError => 0x0,
+            Self::InternalError => 0x1,
+            Self::ProtocolError => 0x2,
+            Self::RequestCancelled => 0x3,
+            Self::ExcessiveLoad => 0x4,
+            Self::UnspecifiedError => 0x5,
+            Self::Unknown(code) => *code,
+        }
+    }
+
+    fn var_int(&self) -> VarInt {
+        VarInt::from_u64(self.code()).unwrap_or(VarInt::MAX)
+    }
+}
+
+impl From<u64> for DoqErrorCode {
+    fn from(code: u64) -> Self {
+        match code {
+            0x0 => Self::NoError,
+            0x1 => Self::InternalError,
+            0x2 => Self::ProtocolError,
+            0x3 => Self::RequestCancelled,
+            0x4 => Self::ExcessiveLoad,
+            0x5 => Self::UnspecifiedError,
+            _ => Self::Unknown(code),
+        }
+    }
+}
+
+impl From<VarInt> for DoqErrorCode {
+    fn from(code: VarInt) -> Self {
+        Self::from(code.into_inner())
+    }
+}
+
+impl fmt::Display for DoqErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoError => write!(f, "DOQ_NO_ERROR"),
+            Self::InternalError => write!(f, "DOQ_INTERNAL_ERROR"),
+            Self::ProtocolError => write!(f, "DOQ_PROTOCOL_ERROR"),
+            Self::RequestCancelled => write!(f, "DOQ_REQUEST_CANCELLED"),
+            Self::ExcessiveLoad => write!(f, "DOQ_EXCESSIVE_LOAD"),
+            Self::UnspecifiedError => write!(f, "DOQ_UNSPECIFIED_ERROR"),
+            Self::Unknown(code) => write!(f, "{:#x}", code),
+        }
+    }
+}
+
+/// Sends queries over QUIC, as described in [RFC 9250].
+///
+/// Each query is sent on its own stream of a connection shared by all
+/// queries to a server, with the same framing as over TCP. Servers are
+/// authenticated as over TLS, see [RFC 9250, Section 7.1]. Connections to
+/// a server resume the TLS session of earlier ones, sending their first
+/// query as 0-RTT data when it can safely be replayed, see
+/// [RFC 9250, Section 4.5].
+///
+/// [RFC 9250]: https://datatracker.ietf.org/doc/html/rfc9250
+/// [RFC 9250, Section 4.5]: https://datatracker.ietf.org/doc/html/rfc9250#section-4.5
+/// [RFC 9250, Section 7.1]: https://datatracker.ietf.org/doc/html/rfc9250#section-7.1
+pub struct DoqClient {
+    /// Time to wait for each query, including opening the connection.
+    pub timeout: Duration,
+    /// Whether to pad queries to a multiple of 128 octets, hiding their
+    /// length from observers, see [RFC 9250, Section 5.4].
+    ///
+    /// [RFC 9250, Section 5.4]: https://datatracker.ietf.org/doc/html/rfc9250#section-5.4
+    pub padding: bool,
+    settings: TlsSettings,
+    config: quinn::ClientConfig,
+    runtime: Runtime,
+    connection: Option<DoqConnection>,
+}
+
+impl fmt::Debug for DoqClient {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DoqClient")
+            .field("timeout", &self.timeout)
+            .field("padding", &self.padding)
+            .field("settings", &self.settings)
+            .finish_non_exhaustive()
+    }
+}
+
+impl DoqClient {
+    /// Port of DNS over QUIC, see [RFC 9250, Section 4.1.1].
+    ///
+    /// [RFC 9250, Section 4.1.1]: https://datatracker.ietf.org/doc/html/rfc9250#section-4.1.1
+    pub const PORT: u16 = 853;
+
+    /// Application protocol negotiated during the TLS handshake, see
+    /// [RFC 9250, Section 4.1].
+    ///
+    /// [RFC 9250, Section 4.1]: https://datatracker.ietf.org/doc/html/rfc9250#section-4.1
+    pub const ALPN: &'static [u8] = b"doq";
+
+    /// Creates a client authenticating servers with `authentication`, and
+    /// checking authentication domain names against the certificates
+    /// issued by `roots`.
+    pub fn new(
+        profile: PrivacyProfile,
+        authentication: Authentication,
+        roots: RootCertStore,
+    ) -> Result<Self, TransportError> {
+        let settings = TlsSettings::new(profile, authentication, roots)?;
+        let mut config = settings.client_config()?;
+        config.alpn_protocols = vec![Self::ALPN.to_vec()];
+        config.enable_early_data = true;
+        let config = QuicClientConfig::try_from(Arc::new(config))
+            .map_err(|e| TransportError::Quic(e.to_string()))?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_io()
+            .enable_time()
+            .build()?;
+        Ok(DoqClient {
+            timeout: Duration::from_secs(5),
+            padding: true,
+            settings,
+            config: quinn::ClientConfig::new(Arc::new(config)),
+            runtime,
+            connection: None,
+        })
+    }
+
+    /// Creates a client trusting the certificate authorities of the Mozilla
+    /// root program.
+    pub fn with_webpki_roots(
+        profile: PrivacyProfile,
+        authentication: Authentication,
+    ) -> Result<Self, TransportError> {
+        let roots = RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+        Self::new(profile, authentication, roots)
+    }
+
+    pub fn profile(&self) -> PrivacyProfile {
+        self.settings.profile()
+    }
+
+    /// Checks if the server of the current connection was authenticated,
+    /// which is always the case with the strict profile.
+    pub fn is_authenticated(&self) -> bool {
+        self.connection.as_ref().is_some_and(|connection| {
+            let chain = connection
+                .inner
+                .peer_identity()
+                .and_then(|identity| identity.downcast::<Vec<CertificateDer>>().ok());
+            self.settings
+                .is_authenticated(chain.as_deref().map(Vec::as_slice), &connection.server_name)
+        })
+    }
+
+    /// Checks if the first query of the current connection was sent as
+    /// 0-RTT data, and the server accepted it.
+    pub fn is_zero_rtt(&self) -> bool {
+        self.connection
+            .as_ref()
+            .is_some_and(|connection| connection.zero_rtt_accepted)
+    }
+
+    /// Sends `query` to `server` on a new stream, reusing the connection to
+    /// the server if still open, and returns the response whatever its
+    /// `RCODE`.
+    ///
+    /// The identifier of the query is set to 0 and responses must keep it,
+    /// see [RFC 9250, Section 4.2.1]. The `edns-tcp-keepalive` option is
+    /// removed, since it must not be used over QUIC, see
+    /// [RFC 9250, Section 5.5.2]. A query is cancelled when it times out,
+    /// see [RFC 9250, Section 4.3.1].
+    ///
+    /// [RFC 9250, Section 4.2.1]: https://datatracker.ietf.org/doc/html/rfc9250#section-4.2.1
+    /// [RFC 9250, Section 4.3.1]: https://datatracker.ietf.org/doc/html/rfc9250#section-4.3.1
+    /// [RFC 9250, Section 5.5.2]: https://datatracker.ietf.org/doc/html/rfc9250#section-5.5.2
+    pub fn query(
+        &mut self,
+        query: &Message,
+        server: SocketAddr,
+    ) -> Result<Response, TransportError> {
+        let mut query = query.clone();
+        query.header.id = 0;
+        if let Some(mut edns) = query.edns() {
+            edns.options
+                .retain(|option| option.code != EdnsOption::TCP_KEEPALIVE);
+            query.set_edns(edns);
+        }
+        if self.padding {
+            query.pad(QUERY_BLOCK_SIZE);
+        }
+        let bytes = query.to_bytes();
+        let len = u16::try_from(bytes.len()).map_err(|_| TransportError::TooLong(bytes.len()))?;
+        let mut framed = len.to_be_bytes().to_vec();
+        framed.extend_from_slice(&bytes);
+        // Zone transfers and updates have side effects or are too costly to
+        // be replayed by an attacker, see [RFC 9250, Section 4.5].
+        let replayable = matches!(query.header.op_code, OpCode::Query | OpCode::Notify)
+            && !query.questions.iter().any(|question| {
+                question.q_type == KnownQType::AXFR || question.q_type == KnownQType::IXFR
+            });
+
+        if self
+            .connection
+            .as_ref()
+            .is_some_and(|connection| connection.server != server || !connection.is_open())
+        {
+            self.close();
+        }
+        let start = Instant::now();
+        let deadline = tokio::time::Instant::now() + self.timeout;
+        let DoqClient {
+            settings,
+            config,
+            runtime,
+            connection,
+            ..
+        } = self;
+        let response = runtime.block_on(async {
+            if connection.is_none() {
+                let connect = DoqConnection::open(settings, config, server, replayable);
+                *connection = Some(
+                    timeout_at(deadline, connect)
+                        .await
+                        .map_err(|_| TransportError::Timeout(1))??,
+                );
+            }
+            let connection = connection.as_mut().expect("connection is open");
+            let mut bytes = connection.exchange(&framed, deadline).await;
+            if let Some(accepted) = connection.zero_rtt.take() {
+                connection.zero_rtt_accepted =
+                    timeout_at(deadline, accepted).await.unwrap_or(false);
+                if !connection.zero_rtt_accepted && bytes.is_err() {
+                    bytes = connection.exchange(&framed, deadline).await;
+                }
+            }
+            bytes
+        });
+        let rtt = start.elapsed();
+
+        let response = response.and_then(|bytes| parse_response(&query, &bytes));
+        if let Err(TransportError::Doq(DoqErrorCode::ProtocolError)) = response {
+            self.abort(DoqErrorCode::ProtocolError);
+        }
+        response.map(|message| Response { message, rtt })
+    }
+
+    /// Closes the connection with `DOQ_NO_ERROR`, as done when a connection
+    /// is no longer needed, see [RFC 9250, Section 5.5.2].
+    ///
+    /// [RFC 9250, Section 5.5.2]: https://datatracker.ietf.org/doc/html/rfc9250#section-5.5.2
+    pub fn close(&mut self) {
+        self.abort(DoqErrorCode::NoError);
+    }
+
+    /// Closes the connection with `code`, waiting for the peer to be
+    /// notified.
+    fn abort(&mut self, code: DoqErrorCode) {
+        if let Some(connection) = self.connection.take() {
+            connection.inner.close(code.var_int(), b"");
+            let idle = connection.endpoint.wait_idle();
+            let _ = self
+                .runtime
+                .block_on(async { tokio::time::timeout(self.timeout, idle).await });
+        }
+    }
+}
+
+impl Drop for DoqClient {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// Connection to a DNS over QUIC server.
+struct DoqConnection {
+    server: SocketAddr,
+    server_name: ServerName<'static>,
+    endpoint: Endpoint,
+    inner: quinn::Connection,
+    /// Resolves once the handshake completes if 0-RTT data was sent.
+    zero_rtt: Option<ZeroRttAccepted>,
+    zero_rtt_accepted: bool,
+}
+
+impl DoqConnection {
+    /// Opens a connection to `server`, returning as soon as queries can be
+    /// sent: before the handshake completes if an earlier session can be
+    /// resumed and the first query is `replayable`.
+    async fn open(
+        settings: &TlsSettings,
+        config: &quinn::ClientConfig,
+        server: SocketAddr,
+        replayable: bool,
+    ) -> Result<Self, TransportError> {
+        let local: SocketAddr = match server {
+            SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+            SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+        };
+        let mut endpoint = Endpoint::client(local)?;
+        endpoint.set_default_client_config(config.clone());
+        let server_name = settings.server_name(server);
+        let connecting = endpoint
+            .connect(server, &server_name.to_str())
+            .map_err(|e| TransportError::Quic(e.to_string()))?;
+
+        let (inner, zero_rtt) = match replayable {
+            true => match connecting.into_0rtt() {
+                Ok((inner, accepted)) => (inner, Some(accepted)),
+                Err(connecting) => (connecting.await.map_err(connection_error)?, None),
+            },
+            false => (connecting.await.map_err(connection_error)?, None),
+        };
+        Ok(DoqConnection {
+            server,
+            server_name,
+            endpoint,
+            inner,
+            zero_rtt,
+            zero_rtt_accepted: false,
+        })
+    }
+
+    fn is_open(&self) -> bool {
+        self.inner.close_reason().is_none()
+    }
+
+    /// Sends a framed query on a new stream, closing the sending side once
+    /// written, and reads what the server sends back until it closes the
+    /// stream. Timing out resets the stream with `DOQ_REQUEST_CANCELLED`.
+    async fn exchange(
+        &self,
+        framed: &[u8],
+        deadline: tokio::time::Instant,
+    ) -> Result<Vec<u8>, TransportError> {
+        let (mut send, mut recv) = timeout_at(deadline, self.inner.open_bi())
+            .await
+            .map_err(|_| TransportError::Timeout(1))?
+            .map_err(connection_error)?;
+        let exchange = async {
+            send.write_all(framed).await.map_err(write_error)?;
+            send.finish()
+                .map_err(|e| TransportError::Quic(e.to_string()))?;
+            recv.read_to_end(2 + u16::MAX as usize)
+                .await
+                .map_err(read_error)
+        };
+        match timeout_at(deadline, exchange).await {
+            Ok(bytes) => bytes,
+            Err(_) => {
+                let code = DoqErrorCode::RequestCancelled.var_int();
+                let _ = send.reset(code);
+                let _ = recv.stop(code);
+                // Lets the connection send the cancellation.
+                tokio::task::yield_now().await;
+                Err(TransportError::Timeout(1))
+            }
+        }
+    }
+}
+
+/// Decodes the response read from the stream of `query`, checking that it
+/// follows the rules of [RFC 9250, Section 4.3.3]: a single message, with
+/// an identifier of 0 and no `edns-tcp-keepalive` option.
+///
+/// [RFC 9250, Section 4.3.3]: https://datatracker.ietf.org/doc/html/rfc9250#section-4.3.3
+fn parse_response(query: &Message, bytes: &[u8]) -> Result<Message, TransportError> {
+    let protocol_error = TransportError::Doq(DoqErrorCode::ProtocolError);
+    let (len, message) = match bytes {
+        [high, low, message @ ..] => (u16::from_be_bytes([*high, *low]), message),
+        _ => return Err(protocol_error),
+    };
+    if usize::from(len) != message.len() {
+        return Err(protocol_error);
+    }
+    let message = Message::try_from(message).map_err(TransportError::Malformed)?;
+    if message.header.id != 0
+        || message
+            .edns()
+            .is_some_and(|edns| edns.option(EdnsOption::TCP_KEEPALIVE).is_some())
+    {
+        return Err(protocol_error);
+    }
+    if !query.is_answered_by(&message) {
+        return Err(TransportError::Quic(
+            "response does not answer the query".to_string(),
+        ));
+    }
+    Ok(message)
+}
+
+fn connection_error(error: ConnectionError) -> TransportError {
+    match error {
+        ConnectionError::ApplicationClosed(close) => TransportError::Doq(close.error_code.into()),
+        ConnectionError::TimedOut => TransportError::Timeout(1),
+        e => TransportError::Quic(e.to_string()),
+    }
+}
+
+fn write_error(error: WriteError) -> TransportError {
+    match error {
+        WriteError::Stopped(code) => TransportError::Doq(code.into()),
+        WriteError::ConnectionLost(e) => connection_error(e),
+        e => TransportError::Quic(e.to_string()),
+    }
+}
+
+fn read_error(error: ReadToEndError) -> TransportError {
+    match error {
+        ReadToEndError::Read(ReadError::Reset(code)) => TransportError::Doq(code.into()),
+        ReadToEndError::Read(ReadError::ConnectionLost(e)) => connection_error(e),
+        ReadToEndError::TooLong => TransportError::Doq(DoqErrorCode::ProtocolError),
+        e => TransportError::Quic(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Domain;
+    use crate::message::{Header, KnownQClass, KnownQType, Question, QR};
+    use quinn::crypto::rustls::QuicServerConfig;
+    use rstest::rstest;
+    use rustls::pki_types::{PrivateKeyDer, PrivatePkcs8KeyDer};
+    use rustls::ServerConfig;
+    use std::sync::mpsc;
+    use std::thread;
+
+    /// How the server stand-in handles queries.
+    #[derive(Debug, Clone, Copy)]
+    enum Mode {
+        Answer,
+        AnswerWithId(u16),
+        Reset(DoqErrorCode),
+        Ignore,
+    }
+
+    /// Query as seen by the server.
+    #[derive(Debug)]
+    struct Seen {
+        connection: usize,
+        id: u16,
+        len: usize,
+        cancelled: Option<DoqErrorCode>,
+    }
+
+    struct Server {
+        addr: SocketAddr,
+        certificate: CertificateDer<'static>,
+        /// Queries received, and the codes the client closed connections
+        /// with.
+        handle: thread::JoinHandle<(Vec<Seen>, Vec<Option<DoqErrorCode>>)>,
+    }
+
+    /// Stands in for a DNS over QUIC server with a self-signed certificate
+    /// for `dns.example`, accepting 0-RTT data: handles the queries of
+    /// `connections` connections according to `mode`, until the client
+    /// closes them.
+    fn serve(connections: usize, mode: Mode) -> Server {
+        let key = rcgen::generate_simple_self_signed(vec!["dns.example".to_string()]).unwrap();
+        let certificate = key.cert.der().clone();
+        let private_key =
+            PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key.key_pair.serialize_der()));
+        let mut config =
+            ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+                .with_protocol_versions(&[&rustls::version::TLS13])
+                .unwrap()
+                .with_no_client_auth()
+                .with_single_cert(vec![certificate.clone()], private_key)
+                .unwrap();
+        config.alpn_protocols = vec![DoqClient::ALPN.to_vec()];
+        config.max_early_data_size = u32::MAX;
+        let config = QuicServerConfig::try_from(config).unwrap();
+        let config = quinn::ServerConfig::with_crypto(Arc::new(config));
+
+        let (sender, receiver) = mpsc::channel();
+        let handle = thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            runtime.block_on(async move {
+                let endpoint = Endpoint::server(config, (Ipv4Addr::LOCALHOST, 0).into()).unwrap();
+                sender.send(endpoint.local_addr().unwrap()).unwrap();
+                let mut seen = Vec::new();
+                let mut closed = Vec::new();
+                for index in 0..connections {
+                    let connecting = endpoint.accept().await.unwrap().accept().unwrap();
+                    let connection = match connecting.into_0rtt() {
+                        Ok((connection, _)) => connection,
+                        Err(connecting) => match connecting.await {
+                            Ok(connection) => connection,
+                            Err(_) => {
+                                closed.push(None);
+                                continue;
+                            }
+                        },
+                    };
+                    loop {
+                        let (mut send, mut recv) = match connection.accept_bi().await {
+                            Ok(streams) => streams,
+                            Err(ConnectionError::ApplicationClosed(close)) => {
+                                closed.push(Some(close.error_code.into()));
+                                break;
+                            }
+                            Err(_) => {
+                                closed.push(None);
+                                break;
+                            }
+                        };
+                        let bytes = recv.read_to_end(usize::MAX).await.unwrap();
+                        let query = Message::try_from(&bytes[2..]).unwrap();
+                        let mut cancelled = None;
+                        match mode {
+                            Mode::Answer | Mode::AnswerWithId(_) => {
+                                let mut response = query.clone();
+                                response.header.qr = QR::Response;
+                                if let Mode::AnswerWithId(id) = mode {
+                                    response.header.id = id;
+                                }
+                                let response = response.to_bytes();
+                                let mut framed = (response.len() as u16).to_be_bytes().to_vec();
+                                framed.extend_from_slice(&response);
+                                send.write_all(&framed).await.unwrap();
+                                send.finish().unwrap();
+                            }
+                            Mode::Reset(code) => send.reset(code.var_int()).unwrap(),
+                            Mode::Ignore => {
+                                cancelled = send.stopped().await.ok().flatten().map(Into::into)
+                            }
+                        }
+                        seen.push(Seen {
+                            connection: index,
+                            id: query.header.id,
+                            len: bytes.len() - 2,
+                            cancelled,
+                        });
+                    }
+                }
+                (seen, closed)
+            })
+        });
+        Server {
+            addr: receiver.recv().unwrap(),
+            certificate,
+            handle,
+        }
+    }
+
+    fn query(op_code: OpCode) -> Message {
+        let mut message = Message::new(Header::new(0xbeef));
+        message.header.op_code = op_code;
+        message.questions.push(Question {
+            q_name: Domain::from_presentation("example.com").unwrap(),
+            q_type: KnownQType::A.into(),
+            q_class: KnownQClass::IN.into(),
+        });
+        message
+    }
+
+    fn client(server: &Server, name: &str) -> DoqClient {
+        let mut roots = RootCertStore::empty();
+        roots.add(server.certificate.clone()).unwrap();
+        let authentication = Authentication {
+            name: Some(Domain::from_presentation(name).unwrap()),
+            spki_pins: Vec::new(),
+        };
+        let mut client = DoqClient::new(PrivacyProfile::Strict, authentication, roots).unwrap();
+        client.timeout = Duration::from_millis(500);
+        client
+    }
+
+    #[rstest]
+    #[case(OpCode::Query, KnownQType::A, true)]
+    #[case(OpCode::Update, KnownQType::A, false)]
+    #[case(OpCode::Query, KnownQType::AXFR, false)]
+    #[case(OpCode::Query, KnownQType::IXFR, false)]
+    fn queries_share_a_connection_and_resume_with_zero_rtt(
+        #[case] op_code: OpCode,
+        #[case] q_type: KnownQType,
+        #[case] zero_rtt: bool,
+    ) {
+        let server = serve(2, Mode::Answer);
+        let mut client = client(&server, "dns.example");
+
+        let response = client.query(&query(OpCode::Query), server.addr).unwrap();
+        assert_eq!(response.message.header.id, 0);
+        assert!(client.is_authenticated());
+        assert!(!client.is_zero_rtt());
+        client.query(&query(OpCode::Query), server.addr).unwrap();
+        client.close();
+
+        let mut last = query(op_code);
+        last.questions[0].q_type = q_type.into();
+        client.query(&last, server.addr).unwrap();
+        assert!(client.is_authenticated());
+        assert_eq!(client.is_zero_rtt(), zero_rtt);
+        client.close();
+
+        let (seen, closed) = server.handle.join().unwrap();
+        let connections: Vec<_> = seen.iter().map(|seen| seen.connection).collect();
+        assert_eq!(connections, [0, 0, 1]);
+        assert!(seen.iter().all(|seen| seen.id == 0));
+        assert!(seen.iter().all(|seen| seen.len % QUERY_BLOCK_SIZE == 0));
+        assert_eq!(closed, [Some(DoqErrorCode::NoError); 2]);
+    }
+
+    #[test]
+    fn nonzero_message_id_is_a_protocol_error() {
+        let server = serve(1, Mode::AnswerWithId(0xbeef));
+        let mut client = client(&server, "dns.example");
+        assert!(matches!(
+            client.query(&query(OpCode::Query), server.addr),
+            Err(TransportError::Doq(DoqErrorCode::ProtocolError))
+        ));
+        let (_, closed) = server.handle.join().unwrap();
+        assert_eq!(closed, [Some(DoqErrorCode::ProtocolError)]);
+    }
+
+    #[test]
+    fn reset_stream_reports_error_code() {
+        let server = serve(1, Mode::Reset(DoqErrorCode::ExcessiveLoad));
+        let mut client = client(&server, "dns.example");
+        assert!(matches!(
+            client.query(&query(OpCode::Query), server.addr),
+            Err(TransportError::Doq(DoqErrorCode::ExcessiveLoad))
+        ));
+        drop(client);
+        let (_, closed) = server.handle.join().unwrap();
+        assert_eq!(closed, [Some(DoqErrorCode::NoError)]);
+    }
+
+    #[test]
+    fn timeout_cancels_the_query() {
+        let server = serve(1, Mode::Ignore);
+        let mut client = client(&server, "dns.example");
+        client.timeout = Duration::from_millis(200);
+        assert!(matches!(
+            client.query(&query(OpCode::Query), server.addr),
+            Err(TransportError::Timeout(1))
+        ));
+        client.close();
+        let (seen, _) = server.handle.join().unwrap();
+        assert_eq!(seen[0].cancelled, Some(DoqErrorCode::RequestCancelled));
+    }
+
+    #[test]
+    fn strict_profile_rejects_unauthenticated_servers() {
+        let server = serve(1, Mode::Answer);
+        let mut client = client(&server, "other.example");
+        assert!(matches!(
+            client.query(&query(OpCode::Query), server.addr),
+            Err(TransportError::Quic(_))
+        ));
+        let (seen, _) = server.handle.join().unwrap();
+        assert!(seen.is_empty());
+    }
+
+    #[rstest]
+    #[case(0x0, DoqErrorCode::NoError)]
+    #[case(0x2, DoqErrorCode::ProtocolError)]
+    #[case(0x5, DoqErrorCode::UnspecifiedError)]
+    #[case(0xd098ea5e, DoqErrorCode::Unknown(0xd098ea5e))]
+    fn doq_error_code_round_trip(#[case] code: u64, #[case] expected: DoqErrorCode) {
+        assert_eq!(DoqErrorCode::from(code), expected);
+        assert_eq!(expected.code(), code);
+    }
+}
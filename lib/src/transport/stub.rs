@@ -0,0 +1,183 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::message::Message;
+use crate::transport::error::TransportError;
+use crate::transport::{Response, TcpClient, TcpConnection, UdpClient};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+
+/// Stub resolver client, sending queries over UDP and retrying them over
+/// TCP when the response is truncated.
+///
+/// TCP connections are kept open and reused for later queries to the same
+/// server, until they have been idle for too long.
+///
+/// For more details, see [RFC 7766, Section 5] and
+/// [RFC 7766, Section 6.2.1].
+///
+/// [RFC 7766, Section 5]: https://datatracker.ietf.org/doc/html/rfc7766#section-5
+/// [RFC 7766, Section 6.2.1]: https://datatracker.ietf.org/doc/html/rfc7766#section-6.2.1
+#[derive(Debug, Default)]
+pub struct StubClient {
+    pub udp: UdpClient,
+    pub tcp: TcpClient,
+    connections: HashMap<SocketAddr, TcpConnection>,
+}
+
+impl StubClient {
+    pub fn new(udp: UdpClient, tcp: TcpClient) -> Self {
+        StubClient {
+            udp,
+            tcp,
+            connections: HashMap::new(),
+        }
+    }
+
+    /// Sends `query` to `server` over UDP, falling back to TCP when the
+    /// response has the `TC` bit set.
+    pub fn query(
+        &mut self,
+        query: &Message,
+        server: SocketAddr,
+    ) -> Result<Response, TransportError> {
+        let response = self.udp.query(query, server)?;
+        if !response.message.header.tc {
+            return Ok(response);
+        }
+        self.query_tcp(query, server)
+    }
+
+    /// Sends `query` to `server` over TCP, reusing an open connection when
+    /// possible. A query on a reused connection that the server closed in
+    /// the meantime is retried on a new one.
+    pub fn query_tcp(
+        &mut self,
+        query: &Message,
+        server: SocketAddr,
+    ) -> Result<Response, TransportError> {
+        self.connections
+            .retain(|_, connection| connection.is_reusable());
+        if let Some(connection) = self.connections.get_mut(&server) {
+            match connection.query(query) {
+                Err(TransportError::Closed | TransportError::Io(_)) => {}
+                result => return result,
+            }
+        }
+
+        let mut connection = self.tcp.connect(server)?;
+        let result = connection.query(query);
+        self.connections.insert(server, connection);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Domain;
+    use crate::message::{Header, KnownQClass, KnownQType, Question, QR};
+    use crate::record::{RData, ResourceRecord};
+    use crate::transport::tcp::{read_framed, write_framed};
+    use std::net::{Ipv4Addr, TcpListener, TcpStream, UdpSocket};
+    use std::thread;
+    use std::time::Duration;
+
+    fn query() -> Message {
+        let mut message = Message::new(Header::new(0));
+        message.questions.push(Question {
+            q_name: Domain::from_presentation("example.com").unwrap(),
+            q_type: KnownQType::A.into(),
+            q_class: KnownQClass::IN.into(),
+        });
+        message
+    }
+
+    fn answer(query: &Message) -> Message {
+        let mut response = query.clone();
+        response.header.qr = QR::Response;
+        response.additionals.clear();
+        response.answers.push(ResourceRecord {
+            name: query.questions[0].q_name.clone(),
+            r_type: KnownQType::A.into(),
+            r_class: KnownQClass::IN.into(),
+            ttl: 300,
+            rdata: RData::A(Ipv4Addr::new(192, 0, 2, 1)),
+        });
+        response
+    }
+
+    fn client() -> StubClient {
+        StubClient::new(
+            UdpClient {
+                timeout: Duration::from_millis(500),
+                attempts: 1,
+            },
+            TcpClient {
+                timeout: Duration::from_millis(500),
+                ..TcpClient::default()
+            },
+        )
+    }
+
+    /// Answers `count` queries read from `stream`.
+    fn answer_queries(stream: &mut TcpStream, count: usize) {
+        for _ in 0..count {
+            let bytes = read_framed(stream).unwrap();
+            let query = Message::try_from(bytes.as_slice()).unwrap();
+            write_framed(stream, &answer(&query).to_bytes()).unwrap();
+        }
+    }
+
+    #[test]
+    fn query_falls_back_to_tcp_on_truncation() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let socket = UdpSocket::bind(addr).unwrap();
+        let udp = thread::spawn(move || {
+            let mut buffer = [0; 512];
+            let (len, from) = socket.recv_from(&mut buffer).unwrap();
+            let mut truncated = Message::try_from(&buffer[..len]).unwrap();
+            truncated.header.qr = QR::Response;
+            truncated.header.tc = true;
+            socket.send_to(&truncated.to_bytes(), from).unwrap();
+        });
+        let tcp = thread::spawn(move || answer_queries(&mut listener.accept().unwrap().0, 1));
+
+        let response = client().query(&query(), addr).unwrap();
+        assert!(!response.message.header.tc);
+        assert_eq!(response.message.answers.len(), 1);
+        udp.join().unwrap();
+        tcp.join().unwrap();
+    }
+
+    #[test]
+    fn query_tcp_reuses_connections() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            answer_queries(&mut listener.accept().unwrap().0, 2);
+            // The first connection is closed, so the next query must
+            // reconnect.
+            answer_queries(&mut listener.accept().unwrap().0, 1);
+        });
+
+        let mut client = client();
+        for _ in 0..3 {
+            let response = client.query_tcp(&query(), addr).unwrap();
+            assert_eq!(response.message.answers.len(), 1);
+        }
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,486 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::message::{Edns, Message};
+use crate::record::rdata::EdnsOption;
+use crate::transport::error::TransportError;
+use crate::transport::udp::is_timeout;
+use crate::transport::Response;
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::time::{Duration, Instant};
+
+/// Opens TCP connections to servers and sends queries over them.
+///
+/// Messages are prefixed with their length on two octets, see
+/// [RFC 1035, Section 4.2.2].
+///
+/// [RFC 1035, Section 4.2.2]: https://datatracker.ietf.org/doc/html/rfc1035#section-4.2.2
+#[derive(Debug, Clone)]
+pub struct TcpClient {
+    /// Time to wait for the connection to be established, and for each
+    /// response after its query is sent.
+    pub timeout: Duration,
+    /// Time an unused connection is kept open, until the server announces
+    /// its own with the `edns-tcp-keepalive` option.
+    pub idle_timeout: Duration,
+    /// Whether to send the `edns-tcp-keepalive` option with queries, see
+    /// [RFC 7828, Section 3.2.1].
+    ///
+    /// [RFC 7828, Section 3.2.1]: https://datatracker.ietf.org/doc/html/rfc7828#section-3.2.1
+    pub keepalive: bool,
+}
+
+impl Default for TcpClient {
+    fn default() -> Self {
+        TcpClient {
+            timeout: Duration::from_secs(5),
+            idle_timeout: Duration::from_secs(10),
+            keepalive: true,
+        }
+    }
+}
+
+impl TcpClient {
+    /// Opens a connection to `server`.
+    pub fn connect(&self, server: SocketAddr) -> Result<TcpConnection, TransportError> {
+        let stream = self.open(server)?;
+        Ok(StreamConnection::new(stream, server, self))
+    }
+
+    /// Opens the TCP stream underlying a connection to `server`.
+    pub(crate) fn open(&self, server: SocketAddr) -> Result<TcpStream, TransportError> {
+        let stream = TcpStream::connect_timeout(&server, self.timeout)?;
+        stream.set_nodelay(true)?;
+        Ok(stream)
+    }
+
+    /// Sends `query` to `server` over a new connection, closed once the
+    /// response arrives.
+    pub fn query(&self, query: &Message, server: SocketAddr) -> Result<Response, TransportError> {
+        self.connect(server)?.query(query)
+    }
+}
+
+/// Byte stream carrying DNS messages, such as a TCP connection or a TLS
+/// session on top of one.
+pub trait Stream: Read + Write {
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()>;
+}
+
+impl Stream for TcpStream {
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        TcpStream::set_read_timeout(self, timeout)
+    }
+}
+
+/// Connection to a server over a [`Stream`], which can carry several
+/// queries at once.
+///
+/// Queries may be pipelined, sending the next one before the previous
+/// responses arrive, and the server may answer them in any order.
+///
+/// For more details, see [RFC 7766, Section 6.2.1].
+///
+/// [RFC 7766, Section 6.2.1]: https://datatracker.ietf.org/doc/html/rfc7766#section-6.2.1
+#[derive(Debug)]
+pub struct StreamConnection<S> {
+    stream: S,
+    server: SocketAddr,
+    timeout: Duration,
+    idle_timeout: Duration,
+    keepalive: bool,
+    /// Block size queries are padded to, if any.
+    padding: Option<usize>,
+    /// Queries waiting for a response, by identifier, with the time they
+    /// were sent.
+    pending: HashMap<u16, (Message, Instant)>,
+    /// Responses read while waiting for another one.
+    received: HashMap<u16, Response>,
+    last_activity: Instant,
+    broken: bool,
+}
+
+/// Connection to a server over TCP.
+pub type TcpConnection = StreamConnection<TcpStream>;
+
+impl<S: Stream> StreamConnection<S> {
+    pub(crate) fn new(stream: S, server: SocketAddr, client: &TcpClient) -> Self {
+        StreamConnection {
+            stream,
+            server,
+            timeout: client.timeout,
+            idle_timeout: client.idle_timeout,
+            keepalive: client.keepalive,
+            padding: None,
+            pending: HashMap::new(),
+            received: HashMap::new(),
+            last_activity: Instant::now(),
+            broken: false,
+        }
+    }
+
+    /// Pads queries to a multiple of `block_size` octets.
+    pub(crate) fn with_padding(mut self, block_size: usize) -> Self {
+        self.padding = Some(block_size);
+        self
+    }
+
+    pub fn stream(&self) -> &S {
+        &self.stream
+    }
+
+    pub fn server(&self) -> SocketAddr {
+        self.server
+    }
+
+    /// Returns the time the connection may stay unused, as announced by
+    /// the server with the `edns-tcp-keepalive` option or configured on
+    /// the client.
+    pub fn idle_timeout(&self) -> Duration {
+        self.idle_timeout
+    }
+
+    /// Checks if the connection can carry more queries: it has not failed,
+    /// and has not been idle for longer than its idle timeout.
+    ///
+    /// For more details, see [RFC 7766, Section 6.2.3].
+    ///
+    /// [RFC 7766, Section 6.2.3]: https://datatracker.ietf.org/doc/html/rfc7766#section-6.2.3
+    pub fn is_reusable(&self) -> bool {
+        !self.broken
+            && (!self.pending.is_empty() || self.last_activity.elapsed() < self.idle_timeout)
+    }
+
+    /// Sends `query` to the server, replacing its identifier with a random
+    /// one not used by other pending queries, which is returned.
+    pub fn send(&mut self, query: &Message) -> Result<u16, TransportError> {
+        if self.broken {
+            return Err(TransportError::Closed);
+        }
+        let mut query = query.clone();
+        query.header.id = loop {
+            let id = rand::random();
+            if !self.pending.contains_key(&id) && !self.received.contains_key(&id) {
+                break id;
+            }
+        };
+        // Changing the additional section would invalidate a signature.
+        if self.keepalive && query.tsig().is_none() {
+            let mut edns = query
+                .edns()
+                .unwrap_or_else(|| Edns::new(Edns::DEFAULT_UDP_PAYLOAD_SIZE));
+            if edns.option(EdnsOption::TCP_KEEPALIVE).is_none() {
+                edns.options.push(EdnsOption::tcp_keepalive(None));
+            }
+            query.set_edns(edns);
+        }
+        if let Some(block_size) = self.padding {
+            query.pad(block_size);
+        }
+
+        if let Err(e) = write_framed(&mut self.stream, &query.to_bytes()) {
+            self.broken = true;
+            return Err(e);
+        }
+        let id = query.header.id;
+        self.last_activity = Instant::now();
+        self.pending.insert(id, (query, self.last_activity));
+        Ok(id)
+    }
+
+    /// Waits for the response to the query sent with identifier `id`,
+    /// keeping the responses to other pending queries that arrive first.
+    pub fn receive(&mut self, id: u16) -> Result<Response, TransportError> {
+        let deadline = match self.pending.get(&id) {
+            Some((_, sent)) => *sent + self.timeout,
+            None => {
+                return self.received.remove(&id).ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidInput, "no query sent with this ID").into()
+                })
+            }
+        };
+        while !self.received.contains_key(&id) {
+            let Some(remaining) = deadline
+                .checked_duration_since(Instant::now())
+                .filter(|remaining| !remaining.is_zero())
+            else {
+                self.pending.remove(&id);
+                return Err(TransportError::Timeout(1));
+            };
+            if let Err(e) = self.read_response(remaining) {
+                self.pending.remove(&id);
+                return Err(e);
+            }
+        }
+        Ok(self.received.remove(&id).expect("response was received"))
+    }
+
+    /// Sends `query` and waits for its response.
+    pub fn query(&mut self, query: &Message) -> Result<Response, TransportError> {
+        let id = self.send(query)?;
+        self.receive(id)
+    }
+
+    /// Reads a message, keeping it if it answers a pending query.
+    ///
+    /// The connection stays usable if the timeout expires before the first
+    /// octet of a message, but is marked as broken on other I/O or framing
+    /// errors, as they may leave part of a message in the stream.
+    fn read_response(&mut self, timeout: Duration) -> Result<(), TransportError> {
+        let mut first = [0; 1];
+        let read = self
+            .stream
+            .set_read_timeout(Some(timeout))
+            .and_then(|_| self.stream.read(&mut first));
+        match read {
+            Ok(0) => {
+                self.broken = true;
+                return Err(TransportError::Closed);
+            }
+            Ok(_) => {}
+            Err(e) if is_timeout(&e) => return Err(TransportError::Timeout(1)),
+            Err(e) => {
+                self.broken = true;
+                return Err(e.into());
+            }
+        }
+        let bytes = read_framed(&mut first.as_slice().chain(&mut self.stream)).map_err(|e| {
+            self.broken = true;
+            match e {
+                TransportError::Io(e) if is_timeout(&e) => TransportError::Timeout(1),
+                e => e,
+            }
+        })?;
+        self.last_activity = Instant::now();
+        let message = Message::try_from(bytes.as_slice()).map_err(TransportError::Malformed)?;
+
+        let id = message.header.id;
+        match self.pending.get(&id) {
+            Some((query, _)) if query.is_answered_by(&message) => {}
+            _ => return Ok(()),
+        }
+        let (_, sent) = self.pending.remove(&id).expect("query is pending");
+        if let Some(timeout) = message.edns().and_then(|edns| edns.tcp_keepalive()) {
+            self.idle_timeout = timeout;
+        }
+        let rtt = sent.elapsed();
+        self.received.insert(id, Response { message, rtt });
+        Ok(())
+    }
+}
+
+/// Writes `message` prefixed with its length on two octets, in a single
+/// write as recommended by [RFC 7766, Section 8].
+///
+/// [RFC 7766, Section 8]: https://datatracker.ietf.org/doc/html/rfc7766#section-8
+pub(crate) fn write_framed(writer: &mut impl Write, message: &[u8]) -> Result<(), TransportError> {
+    let len = u16::try_from(message.len()).map_err(|_| TransportError::TooLong(message.len()))?;
+    let mut bytes = Vec::with_capacity(message.len() + 2);
+    bytes.extend_from_slice(&len.to_be_bytes());
+    bytes.extend_from_slice(message);
+    writer.write_all(&bytes)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads a message prefixed with its length on two octets.
+pub(crate) fn read_framed(reader: &mut impl Read) -> Result<Vec<u8>, TransportError> {
+    let mut len = [0; 2];
+    match reader.read_exact(&mut len) {
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Err(TransportError::Closed),
+        result => result?,
+    }
+    let mut message = vec![0; u16::from_be_bytes(len) as usize];
+    match reader.read_exact(&mut message) {
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Err(TransportError::Closed),
+        result => Ok(result.map(|_| message)?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Domain;
+    use crate::message::{Header, KnownQClass, KnownQType, Question, QR};
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn query(name: &str) -> Message {
+        let mut message = Message::new(Header::new(0));
+        message.questions.push(Question {
+            q_name: Domain::from_presentation(name).unwrap(),
+            q_type: KnownQType::A.into(),
+            q_class: KnownQClass::IN.into(),
+        });
+        message
+    }
+
+    fn answer(query: &Message, keepalive: Option<Duration>) -> Vec<u8> {
+        let mut response = query.clone();
+        response.header.qr = QR::Response;
+        let mut edns = Edns::new(1232);
+        edns.options.push(EdnsOption::tcp_keepalive(keepalive));
+        response.set_edns(edns);
+        response.to_bytes()
+    }
+
+    fn client() -> TcpClient {
+        TcpClient {
+            timeout: Duration::from_millis(500),
+            ..TcpClient::default()
+        }
+    }
+
+    /// Stands in for a server: accepts a single connection and passes it to
+    /// `handler`.
+    fn serve<T, F>(handler: F) -> (SocketAddr, thread::JoinHandle<T>)
+    where
+        T: Send + 'static,
+        F: FnOnce(TcpStream) -> T + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handle = thread::spawn(move || handler(listener.accept().unwrap().0));
+        (addr, handle)
+    }
+
+    fn read_query(stream: &mut TcpStream) -> Message {
+        Message::try_from(read_framed(stream).unwrap().as_slice()).unwrap()
+    }
+
+    #[test]
+    fn query_sends_keepalive_and_adopts_server_timeout() {
+        let (addr, server) = serve(|mut stream| {
+            let query = read_query(&mut stream);
+            let edns = query.edns().unwrap();
+            assert_eq!(edns.option(EdnsOption::TCP_KEEPALIVE), Some(&[][..]));
+            let response = answer(&query, Some(Duration::from_secs(30)));
+            write_framed(&mut stream, &response).unwrap();
+        });
+
+        let mut connection = client().connect(addr).unwrap();
+        assert_eq!(connection.server(), addr);
+        let response = connection.query(&query("example.com")).unwrap();
+        assert_eq!(response.message.questions, query("example.com").questions);
+        assert_eq!(connection.idle_timeout(), Duration::from_secs(30));
+        assert!(connection.is_reusable());
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn connection_matches_out_of_order_responses() {
+        let names = ["a.example", "b.example", "c.example"];
+        let (addr, server) = serve(|mut stream| {
+            let queries: Vec<Message> = (0..3).map(|_| read_query(&mut stream)).collect();
+            let mut stray = queries[0].clone();
+            stray.header.id = stray.header.id.wrapping_add(1);
+            write_framed(&mut stream, &answer(&stray, None)).unwrap();
+            for query in queries.iter().rev() {
+                write_framed(&mut stream, &answer(query, None)).unwrap();
+            }
+        });
+
+        let mut connection = client().connect(addr).unwrap();
+        let ids: Vec<u16> = names
+            .iter()
+            .map(|name| connection.send(&query(name)).unwrap())
+            .collect();
+        for (id, name) in ids.into_iter().zip(names) {
+            let response = connection.receive(id).unwrap();
+            assert_eq!(response.message.header.id, id);
+            assert_eq!(response.message.questions, query(name).questions);
+        }
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn connection_honours_idle_timeouts() {
+        let (addr, server) = serve(|mut stream| {
+            let query = read_query(&mut stream);
+            write_framed(&mut stream, &answer(&query, Some(Duration::ZERO))).unwrap();
+        });
+
+        let mut connection = client().connect(addr).unwrap();
+        let id = connection.send(&query("example.com")).unwrap();
+        assert!(connection.is_reusable());
+        connection.receive(id).unwrap();
+        assert!(!connection.is_reusable());
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn connection_reports_closed_and_timeouts() {
+        let (addr, server) = serve(|mut stream| {
+            read_query(&mut stream);
+            let query = read_query(&mut stream);
+            write_framed(&mut stream, &answer(&query, None)).unwrap();
+        });
+        let mut connection = client().connect(addr).unwrap();
+        assert!(matches!(
+            connection.query(&query("example.com")),
+            Err(TransportError::Timeout(1))
+        ));
+        assert!(connection.is_reusable());
+        connection.query(&query("example.net")).unwrap();
+        server.join().unwrap();
+
+        let (addr, server) = serve(|mut stream| {
+            let query = read_query(&mut stream);
+            let mut response = Vec::new();
+            write_framed(&mut response, &answer(&query, None)).unwrap();
+            stream.write_all(&response[..3]).unwrap();
+            stream
+        });
+        let mut connection = client().connect(addr).unwrap();
+        assert!(matches!(
+            connection.query(&query("example.com")),
+            Err(TransportError::Timeout(1))
+        ));
+        assert!(!connection.is_reusable());
+        drop(server.join().unwrap());
+
+        let (addr, server) = serve(|mut stream| {
+            read_query(&mut stream);
+        });
+        let mut connection = client().connect(addr).unwrap();
+        assert!(matches!(
+            connection.query(&query("example.com")),
+            Err(TransportError::Closed)
+        ));
+        assert!(matches!(
+            connection.send(&query("example.com")),
+            Err(TransportError::Closed)
+        ));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn framing_round_trips() {
+        let mut bytes = Vec::new();
+        write_framed(&mut bytes, b"abc").unwrap();
+        assert_eq!(bytes, [0, 3, b'a', b'b', b'c']);
+        assert_eq!(read_framed(&mut bytes.as_slice()).unwrap(), b"abc");
+        let mut partial = &bytes[..4];
+        assert!(matches!(
+            read_framed(&mut partial),
+            Err(TransportError::Closed)
+        ));
+        assert!(matches!(
+            write_framed(&mut Vec::new(), &[0; 65536]),
+            Err(TransportError::TooLong(65536))
+        ));
+    }
+}
@@ -0,0 +1,625 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dane::certificate::subject_public_key_info;
+use crate::domain::Domain;
+use crate::message::Message;
+use crate::transport::error::TransportError;
+use crate::transport::{Response, Stream, StreamConnection, TcpClient};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::WebPkiServerVerifier;
+use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{
+    CertificateError, ClientConfig, ClientConnection, DigitallySignedStruct, HandshakeKind,
+    RootCertStore, SignatureScheme, StreamOwned,
+};
+use sha2::{Digest, Sha256};
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Block size queries are padded to, see [RFC 8467, Section 4.1].
+///
+/// [RFC 8467, Section 4.1]: https://datatracker.ietf.org/doc/html/rfc8467#section-4.1
+pub(crate) const QUERY_BLOCK_SIZE: usize = 128;
+
+/// Usage profile of DNS over TLS, deciding what happens when the server
+/// cannot be authenticated.
+///
+/// For more details, see [RFC 8310, Section 5].
+///
+/// [RFC 8310, Section 5]: https://datatracker.ietf.org/doc/html/rfc8310#section-5
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrivacyProfile {
+    /// Queries are only sent to authenticated servers.
+    Strict,
+    /// Queries are sent over encrypted connections even if the server
+    /// cannot be authenticated, trading privacy against active attackers
+    /// for availability.
+    Opportunistic,
+}
+
+/// Information used to authenticate a DNS over TLS server: the server is
+/// authenticated if either its certificate is valid for `name`, or its
+/// end-entity certificate matches a pin.
+///
+/// For more details, see [RFC 8310, Section 8].
+///
+/// [RFC 8310, Section 8]: https://datatracker.ietf.org/doc/html/rfc8310#section-8
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Authentication {
+    /// Authentication domain name, which is also sent in the TLS Server
+    /// Name Indication extension.
+    pub name: Option<Domain>,
+    /// SHA-256 digests of the `SubjectPublicKeyInfo` of accepted
+    /// end-entity certificates, see [RFC 7858, Section 4.2].
+    ///
+    /// [RFC 7858, Section 4.2]: https://datatracker.ietf.org/doc/html/rfc7858#section-4.2
+    pub spki_pins: Vec<[u8; 32]>,
+}
+
+/// Computes the SPKI pin of a DER encoded certificate, the SHA-256 digest
+/// of its `SubjectPublicKeyInfo`.
+pub fn spki_pin(certificate: &[u8]) -> Option<[u8; 32]> {
+    subject_public_key_info(certificate).map(|spki| Sha256::digest(spki).into())
+}
+
+/// Sends queries over TLS, as described in [RFC 7858].
+///
+/// Connections share a TLS session cache, so that later connections to a
+/// server resume the session of earlier ones, see
+/// [RFC 7858, Section 3.4].
+///
+/// [RFC 7858]: https://datatracker.ietf.org/doc/html/rfc7858
+/// [RFC 7858, Section 3.4]: https://datatracker.ietf.org/doc/html/rfc7858#section-3.4
+#[derive(Debug, Clone)]
+pub struct DotClient {
+    /// Settings of the underlying TCP connections.
+    pub tcp: TcpClient,
+    /// Whether to pad queries to a multiple of 128 octets, hiding their
+    /// length from observers.
+    pub padding: bool,
+    settings: TlsSettings,
+    config: Arc<ClientConfig>,
+}
+
+impl DotClient {
+    /// Port of DNS over TLS, see [RFC 7858, Section 3.1].
+    ///
+    /// [RFC 7858, Section 3.1]: https://datatracker.ietf.org/doc/html/rfc7858#section-3.1
+    pub const PORT: u16 = 853;
+
+    /// Creates a client authenticating servers with `authentication`, and
+    /// checking authentication domain names against the certificates
+    /// issued by `roots`.
+    pub fn new(
+        profile: PrivacyProfile,
+        authentication: Authentication,
+        roots: RootCertStore,
+    ) -> Result<Self, TransportError> {
+        let settings = TlsSettings::new(profile, authentication, roots)?;
+        let config = settings.client_config()?;
+        Ok(DotClient {
+            tcp: TcpClient::default(),
+            padding: true,
+            settings,
+            config: Arc::new(config),
+        })
+    }
+
+    /// Creates a client trusting the certificate authorities of the Mozilla
+    /// root program.
+    pub fn with_webpki_roots(
+        profile: PrivacyProfile,
+        authentication: Authentication,
+    ) -> Result<Self, TransportError> {
+        let roots = RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+        Self::new(profile, authentication, roots)
+    }
+
+    pub fn profile(&self) -> PrivacyProfile {
+        self.settings.profile()
+    }
+
+    /// Opens a connection to `server`, failing if the TLS handshake fails
+    /// or, with the strict profile, if the server cannot be authenticated.
+    pub fn connect(&self, server: SocketAddr) -> Result<TlsConnection, TransportError> {
+        let mut socket = self.tcp.open(server)?;
+        socket.set_read_timeout(Some(self.tcp.timeout))?;
+        socket.set_write_timeout(Some(self.tcp.timeout))?;
+        let server_name = self.settings.server_name(server);
+        let mut connection = ClientConnection::new(self.config.clone(), server_name.clone())
+            .map_err(TransportError::Tls)?;
+        while connection.is_handshaking() {
+            connection.complete_io(&mut socket).map_err(tls_error)?;
+        }
+
+        let authenticated = self
+            .settings
+            .is_authenticated(connection.peer_certificates(), &server_name);
+        let stream = TlsStream {
+            inner: StreamOwned::new(connection, socket),
+            authenticated,
+        };
+        let connection = StreamConnection::new(stream, server, &self.tcp);
+        Ok(match self.padding {
+            true => connection.with_padding(QUERY_BLOCK_SIZE),
+            false => connection,
+        })
+    }
+
+    /// Sends `query` to `server` over a new connection, closed once the
+    /// response arrives.
+    pub fn query(&self, query: &Message, server: SocketAddr) -> Result<Response, TransportError> {
+        self.connect(server)?.query(query)
+    }
+}
+
+/// TLS session with a DNS over TLS server.
+#[derive(Debug)]
+pub struct TlsStream {
+    inner: StreamOwned<ClientConnection, TcpStream>,
+    authenticated: bool,
+}
+
+impl TlsStream {
+    /// Checks if the server was authenticated, which is always the case
+    /// with the strict profile.
+    pub fn is_authenticated(&self) -> bool {
+        self.authenticated
+    }
+
+    /// Checks if the TLS session was resumed from an earlier connection.
+    pub fn is_resumed(&self) -> bool {
+        self.inner.conn.handshake_kind() == Some(HandshakeKind::Resumed)
+    }
+}
+
+impl Read for TlsStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl Write for TlsStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl Stream for TlsStream {
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.inner.sock.set_read_timeout(timeout)
+    }
+}
+
+/// Connection to a server over TLS.
+pub type TlsConnection = StreamConnection<TlsStream>;
+
+/// TLS settings of a client authenticating servers according to a privacy
+/// profile, shared by the DNS over TLS and DNS over QUIC clients.
+#[derive(Debug, Clone)]
+pub(crate) struct TlsSettings {
+    profile: PrivacyProfile,
+    server_name: Option<ServerName<'static>>,
+    verifier: Arc<DotVerifier>,
+}
+
+impl TlsSettings {
+    pub(crate) fn new(
+        profile: PrivacyProfile,
+        authentication: Authentication,
+        roots: RootCertStore,
+    ) -> Result<Self, TransportError> {
+        let server_name = match &authentication.name {
+            Some(name) => Some(
+                ServerName::try_from(name.to_string())
+                    .map_err(|_| TransportError::InvalidServerName(name.clone()))?,
+            ),
+            None => None,
+        };
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let webpki = match roots.is_empty() {
+            true => None,
+            false => Some(
+                WebPkiServerVerifier::builder_with_provider(roots.into(), provider.clone())
+                    .build()
+                    .map_err(|e| TransportError::Tls(rustls::Error::General(e.to_string())))?,
+            ),
+        };
+        let verifier = Arc::new(DotVerifier {
+            profile,
+            spki_pins: authentication.spki_pins,
+            webpki,
+            provider,
+        });
+        Ok(TlsSettings {
+            profile,
+            server_name,
+            verifier,
+        })
+    }
+
+    pub(crate) fn profile(&self) -> PrivacyProfile {
+        self.profile
+    }
+
+    /// Builds a client configuration verifying server certificates with
+    /// these settings.
+    pub(crate) fn client_config(&self) -> Result<ClientConfig, TransportError> {
+        Ok(
+            ClientConfig::builder_with_provider(self.verifier.provider.clone())
+                .with_safe_default_protocol_versions()
+                .map_err(TransportError::Tls)?
+                .dangerous()
+                .with_custom_certificate_verifier(self.verifier.clone())
+                .with_no_client_auth(),
+        )
+    }
+
+    /// Returns the name sent in the Server Name Indication extension and
+    /// checked against certificates, falling back to the address of
+    /// `server`.
+    pub(crate) fn server_name(&self, server: SocketAddr) -> ServerName<'static> {
+        self.server_name
+            .clone()
+            .unwrap_or_else(|| ServerName::IpAddress(server.ip().into()))
+    }
+
+    /// Checks if the server presenting `chain` is authenticated, which is
+    /// always the case with the strict profile since the handshake fails
+    /// otherwise.
+    pub(crate) fn is_authenticated(
+        &self,
+        chain: Option<&[CertificateDer<'_>]>,
+        server_name: &ServerName<'_>,
+    ) -> bool {
+        match self.profile {
+            PrivacyProfile::Strict => true,
+            PrivacyProfile::Opportunistic => chain
+                .and_then(|chain| chain.split_first())
+                .is_some_and(|(end_entity, intermediates)| {
+                    self.verifier.authenticate(
+                        end_entity,
+                        intermediates,
+                        server_name,
+                        UnixTime::now(),
+                    )
+                }),
+        }
+    }
+}
+
+/// Extracts the TLS error wrapped by `error`, if any.
+pub(crate) fn tls_error(error: io::Error) -> TransportError {
+    match error
+        .get_ref()
+        .and_then(|inner| inner.downcast_ref::<rustls::Error>())
+    {
+        Some(e) => TransportError::Tls(e.clone()),
+        None => TransportError::Io(error),
+    }
+}
+
+/// Verifies server certificates according to a privacy profile.
+#[derive(Debug)]
+struct DotVerifier {
+    profile: PrivacyProfile,
+    spki_pins: Vec<[u8; 32]>,
+    webpki: Option<Arc<WebPkiServerVerifier>>,
+    provider: Arc<CryptoProvider>,
+}
+
+impl DotVerifier {
+    fn authenticate(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        now: UnixTime,
+    ) -> bool {
+        // Intermediates are not checked to be issuers of the end entity, so
+        // only the end entity can match a pin.
+        let pinned = spki_pin(end_entity).is_some_and(|pin| self.spki_pins.contains(&pin));
+        pinned
+            || self.webpki.as_ref().is_some_and(|webpki| {
+                webpki
+                    .verify_server_cert(end_entity, intermediates, server_name, &[], now)
+                    .is_ok()
+            })
+    }
+}
+
+impl ServerCertVerifier for DotVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        if self.profile == PrivacyProfile::Opportunistic
+            || self.authenticate(end_entity, intermediates, server_name, now)
+        {
+            return Ok(ServerCertVerified::assertion());
+        }
+        Err(rustls::Error::InvalidCertificate(
+            CertificateError::ApplicationVerificationFailure,
+        ))
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        certificate: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(
+            message,
+            certificate,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        certificate: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(
+            message,
+            certificate,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::{Header, KnownQClass, KnownQType, Question, QR};
+    use crate::record::rdata::EdnsOption;
+    use crate::transport::tcp::{read_framed, write_framed};
+    use rustls::pki_types::{PrivateKeyDer, PrivatePkcs8KeyDer};
+    use rustls::{ServerConfig, ServerConnection};
+    use std::net::TcpListener;
+    use std::thread;
+
+    struct Server {
+        addr: SocketAddr,
+        certificate: CertificateDer<'static>,
+        handle: thread::JoinHandle<Vec<usize>>,
+    }
+
+    /// Stands in for a DNS over TLS server with a self-signed certificate
+    /// for `dns.example`: accepts `connections` connections and answers
+    /// their queries until they are closed, returning the length of each
+    /// query received.
+    fn serve(connections: usize) -> Server {
+        serve_with_intermediates(connections, Vec::new())
+    }
+
+    /// Same as [`serve`], also sending `intermediates` after the
+    /// certificate of the server.
+    fn serve_with_intermediates(
+        connections: usize,
+        intermediates: Vec<CertificateDer<'static>>,
+    ) -> Server {
+        let key = rcgen::generate_simple_self_signed(vec!["dns.example".to_string()]).unwrap();
+        let certificate = key.cert.der().clone();
+        let chain = std::iter::once(certificate.clone())
+            .chain(intermediates)
+            .collect();
+        let private_key =
+            PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key.key_pair.serialize_der()));
+        let config =
+            ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()
+                .unwrap()
+                .with_no_client_auth()
+                .with_single_cert(chain, private_key)
+                .unwrap();
+        let config = Arc::new(config);
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handle = thread::spawn(move || {
+            let mut lengths = Vec::new();
+            for _ in 0..connections {
+                let socket = listener.accept().unwrap().0;
+                let connection = ServerConnection::new(config.clone()).unwrap();
+                let mut stream = StreamOwned::new(connection, socket);
+                while let Ok(bytes) = read_framed(&mut stream) {
+                    lengths.push(bytes.len());
+                    let mut response = Message::try_from(bytes.as_slice()).unwrap();
+                    response.header.qr = QR::Response;
+                    if write_framed(&mut stream, &response.to_bytes()).is_err() {
+                        break;
+                    }
+                }
+            }
+            lengths
+        });
+        Server {
+            addr,
+            certificate,
+            handle,
+        }
+    }
+
+    fn query() -> Message {
+        let mut message = Message::new(Header::new(0));
+        message.questions.push(Question {
+            q_name: Domain::from_presentation("example.com").unwrap(),
+            q_type: KnownQType::A.into(),
+            q_class: KnownQClass::IN.into(),
+        });
+        message
+    }
+
+    fn roots(certificate: &CertificateDer<'static>) -> RootCertStore {
+        let mut roots = RootCertStore::empty();
+        roots.add(certificate.clone()).unwrap();
+        roots
+    }
+
+    fn by_name(name: &str) -> Authentication {
+        Authentication {
+            name: Some(Domain::from_presentation(name).unwrap()),
+            spki_pins: Vec::new(),
+        }
+    }
+
+    fn client(
+        profile: PrivacyProfile,
+        authentication: Authentication,
+        roots: RootCertStore,
+    ) -> DotClient {
+        let mut client = DotClient::new(profile, authentication, roots).unwrap();
+        client.tcp.timeout = Duration::from_millis(500);
+        client
+    }
+
+    #[test]
+    fn strict_profile_authenticates_by_name_and_resumes_sessions() {
+        let server = serve(2);
+        let client = client(
+            PrivacyProfile::Strict,
+            by_name("dns.example"),
+            roots(&server.certificate),
+        );
+
+        let mut connection = client.connect(server.addr).unwrap();
+        assert!(connection.stream().is_authenticated());
+        assert!(!connection.stream().is_resumed());
+        let response = connection.query(&query()).unwrap();
+        assert_eq!(response.message.questions, query().questions);
+        drop(connection);
+
+        let mut connection = client.connect(server.addr).unwrap();
+        assert!(connection.stream().is_resumed());
+        connection.query(&query()).unwrap();
+        drop(connection);
+
+        let lengths = server.handle.join().unwrap();
+        assert_eq!(lengths.len(), 2);
+        assert!(lengths.iter().all(|len| len % QUERY_BLOCK_SIZE == 0));
+    }
+
+    #[test]
+    fn strict_profile_authenticates_by_spki_pin() {
+        let server = serve(1);
+        let authentication = Authentication {
+            name: None,
+            spki_pins: vec![spki_pin(&server.certificate).unwrap()],
+        };
+        let mut client = client(
+            PrivacyProfile::Strict,
+            authentication,
+            RootCertStore::empty(),
+        );
+        client.padding = false;
+
+        let mut connection = client.connect(server.addr).unwrap();
+        assert!(connection.stream().is_authenticated());
+        connection.query(&query()).unwrap();
+        drop(connection);
+
+        let lengths = server.handle.join().unwrap();
+        let unpadded = query().to_bytes().len();
+        assert!(lengths[0] > unpadded && lengths[0] % QUERY_BLOCK_SIZE != 0);
+    }
+
+    #[rstest::rstest]
+    #[case(by_name("other.example"), true)]
+    #[case(Authentication { name: None, spki_pins: vec![[0; 32]] }, false)]
+    fn strict_profile_rejects_unauthenticated_servers(
+        #[case] authentication: Authentication,
+        #[case] trust_certificate: bool,
+    ) {
+        let server = serve(1);
+        let roots = match trust_certificate {
+            true => roots(&server.certificate),
+            false => RootCertStore::empty(),
+        };
+        let client = client(PrivacyProfile::Strict, authentication, roots);
+        assert!(matches!(
+            client.connect(server.addr),
+            Err(TransportError::Tls(rustls::Error::InvalidCertificate(_)))
+        ));
+        server.handle.join().unwrap();
+    }
+
+    #[test]
+    fn strict_profile_rejects_pinned_intermediates() {
+        let pinned = rcgen::generate_simple_self_signed(vec!["dns.example".to_string()]).unwrap();
+        let pinned = pinned.cert.der().clone();
+        let server = serve_with_intermediates(1, vec![pinned.clone()]);
+        let authentication = Authentication {
+            name: None,
+            spki_pins: vec![spki_pin(&pinned).unwrap()],
+        };
+        let client = client(
+            PrivacyProfile::Strict,
+            authentication,
+            RootCertStore::empty(),
+        );
+        assert!(matches!(
+            client.connect(server.addr),
+            Err(TransportError::Tls(rustls::Error::InvalidCertificate(_)))
+        ));
+        server.handle.join().unwrap();
+    }
+
+    #[test]
+    fn opportunistic_profile_accepts_unauthenticated_servers() {
+        let server = serve(1);
+        let client = client(
+            PrivacyProfile::Opportunistic,
+            by_name("dns.example"),
+            RootCertStore::empty(),
+        );
+
+        let mut connection = client.connect(server.addr).unwrap();
+        assert!(!connection.stream().is_authenticated());
+        let response = connection.query(&query()).unwrap();
+        let edns = response.message.edns().unwrap();
+        assert!(edns.option(EdnsOption::PADDING).is_some());
+        drop(connection);
+        server.handle.join().unwrap();
+    }
+
+    #[test]
+    fn spki_pin_rejects_malformed_certificates() {
+        assert_eq!(spki_pin(&[0x30, 0x00]), None);
+    }
+}
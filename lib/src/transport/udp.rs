@@ -0,0 +1,244 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::message::Message;
+use crate::transport::error::TransportError;
+use crate::transport::Response;
+use rand::Rng;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+
+/// Number of random source ports tried before letting the operating system
+/// pick one.
+const BIND_ATTEMPTS: u32 = 10;
+
+/// Sends queries over UDP, retransmitting them until a matching response
+/// arrives.
+///
+/// Each query gets a random identifier and is sent from a random source
+/// port, and only responses from the server that match the query are
+/// accepted, making forged responses harder to inject. Other datagrams are
+/// ignored.
+///
+/// For more details, see [RFC 5452, Section 9].
+///
+/// [RFC 5452, Section 9]: https://datatracker.ietf.org/doc/html/rfc5452#section-9
+#[derive(Debug, Clone)]
+pub struct UdpClient {
+    /// Time to wait for a response after each transmission.
+    pub timeout: Duration,
+    /// Number of transmissions before giving up.
+    pub attempts: u32,
+}
+
+impl Default for UdpClient {
+    fn default() -> Self {
+        UdpClient {
+            timeout: Duration::from_secs(2),
+            attempts: 3,
+        }
+    }
+}
+
+impl UdpClient {
+    /// Sends `query` to `server`, replacing its identifier with a random
+    /// one, and returns the response whatever its `RCODE`.
+    ///
+    /// A truncated response is returned as is, and the query should then
+    /// be retried over TCP, as [`StubClient`](crate::transport::StubClient)
+    /// does.
+    pub fn query(&self, query: &Message, server: SocketAddr) -> Result<Response, TransportError> {
+        let mut query = query.clone();
+        query.header.id = rand::random();
+        let socket = bind_random_port(server)?;
+        let request = query.to_bytes();
+        let mut buffer = [0; 65535];
+
+        for _ in 0..self.attempts {
+            socket.send_to(&request, server)?;
+            let sent = Instant::now();
+            let deadline = sent + self.timeout;
+            while let Some(remaining) = deadline
+                .checked_duration_since(Instant::now())
+                .filter(|remaining| !remaining.is_zero())
+            {
+                socket.set_read_timeout(Some(remaining))?;
+                let (len, from) = match socket.recv_from(&mut buffer) {
+                    Ok(received) => received,
+                    Err(e) if is_timeout(&e) => break,
+                    Err(e) => return Err(e.into()),
+                };
+                if from != server {
+                    continue;
+                }
+                let Ok(message) = Message::try_from(&buffer[..len]) else {
+                    continue;
+                };
+                if query.is_answered_by(&message) {
+                    return Ok(Response {
+                        message,
+                        rtt: sent.elapsed(),
+                    });
+                }
+            }
+        }
+        Err(TransportError::Timeout(self.attempts))
+    }
+}
+
+/// Binds a socket to a random unprivileged port, falling back to a port
+/// chosen by the operating system.
+fn bind_random_port(server: SocketAddr) -> io::Result<UdpSocket> {
+    let ip: IpAddr = match server {
+        SocketAddr::V4(_) => Ipv4Addr::UNSPECIFIED.into(),
+        SocketAddr::V6(_) => Ipv6Addr::UNSPECIFIED.into(),
+    };
+    let mut rng = rand::thread_rng();
+    for _ in 0..BIND_ATTEMPTS {
+        match UdpSocket::bind((ip, rng.gen_range(1024..=u16::MAX))) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => continue,
+            result => return result,
+        }
+    }
+    UdpSocket::bind((ip, 0))
+}
+
+pub(crate) fn is_timeout(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Domain;
+    use crate::message::{Header, KnownQClass, KnownQType, Question, RCode, QR};
+    use std::thread;
+
+    fn query() -> Message {
+        let mut message = Message::new(Header::new(0));
+        message.header.rd = true;
+        message.questions.push(Question {
+            q_name: Domain::from_presentation("example.com").unwrap(),
+            q_type: KnownQType::A.into(),
+            q_class: KnownQClass::IN.into(),
+        });
+        message
+    }
+
+    fn client() -> UdpClient {
+        UdpClient {
+            timeout: Duration::from_millis(200),
+            attempts: 3,
+        }
+    }
+
+    fn answer(query: &Message) -> Message {
+        let mut response = query.clone();
+        response.header.qr = QR::Response;
+        response.header.ra = true;
+        response.header.r_code = RCode::NameError;
+        response
+    }
+
+    /// Stands in for a server: ignores the first `dropped` queries, answers
+    /// the next one with responses that do not match it, from the right and
+    /// a wrong address, then with a matching response, and returns the
+    /// number of queries received.
+    fn server(dropped: usize) -> (SocketAddr, thread::JoinHandle<usize>) {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = socket.local_addr().unwrap();
+        let handle = thread::spawn(move || {
+            let mut buffer = [0; 512];
+            for received in 1.. {
+                let (len, from) = socket.recv_from(&mut buffer).unwrap();
+                if received <= dropped {
+                    continue;
+                }
+                let query = Message::try_from(&buffer[..len]).unwrap();
+
+                let mut wrong_id = answer(&query);
+                wrong_id.header.id = wrong_id.header.id.wrapping_add(1);
+                let mut wrong_question = answer(&query);
+                wrong_question.questions[0].q_type = KnownQType::AAAA.into();
+                for stray in [wrong_id, wrong_question] {
+                    socket.send_to(&stray.to_bytes(), from).unwrap();
+                }
+                socket.send_to(b"garbage", from).unwrap();
+                let spoofer = UdpSocket::bind("127.0.0.1:0").unwrap();
+                spoofer.send_to(&answer(&query).to_bytes(), from).unwrap();
+
+                socket.send_to(&answer(&query).to_bytes(), from).unwrap();
+                return received;
+            }
+            unreachable!()
+        });
+        (addr, handle)
+    }
+
+    #[test]
+    fn query_retries_until_answered() {
+        let (addr, server) = server(1);
+        let response = client().query(&query(), addr).unwrap();
+        assert_eq!(response.message.header.r_code, RCode::NameError);
+        assert_eq!(response.message.questions, query().questions);
+        assert!(response.rtt < Duration::from_millis(200));
+        assert_eq!(server.join().unwrap(), 2);
+    }
+
+    #[test]
+    fn query_times_out() {
+        let silent = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = silent.local_addr().unwrap();
+        assert!(matches!(
+            client().query(&query(), addr),
+            Err(TransportError::Timeout(3))
+        ));
+
+        let mut buffer = [0; 512];
+        silent.set_nonblocking(true).unwrap();
+        let mut received = 0;
+        while silent.recv_from(&mut buffer).is_ok() {
+            received += 1;
+        }
+        assert_eq!(received, 3);
+    }
+
+    #[test]
+    fn query_randomizes_id_and_port() {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = socket.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let mut buffer = [0; 512];
+            let mut seen = Vec::new();
+            for _ in 0..4 {
+                let (len, from) = socket.recv_from(&mut buffer).unwrap();
+                let query = Message::try_from(&buffer[..len]).unwrap();
+                socket.send_to(&answer(&query).to_bytes(), from).unwrap();
+                seen.push((query.header.id, from.port()));
+            }
+            seen
+        });
+        for _ in 0..4 {
+            client().query(&query(), addr).unwrap();
+        }
+
+        let seen = server.join().unwrap();
+        assert!(seen.iter().any(|(id, _)| *id != seen[0].0));
+        assert!(seen.iter().any(|(_, port)| *port != seen[0].1));
+    }
+}
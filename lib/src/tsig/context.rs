@@ -0,0 +1,686 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::Domain;
+use crate::message::error::MessageTryFromError;
+use crate::message::{Header, KnownQClass, KnownQType, Message, RCode};
+use crate::record::rdata::Tsig;
+use crate::record::{RData, ResourceRecord};
+use crate::tsig::error::{TsigError, TsigRCode};
+use crate::tsig::key::{TsigAlgorithm, TsigKey};
+
+/// Fudge recommended by [RFC 8945, Section 10], in seconds.
+///
+/// [RFC 8945, Section 10]: https://datatracker.ietf.org/doc/html/rfc8945#section-10
+pub const DEFAULT_FUDGE: u16 = 300;
+
+/// Maximum number of unsigned messages allowed in a row in a multi-message
+/// response, see [RFC 8945, Section 5.3.1].
+///
+/// [RFC 8945, Section 5.3.1]: https://datatracker.ietf.org/doc/html/rfc8945#section-5.3.1
+const MAX_UNSIGNED: usize = 99;
+
+/// State of a `TSIG` transaction: a request, its response and, for zone
+/// transfers, the following messages of the response.
+///
+/// A client signs the request and verifies the responses, while a server
+/// verifies the request and signs the responses. Each MAC covers the
+/// previous one, so every message of the transaction must go through the
+/// same context in order.
+///
+/// For more details, see [RFC 8945, Section 5].
+///
+/// [RFC 8945, Section 5]: https://datatracker.ietf.org/doc/html/rfc8945#section-5
+#[derive(Debug)]
+pub struct TsigContext {
+    key: TsigKey,
+    fudge: u16,
+    prior_mac: Option<Vec<u8>>,
+    /// Number of messages signed or verified so far.
+    exchanged: usize,
+    /// Unsigned messages received since the last signed one.
+    unsigned: Vec<u8>,
+    unsigned_count: usize,
+}
+
+impl TsigContext {
+    pub fn new(key: TsigKey) -> Self {
+        TsigContext {
+            key,
+            fudge: DEFAULT_FUDGE,
+            prior_mac: None,
+            exchanged: 0,
+            unsigned: Vec::new(),
+            unsigned_count: 0,
+        }
+    }
+
+    /// Sets the fudge written in the signed messages, in seconds.
+    pub fn with_fudge(mut self, fudge: u16) -> Self {
+        self.fudge = fudge;
+        self
+    }
+
+    pub fn key(&self) -> &TsigKey {
+        &self.key
+    }
+
+    /// Returns the MAC of the last message signed or verified.
+    pub fn mac(&self) -> Option<&[u8]> {
+        self.prior_mac.as_deref()
+    }
+
+    /// Appends a `TSIG` record to `message`, signed at `now` in seconds
+    /// since the epoch.
+    ///
+    /// The first two messages of the transaction are signed with all the
+    /// `TSIG` variables, the following ones only with the timers.
+    pub fn sign(&mut self, message: &[u8], now: u64) -> Result<Vec<u8>, TsigError> {
+        self.append(message, now, 0, Vec::new())
+    }
+
+    /// Records a message of a multi-message response that is sent without
+    /// a `TSIG` record. It is covered by the next signed message.
+    pub fn skip(&mut self, message: &[u8]) {
+        self.unsigned.extend_from_slice(message);
+        self.unsigned_count += 1;
+    }
+
+    /// Signs a `BADTIME` response to a request that failed verification
+    /// with [`TsigError::BadTime`].
+    ///
+    /// The response keeps the time at which the request was signed, so that
+    /// the client can check it, and carries the time of the server in its
+    /// other data.
+    ///
+    /// For more details, see [RFC 8945, Section 5.2.3].
+    ///
+    /// [RFC 8945, Section 5.2.3]: https://datatracker.ietf.org/doc/html/rfc8945#section-5.2.3
+    pub fn sign_bad_time(
+        &mut self,
+        response: &[u8],
+        time_signed: u64,
+        now: u64,
+    ) -> Result<Vec<u8>, TsigError> {
+        let mut response = response.to_vec();
+        set_not_auth(&mut response);
+        let other_data = now.to_be_bytes()[2..].to_vec();
+        self.append(
+            &response,
+            time_signed,
+            TsigRCode::BadTime as u16,
+            other_data,
+        )
+    }
+
+    /// Verifies the `TSIG` record of `message` at `now`, in seconds since
+    /// the epoch, returning it.
+    ///
+    /// After the first two messages of the transaction, messages without
+    /// `TSIG` record are accepted and `None` is returned; they must be
+    /// followed by a signed message, see [`TsigContext::finish`]. The MAC
+    /// is checked before the time, and a message with a valid MAC but
+    /// outside of the fudge window still becomes the prior MAC of the
+    /// transaction, so that a `BADTIME` response can be signed.
+    ///
+    /// For more details, see [RFC 8945, Section 5.2] and
+    /// [RFC 8945, Section 5.3.1].
+    ///
+    /// [RFC 8945, Section 5.2]: https://datatracker.ietf.org/doc/html/rfc8945#section-5.2
+    /// [RFC 8945, Section 5.3.1]: https://datatracker.ietf.org/doc/html/rfc8945#section-5.3.1
+    pub fn verify(&mut self, message: &[u8], now: u64) -> Result<Option<Tsig>, TsigError> {
+        let Some((owner, tsig, stripped)) = split(message)? else {
+            if self.exchanged < 2 {
+                return Err(TsigError::Unsigned);
+            }
+            if self.unsigned_count >= MAX_UNSIGNED {
+                return Err(TsigError::TooManyUnsigned);
+            }
+            self.skip(message);
+            return Ok(None);
+        };
+
+        if owner != *self.key.name()
+            || TsigAlgorithm::from_name(&tsig.algorithm) != Some(self.key.algorithm())
+        {
+            return Err(TsigError::BadKey(owner));
+        }
+        // BADKEY and BADSIG responses cannot be signed.
+        if tsig.mac.is_empty() && tsig.error != 0 {
+            return Err(TsigError::Rejected(tsig.error));
+        }
+        let mac_len = self.key.algorithm().mac_len();
+        if tsig.mac.len() > mac_len {
+            return Err(TsigError::Malformed);
+        }
+        if tsig.mac.len() < mac_len.div_ceil(2).max(10) {
+            return Err(TsigError::BadTrunc(tsig.mac.len()));
+        }
+
+        let digest = self.digest(&stripped, &tsig);
+        if !self
+            .key
+            .algorithm()
+            .verify(self.key.secret(), &digest, &tsig.mac)
+        {
+            return Err(TsigError::BadSig);
+        }
+        self.advance(tsig.mac.clone());
+
+        if tsig.error != 0 {
+            return Err(TsigError::Rejected(tsig.error));
+        }
+        if now.abs_diff(tsig.time_signed) > u64::from(tsig.fudge) {
+            return Err(TsigError::BadTime {
+                time_signed: tsig.time_signed,
+                now,
+            });
+        }
+        Ok(Some(tsig))
+    }
+
+    /// Checks that the last message received was signed, as required at
+    /// the end of a multi-message response.
+    pub fn finish(&self) -> Result<(), TsigError> {
+        match self.unsigned_count {
+            0 => Ok(()),
+            _ => Err(TsigError::Unsigned),
+        }
+    }
+
+    fn append(
+        &mut self,
+        message: &[u8],
+        time_signed: u64,
+        error: u16,
+        other_data: Vec<u8>,
+    ) -> Result<Vec<u8>, TsigError> {
+        Header::try_from(message).map_err(MessageTryFromError::from)?;
+
+        let mut tsig = Tsig {
+            algorithm: self.key.algorithm().name(),
+            time_signed,
+            fudge: self.fudge,
+            mac: Vec::new(),
+            original_id: u16::from_be_bytes([message[0], message[1]]),
+            error,
+            other_data,
+        };
+        let digest = self.digest(message, &tsig);
+        tsig.mac = self.key.algorithm().mac(self.key.secret(), &digest);
+        self.advance(tsig.mac.clone());
+
+        Ok(append_tsig(message, self.key.name().clone(), tsig))
+    }
+
+    /// Returns the data covered by the MAC of `message`, given without its
+    /// `TSIG` record.
+    ///
+    /// For more details, see [RFC 8945, Section 4.3].
+    ///
+    /// [RFC 8945, Section 4.3]: https://datatracker.ietf.org/doc/html/rfc8945#section-4.3
+    fn digest(&self, message: &[u8], tsig: &Tsig) -> Vec<u8> {
+        let mut digest = Vec::new();
+        if let Some(prior_mac) = &self.prior_mac {
+            digest.extend_from_slice(&(prior_mac.len() as u16).to_be_bytes());
+            digest.extend_from_slice(prior_mac);
+        }
+        digest.extend_from_slice(&self.unsigned);
+        digest.extend_from_slice(message);
+
+        if self.exchanged < 2 {
+            digest.extend_from_slice(&self.key.name().to_canonical_bytes());
+            digest.extend_from_slice(&(KnownQClass::ANY as u16).to_be_bytes());
+            digest.extend_from_slice(&0u32.to_be_bytes());
+            digest.extend_from_slice(&tsig.algorithm.to_canonical_bytes());
+            digest.extend_from_slice(&tsig.time_signed.to_be_bytes()[2..]);
+            digest.extend_from_slice(&tsig.fudge.to_be_bytes());
+            digest.extend_from_slice(&tsig.error.to_be_bytes());
+            digest.extend_from_slice(&(tsig.other_data.len() as u16).to_be_bytes());
+            digest.extend_from_slice(&tsig.other_data);
+        } else {
+            digest.extend_from_slice(&tsig.time_signed.to_be_bytes()[2..]);
+            digest.extend_from_slice(&tsig.fudge.to_be_bytes());
+        }
+        digest
+    }
+
+    fn advance(&mut self, mac: Vec<u8>) {
+        self.prior_mac = Some(mac);
+        self.exchanged += 1;
+        self.unsigned.clear();
+        self.unsigned_count = 0;
+    }
+}
+
+/// Returns the key among `keys` that signed `message`.
+///
+/// A server looks up the key of a request with this function before
+/// verifying it with a [`TsigContext`].
+pub fn find_key<'a>(message: &[u8], keys: &'a [TsigKey]) -> Result<&'a TsigKey, TsigError> {
+    let (owner, tsig, _) = split(message)?.ok_or(TsigError::Unsigned)?;
+    let algorithm = TsigAlgorithm::from_name(&tsig.algorithm);
+    keys.iter()
+        .find(|key| *key.name() == owner && Some(key.algorithm()) == algorithm)
+        .ok_or(TsigError::BadKey(owner))
+}
+
+/// Appends to `response` an unsigned `TSIG` record reporting `error` to the
+/// signer of `request`, and sets its `RCODE` to `NOTAUTH`.
+///
+/// This is how servers answer requests failing verification with
+/// `BADKEY`, `BADSIG` or `BADTRUNC`, see [RFC 8945, Section 5.2].
+///
+/// [RFC 8945, Section 5.2]: https://datatracker.ietf.org/doc/html/rfc8945#section-5.2
+pub fn unsigned_error(
+    response: &[u8],
+    request: &[u8],
+    error: TsigRCode,
+) -> Result<Vec<u8>, TsigError> {
+    Header::try_from(response).map_err(MessageTryFromError::from)?;
+    let (owner, request_tsig, _) = split(request)?.ok_or(TsigError::Unsigned)?;
+
+    let mut response = response.to_vec();
+    set_not_auth(&mut response);
+    let tsig = Tsig {
+        algorithm: request_tsig.algorithm,
+        time_signed: request_tsig.time_signed,
+        fudge: request_tsig.fudge,
+        mac: Vec::new(),
+        original_id: u16::from_be_bytes([response[0], response[1]]),
+        error: error as u16,
+        other_data: Vec::new(),
+    };
+    Ok(append_tsig(&response, owner, tsig))
+}
+
+/// Splits the `TSIG` record off `message`, returning its owner, its `RDATA`
+/// and the message as it was before signing.
+fn split(message: &[u8]) -> Result<Option<(Domain, Tsig, Vec<u8>)>, TsigError> {
+    let (decoded, offsets) = Message::from_wire(message)?;
+    let Some(record) = decoded.tsig() else {
+        if decoded.records().any(|r| r.r_type == KnownQType::TSIG) {
+            return Err(TsigError::Malformed);
+        }
+        return Ok(None);
+    };
+    let RData::TSIG(tsig) = &record.rdata else {
+        unreachable!("Message::tsig() only returns TSIG records");
+    };
+    let tsig_count = decoded
+        .records()
+        .filter(|r| r.r_type == KnownQType::TSIG)
+        .count();
+    if tsig_count > 1 || record.r_class != KnownQClass::ANY || record.ttl != 0 {
+        return Err(TsigError::Malformed);
+    }
+
+    let offset = *offsets.last().expect("the TSIG record is an additional");
+    let mut stripped = message[..offset].to_vec();
+    stripped[0..2].copy_from_slice(&tsig.original_id.to_be_bytes());
+    stripped[10..12].copy_from_slice(&(decoded.header.ar_count - 1).to_be_bytes());
+    Ok(Some((record.name.clone(), tsig.clone(), stripped)))
+}
+
+fn append_tsig(message: &[u8], owner: Domain, tsig: Tsig) -> Vec<u8> {
+    let mut signed = message.to_vec();
+    let ar_count = u16::from_be_bytes([signed[10], signed[11]]) + 1;
+    signed[10..12].copy_from_slice(&ar_count.to_be_bytes());
+    let record = ResourceRecord {
+        name: owner,
+        r_type: KnownQType::TSIG.into(),
+        r_class: KnownQClass::ANY.into(),
+        ttl: 0,
+        rdata: RData::TSIG(tsig),
+    };
+    signed.extend_from_slice(&record.to_bytes());
+    signed
+}
+
+fn set_not_auth(message: &mut [u8]) {
+    message[3] = (message[3] & 0xf0) | RCode::NotAuth as u8;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::{Question, QR};
+    use crate::record::rdata::Soa;
+    use rstest::rstest;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn key(algorithm: TsigAlgorithm) -> TsigKey {
+        TsigKey::new(
+            Domain::from_presentation("test-key").unwrap(),
+            algorithm,
+            b"0123456789abcdef0123456789abcdef".to_vec(),
+        )
+    }
+
+    fn query(id: u16, r_type: KnownQType) -> Vec<u8> {
+        let mut header = Header::new(id);
+        header.rd = true;
+        let mut message = Message::new(header);
+        message.questions.push(Question {
+            q_name: Domain::from_presentation("example.com").unwrap(),
+            q_type: r_type.into(),
+            q_class: KnownQClass::IN.into(),
+        });
+        message.to_bytes()
+    }
+
+    fn response(request: &[u8], serial: u32) -> Vec<u8> {
+        let mut message = Message::try_from(request).unwrap();
+        message.header.qr = QR::Response;
+        message.answers.push(ResourceRecord {
+            name: Domain::from_presentation("example.com").unwrap(),
+            r_type: KnownQType::SOA.into(),
+            r_class: KnownQClass::IN.into(),
+            ttl: 3600,
+            rdata: RData::SOA(Soa {
+                m_name: Domain::from_presentation("ns.example.com").unwrap(),
+                r_name: Domain::from_presentation("admin.example.com").unwrap(),
+                serial,
+                refresh: 7200,
+                retry: 3600,
+                expire: 1209600,
+                minimum: 300,
+            }),
+        });
+        message.to_bytes()
+    }
+
+    #[test]
+    fn sign_matches_independent_mac() {
+        let message = query(0x1234, KnownQType::A);
+        assert_eq!(
+            data_encoding::HEXLOWER.encode(&message),
+            "123401000001000000000000076578616d706c6503636f6d0000010001"
+        );
+
+        let mut context = TsigContext::new(key(TsigAlgorithm::HmacSha256));
+        let signed = context.sign(&message, NOW).unwrap();
+
+        let decoded = Message::try_from(signed.as_slice()).unwrap();
+        assert_eq!(decoded.header.ar_count, 1);
+        let record = decoded.tsig().unwrap();
+        assert_eq!(record.name, Domain::from_presentation("test-key").unwrap());
+        let RData::TSIG(tsig) = &record.rdata else {
+            panic!("not a TSIG record");
+        };
+        assert_eq!(tsig.time_signed, NOW);
+        assert_eq!(tsig.fudge, DEFAULT_FUDGE);
+        assert_eq!(tsig.original_id, 0x1234);
+        // Computed with Python's hmac module over the hand-assembled
+        // message and TSIG variables.
+        assert_eq!(
+            data_encoding::BASE64.encode(&tsig.mac),
+            "xdnfpA3Ubp4NdcHqK/V0E/ctzf4kFvDoJdY8wex8h+w="
+        );
+        assert_eq!(context.mac(), Some(tsig.mac.as_slice()));
+    }
+
+    #[rstest]
+    #[case(TsigAlgorithm::HmacMd5)]
+    #[case(TsigAlgorithm::HmacSha256)]
+    #[case(TsigAlgorithm::HmacSha384)]
+    #[case(TsigAlgorithm::HmacSha512)]
+    fn request_response_round_trip(#[case] algorithm: TsigAlgorithm) {
+        let keys = [key(algorithm)];
+        let mut client = TsigContext::new(keys[0].clone());
+        let request = client.sign(&query(7, KnownQType::SOA), NOW).unwrap();
+
+        let mut server = TsigContext::new(find_key(&request, &keys).unwrap().clone());
+        assert!(server.verify(&request, NOW + 10).unwrap().is_some());
+        assert_eq!(server.mac(), client.mac());
+
+        let unsigned = response(&query(7, KnownQType::SOA), 1);
+        let signed = server.sign(&unsigned, NOW + 10).unwrap();
+        assert!(client.verify(&signed, NOW + 20).unwrap().is_some());
+        assert_eq!(client.finish(), Ok(()));
+    }
+
+    #[test]
+    fn verify_restores_original_id() {
+        let mut client = TsigContext::new(key(TsigAlgorithm::HmacSha256));
+        let mut request = client.sign(&query(7, KnownQType::A), NOW).unwrap();
+        // A forwarder may change the ID of the message.
+        request[0..2].copy_from_slice(&99u16.to_be_bytes());
+
+        let mut server = TsigContext::new(key(TsigAlgorithm::HmacSha256));
+        assert!(server.verify(&request, NOW).is_ok());
+    }
+
+    #[test]
+    fn verify_detects_tampering() {
+        let mut client = TsigContext::new(key(TsigAlgorithm::HmacSha256));
+        let mut request = client.sign(&query(7, KnownQType::A), NOW).unwrap();
+        // Turn the question into an MX query.
+        request[27] = 15;
+
+        let mut server = TsigContext::new(key(TsigAlgorithm::HmacSha256));
+        assert_eq!(server.verify(&request, NOW), Err(TsigError::BadSig));
+        assert_eq!(server.mac(), None);
+    }
+
+    #[test]
+    fn verify_rejects_unknown_key() {
+        let mut client = TsigContext::new(key(TsigAlgorithm::HmacSha256));
+        let request = client.sign(&query(7, KnownQType::A), NOW).unwrap();
+
+        let other = TsigKey::new(
+            Domain::from_presentation("other-key").unwrap(),
+            TsigAlgorithm::HmacSha256,
+            vec![0; 32],
+        );
+        let name = Domain::from_presentation("test-key").unwrap();
+        assert_eq!(
+            find_key(&request, std::slice::from_ref(&other)),
+            Err(TsigError::BadKey(name.clone()))
+        );
+        assert_eq!(
+            TsigContext::new(other).verify(&request, NOW),
+            Err(TsigError::BadKey(name.clone()))
+        );
+        // Same name, different algorithm.
+        assert_eq!(
+            find_key(&request, &[key(TsigAlgorithm::HmacSha512)]),
+            Err(TsigError::BadKey(name))
+        );
+
+        let error = unsigned_error(
+            &response(&query(7, KnownQType::A), 1),
+            &request,
+            TsigRCode::BadKey,
+        )
+        .unwrap();
+        let decoded = Message::try_from(error.as_slice()).unwrap();
+        assert_eq!(decoded.header.r_code, RCode::NotAuth);
+        assert_eq!(client.verify(&error, NOW), Err(TsigError::Rejected(17)));
+    }
+
+    #[test]
+    fn verify_rejects_wrong_secret() {
+        let mut client = TsigContext::new(key(TsigAlgorithm::HmacSha256));
+        let request = client.sign(&query(7, KnownQType::A), NOW).unwrap();
+
+        let wrong = TsigKey::new(
+            Domain::from_presentation("test-key").unwrap(),
+            TsigAlgorithm::HmacSha256,
+            vec![0; 32],
+        );
+        assert_eq!(
+            TsigContext::new(wrong).verify(&request, NOW),
+            Err(TsigError::BadSig)
+        );
+    }
+
+    #[rstest]
+    #[case(NOW - 300, true)]
+    #[case(NOW + 300, true)]
+    #[case(NOW - 301, false)]
+    #[case(NOW + 301, false)]
+    fn verify_checks_fudge(#[case] now: u64, #[case] valid: bool) {
+        let mut client = TsigContext::new(key(TsigAlgorithm::HmacSha256));
+        let request = client.sign(&query(7, KnownQType::A), NOW).unwrap();
+
+        let mut server = TsigContext::new(key(TsigAlgorithm::HmacSha256));
+        let result = server.verify(&request, now);
+        if valid {
+            assert!(result.is_ok());
+        } else {
+            assert_eq!(
+                result,
+                Err(TsigError::BadTime {
+                    time_signed: NOW,
+                    now
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn bad_time_response_is_signed() {
+        let mut client = TsigContext::new(key(TsigAlgorithm::HmacSha256));
+        let request = client.sign(&query(7, KnownQType::A), NOW).unwrap();
+
+        let server_time = NOW + 1000;
+        let mut server = TsigContext::new(key(TsigAlgorithm::HmacSha256));
+        let Err(TsigError::BadTime { time_signed, .. }) = server.verify(&request, server_time)
+        else {
+            panic!("request should be outside of the fudge window");
+        };
+        let response = server
+            .sign_bad_time(
+                &response(&query(7, KnownQType::A), 1),
+                time_signed,
+                server_time,
+            )
+            .unwrap();
+
+        let decoded = Message::try_from(response.as_slice()).unwrap();
+        assert_eq!(decoded.header.r_code, RCode::NotAuth);
+        let RData::TSIG(tsig) = &decoded.tsig().unwrap().rdata else {
+            panic!("not a TSIG record");
+        };
+        assert_eq!(tsig.time_signed, NOW);
+        assert_eq!(tsig.other_data, server_time.to_be_bytes()[2..]);
+        // The MAC is valid, so the client learns the server time.
+        assert_eq!(client.verify(&response, NOW), Err(TsigError::Rejected(18)));
+    }
+
+    #[test]
+    fn verify_rejects_unsigned_and_truncated() {
+        let mut server = TsigContext::new(key(TsigAlgorithm::HmacSha256));
+        assert_eq!(
+            server.verify(&query(7, KnownQType::A), NOW),
+            Err(TsigError::Unsigned)
+        );
+
+        let mut client = TsigContext::new(key(TsigAlgorithm::HmacSha256));
+        let request = client.sign(&query(7, KnownQType::A), NOW).unwrap();
+        let mut message = Message::try_from(request.as_slice()).unwrap();
+        let RData::TSIG(tsig) = &mut message.additionals[0].rdata else {
+            panic!("not a TSIG record");
+        };
+        tsig.mac.truncate(8);
+        assert_eq!(
+            server.verify(&message.to_bytes(), NOW),
+            Err(TsigError::BadTrunc(8))
+        );
+
+        message.additionals[0].ttl = 1;
+        assert_eq!(
+            server.verify(&message.to_bytes(), NOW),
+            Err(TsigError::Malformed)
+        );
+    }
+
+    #[test]
+    fn zone_transfer_stream() {
+        let keys = [key(TsigAlgorithm::HmacSha256)];
+        let mut client = TsigContext::new(keys[0].clone());
+        let unsigned_request = query(7, KnownQType::AXFR);
+        let request = client.sign(&unsigned_request, NOW).unwrap();
+
+        let mut server = TsigContext::new(keys[0].clone());
+        server.verify(&request, NOW).unwrap();
+
+        // The server signs the first and last messages and every other one.
+        let mut stream = Vec::new();
+        for serial in 0..6 {
+            let message = response(&unsigned_request, serial);
+            if serial == 0 || serial % 2 == 1 {
+                stream.push(server.sign(&message, NOW + u64::from(serial)).unwrap());
+            } else {
+                server.skip(&message);
+                stream.push(message);
+            }
+        }
+
+        let verified: Vec<bool> = stream
+            .iter()
+            .map(|message| client.verify(message, NOW + 5).unwrap().is_some())
+            .collect();
+        assert_eq!(verified, [true, true, false, true, false, true]);
+        assert_eq!(client.finish(), Ok(()));
+    }
+
+    #[test]
+    fn zone_transfer_stream_failures() {
+        let mut client = TsigContext::new(key(TsigAlgorithm::HmacSha256));
+        let unsigned_request = query(7, KnownQType::AXFR);
+        let request = client.sign(&unsigned_request, NOW).unwrap();
+        let mut server = TsigContext::new(key(TsigAlgorithm::HmacSha256));
+        server.verify(&request, NOW).unwrap();
+
+        // The first message of the response must be signed.
+        let first = response(&unsigned_request, 0);
+        assert_eq!(
+            TsigContext::new(key(TsigAlgorithm::HmacSha256))
+                .verify(&first, NOW)
+                .err(),
+            Some(TsigError::Unsigned)
+        );
+        let first = server.sign(&first, NOW).unwrap();
+        client.verify(&first, NOW).unwrap();
+
+        for serial in 1..=MAX_UNSIGNED as u32 {
+            let message = response(&unsigned_request, serial);
+            assert_eq!(client.verify(&message, NOW), Ok(None));
+        }
+        assert_eq!(client.finish(), Err(TsigError::Unsigned));
+        assert_eq!(
+            client.verify(&response(&unsigned_request, 100), NOW),
+            Err(TsigError::TooManyUnsigned)
+        );
+
+        // Dropping one of the unsigned messages breaks the next MAC.
+        let mut client = TsigContext::new(key(TsigAlgorithm::HmacSha256));
+        let mut server = TsigContext::new(key(TsigAlgorithm::HmacSha256));
+        server
+            .verify(&client.sign(&unsigned_request, NOW).unwrap(), NOW)
+            .unwrap();
+        client
+            .verify(
+                &server.sign(&response(&unsigned_request, 0), NOW).unwrap(),
+                NOW,
+            )
+            .unwrap();
+        server.skip(&response(&unsigned_request, 1));
+        let last = server.sign(&response(&unsigned_request, 2), NOW).unwrap();
+        assert_eq!(client.verify(&last, NOW), Err(TsigError::BadSig));
+    }
+}
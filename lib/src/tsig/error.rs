@@ -0,0 +1,174 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::Domain;
+use crate::message::error::MessageTryFromError;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum TsigError {
+    /// The message could not be decoded.
+    Message(MessageTryFromError),
+    /// The message carries no `TSIG` record, but one is required.
+    Unsigned,
+    /// The `TSIG` record is not the last record of the additional section,
+    /// or its class or TTL are invalid.
+    Malformed,
+    /// More unsigned messages were received in a row than allowed.
+    TooManyUnsigned,
+    /// The key name or algorithm are not known to the verifier.
+    BadKey(Domain),
+    /// The MAC does not match.
+    BadSig,
+    /// The message was signed outside of the allowed fudge window.
+    BadTime { time_signed: u64, now: u64 },
+    /// The MAC is truncated below the accepted length.
+    BadTrunc(usize),
+    /// The peer answered with a `TSIG` error code.
+    Rejected(u16),
+}
+
+impl TsigError {
+    /// Returns the `TSIG` error code to report to the signer of a message
+    /// that failed verification with this error, if any.
+    pub fn rcode(&self) -> Option<TsigRCode> {
+        match self {
+            Self::BadKey(_) => Some(TsigRCode::BadKey),
+            Self::BadSig => Some(TsigRCode::BadSig),
+            Self::BadTime { .. } => Some(TsigRCode::BadTime),
+            Self::BadTrunc(_) => Some(TsigRCode::BadTrunc),
+            _ => None,
+        }
+    }
+}
+
+impl From<MessageTryFromError> for TsigError {
+    fn from(error: MessageTryFromError) -> TsigError {
+        TsigError::Message(error)
+    }
+}
+
+impl fmt::Display for TsigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Message(e) => e.fmt(f),
+            Self::Unsigned => write!(f, "message is not signed with TSIG"),
+            Self::Malformed => write!(f, "TSIG record is malformed"),
+            Self::TooManyUnsigned => write!(f, "too many unsigned messages in a row"),
+            Self::BadKey(name) => write!(f, "TSIG key '{}' is not known", name),
+            Self::BadSig => write!(f, "TSIG signature does not match"),
+            Self::BadTime { time_signed, now } => write!(
+                f,
+                "message signed at '{}' is outside of the fudge window at '{}'",
+                time_signed, now
+            ),
+            Self::BadTrunc(len) => write!(f, "TSIG MAC truncated to {} octets", len),
+            Self::Rejected(error) => write!(f, "peer rejected the TSIG with error '{}'", error),
+        }
+    }
+}
+
+impl Error for TsigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Message(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Error codes carried in the `Error` field of a `TSIG` record.
+///
+/// For more details, see [RFC 8945, Section 3].
+///
+/// [RFC 8945, Section 3]: https://datatracker.ietf.org/doc/html/rfc8945#section-3
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[repr(u16)]
+pub enum TsigRCode {
+    BadSig = 16,
+    BadKey = 17,
+    BadTime = 18,
+    BadTrunc = 22,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum KeyConfigError {
+    UnexpectedToken(String),
+    UnexpectedEnd,
+    MissingAlgorithm(String),
+    MissingSecret(String),
+    UnknownAlgorithm(String),
+    InvalidSecret(String),
+    InvalidName(String),
+}
+
+impl fmt::Display for KeyConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnexpectedToken(token) => write!(f, "unexpected '{}' in key clause", token),
+            Self::UnexpectedEnd => write!(f, "key clause ends unexpectedly"),
+            Self::MissingAlgorithm(name) => write!(f, "key '{}' has no algorithm", name),
+            Self::MissingSecret(name) => write!(f, "key '{}' has no secret", name),
+            Self::UnknownAlgorithm(algorithm) => {
+                write!(f, "TSIG algorithm '{}' is not supported", algorithm)
+            }
+            Self::InvalidSecret(name) => write!(f, "key '{}' has an invalid secret", name),
+            Self::InvalidName(name) => write!(f, "'{}' is not a valid key name", name),
+        }
+    }
+}
+
+impl Error for KeyConfigError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(TsigError::Unsigned, "message is not signed with TSIG")]
+    #[case(TsigError::BadKey(Domain::from_presentation("key.example").unwrap()), "TSIG key 'key.example' is not known")]
+    #[case(TsigError::BadSig, "TSIG signature does not match")]
+    #[case(
+        TsigError::BadTime { time_signed: 10, now: 400 },
+        "message signed at '10' is outside of the fudge window at '400'"
+    )]
+    #[case(TsigError::BadTrunc(4), "TSIG MAC truncated to 4 octets")]
+    #[case(TsigError::Rejected(17), "peer rejected the TSIG with error '17'")]
+    fn tsig_error_display(#[case] err: TsigError, #[case] msg: &str) {
+        assert_eq!(err.to_string(), msg);
+    }
+
+    #[rstest]
+    #[case(TsigError::BadSig, Some(TsigRCode::BadSig))]
+    #[case(TsigError::BadKey(Domain::new()), Some(TsigRCode::BadKey))]
+    #[case(TsigError::BadTime { time_signed: 0, now: 0 }, Some(TsigRCode::BadTime))]
+    #[case(TsigError::BadTrunc(4), Some(TsigRCode::BadTrunc))]
+    #[case(TsigError::Unsigned, None)]
+    fn tsig_error_rcode(#[case] err: TsigError, #[case] expected: Option<TsigRCode>) {
+        assert_eq!(err.rcode(), expected);
+    }
+
+    #[rstest]
+    #[case(KeyConfigError::UnexpectedEnd, "key clause ends unexpectedly")]
+    #[case(KeyConfigError::MissingSecret("k".to_string()), "key 'k' has no secret")]
+    #[case(
+        KeyConfigError::UnknownAlgorithm("hmac-sha1".to_string()),
+        "TSIG algorithm 'hmac-sha1' is not supported"
+    )]
+    fn key_config_error_display(#[case] err: KeyConfigError, #[case] msg: &str) {
+        assert_eq!(err.to_string(), msg);
+    }
+}
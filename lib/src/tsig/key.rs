@@ -0,0 +1,418 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::Domain;
+use crate::tsig::error::KeyConfigError;
+use hmac::{Hmac, Mac};
+use md5::Md5;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::{Sha256, Sha384, Sha512};
+use std::fmt;
+use std::str::FromStr;
+
+/// HMAC algorithms usable with `TSIG`.
+///
+/// For more details, see [RFC 8945, Section 6].
+///
+/// [RFC 8945, Section 6]: https://datatracker.ietf.org/doc/html/rfc8945#section-6
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TsigAlgorithm {
+    /// Kept for interoperability with older peers only.
+    HmacMd5,
+    HmacSha256,
+    HmacSha384,
+    HmacSha512,
+}
+
+impl TsigAlgorithm {
+    /// Returns the name identifying the algorithm in `TSIG` records.
+    pub fn name(&self) -> Domain {
+        Domain::from_presentation(match self {
+            Self::HmacMd5 => "hmac-md5.sig-alg.reg.int",
+            Self::HmacSha256 => "hmac-sha256",
+            Self::HmacSha384 => "hmac-sha384",
+            Self::HmacSha512 => "hmac-sha512",
+        })
+        .expect("algorithm names are valid domains")
+    }
+
+    /// Returns the algorithm identified by `name` in a `TSIG` record.
+    pub fn from_name(name: &Domain) -> Option<Self> {
+        [
+            Self::HmacMd5,
+            Self::HmacSha256,
+            Self::HmacSha384,
+            Self::HmacSha512,
+        ]
+        .into_iter()
+        .find(|algorithm| algorithm.name() == *name)
+    }
+
+    /// Returns the length of an untruncated MAC, in octets.
+    pub fn mac_len(&self) -> usize {
+        match self {
+            Self::HmacMd5 => 16,
+            Self::HmacSha256 => 32,
+            Self::HmacSha384 => 48,
+            Self::HmacSha512 => 64,
+        }
+    }
+
+    /// Computes the MAC of `data` with `secret`.
+    pub fn mac(&self, secret: &[u8], data: &[u8]) -> Vec<u8> {
+        match self {
+            Self::HmacMd5 => mac::<Hmac<Md5>>(secret, data),
+            Self::HmacSha256 => mac::<Hmac<Sha256>>(secret, data),
+            Self::HmacSha384 => mac::<Hmac<Sha384>>(secret, data),
+            Self::HmacSha512 => mac::<Hmac<Sha512>>(secret, data),
+        }
+    }
+
+    /// Checks in constant time that `mac` is the MAC of `data` with
+    /// `secret`, possibly truncated to its leftmost octets.
+    pub fn verify(&self, secret: &[u8], data: &[u8], mac: &[u8]) -> bool {
+        match self {
+            Self::HmacMd5 => keyed::<Hmac<Md5>>(secret, data).verify_truncated_left(mac),
+            Self::HmacSha256 => keyed::<Hmac<Sha256>>(secret, data).verify_truncated_left(mac),
+            Self::HmacSha384 => keyed::<Hmac<Sha384>>(secret, data).verify_truncated_left(mac),
+            Self::HmacSha512 => keyed::<Hmac<Sha512>>(secret, data).verify_truncated_left(mac),
+        }
+        .is_ok()
+    }
+}
+
+fn mac<M: Mac + hmac::digest::KeyInit>(secret: &[u8], data: &[u8]) -> Vec<u8> {
+    keyed::<M>(secret, data).finalize().into_bytes().to_vec()
+}
+
+fn keyed<M: Mac + hmac::digest::KeyInit>(secret: &[u8], data: &[u8]) -> M {
+    let mut mac = <M as Mac>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac
+}
+
+impl fmt::Display for TsigAlgorithm {
+    /// Formats the algorithm as named in BIND configuration files.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::HmacMd5 => write!(f, "hmac-md5"),
+            Self::HmacSha256 => write!(f, "hmac-sha256"),
+            Self::HmacSha384 => write!(f, "hmac-sha384"),
+            Self::HmacSha512 => write!(f, "hmac-sha512"),
+        }
+    }
+}
+
+impl FromStr for TsigAlgorithm {
+    type Err = KeyConfigError;
+
+    /// Parses an algorithm either as named in BIND configuration files or
+    /// by its full `TSIG` algorithm name.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().trim_end_matches('.') {
+            "hmac-md5" | "hmac-md5.sig-alg.reg.int" => Ok(Self::HmacMd5),
+            "hmac-sha256" => Ok(Self::HmacSha256),
+            "hmac-sha384" => Ok(Self::HmacSha384),
+            "hmac-sha512" => Ok(Self::HmacSha512),
+            _ => Err(KeyConfigError::UnknownAlgorithm(s.to_string())),
+        }
+    }
+}
+
+/// Shared secret used to sign and verify messages with `TSIG`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TsigKey {
+    name: Domain,
+    algorithm: TsigAlgorithm,
+    secret: Vec<u8>,
+}
+
+impl TsigKey {
+    pub fn new(name: Domain, algorithm: TsigAlgorithm, secret: Vec<u8>) -> Self {
+        TsigKey {
+            name,
+            algorithm,
+            secret,
+        }
+    }
+
+    /// Generates a random key with a secret as long as the MAC of
+    /// `algorithm`, like `tsig-keygen` does.
+    pub fn generate(name: Domain, algorithm: TsigAlgorithm) -> Self {
+        let mut secret = vec![0; algorithm.mac_len()];
+        OsRng.fill_bytes(&mut secret);
+        TsigKey::new(name, algorithm, secret)
+    }
+
+    pub fn name(&self) -> &Domain {
+        &self.name
+    }
+
+    pub fn algorithm(&self) -> TsigAlgorithm {
+        self.algorithm
+    }
+
+    pub fn secret(&self) -> &[u8] {
+        &self.secret
+    }
+
+    /// Formats the key as a BIND `key` clause, in the same layout as the
+    /// output of `tsig-keygen`.
+    pub fn to_bind_config(&self) -> String {
+        format!(
+            "key \"{}\" {{\n\talgorithm {};\n\tsecret \"{}\";\n}};\n",
+            self.name,
+            self.algorithm,
+            data_encoding::BASE64.encode(&self.secret)
+        )
+    }
+}
+
+impl fmt::Debug for TsigKey {
+    /// Formats the key without revealing its secret.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TsigKey")
+            .field("name", &self.name)
+            .field("algorithm", &self.algorithm)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Parses the `key` clauses of a BIND configuration file, such as the
+/// output of `tsig-keygen`.
+///
+/// Comments in the `#`, `//` and `/* */` styles are ignored. Statements
+/// other than `algorithm` and `secret` are rejected.
+pub fn parse_bind_keys(config: &str) -> Result<Vec<TsigKey>, KeyConfigError> {
+    let tokens = tokenize(config)?;
+    let mut tokens = tokens.iter().map(String::as_str);
+    let mut keys = Vec::new();
+
+    while let Some(token) = tokens.next() {
+        if token != "key" {
+            return Err(KeyConfigError::UnexpectedToken(token.to_string()));
+        }
+        let name = next(&mut tokens)?;
+        expect(&mut tokens, "{")?;
+
+        let mut algorithm = None;
+        let mut secret = None;
+        loop {
+            match next(&mut tokens)? {
+                "}" => break,
+                "algorithm" => algorithm = Some(next(&mut tokens)?.parse::<TsigAlgorithm>()?),
+                "secret" => {
+                    let encoded = next(&mut tokens)?;
+                    secret = Some(
+                        data_encoding::BASE64
+                            .decode(encoded.as_bytes())
+                            .map_err(|_| KeyConfigError::InvalidSecret(name.to_string()))?,
+                    );
+                }
+                other => return Err(KeyConfigError::UnexpectedToken(other.to_string())),
+            }
+            expect(&mut tokens, ";")?;
+        }
+        expect(&mut tokens, ";")?;
+
+        let domain = Domain::from_presentation(name)
+            .map_err(|_| KeyConfigError::InvalidName(name.to_string()))?;
+        keys.push(TsigKey::new(
+            domain,
+            algorithm.ok_or_else(|| KeyConfigError::MissingAlgorithm(name.to_string()))?,
+            secret.ok_or_else(|| KeyConfigError::MissingSecret(name.to_string()))?,
+        ));
+    }
+
+    Ok(keys)
+}
+
+fn next<'a>(tokens: &mut impl Iterator<Item = &'a str>) -> Result<&'a str, KeyConfigError> {
+    tokens.next().ok_or(KeyConfigError::UnexpectedEnd)
+}
+
+fn expect<'a>(
+    tokens: &mut impl Iterator<Item = &'a str>,
+    expected: &str,
+) -> Result<(), KeyConfigError> {
+    match next(tokens)? {
+        token if token == expected => Ok(()),
+        token => Err(KeyConfigError::UnexpectedToken(token.to_string())),
+    }
+}
+
+/// Splits a configuration into words, quoted strings and the `{`, `}` and
+/// `;` punctuation, dropping comments. Quotes are removed from strings.
+fn tokenize(config: &str) -> Result<Vec<String>, KeyConfigError> {
+    let mut tokens = Vec::new();
+    let mut chars = config.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '#' => while chars.next_if(|&c| c != '\n').is_some() {},
+            '/' if chars.next_if_eq(&'/').is_some() => {
+                while chars.next_if(|&c| c != '\n').is_some() {}
+            }
+            '/' if chars.next_if_eq(&'*').is_some() => loop {
+                match chars.next() {
+                    Some('*') if chars.next_if_eq(&'/').is_some() => break,
+                    Some(_) => {}
+                    None => return Err(KeyConfigError::UnexpectedEnd),
+                }
+            },
+            '{' | '}' | ';' => tokens.push(c.to_string()),
+            '"' => {
+                let mut string = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => string.push(c),
+                        None => return Err(KeyConfigError::UnexpectedEnd),
+                    }
+                }
+                tokens.push(string);
+            }
+            c => {
+                let mut word = c.to_string();
+                while let Some(c) =
+                    chars.next_if(|&c| !c.is_whitespace() && !matches!(c, '{' | '}' | ';' | '"'))
+                {
+                    word.push(c);
+                }
+                tokens.push(word);
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(TsigAlgorithm::HmacMd5, "hmac-md5.sig-alg.reg.int", "hmac-md5", 16)]
+    #[case(TsigAlgorithm::HmacSha256, "hmac-sha256", "hmac-sha256", 32)]
+    #[case(TsigAlgorithm::HmacSha384, "hmac-sha384", "hmac-sha384", 48)]
+    #[case(TsigAlgorithm::HmacSha512, "hmac-sha512", "hmac-sha512", 64)]
+    fn tsig_algorithm_names(
+        #[case] algorithm: TsigAlgorithm,
+        #[case] name: &str,
+        #[case] bind_name: &str,
+        #[case] mac_len: usize,
+    ) {
+        let domain = Domain::from_presentation(name).unwrap();
+        assert_eq!(algorithm.name(), domain);
+        assert_eq!(TsigAlgorithm::from_name(&domain), Some(algorithm));
+        assert_eq!(algorithm.to_string(), bind_name);
+        assert_eq!(bind_name.parse::<TsigAlgorithm>(), Ok(algorithm));
+        assert_eq!(name.to_uppercase().parse::<TsigAlgorithm>(), Ok(algorithm));
+        assert_eq!(algorithm.mac(b"secret", b"data").len(), mac_len);
+    }
+
+    #[test]
+    fn tsig_algorithm_unknown() {
+        let domain = Domain::from_presentation("hmac-sha1").unwrap();
+        assert_eq!(TsigAlgorithm::from_name(&domain), None);
+        assert_eq!(
+            "hmac-sha1".parse::<TsigAlgorithm>(),
+            Err(KeyConfigError::UnknownAlgorithm("hmac-sha1".to_string()))
+        );
+    }
+
+    #[test]
+    fn tsig_algorithm_mac() {
+        // RFC 4231, test case 2.
+        let mac = TsigAlgorithm::HmacSha256.mac(b"Jefe", b"what do ya want for nothing?");
+        assert_eq!(
+            data_encoding::HEXLOWER.encode(&mac),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert!(TsigAlgorithm::HmacSha256.verify(b"Jefe", b"what do ya want for nothing?", &mac));
+        assert!(TsigAlgorithm::HmacSha256.verify(
+            b"Jefe",
+            b"what do ya want for nothing?",
+            &mac[..16]
+        ));
+        assert!(!TsigAlgorithm::HmacSha256.verify(b"Jefe", b"what do ya want?", &mac));
+    }
+
+    #[test]
+    fn tsig_key_bind_config_round_trip() {
+        let name = Domain::from_presentation("transfer.example.com").unwrap();
+        let key = TsigKey::generate(name, TsigAlgorithm::HmacSha512);
+        assert_eq!(key.secret().len(), 64);
+
+        let config = key.to_bind_config();
+        assert!(config.starts_with("key \"transfer.example.com\" {\n\talgorithm hmac-sha512;\n"));
+        assert_eq!(parse_bind_keys(&config), Ok(vec![key]));
+    }
+
+    #[test]
+    fn tsig_key_debug_hides_secret() {
+        let key = TsigKey::new(
+            Domain::new(),
+            TsigAlgorithm::HmacSha256,
+            b"hunter2".to_vec(),
+        );
+        assert!(!format!("{:?}", key).contains("104"));
+    }
+
+    #[test]
+    fn parse_bind_keys_with_comments() {
+        let config = r#"
+            # Transfers from the primary.
+            key "xfr.example." {
+                algorithm HMAC-SHA256; // case does not matter
+                /* generated by
+                   tsig-keygen */
+                secret "c2VjcmV0";
+            };
+            key update-key { secret "AAEC"; algorithm hmac-md5; };
+        "#;
+        let keys = parse_bind_keys(config).unwrap();
+        assert_eq!(
+            keys,
+            vec![
+                TsigKey::new(
+                    Domain::from_presentation("xfr.example").unwrap(),
+                    TsigAlgorithm::HmacSha256,
+                    b"secret".to_vec()
+                ),
+                TsigKey::new(
+                    Domain::from_presentation("update-key").unwrap(),
+                    TsigAlgorithm::HmacMd5,
+                    vec![0, 1, 2]
+                ),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case("options { };", KeyConfigError::UnexpectedToken("options".to_string()))]
+    #[case("key k { algorithm hmac-sha256; }", KeyConfigError::UnexpectedEnd)]
+    #[case("key k { secret \"AAEC\"; };", KeyConfigError::MissingAlgorithm("k".to_string()))]
+    #[case("key k { algorithm hmac-sha256; };", KeyConfigError::MissingSecret("k".to_string()))]
+    #[case("key k { algorithm hmac-sha1; };", KeyConfigError::UnknownAlgorithm("hmac-sha1".to_string()))]
+    #[case("key k { algorithm hmac-sha256; secret \"!\"; };", KeyConfigError::InvalidSecret("k".to_string()))]
+    #[case("key k { keyid 1; };", KeyConfigError::UnexpectedToken("keyid".to_string()))]
+    #[case("key k { /* unterminated", KeyConfigError::UnexpectedEnd)]
+    fn parse_bind_keys_failure(#[case] config: &str, #[case] expected: KeyConfigError) {
+        assert_eq!(parse_bind_keys(config), Err(expected));
+    }
+}
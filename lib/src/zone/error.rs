@@ -0,0 +1,290 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::error::TryFromError;
+use crate::domain::Domain;
+use crate::message::error::QTypeFromStrError;
+use crate::message::QClass;
+use crate::record::error::RDataParseError;
+use crate::zone::parser::MAX_GENERATE_RECORDS;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq)]
+pub enum ZoneError {
+    OutOfZone(Domain),
+    ClassMismatch(QClass),
+}
+
+impl fmt::Display for ZoneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::OutOfZone(name) => write!(f, "'{}' is outside of the zone", name),
+            Self::ClassMismatch(class) => {
+                write!(f, "class '{}' does not match the class of the zone", class)
+            }
+        }
+    }
+}
+
+impl Error for ZoneError {}
+
+#[derive(Debug, PartialEq)]
+pub struct ZonemdSchemeTryFromError(pub u8);
+
+impl fmt::Display for ZonemdSchemeTryFromError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ZONEMD scheme '{}' is not supported", self.0)
+    }
+}
+
+impl Error for ZonemdSchemeTryFromError {}
+
+#[derive(Debug, PartialEq)]
+pub struct ZonemdAlgorithmTryFromError(pub u8);
+
+impl fmt::Display for ZonemdAlgorithmTryFromError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ZONEMD hash algorithm '{}' is not supported", self.0)
+    }
+}
+
+impl Error for ZonemdAlgorithmTryFromError {}
+
+/// Errors found while computing or verifying the digest of a zone, see
+/// [RFC 8976, Section 4].
+///
+/// [RFC 8976, Section 4]: https://datatracker.ietf.org/doc/html/rfc8976#section-4
+#[derive(Debug, PartialEq)]
+pub enum ZonemdError {
+    MissingSoa,
+    MissingZonemd,
+    /// No `ZONEMD` record has the serial of the `SOA` record.
+    SerialMismatch {
+        zonemd: u32,
+        soa: u32,
+    },
+    /// No `ZONEMD` record uses a supported scheme and hash algorithm.
+    Unsupported,
+    DuplicateDigest {
+        scheme: u8,
+        hash_algorithm: u8,
+    },
+    DigestMismatch,
+    Zone(ZoneError),
+}
+
+impl From<ZoneError> for ZonemdError {
+    fn from(error: ZoneError) -> ZonemdError {
+        ZonemdError::Zone(error)
+    }
+}
+
+impl fmt::Display for ZonemdError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingSoa => write!(f, "zone apex has no SOA record"),
+            Self::MissingZonemd => write!(f, "zone apex has no ZONEMD records"),
+            Self::SerialMismatch { zonemd, soa } => write!(
+                f,
+                "ZONEMD serial {} does not match SOA serial {}",
+                zonemd, soa
+            ),
+            Self::Unsupported => {
+                write!(f, "no ZONEMD record uses a supported scheme and algorithm")
+            }
+            Self::DuplicateDigest {
+                scheme,
+                hash_algorithm,
+            } => write!(
+                f,
+                "several ZONEMD records use scheme {} and hash algorithm {}",
+                scheme, hash_algorithm
+            ),
+            Self::DigestMismatch => write!(f, "zone digest does not match ZONEMD"),
+            Self::Zone(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for ZonemdError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Zone(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Error found while parsing a master file, with the file and line where
+/// the offending entry starts.
+#[derive(Debug)]
+pub struct ParseError {
+    pub file: PathBuf,
+    pub line: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.file.display(), self.line, self.kind)
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.kind.source()
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseErrorKind {
+    Io(io::Error),
+    UnbalancedParentheses,
+    UnterminatedQuote,
+    UnknownDirective(String),
+    /// A directive has missing or unexpected arguments.
+    InvalidDirective(&'static str),
+    IncludeTooDeep,
+    InvalidTtl(String),
+    MissingTtl,
+    MissingOwner,
+    MissingType,
+    InvalidName(String, TryFromError),
+    InvalidType(QTypeFromStrError),
+    InvalidRData(RDataParseError),
+    InvalidGenerate(String),
+    /// A `$GENERATE` range expands to the given number of records, more
+    /// than the parser accepts.
+    GenerateTooLarge(u64),
+    Zone(ZoneError),
+}
+
+impl From<io::Error> for ParseErrorKind {
+    fn from(error: io::Error) -> ParseErrorKind {
+        ParseErrorKind::Io(error)
+    }
+}
+
+impl From<QTypeFromStrError> for ParseErrorKind {
+    fn from(error: QTypeFromStrError) -> ParseErrorKind {
+        ParseErrorKind::InvalidType(error)
+    }
+}
+
+impl From<RDataParseError> for ParseErrorKind {
+    fn from(error: RDataParseError) -> ParseErrorKind {
+        ParseErrorKind::InvalidRData(error)
+    }
+}
+
+impl From<ZoneError> for ParseErrorKind {
+    fn from(error: ZoneError) -> ParseErrorKind {
+        ParseErrorKind::Zone(error)
+    }
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(e) => e.fmt(f),
+            Self::UnbalancedParentheses => write!(f, "unbalanced parentheses"),
+            Self::UnterminatedQuote => write!(f, "unterminated quoted string"),
+            Self::UnknownDirective(name) => write!(f, "unknown directive '{}'", name),
+            Self::InvalidDirective(name) => write!(f, "invalid arguments for {}", name),
+            Self::IncludeTooDeep => write!(f, "too many nested $INCLUDE directives"),
+            Self::InvalidTtl(text) => write!(f, "invalid TTL '{}'", text),
+            Self::MissingTtl => write!(f, "record has no TTL and no default TTL is set"),
+            Self::MissingOwner => write!(f, "record has no owner name"),
+            Self::MissingType => write!(f, "record has no type"),
+            Self::InvalidName(text, e) => write!(f, "invalid name '{}': {}", text, e),
+            Self::InvalidType(e) => e.fmt(f),
+            Self::InvalidRData(e) => e.fmt(f),
+            Self::InvalidGenerate(text) => write!(f, "invalid $GENERATE template '{}'", text),
+            Self::GenerateTooLarge(count) => write!(
+                f,
+                "$GENERATE range expands to {} records, more than the maximum of {}",
+                count, MAX_GENERATE_RECORDS
+            ),
+            Self::Zone(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for ParseErrorKind {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::InvalidName(_, e) => Some(e),
+            Self::InvalidType(e) => Some(e),
+            Self::InvalidRData(e) => Some(e),
+            Self::Zone(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(ZoneError::OutOfZone(Domain::try_from("example.org".to_string()).unwrap()), "'example.org' is outside of the zone")]
+    #[case(
+        ZoneError::ClassMismatch(QClass::new(3)),
+        "class 'CH' does not match the class of the zone"
+    )]
+    fn zone_error_display(#[case] err: ZoneError, #[case] msg: &str) {
+        assert_eq!(err.to_string(), msg);
+    }
+
+    #[rstest]
+    #[case(
+        ZonemdError::SerialMismatch { zonemd: 1, soa: 2 },
+        "ZONEMD serial 1 does not match SOA serial 2"
+    )]
+    #[case(
+        ZonemdError::DuplicateDigest { scheme: 1, hash_algorithm: 2 },
+        "several ZONEMD records use scheme 1 and hash algorithm 2"
+    )]
+    #[case(ZonemdError::DigestMismatch, "zone digest does not match ZONEMD")]
+    fn zonemd_error_display(#[case] err: ZonemdError, #[case] msg: &str) {
+        assert_eq!(err.to_string(), msg);
+    }
+
+    #[rstest]
+    #[case(
+        ParseErrorKind::UnbalancedParentheses,
+        "db.example:7: unbalanced parentheses"
+    )]
+    #[case(
+        ParseErrorKind::InvalidTtl("1y".to_string()),
+        "db.example:7: invalid TTL '1y'"
+    )]
+    #[case(
+        ParseErrorKind::InvalidRData(RDataParseError::MissingField("exchange")),
+        "db.example:7: missing exchange"
+    )]
+    fn parse_error_display(#[case] kind: ParseErrorKind, #[case] msg: &str) {
+        let err = ParseError {
+            file: PathBuf::from("db.example"),
+            line: 7,
+            kind,
+        };
+        assert_eq!(err.to_string(), msg);
+    }
+}
@@ -0,0 +1,27 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub mod error;
+mod parser;
+mod validate;
+mod writer;
+#[allow(clippy::module_inception)]
+mod zone;
+mod zonemd;
+
+pub use parser::ZoneParser;
+pub use validate::{Diagnostic, Rule, Severity};
+pub use writer::{write_zone, WriterConfig};
+pub use zone::Zone;
+pub use zonemd::{ZonemdAlgorithm, ZonemdScheme};
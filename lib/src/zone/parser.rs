@@ -0,0 +1,801 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::Domain;
+use crate::message::{KnownQClass, QClass, QType};
+use crate::record::rdata::parse_ttl;
+use crate::record::{RData, ResourceRecord};
+use crate::zone::error::{ParseError, ParseErrorKind};
+use crate::zone::Zone;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Maximum nesting depth of `$INCLUDE` directives, which guards against
+/// files that include themselves.
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// Maximum number of records a single `$GENERATE` directive may expand to,
+/// which guards against ranges that would exhaust memory.
+pub(crate) const MAX_GENERATE_RECORDS: u64 = 65536;
+
+/// Streaming parser of master (zone) files, yielding one resource record at
+/// a time.
+///
+/// Besides the syntax of [RFC 1035, Section 5], the parser understands:
+/// - the `$TTL` directive of [RFC 2308, Section 4];
+/// - TTLs written with units, such as `1h30m`;
+/// - BIND's `$GENERATE` directive, in the form
+///   `$GENERATE start-stop[/step] lhs [ttl] [class] type rhs`, where `$` in
+///   `lhs` and `rhs` is replaced by the iterator and `${offset,width,base}`
+///   formats it with one of the `d`, `o`, `x`, `X`, `n` and `N` bases. For
+///   the nibble bases, `width` counts nibbles.
+///
+/// Records without a TTL take the one of the last `$TTL` directive or, if
+/// there is none, the one of the previous record. Records without a class
+/// inherit the class of the previous record.
+///
+/// # Example
+/// ```
+/// use dns_lib::zone::ZoneParser;
+/// use dns_lib::Domain;
+/// use std::io::Cursor;
+///
+/// let text = "\
+/// $TTL 1h
+/// @   IN SOA ns1 hostmaster ( 1 2h 1h 2w 5m )
+///         NS ns1
+/// ns1     A  192.0.2.1
+/// ";
+/// let origin = Domain::from_presentation("example.com.").unwrap();
+/// let parser = ZoneParser::from_reader(Cursor::new(text), "db.example", origin);
+/// let records = parser.collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(records[2].to_string(), "ns1.example.com. 3600 IN A 192.0.2.1");
+/// ```
+///
+/// [RFC 1035, Section 5]: https://datatracker.ietf.org/doc/html/rfc1035#section-5
+/// [RFC 2308, Section 4]: https://datatracker.ietf.org/doc/html/rfc2308#section-4
+pub struct ZoneParser {
+    sources: Vec<Source>,
+    zone_origin: Domain,
+    origin: Domain,
+    class: QClass,
+    default_ttl: Option<u32>,
+    last_ttl: Option<u32>,
+    last_owner: Option<Domain>,
+    pending: VecDeque<ResourceRecord>,
+    file: PathBuf,
+    line: usize,
+}
+
+/// File being read, either the main one or an included one.
+struct Source {
+    reader: Box<dyn BufRead>,
+    file: PathBuf,
+    line: usize,
+    /// Origin and owner name to restore once an included file is read.
+    saved: Option<(Domain, Option<Domain>)>,
+}
+
+/// Logical entry of a master file, either a single line or several lines
+/// joined by parentheses.
+struct Entry {
+    /// Whether the entry starts with a blank, inheriting the previous owner.
+    blank_owner: bool,
+    tokens: Vec<String>,
+}
+
+impl ZoneParser {
+    /// Opens the master file at `path`, whose relative names are completed
+    /// with `origin` until a `$ORIGIN` directive is found.
+    pub fn open(path: impl AsRef<Path>, origin: Domain) -> io::Result<Self> {
+        let file = File::open(path.as_ref())?;
+        Ok(ZoneParser::from_reader(
+            BufReader::new(file),
+            path.as_ref(),
+            origin,
+        ))
+    }
+
+    /// Parses the master file read from `reader`. The `file` name is used
+    /// in errors and to resolve relative `$INCLUDE` paths.
+    pub fn from_reader(
+        reader: impl BufRead + 'static,
+        file: impl Into<PathBuf>,
+        origin: Domain,
+    ) -> Self {
+        let file = file.into();
+        ZoneParser {
+            sources: vec![Source {
+                reader: Box::new(reader),
+                file: file.clone(),
+                line: 0,
+                saved: None,
+            }],
+            zone_origin: origin.clone(),
+            origin,
+            class: KnownQClass::IN.into(),
+            default_ttl: None,
+            last_ttl: None,
+            last_owner: None,
+            pending: VecDeque::new(),
+            file,
+            line: 0,
+        }
+    }
+
+    /// Sets the class of the records that precede any explicit class,
+    /// which is `IN` by default.
+    pub fn with_class(mut self, class: QClass) -> Self {
+        self.class = class;
+        self
+    }
+
+    /// Reads all the records into a [`Zone`] rooted at the origin the
+    /// parser was created with. The zone takes the class of its first
+    /// record.
+    pub fn into_zone(mut self) -> Result<Zone, ParseError> {
+        let origin = self.zone_origin.clone();
+        let mut zone: Option<Zone> = None;
+        let mut error = None;
+        for record in self.by_ref() {
+            let record = record?;
+            let zone = zone.get_or_insert_with(|| Zone::new(origin.clone(), record.r_class));
+            if let Err(e) = zone.insert(record) {
+                error = Some(e);
+                break;
+            }
+        }
+        if let Some(e) = error {
+            return Err(self.error(e.into()));
+        }
+        Ok(match zone {
+            Some(zone) => zone,
+            None => Zone::new(origin, self.class),
+        })
+    }
+
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError {
+            file: self.file.clone(),
+            line: self.line,
+            kind,
+        }
+    }
+
+    /// Reads a line from the innermost source, returning `Ok(0)` at its end.
+    fn read_line(&mut self, text: &mut String) -> io::Result<usize> {
+        let Some(source) = self.sources.last_mut() else {
+            return Ok(0);
+        };
+        text.clear();
+        let read = source.reader.read_line(text)?;
+        source.line += 1;
+        Ok(read)
+    }
+
+    fn pop_source(&mut self) {
+        if let Some((origin, owner)) = self.sources.pop().and_then(|source| source.saved) {
+            self.origin = origin;
+            self.last_owner = owner;
+        }
+    }
+
+    fn read_entry(&mut self) -> Option<Result<Entry, ParseError>> {
+        let mut text = String::new();
+        loop {
+            let source = self.sources.last()?;
+            self.file = source.file.clone();
+            self.line = source.line + 1;
+            match self.read_line(&mut text) {
+                Ok(0) => {
+                    self.pop_source();
+                    continue;
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    self.pop_source();
+                    return Some(Err(self.error(e.into())));
+                }
+            }
+
+            let mut entry = Entry {
+                blank_owner: text.starts_with([' ', '\t']),
+                tokens: Vec::new(),
+            };
+            let mut depth = 0;
+            loop {
+                if let Err(kind) = tokenize(&text, &mut depth, &mut entry.tokens) {
+                    return Some(Err(self.error(kind)));
+                }
+                if depth == 0 {
+                    break;
+                }
+                match self.read_line(&mut text) {
+                    Ok(0) => return Some(Err(self.error(ParseErrorKind::UnbalancedParentheses))),
+                    Ok(_) => {}
+                    Err(e) => return Some(Err(self.error(e.into()))),
+                }
+            }
+            if !entry.tokens.is_empty() {
+                return Some(Ok(entry));
+            }
+        }
+    }
+
+    fn process(&mut self, entry: Entry) -> Result<Option<ResourceRecord>, ParseErrorKind> {
+        if !entry.blank_owner && entry.tokens[0].starts_with('$') {
+            self.directive(&entry.tokens)?;
+            return Ok(None);
+        }
+        self.record(&entry.tokens, entry.blank_owner).map(Some)
+    }
+
+    fn directive(&mut self, tokens: &[String]) -> Result<(), ParseErrorKind> {
+        let args: Vec<&str> = tokens[1..].iter().map(String::as_str).collect();
+        match tokens[0].to_ascii_uppercase().as_str() {
+            "$ORIGIN" => {
+                let [origin] = args[..] else {
+                    return Err(ParseErrorKind::InvalidDirective("$ORIGIN"));
+                };
+                self.origin = self.name(origin)?;
+            }
+            "$TTL" => {
+                let [ttl] = args[..] else {
+                    return Err(ParseErrorKind::InvalidDirective("$TTL"));
+                };
+                self.default_ttl = Some(
+                    parse_ttl(ttl).ok_or_else(|| ParseErrorKind::InvalidTtl(ttl.to_string()))?,
+                );
+            }
+            "$INCLUDE" => self.include(&args)?,
+            "$GENERATE" => self.generate(&args)?,
+            _ => return Err(ParseErrorKind::UnknownDirective(tokens[0].clone())),
+        }
+        Ok(())
+    }
+
+    /// Starts reading the file named by a `$INCLUDE` directive, relative to
+    /// the directory of the including file.
+    fn include(&mut self, args: &[&str]) -> Result<(), ParseErrorKind> {
+        let (file, origin) = match args {
+            [file] => (file, None),
+            [file, origin] => (file, Some(self.name(origin)?)),
+            _ => return Err(ParseErrorKind::InvalidDirective("$INCLUDE")),
+        };
+        if self.sources.len() >= MAX_INCLUDE_DEPTH {
+            return Err(ParseErrorKind::IncludeTooDeep);
+        }
+
+        let mut path = PathBuf::from(file.trim_matches('"'));
+        if path.is_relative() {
+            if let Some(dir) = self.file.parent() {
+                path = dir.join(path);
+            }
+        }
+        let reader = BufReader::new(File::open(&path)?);
+        self.sources.push(Source {
+            reader: Box::new(reader),
+            file: path,
+            line: 0,
+            saved: Some((self.origin.clone(), self.last_owner.clone())),
+        });
+        if let Some(origin) = origin {
+            self.origin = origin;
+        }
+        Ok(())
+    }
+
+    /// Expands a `$GENERATE` directive, queuing the generated records.
+    fn generate(&mut self, args: &[&str]) -> Result<(), ParseErrorKind> {
+        let [range, lhs, template @ ..] = args else {
+            return Err(ParseErrorKind::InvalidDirective("$GENERATE"));
+        };
+        if template.is_empty() {
+            return Err(ParseErrorKind::InvalidDirective("$GENERATE"));
+        }
+        let (start, stop, step) =
+            parse_range(range).ok_or_else(|| ParseErrorKind::InvalidGenerate(range.to_string()))?;
+        let count = u64::from((stop - start) / step) + 1;
+        if count > MAX_GENERATE_RECORDS {
+            return Err(ParseErrorKind::GenerateTooLarge(count));
+        }
+
+        let mut records = Vec::new();
+        let mut value = start;
+        while value <= stop {
+            let tokens = std::iter::once(lhs)
+                .chain(template)
+                .map(|text| substitute(text, value))
+                .collect::<Result<Vec<String>, ParseErrorKind>>()?;
+            records.push(self.record(&tokens, false)?);
+            value = match value.checked_add(step) {
+                Some(value) => value,
+                None => break,
+            };
+        }
+        self.pending.extend(records);
+        Ok(())
+    }
+
+    /// Parses a record entry: `[owner] [ttl] [class] type rdata`, where the
+    /// TTL and the class may appear in either order.
+    fn record(
+        &mut self,
+        tokens: &[String],
+        blank_owner: bool,
+    ) -> Result<ResourceRecord, ParseErrorKind> {
+        let mut fields = tokens.iter().map(String::as_str);
+        let name = match blank_owner {
+            true => self
+                .last_owner
+                .clone()
+                .ok_or(ParseErrorKind::MissingOwner)?,
+            false => self.name(fields.next().ok_or(ParseErrorKind::MissingOwner)?)?,
+        };
+
+        let (mut ttl, mut class) = (None, None);
+        let r_type = loop {
+            let field = fields.next().ok_or(ParseErrorKind::MissingType)?;
+            if ttl.is_none() && field.starts_with(|c: char| c.is_ascii_digit()) {
+                let value = parse_ttl(field)
+                    .ok_or_else(|| ParseErrorKind::InvalidTtl(field.to_string()))?;
+                ttl = Some(value);
+                continue;
+            }
+            match QClass::from_str(field) {
+                Ok(value) if class.is_none() => class = Some(value),
+                _ => break QType::from_str(field)?,
+            }
+        };
+
+        let fields: Vec<&str> = fields.collect();
+        let rdata = RData::from_presentation(r_type, &fields, &self.origin)?;
+        let soa_minimum = match &rdata {
+            RData::SOA(soa) => Some(soa.minimum),
+            _ => None,
+        };
+        let ttl = ttl
+            .or(self.default_ttl)
+            .or(self.last_ttl)
+            .or(soa_minimum)
+            .ok_or(ParseErrorKind::MissingTtl)?;
+        let r_class = class.unwrap_or(self.class);
+
+        self.last_owner = Some(name.clone());
+        self.last_ttl = Some(ttl);
+        self.class = r_class;
+        Ok(ResourceRecord {
+            name,
+            r_type,
+            r_class,
+            ttl,
+            rdata,
+        })
+    }
+
+    fn name(&self, text: &str) -> Result<Domain, ParseErrorKind> {
+        Domain::from_presentation_relative(text, &self.origin)
+            .map_err(|e| ParseErrorKind::InvalidName(text.to_string(), e))
+    }
+}
+
+impl Iterator for ZoneParser {
+    type Item = Result<ResourceRecord, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(record) = self.pending.pop_front() {
+                return Some(Ok(record));
+            }
+            let entry = match self.read_entry()? {
+                Ok(entry) => entry,
+                Err(e) => return Some(Err(e)),
+            };
+            match self.process(entry) {
+                Ok(Some(record)) => return Some(Ok(record)),
+                Ok(None) => {}
+                Err(kind) => return Some(Err(self.error(kind))),
+            }
+        }
+    }
+}
+
+/// Splits a line into tokens, dropping comments and tracking the depth of
+/// parentheses. Quoted strings keep their quotes, and escapes are kept as
+/// written, so that each `RDATA` parser can interpret them.
+fn tokenize(text: &str, depth: &mut usize, tokens: &mut Vec<String>) -> Result<(), ParseErrorKind> {
+    let mut token = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                token.push(c);
+                token.extend(chars.next());
+            }
+            '"' => {
+                token.push(c);
+                loop {
+                    match chars.next() {
+                        Some('\\') => {
+                            token.push('\\');
+                            token.extend(chars.next());
+                        }
+                        Some('"') => break token.push('"'),
+                        Some('\n' | '\r') | None => return Err(ParseErrorKind::UnterminatedQuote),
+                        Some(c) => token.push(c),
+                    }
+                }
+            }
+            ';' => break,
+            '(' | ')' => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                }
+                *depth = match c {
+                    '(' => *depth + 1,
+                    _ => depth
+                        .checked_sub(1)
+                        .ok_or(ParseErrorKind::UnbalancedParentheses)?,
+                };
+            }
+            c if c.is_whitespace() => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                }
+            }
+            c => token.push(c),
+        }
+    }
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+    Ok(())
+}
+
+/// Parses the `start-stop[/step]` range of a `$GENERATE` directive.
+fn parse_range(text: &str) -> Option<(u32, u32, u32)> {
+    let (range, step) = match text.split_once('/') {
+        Some((range, step)) => (range, step.parse().ok()?),
+        None => (text, 1),
+    };
+    let (start, stop) = range.split_once('-')?;
+    let (start, stop): (u32, u32) = (start.parse().ok()?, stop.parse().ok()?);
+    (step > 0 && start <= stop).then_some((start, stop, step))
+}
+
+/// Replaces the `$` and `${offset,width,base}` modifiers of a `$GENERATE`
+/// template with `value`. A literal dollar is written as `\$`.
+fn substitute(template: &str, value: u32) -> Result<String, ParseErrorKind> {
+    let invalid = || ParseErrorKind::InvalidGenerate(template.to_string());
+    let mut text = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'$') => text.extend(chars.next()),
+            '\\' => {
+                text.push(c);
+                text.extend(chars.next());
+            }
+            '$' if chars.peek() == Some(&'{') => {
+                chars.next();
+                let mut modifier = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => modifier.push(c),
+                        None => return Err(invalid()),
+                    }
+                }
+                text.push_str(&format_modifier(&modifier, value).ok_or_else(invalid)?);
+            }
+            '$' => text.push_str(&value.to_string()),
+            c => text.push(c),
+        }
+    }
+    Ok(text)
+}
+
+fn format_modifier(modifier: &str, value: u32) -> Option<String> {
+    let mut parts = modifier.split(',');
+    let offset: i64 = parts.next()?.parse().ok()?;
+    let width: usize = parts.next().map_or(Ok(0), str::parse).ok()?;
+    let base = parts.next().unwrap_or("d");
+    if parts.next().is_some() {
+        return None;
+    }
+
+    let value = u32::try_from(i64::from(value) + offset).ok()?;
+    Some(match base {
+        "d" => format!("{:0width$}", value),
+        "o" => format!("{:0width$o}", value),
+        "x" => format!("{:0width$x}", value),
+        "X" => format!("{:0width$X}", value),
+        "n" | "N" => {
+            let hex = format!("{:0width$x}", value);
+            let nibbles: Vec<String> = hex.chars().rev().map(String::from).collect();
+            match base {
+                "N" => nibbles.join(".").to_ascii_uppercase(),
+                _ => nibbles.join("."),
+            }
+        }
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::KnownQType;
+    use rstest::rstest;
+    use std::fs;
+    use std::io::Cursor;
+
+    fn name(text: &str) -> Domain {
+        Domain::from_presentation(text).unwrap()
+    }
+
+    fn parser(text: &'static str) -> ZoneParser {
+        ZoneParser::from_reader(Cursor::new(text), "db.example", name("example.com."))
+    }
+
+    fn parse(text: &'static str) -> Vec<String> {
+        parser(text)
+            .map(|record| record.unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn zone_parser_parses_master_file() {
+        let records = parse(
+            "\
+$ORIGIN example.com.
+$TTL 1h30m
+; The SOA record spans several lines.
+@       IN  SOA ns1 hostmaster.example.com. (
+                2024010101 ; serial
+                2h 1h 2w 5m )
+        IN  NS  ns1
+        86400 NS ns2.example.net.
+ns1     A   192.0.2.1 ; comment
+        AAAA 2001:db8::1
+www     IN 300 CNAME @
+mail.example.com. 10 IN MX 10 mail
+$ORIGIN sub
+host    MX  20 @
+",
+        );
+        assert_eq!(
+            records,
+            [
+                "example.com. 5400 IN SOA ns1.example.com. hostmaster.example.com. 2024010101 7200 3600 1209600 300",
+                "example.com. 5400 IN NS ns1.example.com.",
+                "example.com. 86400 IN NS ns2.example.net.",
+                "ns1.example.com. 5400 IN A 192.0.2.1",
+                "ns1.example.com. 5400 IN AAAA 2001:db8::1",
+                "www.example.com. 300 IN CNAME example.com.",
+                "mail.example.com. 10 IN MX 10 mail.example.com.",
+                "host.sub.example.com. 5400 IN MX 20 sub.example.com.",
+            ]
+        );
+    }
+
+    #[test]
+    fn zone_parser_inherits_ttl_and_class() {
+        let records = parse(
+            "\
+@   CH SOA ns1 hostmaster 1 2 3 4 600
+    NS ns1
+a   1d A 192.0.2.1
+b   A 192.0.2.2
+",
+        );
+        assert_eq!(
+            records,
+            [
+                "example.com. 600 CH SOA ns1.example.com. hostmaster.example.com. 1 2 3 4 600",
+                "example.com. 600 CH NS ns1.example.com.",
+                "a.example.com. 86400 CH A 192.0.2.1",
+                "b.example.com. 86400 CH A 192.0.2.2",
+            ]
+        );
+    }
+
+    #[test]
+    fn zone_parser_reads_quoted_strings() {
+        let records = parse(
+            "\
+$TTL 60
+txt  TXT   \"v=spf1 ip4:192.0.2.0/24\" ( \"; -all\"
+             \"(x)\" )
+host HINFO \"Intel x86\" \"\"
+",
+        );
+        assert_eq!(
+            records,
+            [
+                "txt.example.com. 60 IN TXT \"v=spf1 ip4:192.0.2.0/24\" \"; -all\" \"(x)\"",
+                "host.example.com. 60 IN HINFO \"Intel x86\" \"\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn zone_parser_reads_generic_records() {
+        let records = parse(
+            "\
+a 60 CLASS32 TYPE65280 \\# 3 abcdef
+b 60 class1 type1 \\# 4 C0000201
+c 60 IN TXT \\# 0
+",
+        );
+        assert_eq!(
+            records,
+            [
+                "a.example.com. 60 CLASS32 TYPE65280 \\# 3 ABCDEF",
+                "b.example.com. 60 IN A 192.0.2.1",
+                "c.example.com. 60 IN TXT \\# 0",
+            ]
+        );
+    }
+
+    #[test]
+    fn zone_parser_uses_default_class() {
+        let records: Vec<ResourceRecord> = parser("a 60 A 192.0.2.1\n")
+            .with_class(KnownQClass::HS.into())
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(records[0].r_class, QClass::from(KnownQClass::HS));
+    }
+
+    #[test]
+    fn zone_parser_expands_generate() {
+        let records = parse(
+            "\
+$TTL 60
+$GENERATE 1-3 host-$ A 192.0.2.$
+$GENERATE 10-14/2 ${-10,3,d}.rev PTR host${0,2,x}\\$.example.com.
+$GENERATE 255-255 ${0,0,N} 300 IN TXT \\# 0
+",
+        );
+        assert_eq!(
+            records,
+            [
+                "host-1.example.com. 60 IN A 192.0.2.1",
+                "host-2.example.com. 60 IN A 192.0.2.2",
+                "host-3.example.com. 60 IN A 192.0.2.3",
+                "000.rev.example.com. 60 IN PTR host0a\\$.example.com.",
+                "002.rev.example.com. 60 IN PTR host0c\\$.example.com.",
+                "004.rev.example.com. 60 IN PTR host0e\\$.example.com.",
+                "F.F.example.com. 300 IN TXT \\# 0",
+            ]
+        );
+    }
+
+    #[test]
+    fn zone_parser_follows_includes() {
+        let dir = std::env::temp_dir().join(format!("dns_lib-include-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("db.example"),
+            "$TTL 60\nwww A 192.0.2.1\n$INCLUDE db.sub sub\n  AAAA ::1\nmail A 192.0.2.2\n",
+        )
+        .unwrap();
+        fs::write(dir.join("db.sub"), "@ A 192.0.2.3\nhost A 192.0.2.4\n").unwrap();
+
+        let parser = ZoneParser::open(dir.join("db.example"), name("example.com.")).unwrap();
+        let records: Vec<String> = parser.map(|r| r.unwrap().to_string()).collect();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            records,
+            [
+                "www.example.com. 60 IN A 192.0.2.1",
+                "sub.example.com. 60 IN A 192.0.2.3",
+                "host.sub.example.com. 60 IN A 192.0.2.4",
+                "www.example.com. 60 IN AAAA ::1",
+                "mail.example.com. 60 IN A 192.0.2.2",
+            ]
+        );
+    }
+
+    #[test]
+    fn zone_parser_into_zone() {
+        let zone =
+            parser("@ 60 IN SOA ns1 hostmaster 1 2 3 4 5\nwww A 192.0.2.1\nwww A 192.0.2.1\n")
+                .into_zone()
+                .unwrap();
+        assert_eq!(zone.origin(), &name("example.com"));
+        assert_eq!(zone.len(), 2);
+        assert!(zone
+            .rrset(&name("www.example.com"), KnownQType::A.into())
+            .is_some());
+
+        let err = parser("$TTL 60\nwww.example.org. A 192.0.2.1\n")
+            .into_zone()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "db.example:2: 'www.example.org' is outside of the zone"
+        );
+    }
+
+    #[rstest]
+    #[case(
+        "a A 192.0.2.1\n",
+        "db.example:1: record has no TTL and no default TTL is set"
+    )]
+    #[case("$TTL 1\n\n  A 192.0.2.1\n", "db.example:3: record has no owner name")]
+    #[case("$TTL 1\na ( A\n192.0.2.1\n", "db.example:2: unbalanced parentheses")]
+    #[case("$TTL 1\na A 192.0.2.1 )\n", "db.example:2: unbalanced parentheses")]
+    #[case("$TTL 1\na TXT \"open\n", "db.example:2: unterminated quoted string")]
+    #[case("$TTL 1h1y\n", "db.example:1: invalid TTL '1h1y'")]
+    #[case("$TTL\n", "db.example:1: invalid arguments for $TTL")]
+    #[case("$BOGUS x\n", "db.example:1: unknown directive '$BOGUS'")]
+    #[case("a 60 IN\n", "db.example:1: record has no type")]
+    #[case("a 60 BOGUS x\n", "db.example:1: 'BOGUS' is not a known record type")]
+    #[case("; comment\n\na 60 (\nMX\n10 ) \n", "db.example:3: missing exchange")]
+    #[case(
+        "a..b 60 A 192.0.2.1\n",
+        "db.example:1: invalid name 'a..b': label is empty"
+    )]
+    #[case(
+        "$GENERATE 3-1 a A 192.0.2.$\n",
+        "db.example:1: invalid $GENERATE template '3-1'"
+    )]
+    #[case(
+        "$GENERATE 1-3 a${1,2 60 A 192.0.2.1\n",
+        "db.example:1: invalid $GENERATE template 'a${1,2'"
+    )]
+    #[case(
+        "$GENERATE 0-4294967295 a$ 60 A 192.0.2.1\n",
+        "db.example:1: $GENERATE range expands to 4294967296 records, more than the maximum of 65536"
+    )]
+    #[case("$INCLUDE\n", "db.example:1: invalid arguments for $INCLUDE")]
+    fn zone_parser_fails(#[case] text: &'static str, #[case] msg: &str) {
+        let err = parser(text).find_map(Result::err).unwrap();
+        assert_eq!(err.to_string(), msg);
+    }
+
+    #[test]
+    fn zone_parser_continues_after_errors() {
+        let results: Vec<bool> = parser("$TTL 60\na A x\nb A 192.0.2.1\n")
+            .map(|r| r.is_ok())
+            .collect();
+        assert_eq!(results, [false, true]);
+    }
+
+    #[rstest]
+    #[case("$", 7, "7")]
+    #[case("\\$$", 7, "$7")]
+    #[case("${1}", 7, "8")]
+    #[case("${-7,3}", 7, "000")]
+    #[case("${0,4,o}", 8, "0010")]
+    #[case("${0,2,X}", 255, "FF")]
+    #[case("${0,4,n}", 0x1a, "a.1.0.0")]
+    fn substitute_works(#[case] template: &str, #[case] value: u32, #[case] expected: &str) {
+        assert_eq!(substitute(template, value).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("${-8}")]
+    #[case("${0,1,z}")]
+    #[case("${0,1,d,x}")]
+    #[case("${x}")]
+    fn substitute_fails(#[case] template: &str) {
+        assert!(substitute(template, 7).is_err());
+    }
+}
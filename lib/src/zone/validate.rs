@@ -0,0 +1,553 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::Domain;
+use crate::message::{KnownQType, QType};
+use crate::record::{RData, ResourceRecord};
+use crate::zone::Zone;
+use std::collections::HashSet;
+use std::fmt;
+
+/// Types that may share an owner name with a `CNAME` record, see
+/// [RFC 4035, Section 2.5].
+///
+/// [RFC 4035, Section 2.5]: https://datatracker.ietf.org/doc/html/rfc4035#section-2.5
+const CNAME_COMPANIONS: [KnownQType; 3] = [KnownQType::CNAME, KnownQType::RRSIG, KnownQType::NSEC];
+
+/// Types that are authoritative at a delegation point, see
+/// [RFC 4035, Section 2.2].
+///
+/// [RFC 4035, Section 2.2]: https://datatracker.ietf.org/doc/html/rfc4035#section-2.2
+const DELEGATION_TYPES: [KnownQType; 4] = [
+    KnownQType::NS,
+    KnownQType::DS,
+    KnownQType::RRSIG,
+    KnownQType::NSEC,
+];
+
+/// Largest `SOA` minimum worth caching negative answers for, see
+/// [RFC 2308, Section 5].
+///
+/// [RFC 2308, Section 5]: https://datatracker.ietf.org/doc/html/rfc2308#section-5
+const MAX_SOA_MINIMUM: u32 = 86_400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Check performed by [`Zone::validate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rule {
+    /// A `CNAME` record shares its owner name with other data, see
+    /// [RFC 1034, Section 3.6.2].
+    ///
+    /// [RFC 1034, Section 3.6.2]: https://datatracker.ietf.org/doc/html/rfc1034#section-3.6.2
+    CnameAndOtherData,
+    /// An owner name has more than one `CNAME` record, see
+    /// [RFC 2181, Section 10.1].
+    ///
+    /// [RFC 2181, Section 10.1]: https://datatracker.ietf.org/doc/html/rfc2181#section-10.1
+    MultipleCnames,
+    MissingSoa,
+    MissingNs,
+    /// An `NS` record names a server inside the zone that has no address
+    /// records.
+    MissingGlue,
+    /// An `NS` or `MX` record names an alias, see [RFC 2181, Section 10.3].
+    ///
+    /// [RFC 2181, Section 10.3]: https://datatracker.ietf.org/doc/html/rfc2181#section-10.3
+    TargetIsCname,
+    /// Data at or below a delegation point that is neither authoritative
+    /// nor glue, and will never be served.
+    OccludedName,
+    /// The records of a record set have different TTLs, see
+    /// [RFC 2181, Section 5.2].
+    ///
+    /// [RFC 2181, Section 5.2]: https://datatracker.ietf.org/doc/html/rfc2181#section-5.2
+    TtlMismatch,
+    /// The timers of the `SOA` record are inconsistent or out of range.
+    SoaTimers,
+    /// Data below the owner of a `DNAME` record, which queries never reach,
+    /// see [RFC 6672, Section 2.4].
+    ///
+    /// [RFC 6672, Section 2.4]: https://datatracker.ietf.org/doc/html/rfc6672#section-2.4
+    OccludedByDname,
+    /// An owner name has more than one `DNAME` record, see
+    /// [RFC 6672, Section 2.4].
+    ///
+    /// [RFC 6672, Section 2.4]: https://datatracker.ietf.org/doc/html/rfc6672#section-2.4
+    MultipleDnames,
+}
+
+impl Rule {
+    /// Returns the stable identifier of the rule.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::CnameAndOtherData => "cname-and-other-data",
+            Self::MultipleCnames => "multiple-cnames",
+            Self::MissingSoa => "missing-soa",
+            Self::MissingNs => "missing-ns",
+            Self::MissingGlue => "missing-glue",
+            Self::TargetIsCname => "target-is-cname",
+            Self::OccludedName => "occluded-name",
+            Self::TtlMismatch => "ttl-mismatch",
+            Self::SoaTimers => "soa-timers",
+            Self::OccludedByDname => "occluded-by-dname",
+            Self::MultipleDnames => "multiple-dnames",
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::OccludedName | Self::OccludedByDname | Self::TtlMismatch | Self::SoaTimers => {
+                Severity::Warning
+            }
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.id())
+    }
+}
+
+/// Problem found by [`Zone::validate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub owner: Domain,
+    pub rule: Rule,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn new(owner: &Domain, rule: Rule, message: String) -> Self {
+        Diagnostic {
+            severity: rule.severity(),
+            owner: owner.clone(),
+            rule,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}: {} [{}]",
+            self.severity,
+            self.owner.to_fqdn_string(),
+            self.message,
+            self.rule
+        )
+    }
+}
+
+impl Zone {
+    /// Checks the zone for common mistakes, returning the problems found
+    /// in canonical order of their owner names.
+    ///
+    /// Diagnostics of [`Severity::Error`] make the zone unsafe to serve,
+    /// while those of [`Severity::Warning`] point at data that is likely
+    /// wrong or ignored.
+    ///
+    /// # Example
+    /// ```
+    /// use dns_lib::message::{KnownQClass, KnownQType};
+    /// use dns_lib::record::{RData, ResourceRecord};
+    /// use dns_lib::zone::{Rule, Zone};
+    /// use dns_lib::Domain;
+    ///
+    /// let origin = Domain::from_presentation("example.com").unwrap();
+    /// let zone = Zone::new(origin, KnownQClass::IN.into());
+    ///
+    /// let rules: Vec<Rule> = zone.validate().iter().map(|d| d.rule).collect();
+    /// assert_eq!(rules, [Rule::MissingSoa, Rule::MissingNs]);
+    /// ```
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let origin = self.origin();
+        let apex_types = self.types_at(origin);
+        if !apex_types.contains(&KnownQType::SOA.into()) {
+            diagnostics.push(Diagnostic::new(
+                origin,
+                Rule::MissingSoa,
+                "zone apex has no SOA record".to_string(),
+            ));
+        }
+        if !apex_types.contains(&KnownQType::NS.into()) {
+            diagnostics.push(Diagnostic::new(
+                origin,
+                Rule::MissingNs,
+                "zone apex has no NS records".to_string(),
+            ));
+        }
+
+        let ns_targets: HashSet<&Domain> = self
+            .records()
+            .filter_map(|record| match &record.rdata {
+                RData::NS(target) => Some(target),
+                _ => None,
+            })
+            .collect();
+        for name in self.names() {
+            let types = self.types_at(name);
+            if let Some(cut) = self.enclosing_delegation(name) {
+                self.check_occluded(name, &types, &ns_targets, Some(&cut), &mut diagnostics);
+                continue;
+            }
+            if let Some(dname) = self.enclosing_dname(name) {
+                let types: Vec<String> = types.iter().map(QType::to_string).collect();
+                diagnostics.push(Diagnostic::new(
+                    name,
+                    Rule::OccludedByDname,
+                    format!(
+                        "{} occluded by the DNAME at {}",
+                        types.join(", "),
+                        dname.name.to_fqdn_string()
+                    ),
+                ));
+                continue;
+            }
+            if self.is_delegation(name) {
+                self.check_occluded(name, &types, &ns_targets, None, &mut diagnostics);
+            }
+            self.check_cname(name, &types, &mut diagnostics);
+            let count = self.records_at(name, KnownQType::DNAME.into()).len();
+            if count > 1 {
+                diagnostics.push(Diagnostic::new(
+                    name,
+                    Rule::MultipleDnames,
+                    format!("owner has {} DNAME records", count),
+                ));
+            }
+            for r_type in types {
+                let records = self.records_at(name, r_type);
+                self.check_ttls(name, r_type, records, &mut diagnostics);
+                for record in records {
+                    self.check_target(record, &mut diagnostics);
+                }
+            }
+        }
+
+        if let Some((record, soa)) = self.soa() {
+            let mut problems = Vec::new();
+            if soa.retry >= soa.refresh {
+                problems.push("retry is not shorter than refresh");
+            }
+            if soa.expire <= soa.refresh.saturating_add(soa.retry) {
+                problems.push("expire is not longer than refresh plus retry");
+            }
+            if soa.minimum > MAX_SOA_MINIMUM {
+                problems.push("minimum exceeds one day");
+            }
+            if !problems.is_empty() {
+                diagnostics.push(Diagnostic::new(
+                    &record.name,
+                    Rule::SoaTimers,
+                    problems.join(", "),
+                ));
+            }
+        }
+
+        diagnostics.sort_by(|a, b| a.owner.cmp(&b.owner));
+        diagnostics
+    }
+
+    fn check_cname(&self, name: &Domain, types: &[QType], diagnostics: &mut Vec<Diagnostic>) {
+        if !types.contains(&KnownQType::CNAME.into()) {
+            return;
+        }
+        let others: Vec<String> = types
+            .iter()
+            .filter(|r_type| !CNAME_COMPANIONS.iter().any(|t| **r_type == *t))
+            .map(|r_type| r_type.to_string())
+            .collect();
+        if !others.is_empty() {
+            diagnostics.push(Diagnostic::new(
+                name,
+                Rule::CnameAndOtherData,
+                format!("CNAME shares its owner with {}", others.join(", ")),
+            ));
+        }
+        let count = self.records_at(name, KnownQType::CNAME.into()).len();
+        if count > 1 {
+            diagnostics.push(Diagnostic::new(
+                name,
+                Rule::MultipleCnames,
+                format!("owner has {} CNAME records", count),
+            ));
+        }
+    }
+
+    /// Reports data that is not served because it is at or below the
+    /// delegation point `cut`, where `None` means `name` itself.
+    fn check_occluded(
+        &self,
+        name: &Domain,
+        types: &[QType],
+        ns_targets: &HashSet<&Domain>,
+        cut: Option<&Domain>,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let is_glue = |r_type: &QType| {
+            (*r_type == KnownQType::A || *r_type == KnownQType::AAAA) && ns_targets.contains(name)
+        };
+        let occluded: Vec<String> = types
+            .iter()
+            .filter(|r_type| cut.is_some() || !DELEGATION_TYPES.iter().any(|t| **r_type == *t))
+            .filter(|r_type| !is_glue(r_type))
+            .map(|r_type| r_type.to_string())
+            .collect();
+        if occluded.is_empty() {
+            return;
+        }
+        let cut = cut.unwrap_or(name);
+        diagnostics.push(Diagnostic::new(
+            name,
+            Rule::OccludedName,
+            format!(
+                "{} occluded by the delegation at {}",
+                occluded.join(", "),
+                cut.to_fqdn_string()
+            ),
+        ));
+    }
+
+    fn check_ttls(
+        &self,
+        name: &Domain,
+        r_type: QType,
+        records: &[ResourceRecord],
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let mut ttls: Vec<u32> = records.iter().map(|record| record.ttl).collect();
+        ttls.sort_unstable();
+        ttls.dedup();
+        if ttls.len() > 1 {
+            let ttls: Vec<String> = ttls.iter().map(u32::to_string).collect();
+            diagnostics.push(Diagnostic::new(
+                name,
+                Rule::TtlMismatch,
+                format!("{} records have TTLs {}", r_type, ttls.join(", ")),
+            ));
+        }
+    }
+
+    /// Checks the name server or mail exchange named by `record`, when it
+    /// is inside the zone.
+    fn check_target(&self, record: &ResourceRecord, diagnostics: &mut Vec<Diagnostic>) {
+        let target = match &record.rdata {
+            RData::NS(target) => target,
+            RData::MX(mx) => &mx.exchange,
+            _ => return,
+        };
+        if !target.is_subdomain_of(self.origin()) {
+            return;
+        }
+
+        let types = self.types_at(target);
+        if types.contains(&KnownQType::CNAME.into()) {
+            diagnostics.push(Diagnostic::new(
+                &record.name,
+                Rule::TargetIsCname,
+                format!(
+                    "{} target {} is an alias",
+                    record.r_type,
+                    target.to_fqdn_string()
+                ),
+            ));
+        } else if matches!(record.rdata, RData::NS(_))
+            && !types.contains(&KnownQType::A.into())
+            && !types.contains(&KnownQType::AAAA.into())
+        {
+            diagnostics.push(Diagnostic::new(
+                &record.name,
+                Rule::MissingGlue,
+                format!(
+                    "name server {} has no address records",
+                    target.to_fqdn_string()
+                ),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zone::ZoneParser;
+    use rstest::rstest;
+    use std::io::Cursor;
+
+    const APEX: &str = "\
+$TTL 3600
+@       SOA ns1 hostmaster 1 7200 3600 1209600 300
+        NS  ns1
+ns1     A   192.0.2.1
+";
+
+    fn zone(text: &str) -> Zone {
+        let origin = Domain::from_presentation("example.com.").unwrap();
+        let text = format!("{}{}", APEX, text);
+        ZoneParser::from_reader(Cursor::new(text), "db.example", origin)
+            .into_zone()
+            .unwrap()
+    }
+
+    fn findings(zone: &Zone) -> Vec<(Severity, String, Rule)> {
+        zone.validate()
+            .into_iter()
+            .map(|d| (d.severity, d.owner.to_string(), d.rule))
+            .collect()
+    }
+
+    #[test]
+    fn validate_accepts_clean_zone() {
+        let zone = zone(
+            "\
+www     CNAME ns1
+mail    MX  10 ns1
+sub     NS  ns.sub
+        DS  1 8 2 AABB
+ns.sub  A   192.0.2.2
+out     NS  ns.example.net.
+",
+        );
+        assert_eq!(zone.validate(), []);
+    }
+
+    #[rstest]
+    #[case(
+        "www CNAME ns1\nwww A 192.0.2.9\n",
+        Severity::Error,
+        "www.example.com",
+        Rule::CnameAndOtherData
+    )]
+    #[case(
+        "www CNAME a\nwww CNAME b\n",
+        Severity::Error,
+        "www.example.com",
+        Rule::MultipleCnames
+    )]
+    #[case(
+        "sub NS ns.sub\n",
+        Severity::Error,
+        "sub.example.com",
+        Rule::MissingGlue
+    )]
+    #[case("@ NS ns2\n", Severity::Error, "example.com", Rule::MissingGlue)]
+    #[case(
+        "alias CNAME ns1\nmail MX 10 alias\n",
+        Severity::Error,
+        "mail.example.com",
+        Rule::TargetIsCname
+    )]
+    #[case(
+        "alias CNAME ns1\nsub NS alias\n",
+        Severity::Error,
+        "sub.example.com",
+        Rule::TargetIsCname
+    )]
+    #[case(
+        "sub NS ns.example.net.\nwww.sub A 192.0.2.2\n",
+        Severity::Warning,
+        "www.sub.example.com",
+        Rule::OccludedName
+    )]
+    #[case(
+        "sub NS ns.example.net.\nsub TXT \\# 1 00\n",
+        Severity::Warning,
+        "sub.example.com",
+        Rule::OccludedName
+    )]
+    #[case(
+        "old DNAME example.net.\nwww.old A 192.0.2.2\n",
+        Severity::Warning,
+        "www.old.example.com",
+        Rule::OccludedByDname
+    )]
+    #[case(
+        "old DNAME example.net.\nold DNAME example.org.\n",
+        Severity::Error,
+        "old.example.com",
+        Rule::MultipleDnames
+    )]
+    #[case(
+        "old DNAME example.net.\nold CNAME ns1\n",
+        Severity::Error,
+        "old.example.com",
+        Rule::CnameAndOtherData
+    )]
+    #[case(
+        "www A 192.0.2.1\nwww 60 A 192.0.2.2\n",
+        Severity::Warning,
+        "www.example.com",
+        Rule::TtlMismatch
+    )]
+    fn validate_reports(
+        #[case] text: &str,
+        #[case] severity: Severity,
+        #[case] owner: &str,
+        #[case] rule: Rule,
+    ) {
+        assert_eq!(findings(&zone(text)), [(severity, owner.to_string(), rule)]);
+    }
+
+    #[test]
+    fn validate_checks_apex() {
+        let origin = Domain::from_presentation("example.com.").unwrap();
+        let text = "@ 60 SOA ns1 hostmaster 1 3600 7200 3600 604800\n";
+        let zone = ZoneParser::from_reader(Cursor::new(text), "db.example", origin)
+            .into_zone()
+            .unwrap();
+
+        let diagnostics = zone.validate();
+        let rules: Vec<Rule> = diagnostics.iter().map(|d| d.rule).collect();
+        assert_eq!(rules, [Rule::MissingNs, Rule::SoaTimers]);
+        assert_eq!(
+            diagnostics[1].to_string(),
+            "warning: example.com.: retry is not shorter than refresh, \
+             expire is not longer than refresh plus retry, \
+             minimum exceeds one day [soa-timers]"
+        );
+    }
+
+    #[test]
+    fn diagnostic_display() {
+        let diagnostic = Diagnostic::new(
+            &Domain::from_presentation("www.example.com").unwrap(),
+            Rule::CnameAndOtherData,
+            "CNAME shares its owner with A".to_string(),
+        );
+        assert_eq!(
+            diagnostic.to_string(),
+            "error: www.example.com.: CNAME shares its owner with A [cname-and-other-data]"
+        );
+    }
+}
@@ -0,0 +1,356 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::message::KnownQType;
+use crate::record::{RData, ResourceRecord};
+use crate::zone::Zone;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+/// Smallest width given to wrapped binary data, however deep the indent.
+const MIN_CHUNK_WIDTH: usize = 32;
+
+/// Options of [`write_zone`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct WriterConfig {
+    /// Write a `$TTL` directive with the most common TTL of the zone, and
+    /// omit the TTL of the records that use it.
+    pub hoist_ttl: bool,
+
+    /// Width in characters beyond which the `RDATA` of a record is wrapped
+    /// over several lines within parentheses.
+    pub max_width: usize,
+}
+
+impl Default for WriterConfig {
+    fn default() -> Self {
+        WriterConfig {
+            hoist_ttl: true,
+            max_width: 80,
+        }
+    }
+}
+
+/// Writes `zone` as a master file in a canonical form, so that the same
+/// records always produce the same text.
+///
+/// Owner names are written relative to an `$ORIGIN` directive, in
+/// canonical order, see [RFC 4034, Section 6.1]. Each owner name appears
+/// only on the first of its records, with the `SOA` record first at the
+/// apex and the rest ordered by type and `RDATA`. Columns are aligned, and
+/// long `RDATA` such as keys and signatures is wrapped in parentheses.
+/// Parsing the output with [`ZoneParser`](crate::zone::ZoneParser) yields
+/// the records of `zone` again.
+///
+/// # Example
+/// ```
+/// use dns_lib::message::{KnownQClass, KnownQType};
+/// use dns_lib::record::{RData, ResourceRecord};
+/// use dns_lib::zone::{write_zone, WriterConfig, Zone};
+/// use dns_lib::Domain;
+///
+/// let origin = Domain::from_presentation("example.com").unwrap();
+/// let mut zone = Zone::new(origin, KnownQClass::IN.into());
+/// zone.insert(ResourceRecord {
+///     name: Domain::from_presentation("www.example.com").unwrap(),
+///     r_type: KnownQType::A.into(),
+///     r_class: KnownQClass::IN.into(),
+///     ttl: 3600,
+///     rdata: RData::A("192.0.2.1".parse().unwrap()),
+/// })
+/// .unwrap();
+///
+/// let mut text = Vec::new();
+/// write_zone(&zone, &WriterConfig::default(), &mut text).unwrap();
+/// assert_eq!(
+///     String::from_utf8(text).unwrap(),
+///     "$ORIGIN example.com.\n$TTL 3600\nwww IN A 192.0.2.1\n"
+/// );
+/// ```
+///
+/// [RFC 4034, Section 6.1]: https://datatracker.ietf.org/doc/html/rfc4034#section-6.1
+pub fn write_zone(zone: &Zone, config: &WriterConfig, out: &mut impl Write) -> io::Result<()> {
+    let origin = zone.origin();
+    let records = sorted_records(zone);
+    let default_ttl = match config.hoist_ttl {
+        true => most_common_ttl(&records),
+        false => None,
+    };
+
+    writeln!(out, "$ORIGIN {}", origin.to_fqdn_string())?;
+    if let Some(ttl) = default_ttl {
+        writeln!(out, "$TTL {}", ttl)?;
+    }
+
+    let ttl = |record: &ResourceRecord| match Some(record.ttl) == default_ttl {
+        true => String::new(),
+        false => record.ttl.to_string(),
+    };
+    let owner_width = width(records.iter().map(|r| r.name.to_relative_string(origin)));
+    let ttl_width = width(records.iter().map(|r| ttl(r)));
+    let type_width = width(records.iter().map(|r| r.r_type.to_string()));
+
+    let mut previous = None;
+    for record in records {
+        let owner = match previous == Some(&record.name) {
+            true => String::new(),
+            false => record.name.to_relative_string(origin),
+        };
+        previous = Some(&record.name);
+
+        let mut prefix = format!("{:<owner_width$} ", owner);
+        if ttl_width > 0 {
+            prefix.push_str(&format!("{:>ttl_width$} ", ttl(record)));
+        }
+        prefix.push_str(&format!(
+            "{} {:<type_width$} ",
+            record.r_class,
+            record.r_type.to_string()
+        ));
+
+        let rdata = record.rdata.to_string();
+        match prefix.len() + rdata.len() > config.max_width {
+            true => write_wrapped(out, &prefix, &record.rdata, &rdata, config.max_width)?,
+            false => writeln!(out, "{}{}", prefix, rdata)?,
+        }
+    }
+    Ok(())
+}
+
+/// Returns the records of `zone` in the order they are written.
+fn sorted_records(zone: &Zone) -> Vec<&ResourceRecord> {
+    let not_soa = |record: &ResourceRecord| record.r_type != KnownQType::SOA;
+    let mut records: Vec<&ResourceRecord> = zone.records().collect();
+    records.sort_by(|a, b| {
+        a.name
+            .cmp(&b.name)
+            .then_with(|| not_soa(a).cmp(&not_soa(b)))
+            .then_with(|| a.r_type.cmp(&b.r_type))
+            .then_with(|| {
+                a.rdata
+                    .to_canonical_bytes()
+                    .cmp(&b.rdata.to_canonical_bytes())
+            })
+    });
+    records
+}
+
+/// Returns the TTL shared by most records, the smallest one on ties.
+fn most_common_ttl(records: &[&ResourceRecord]) -> Option<u32> {
+    let mut counts: HashMap<u32, usize> = HashMap::new();
+    for record in records {
+        *counts.entry(record.ttl).or_default() += 1;
+    }
+    counts
+        .into_iter()
+        .max_by_key(|&(ttl, count)| (count, Reverse(ttl)))
+        .map(|(ttl, _)| ttl)
+}
+
+fn width(columns: impl Iterator<Item = String>) -> usize {
+    columns.map(|column| column.len()).max().unwrap_or(0)
+}
+
+/// Writes `rdata` over several lines within parentheses, indented under
+/// its first field. The binary data ending keys, signatures, digests and
+/// generic `RDATA` is split in chunks, which parsers join back together.
+fn write_wrapped(
+    out: &mut impl Write,
+    prefix: &str,
+    rdata: &RData,
+    text: &str,
+    max_width: usize,
+) -> io::Result<()> {
+    let mut fields = split_fields(text);
+    let binary_tail = matches!(
+        rdata,
+        RData::CERT(_)
+            | RData::DS(_)
+            | RData::CDS(_)
+            | RData::DNSKEY(_)
+            | RData::CDNSKEY(_)
+            | RData::KEY(_)
+            | RData::OPENPGPKEY(_)
+            | RData::RRSIG(_)
+            | RData::SIG(_)
+            | RData::SSHFP(_)
+            | RData::SMIMEA(_)
+            | RData::TLSA(_)
+            | RData::ZONEMD(_)
+            | RData::Unknown(_)
+    );
+    let indent = " ".repeat(prefix.len());
+    let line_width = max_width.saturating_sub(indent.len()).max(MIN_CHUNK_WIDTH);
+
+    let body: Vec<String> = match binary_tail && fields.len() > 1 {
+        true => {
+            let tail: Vec<char> = fields.pop().unwrap_or_default().chars().collect();
+            tail.chunks(line_width)
+                .map(|chunk| chunk.iter().collect())
+                .collect()
+        }
+        false => fields.split_off(fields.len().min(1)),
+    };
+    writeln!(out, "{}{} (", prefix, fields.join(" "))?;
+
+    let mut lines: Vec<String> = Vec::new();
+    for field in body {
+        match lines.last_mut() {
+            Some(line) if line.len() + 1 + field.len() <= line_width => {
+                line.push(' ');
+                line.push_str(&field);
+            }
+            _ => lines.push(field),
+        }
+    }
+    match lines.split_last() {
+        Some((last, lines)) => {
+            for line in lines {
+                writeln!(out, "{}{}", indent, line)?;
+            }
+            writeln!(out, "{}{} )", indent, last)
+        }
+        None => writeln!(out, "{})", indent),
+    }
+}
+
+/// Splits `RDATA` in presentation format on whitespace, keeping quoted
+/// strings and escaped characters within their field.
+fn split_fields(text: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                field.push(c);
+                field.extend(chars.next());
+            }
+            '"' => {
+                quoted = !quoted;
+                field.push(c);
+            }
+            c if c.is_whitespace() && !quoted => {
+                if !field.is_empty() {
+                    fields.push(std::mem::take(&mut field));
+                }
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() {
+        fields.push(field);
+    }
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Domain;
+    use crate::zone::ZoneParser;
+    use rstest::rstest;
+    use std::io::Cursor;
+
+    const ZONE: &str = "\
+$ORIGIN example.com.
+$TTL 3600
+www 300 IN A 192.0.2.2
+www IN A 192.0.2.1
+@ IN NS ns1
+@ IN SOA ns1 hostmaster 2024010101 7200 3600 1209600 300
+@ IN DNSKEY 257 3 8 AwEAAaz/tAm8yTn4Mfeh5eyI96WSVexTBAvkMgJzkKTOiW1vkIbzxeF3+/4RgWOq7HrxRixHlFlExOLAJr5emLvN7SWXgnLh4+B5xQlNVz8Og8kvArMtNROxVQuCaSnIDdD5LKyWbRd2n9WGe2R8PzgCmr3EgVLrjyBxWezF0jLHwVN8efS3rCj/EWgvIWgb9tarpVUDK/b58Da+sqqls3eNbuv7pr+eoZG+SrDK6nWeL3c6H5Apxz7LjVc1uTIdsIXxuOLYA4/ilBmSVIzuDWfdRUfhHdY6+cn8HFRm+2hM8AnXGXws9555KrUB5qihylGa8subX2Nn6UwNR1AkUTV74bU=
+mail IN MX 10 mail
+mail IN AAAA 2001:db8::25
+";
+
+    fn parse(text: &str) -> Zone {
+        let origin = Domain::from_presentation("example.com.").unwrap();
+        ZoneParser::from_reader(Cursor::new(text.to_string()), "db.example", origin)
+            .into_zone()
+            .unwrap()
+    }
+
+    fn write(zone: &Zone, config: &WriterConfig) -> String {
+        let mut text = Vec::new();
+        write_zone(zone, config, &mut text).unwrap();
+        String::from_utf8(text).unwrap()
+    }
+
+    fn sorted(zone: &Zone) -> Vec<String> {
+        let mut records: Vec<String> = zone.records().map(|r| r.to_string()).collect();
+        records.sort();
+        records
+    }
+
+    #[test]
+    fn write_zone_is_canonical() {
+        let text = write(&parse(ZONE), &WriterConfig::default());
+        assert_eq!(
+            text,
+            "\
+$ORIGIN example.com.
+$TTL 3600
+@        IN SOA    ns1.example.com. (
+                   hostmaster.example.com. 2024010101 7200 3600 1209600 300 )
+         IN NS     ns1.example.com.
+         IN DNSKEY 257 3 8 (
+                   AwEAAaz/tAm8yTn4Mfeh5eyI96WSVexTBAvkMgJzkKTOiW1vkIbzxeF3+/4Rg
+                   WOq7HrxRixHlFlExOLAJr5emLvN7SWXgnLh4+B5xQlNVz8Og8kvArMtNROxVQ
+                   uCaSnIDdD5LKyWbRd2n9WGe2R8PzgCmr3EgVLrjyBxWezF0jLHwVN8efS3rCj
+                   /EWgvIWgb9tarpVUDK/b58Da+sqqls3eNbuv7pr+eoZG+SrDK6nWeL3c6H5Ap
+                   xz7LjVc1uTIdsIXxuOLYA4/ilBmSVIzuDWfdRUfhHdY6+cn8HFRm+2hM8AnXG
+                   Xws9555KrUB5qihylGa8subX2Nn6UwNR1AkUTV74bU= )
+mail     IN MX     10 mail.example.com.
+         IN AAAA   2001:db8::25
+www      IN A      192.0.2.1
+     300 IN A      192.0.2.2
+"
+        );
+    }
+
+    #[rstest]
+    #[case(WriterConfig::default())]
+    #[case(WriterConfig { hoist_ttl: false, max_width: 80 })]
+    #[case(WriterConfig { hoist_ttl: true, max_width: 0 })]
+    fn write_zone_round_trips(#[case] config: WriterConfig) {
+        let zone = parse(ZONE);
+        let text = write(&zone, &config);
+        let parsed = parse(&text);
+        assert_eq!(sorted(&parsed), sorted(&zone));
+        assert_eq!(write(&parsed, &config), text);
+    }
+
+    #[test]
+    fn write_zone_without_hoisting() {
+        let text = write(
+            &parse("$TTL 60\n@ A 192.0.2.1\n"),
+            &WriterConfig {
+                hoist_ttl: false,
+                max_width: 80,
+            },
+        );
+        assert_eq!(text, "$ORIGIN example.com.\n@ 60 IN A 192.0.2.1\n");
+    }
+
+    #[rstest]
+    #[case("1 2 3", &["1", "2", "3"])]
+    #[case("\"a b\" c\\ d", &["\"a b\"", "c\\ d"])]
+    #[case("  a  ", &["a"])]
+    fn split_fields_works(#[case] text: &str, #[case] expected: &[&str]) {
+        assert_eq!(split_fields(text), expected);
+    }
+}
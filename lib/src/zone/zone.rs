@@ -0,0 +1,377 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::Domain;
+use crate::message::{KnownQType, QClass, QType};
+use crate::record::rdata::Soa;
+use crate::record::{RData, RecordSet, ResourceRecord};
+use crate::zone::error::ZoneError;
+use std::collections::BTreeMap;
+
+/// Authoritative data for a zone: every resource record at or below the
+/// zone origin, indexed by owner name and type.
+///
+/// Owner names are kept in canonical order, see [RFC 4034, Section 6.1],
+/// so that iterating over a zone yields a stable sequence suitable for
+/// signing and for writing zone files.
+///
+/// [RFC 4034, Section 6.1]: https://datatracker.ietf.org/doc/html/rfc4034#section-6.1
+#[derive(Debug, Clone, PartialEq)]
+pub struct Zone {
+    origin: Domain,
+    class: QClass,
+    nodes: BTreeMap<Domain, BTreeMap<QType, Vec<ResourceRecord>>>,
+}
+
+impl Zone {
+    pub fn new(origin: Domain, class: QClass) -> Self {
+        Zone {
+            origin,
+            class,
+            nodes: BTreeMap::new(),
+        }
+    }
+
+    pub fn origin(&self) -> &Domain {
+        &self.origin
+    }
+
+    pub fn class(&self) -> QClass {
+        self.class
+    }
+
+    /// Adds `record` to the zone. Records already present with identical
+    /// `RDATA` are not added twice.
+    ///
+    /// # Example
+    /// ```
+    /// use dns_lib::message::{KnownQClass, KnownQType};
+    /// use dns_lib::record::{RData, ResourceRecord};
+    /// use dns_lib::zone::Zone;
+    /// use dns_lib::Domain;
+    ///
+    /// let origin = Domain::try_from("example.com".to_string()).unwrap();
+    /// let mut zone = Zone::new(origin, KnownQClass::IN.into());
+    ///
+    /// let record = ResourceRecord {
+    ///     name: Domain::try_from("www.example.com".to_string()).unwrap(),
+    ///     r_type: KnownQType::A.into(),
+    ///     r_class: KnownQClass::IN.into(),
+    ///     ttl: 3600,
+    ///     rdata: RData::A("192.0.2.1".parse().unwrap()),
+    /// };
+    /// assert!(zone.insert(record).is_ok());
+    /// assert_eq!(zone.len(), 1);
+    /// ```
+    pub fn insert(&mut self, record: ResourceRecord) -> Result<(), ZoneError> {
+        if !record.name.is_subdomain_of(&self.origin) {
+            return Err(ZoneError::OutOfZone(record.name));
+        }
+        if record.r_class != self.class {
+            return Err(ZoneError::ClassMismatch(record.r_class));
+        }
+
+        let records = self
+            .nodes
+            .entry(record.name.clone())
+            .or_default()
+            .entry(record.r_type)
+            .or_default();
+        if !records.iter().any(|r| r.rdata == record.rdata) {
+            records.push(record);
+        }
+        Ok(())
+    }
+
+    /// Removes and returns all records of type `r_type` owned by `name`.
+    pub fn remove_rrset(&mut self, name: &Domain, r_type: QType) -> Vec<ResourceRecord> {
+        let Some(node) = self.nodes.get_mut(name) else {
+            return Vec::new();
+        };
+        let removed = node.remove(&r_type).unwrap_or_default();
+        if node.is_empty() {
+            self.nodes.remove(name);
+        }
+        removed
+    }
+
+    /// Returns the record set of type `r_type` owned by `name`, if any.
+    pub fn rrset(&self, name: &Domain, r_type: QType) -> Option<RecordSet> {
+        RecordSet::from_records(self.nodes.get(name)?.get(&r_type)?)
+    }
+
+    /// Returns the records of type `r_type` owned by `name`. Unlike
+    /// [`Zone::rrset`], each record keeps its own TTL.
+    pub fn records_at(&self, name: &Domain, r_type: QType) -> &[ResourceRecord] {
+        self.nodes
+            .get(name)
+            .and_then(|node| node.get(&r_type))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Iterates over all record sets, ordered by owner name and then by
+    /// type.
+    pub fn rrsets(&self) -> impl Iterator<Item = RecordSet> + '_ {
+        self.nodes
+            .values()
+            .flat_map(|node| node.values())
+            .filter_map(RecordSet::from_records)
+    }
+
+    /// Iterates over all records, ordered by owner name and then by type.
+    pub fn records(&self) -> impl Iterator<Item = &ResourceRecord> {
+        self.nodes.values().flat_map(|node| node.values()).flatten()
+    }
+
+    /// Iterates over all owner names, in canonical order.
+    pub fn names(&self) -> impl Iterator<Item = &Domain> {
+        self.nodes.keys()
+    }
+
+    /// Returns the types of the records owned by `name`, in ascending order.
+    pub fn types_at(&self, name: &Domain) -> Vec<QType> {
+        match self.nodes.get(name) {
+            Some(node) => node.keys().copied().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns the `SOA` record at the zone apex, if present.
+    pub fn soa(&self) -> Option<(&ResourceRecord, &Soa)> {
+        let records = self.nodes.get(&self.origin)?.get(&KnownQType::SOA.into())?;
+        records.iter().find_map(|record| match &record.rdata {
+            RData::SOA(soa) => Some((record, soa)),
+            _ => None,
+        })
+    }
+
+    /// Checks if `name` is a delegation point, that is, a name below the
+    /// apex owning `NS` records.
+    pub fn is_delegation(&self, name: &Domain) -> bool {
+        *name != self.origin
+            && self
+                .nodes
+                .get(name)
+                .is_some_and(|node| node.contains_key(&KnownQType::NS.into()))
+    }
+
+    /// Returns the delegation point strictly above `name`, if any. Data at
+    /// such names is not authoritative: it is either glue or occluded.
+    pub fn enclosing_delegation(&self, name: &Domain) -> Option<Domain> {
+        let mut current = name.parent()?;
+        while current != self.origin && current.is_subdomain_of(&self.origin) {
+            if self.is_delegation(&current) {
+                return Some(current);
+            }
+            current = current.parent()?;
+        }
+        None
+    }
+
+    /// Returns the `DNAME` record owned by a name strictly above `name`,
+    /// where the search for `name` stops and a `CNAME` record is
+    /// synthesized instead, see [RFC 6672, Section 3.2].
+    ///
+    /// The search descends from the apex, so the `DNAME` record closest to
+    /// it wins, and it ends at delegation points.
+    ///
+    /// [RFC 6672, Section 3.2]: https://datatracker.ietf.org/doc/html/rfc6672#section-3.2
+    pub fn enclosing_dname(&self, name: &Domain) -> Option<&ResourceRecord> {
+        std::iter::successors(name.parent(), Domain::parent)
+            .take_while(|current| current.is_subdomain_of(&self.origin))
+            .fold(None, |found, current| {
+                if self.is_delegation(&current) {
+                    None
+                } else {
+                    self.records_at(&current, KnownQType::DNAME.into())
+                        .first()
+                        .or(found)
+                }
+            })
+    }
+
+    pub fn len(&self) -> usize {
+        self.records().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::KnownQClass;
+    use std::net::Ipv4Addr;
+
+    fn name(text: &str) -> Domain {
+        Domain::from_presentation(text).unwrap()
+    }
+
+    fn record(owner: &str, rdata: RData) -> ResourceRecord {
+        let r_type = match &rdata {
+            RData::A(_) => KnownQType::A,
+            RData::NS(_) => KnownQType::NS,
+            RData::DNAME(_) => KnownQType::DNAME,
+            _ => KnownQType::SOA,
+        };
+        ResourceRecord {
+            name: name(owner),
+            r_type: r_type.into(),
+            r_class: KnownQClass::IN.into(),
+            ttl: 300,
+            rdata,
+        }
+    }
+
+    fn zone() -> Zone {
+        let mut zone = Zone::new(name("example.com"), KnownQClass::IN.into());
+        let soa = Soa {
+            m_name: name("ns.example.com"),
+            r_name: name("hostmaster.example.com"),
+            serial: 1,
+            refresh: 7200,
+            retry: 3600,
+            expire: 1209600,
+            minimum: 300,
+        };
+        zone.insert(record("example.com", RData::SOA(soa))).unwrap();
+        zone.insert(record(
+            "www.example.com",
+            RData::A(Ipv4Addr::new(192, 0, 2, 2)),
+        ))
+        .unwrap();
+        zone.insert(record(
+            "sub.example.com",
+            RData::NS(name("ns.sub.example.com")),
+        ))
+        .unwrap();
+        zone.insert(record(
+            "ns.sub.example.com",
+            RData::A(Ipv4Addr::new(192, 0, 2, 3)),
+        ))
+        .unwrap();
+        zone.insert(record(
+            "a.example.com",
+            RData::A(Ipv4Addr::new(192, 0, 2, 1)),
+        ))
+        .unwrap();
+        zone
+    }
+
+    #[test]
+    fn zone_iterates_in_canonical_order() {
+        let names: Vec<String> = zone().names().map(|n| n.to_string()).collect();
+        assert_eq!(
+            names,
+            [
+                "example.com",
+                "a.example.com",
+                "sub.example.com",
+                "ns.sub.example.com",
+                "www.example.com"
+            ]
+        );
+    }
+
+    #[test]
+    fn zone_insert_deduplicates() {
+        let mut zone = zone();
+        let len = zone.len();
+        zone.insert(record(
+            "WWW.example.com",
+            RData::A(Ipv4Addr::new(192, 0, 2, 2)),
+        ))
+        .unwrap();
+        assert_eq!(zone.len(), len);
+        zone.insert(record(
+            "www.example.com",
+            RData::A(Ipv4Addr::new(192, 0, 2, 9)),
+        ))
+        .unwrap();
+        assert_eq!(zone.len(), len + 1);
+        assert_eq!(
+            zone.rrset(&name("www.example.com"), KnownQType::A.into())
+                .unwrap()
+                .rdatas
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn zone_insert_fails() {
+        let mut zone = zone();
+        let outside = record("example.org", RData::A(Ipv4Addr::LOCALHOST));
+        assert_eq!(
+            zone.insert(outside),
+            Err(ZoneError::OutOfZone(name("example.org")))
+        );
+
+        let mut chaos = record("a.example.com", RData::A(Ipv4Addr::LOCALHOST));
+        chaos.r_class = KnownQClass::CH.into();
+        assert_eq!(
+            zone.insert(chaos),
+            Err(ZoneError::ClassMismatch(KnownQClass::CH.into()))
+        );
+    }
+
+    #[test]
+    fn zone_remove_rrset_drops_empty_names() {
+        let mut zone = zone();
+        let removed = zone.remove_rrset(&name("a.example.com"), KnownQType::A.into());
+        assert_eq!(removed.len(), 1);
+        assert!(zone.types_at(&name("a.example.com")).is_empty());
+        assert!(!zone.names().any(|n| *n == name("a.example.com")));
+    }
+
+    #[test]
+    fn zone_delegations() {
+        let zone = zone();
+        assert!(zone.soa().is_some());
+        assert!(zone.is_delegation(&name("sub.example.com")));
+        assert!(!zone.is_delegation(&name("example.com")));
+        assert_eq!(
+            zone.enclosing_delegation(&name("ns.sub.example.com")),
+            Some(name("sub.example.com"))
+        );
+        assert_eq!(zone.enclosing_delegation(&name("sub.example.com")), None);
+        assert_eq!(zone.enclosing_delegation(&name("www.example.com")), None);
+    }
+
+    #[test]
+    fn zone_enclosing_dname() {
+        let mut zone = zone();
+        let dname = record("old.example.com", RData::DNAME(name("example.net")));
+        zone.insert(dname.clone()).unwrap();
+        zone.insert(record(
+            "x.sub.example.com",
+            RData::DNAME(name("example.org")),
+        ))
+        .unwrap();
+
+        assert_eq!(
+            zone.enclosing_dname(&name("www.old.example.com")),
+            Some(&dname)
+        );
+        assert_eq!(
+            zone.enclosing_dname(&name("a.b.old.example.com")),
+            Some(&dname)
+        );
+        assert_eq!(zone.enclosing_dname(&name("old.example.com")), None);
+        assert_eq!(zone.enclosing_dname(&name("www.example.com")), None);
+        assert_eq!(zone.enclosing_dname(&name("www.x.sub.example.com")), None);
+    }
+}
@@ -0,0 +1,358 @@
+// Copyright 2024 Matei Bogdan Radu
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::message::{KnownQType, QType};
+use crate::record::rdata::Zonemd;
+use crate::record::{RData, ResourceRecord};
+use crate::zone::error::{ZonemdAlgorithmTryFromError, ZonemdError, ZonemdSchemeTryFromError};
+use crate::zone::Zone;
+use sha2::{Digest, Sha384, Sha512};
+use std::collections::HashSet;
+use std::fmt;
+
+/// Scheme used to compute a `ZONEMD` digest.
+///
+/// For more details, see [RFC 8976, Section 5.2].
+///
+/// [RFC 8976, Section 5.2]: https://datatracker.ietf.org/doc/html/rfc8976#section-5.2
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum ZonemdScheme {
+    /// Single digest over all the records of the zone, see
+    /// [RFC 8976, Section 3.3.1].
+    ///
+    /// [RFC 8976, Section 3.3.1]: https://datatracker.ietf.org/doc/html/rfc8976#section-3.3.1
+    Simple = 1,
+}
+
+impl TryFrom<u8> for ZonemdScheme {
+    type Error = ZonemdSchemeTryFromError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Self::Simple),
+            unsupported => Err(ZonemdSchemeTryFromError(unsupported)),
+        }
+    }
+}
+
+/// Hash algorithm of a `ZONEMD` digest.
+///
+/// For more details, see [RFC 8976, Section 5.3].
+///
+/// [RFC 8976, Section 5.3]: https://datatracker.ietf.org/doc/html/rfc8976#section-5.3
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum ZonemdAlgorithm {
+    Sha384 = 1,
+    Sha512 = 2,
+}
+
+impl ZonemdAlgorithm {
+    /// Returns the length in octets of digests of this algorithm.
+    pub fn digest_len(&self) -> usize {
+        match self {
+            Self::Sha384 => 48,
+            Self::Sha512 => 64,
+        }
+    }
+}
+
+impl TryFrom<u8> for ZonemdAlgorithm {
+    type Error = ZonemdAlgorithmTryFromError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Self::Sha384),
+            2 => Ok(Self::Sha512),
+            unsupported => Err(ZonemdAlgorithmTryFromError(unsupported)),
+        }
+    }
+}
+
+impl fmt::Display for ZonemdAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Sha384 => write!(f, "SHA-384"),
+            Self::Sha512 => write!(f, "SHA-512"),
+        }
+    }
+}
+
+impl Zone {
+    /// Computes the `ZONEMD` `RDATA` of the zone, with the serial of its
+    /// `SOA` record.
+    ///
+    /// The digest covers the canonical form of every record in the zone,
+    /// in canonical order, except the apex `ZONEMD` record set and the
+    /// signatures covering it, see [RFC 8976, Section 3.3].
+    ///
+    /// [RFC 8976, Section 3.3]: https://datatracker.ietf.org/doc/html/rfc8976#section-3.3
+    pub fn zonemd(
+        &self,
+        scheme: ZonemdScheme,
+        algorithm: ZonemdAlgorithm,
+    ) -> Result<Zonemd, ZonemdError> {
+        let (_, soa) = self.soa().ok_or(ZonemdError::MissingSoa)?;
+        let digest = match scheme {
+            ZonemdScheme::Simple => match algorithm {
+                ZonemdAlgorithm::Sha384 => self.simple_digest::<Sha384>(),
+                ZonemdAlgorithm::Sha512 => self.simple_digest::<Sha512>(),
+            },
+        };
+        Ok(Zonemd {
+            serial: soa.serial,
+            scheme: scheme as u8,
+            hash_algorithm: algorithm as u8,
+            digest,
+        })
+    }
+
+    /// Replaces the apex `ZONEMD` record set with one record per algorithm
+    /// in `algorithms`, using the `SIMPLE` scheme and the TTL of the `SOA`
+    /// record.
+    ///
+    /// A signed zone must be signed again afterwards, so that the new
+    /// record set gets an `RRSIG`.
+    pub fn add_zonemd(&mut self, algorithms: &[ZonemdAlgorithm]) -> Result<(), ZonemdError> {
+        let (soa, _) = self.soa().ok_or(ZonemdError::MissingSoa)?;
+        let (ttl, r_class) = (soa.ttl, soa.r_class);
+
+        let name = self.origin().clone();
+        self.remove_rrset(&name, KnownQType::ZONEMD.into());
+        let records = algorithms
+            .iter()
+            .map(|algorithm| self.zonemd(ZonemdScheme::Simple, *algorithm))
+            .collect::<Result<Vec<Zonemd>, ZonemdError>>()?;
+        for zonemd in records {
+            self.insert(ResourceRecord {
+                name: name.clone(),
+                r_type: KnownQType::ZONEMD.into(),
+                r_class,
+                ttl,
+                rdata: RData::ZONEMD(zonemd),
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Verifies the zone against its apex `ZONEMD` records, returning the
+    /// one that matches.
+    ///
+    /// Records whose serial differs from the `SOA` serial, or that use an
+    /// unsupported scheme or algorithm, are ignored. The authenticity of
+    /// the `ZONEMD` records themselves is left to DNSSEC validation.
+    ///
+    /// For more details, see [RFC 8976, Section 4].
+    ///
+    /// [RFC 8976, Section 4]: https://datatracker.ietf.org/doc/html/rfc8976#section-4
+    pub fn verify_zonemd(&self) -> Result<Zonemd, ZonemdError> {
+        let (_, soa) = self.soa().ok_or(ZonemdError::MissingSoa)?;
+        let records = self.records_at(self.origin(), KnownQType::ZONEMD.into());
+        if records.is_empty() {
+            return Err(ZonemdError::MissingZonemd);
+        }
+
+        let mut seen = HashSet::new();
+        let mut serial_mismatch = None;
+        let mut candidates = Vec::new();
+        for record in records {
+            let RData::ZONEMD(zonemd) = &record.rdata else {
+                continue;
+            };
+            if !seen.insert((zonemd.scheme, zonemd.hash_algorithm)) {
+                return Err(ZonemdError::DuplicateDigest {
+                    scheme: zonemd.scheme,
+                    hash_algorithm: zonemd.hash_algorithm,
+                });
+            }
+            if zonemd.serial != soa.serial {
+                serial_mismatch = Some(zonemd.serial);
+                continue;
+            }
+            let (Ok(scheme), Ok(algorithm)) = (
+                ZonemdScheme::try_from(zonemd.scheme),
+                ZonemdAlgorithm::try_from(zonemd.hash_algorithm),
+            ) else {
+                continue;
+            };
+            candidates.push((zonemd, scheme, algorithm));
+        }
+
+        if candidates.is_empty() {
+            return Err(match serial_mismatch {
+                Some(zonemd) => ZonemdError::SerialMismatch {
+                    zonemd,
+                    soa: soa.serial,
+                },
+                None => ZonemdError::Unsupported,
+            });
+        }
+        for (zonemd, scheme, algorithm) in candidates {
+            if self.zonemd(scheme, algorithm)?.digest == zonemd.digest {
+                return Ok(zonemd.clone());
+            }
+        }
+        Err(ZonemdError::DigestMismatch)
+    }
+
+    fn simple_digest<D: Digest>(&self) -> Vec<u8> {
+        let origin = self.origin();
+        let zonemd_type = QType::from(KnownQType::ZONEMD);
+        let mut hasher = D::new();
+        for name in self.names() {
+            for r_type in self.types_at(name) {
+                if *name == *origin && r_type == zonemd_type {
+                    continue;
+                }
+                let mut rdatas: Vec<(Vec<u8>, &ResourceRecord)> = self
+                    .records_at(name, r_type)
+                    .iter()
+                    .filter(|record| match &record.rdata {
+                        RData::RRSIG(rrsig) => {
+                            *name != *origin || rrsig.type_covered != zonemd_type
+                        }
+                        _ => true,
+                    })
+                    .map(|record| (record.rdata.to_canonical_bytes(), record))
+                    .collect();
+                rdatas.sort_by(|a, b| a.0.cmp(&b.0));
+                rdatas.dedup_by(|a, b| a.0 == b.0);
+                for (_, record) in rdatas {
+                    hasher.update(record.to_canonical_bytes(record.ttl));
+                }
+            }
+        }
+        hasher.finalize().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Domain;
+    use crate::zone::ZoneParser;
+    use rstest::rstest;
+    use std::io::Cursor;
+
+    // Example from RFC 8976, Appendix A.1.
+    const SIMPLE: &str = "\
+example.      86400  IN  SOA     ns1 admin 2018031900 (
+                                 1800 900 604800 86400 )
+              86400  IN  NS      ns1
+              86400  IN  NS      ns2
+              86400  IN  ZONEMD  2018031900 1 1 (
+                                 c68090d90a7aed71
+                                 6bc459f9340e3d7c
+                                 1370d4d24b7e2fc3
+                                 a1ddc0b9a87153b9
+                                 a9713b3c9ae5cc27
+                                 777f98b8e730044c )
+ns1           3600   IN  A       203.0.113.63
+ns2           3600   IN  AAAA    2001:db8::63
+";
+
+    fn zone(text: &str) -> Zone {
+        let origin = Domain::from_presentation("example.").unwrap();
+        ZoneParser::from_reader(Cursor::new(text.to_string()), "db.example", origin)
+            .into_zone()
+            .unwrap()
+    }
+
+    fn zonemds(zone: &Zone) -> Vec<String> {
+        zone.records_at(zone.origin(), KnownQType::ZONEMD.into())
+            .iter()
+            .map(|record| record.rdata.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn verify_zonemd_matches_rfc_example() {
+        let zone = zone(SIMPLE);
+        let zonemd = zone.verify_zonemd().unwrap();
+        assert_eq!(zonemd.serial, 2018031900);
+        assert_eq!(
+            zone.zonemd(ZonemdScheme::Simple, ZonemdAlgorithm::Sha384),
+            Ok(zonemd)
+        );
+    }
+
+    #[test]
+    fn add_zonemd_replaces_records() {
+        let mut zone = zone(SIMPLE);
+        zone.add_zonemd(&[ZonemdAlgorithm::Sha384, ZonemdAlgorithm::Sha512])
+            .unwrap();
+
+        let records = zonemds(&zone);
+        assert_eq!(records.len(), 2);
+        assert!(records[0].starts_with("2018031900 1 1 C68090D90A7AED71"));
+        assert!(records[1].starts_with("2018031900 1 2 "));
+        assert_eq!(records[1].len(), "2018031900 1 2 ".len() + 128);
+        assert_eq!(
+            zone.records_at(zone.origin(), KnownQType::ZONEMD.into())[0].ttl,
+            86400
+        );
+        assert!(zone.verify_zonemd().is_ok());
+    }
+
+    #[test]
+    fn zonemd_ignores_apex_signatures_of_zonemd() {
+        let signed = format!(
+            "{}{}",
+            SIMPLE,
+            "example. 86400 IN RRSIG ZONEMD 13 1 86400 20300101000000 20200101000000 1 example. AAAA\n"
+        );
+        assert!(zone(&signed).verify_zonemd().is_ok());
+
+        let covered = format!(
+            "{}{}",
+            SIMPLE,
+            "example. 86400 IN RRSIG NS 13 1 86400 20300101000000 20200101000000 1 example. AAAA\n"
+        );
+        assert_eq!(
+            zone(&covered).verify_zonemd(),
+            Err(ZonemdError::DigestMismatch)
+        );
+    }
+
+    #[rstest]
+    #[case("ns3 3600 IN A 203.0.113.64\n", ZonemdError::DigestMismatch)]
+    #[case("@ 86400 IN ZONEMD 2018031900 1 1 00\n", ZonemdError::DuplicateDigest { scheme: 1, hash_algorithm: 1 })]
+    fn verify_zonemd_fails(#[case] extra: &str, #[case] expected: ZonemdError) {
+        let zone = zone(&format!("{}{}", SIMPLE, extra));
+        assert_eq!(zone.verify_zonemd(), Err(expected));
+    }
+
+    #[rstest]
+    #[case("2018031901 1 1 00", ZonemdError::SerialMismatch { zonemd: 2018031901, soa: 2018031900 })]
+    #[case("2018031900 240 1 00", ZonemdError::Unsupported)]
+    #[case("2018031900 1 240 00", ZonemdError::Unsupported)]
+    fn verify_zonemd_skips_unusable_records(#[case] rdata: &str, #[case] expected: ZonemdError) {
+        let text = format!(
+            "@ 60 IN SOA ns1 admin 2018031900 1800 900 604800 86400\n@ 60 IN ZONEMD {}\n",
+            rdata
+        );
+        assert_eq!(zone(&text).verify_zonemd(), Err(expected));
+    }
+
+    #[test]
+    fn zonemd_requires_soa() {
+        let mut zone = zone("ns1 60 IN A 192.0.2.1\n");
+        assert_eq!(zone.verify_zonemd(), Err(ZonemdError::MissingSoa));
+        assert_eq!(
+            zone.add_zonemd(&[ZonemdAlgorithm::Sha384]),
+            Err(ZonemdError::MissingSoa)
+        );
+        let zone = self::zone("@ 60 IN SOA ns1 admin 1 2 3 4 5\n");
+        assert_eq!(zone.verify_zonemd(), Err(ZonemdError::MissingZonemd));
+    }
+}